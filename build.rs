@@ -0,0 +1,43 @@
+// build.rs
+// Embeds build metadata for `--version --verbose` and the settings window's About tab.
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    let sha = git(&["rev-parse", "--short=10", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|s| !s.is_empty());
+    println!("cargo:rustc-env=MATRIX_OVERLAY_GIT_SHA={}{}", sha, if dirty { "-dirty" } else { "" });
+
+    // Reproducible builds set SOURCE_DATE_EPOCH; otherwise use the current time
+    let epoch = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0));
+    println!("cargo:rustc-env=MATRIX_OVERLAY_BUILD_DATE={}", utc_date(epoch));
+
+    println!("cargo:rustc-env=MATRIX_OVERLAY_TARGET={}", std::env::var("TARGET").unwrap_or_default());
+    println!("cargo:rustc-env=MATRIX_OVERLAY_PROFILE={}", std::env::var("PROFILE").unwrap_or_default());
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// "YYYY-MM-DD" for a Unix timestamp (days-to-civil, Howard Hinnant's algorithm).
+fn utc_date(epoch: u64) -> String {
+    let days = (epoch / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,209 @@
+//! Threshold levels and desktop alerts.
+//! Classifies metric values against their `metric_settings` thresholds, tracks
+//! level transitions (with hysteresis), and sends debounced `notify-send` alerts.
+
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::config::{effective_thresholds, MetricSettings, Thresholds};
+use crate::metrics::{MetricId, MetricValue};
+
+/// Severity of a metric relative to its thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum AlertLevel {
+    #[default]
+    Normal,
+    Warn,
+    Crit,
+}
+
+/// Computes the new level for `value`, given the previous level.
+///
+/// Rising is immediate; falling back requires the value to drop `hysteresis`
+/// below the threshold, so readings hovering at a limit don't flap. With
+/// `below` set the comparison is mirrored.
+pub fn classify(value: f64, thresholds: &Thresholds, hysteresis: f64, previous: AlertLevel) -> AlertLevel {
+    let sign = if thresholds.below { -1.0 } else { 1.0 };
+    let above = |limit: Option<f64>, level: AlertLevel| match limit {
+        Some(l) if previous >= level => sign * value >= sign * l - hysteresis,
+        Some(l) => sign * value >= sign * l,
+        None => false,
+    };
+    if above(thresholds.crit, AlertLevel::Crit) {
+        AlertLevel::Crit
+    } else if above(thresholds.warn, AlertLevel::Warn) {
+        AlertLevel::Warn
+    } else {
+        AlertLevel::Normal
+    }
+}
+
+#[derive(Debug, Default)]
+struct AlertState {
+    level: AlertLevel,
+    last_notified: Option<Instant>,
+}
+
+/// Tracks per-metric levels across collection cycles.
+#[derive(Debug, Default)]
+pub struct AlertManager {
+    states: HashMap<String, AlertState>,
+}
+
+impl AlertManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops all tracked levels (e.g. after a config reload).
+    pub fn reset(&mut self) {
+        self.states.clear();
+    }
+
+    /// Updates levels for all metrics with thresholds and returns the current non-normal levels.
+    /// Sends a notification when a metric rises to a higher level, at most once per debounce window.
+    pub fn update(
+        &mut self,
+        settings: &HashMap<String, MetricSettings>,
+        values: &HashMap<MetricId, MetricValue>,
+    ) -> HashMap<MetricId, AlertLevel> {
+        let mut levels = HashMap::new();
+        for (id, value) in values {
+            let thresholds = match effective_thresholds(settings, id.as_str()) {
+                Some(t) => t,
+                None => continue,
+            };
+            let hysteresis = settings.get(id.as_str()).map_or(0.0, |cfg| cfg.hysteresis);
+            let state = self.states.entry(id.as_str().to_string()).or_default();
+            let level = match (state_level(id, value), value.as_f64()) {
+                (Some(level), _) => level,
+                (None, Some(v)) => classify(v, &thresholds, hysteresis, state.level),
+                (None, None) => continue,
+            };
+
+            if level > state.level && thresholds.notify {
+                let debounce = Duration::from_secs(thresholds.debounce_secs);
+                let due = state.last_notified.is_none_or(|t| t.elapsed() >= debounce);
+                if due {
+                    notify(id, value, level);
+                    state.last_notified = Some(Instant::now());
+                }
+            }
+            if level != state.level {
+                log::info!("Metric {} changed level {:?} -> {:?} ({:?})", id.as_str(), state.level, level, value);
+            }
+            state.level = level;
+
+            if level != AlertLevel::Normal {
+                levels.insert(id.clone(), level);
+            }
+        }
+        levels
+    }
+}
+
+/// Level of state-valued metrics that have no numeric reading (systemd units: "failed" is
+/// critical; CI runs: "fail" is critical, "running" a warning; an unsynchronized clock is a warning).
+pub fn state_level(id: &MetricId, value: &MetricValue) -> Option<AlertLevel> {
+    match (id, value) {
+        (MetricId::Custom(name), MetricValue::String(state)) if name.starts_with("service_") => {
+            Some(if state == "failed" { AlertLevel::Crit } else { AlertLevel::Normal })
+        }
+        (MetricId::Custom(name), MetricValue::String(state)) if name.starts_with("ci_") => Some(match state.as_str() {
+            "fail" => AlertLevel::Crit,
+            "running" => AlertLevel::Warn,
+            _ => AlertLevel::Normal,
+        }),
+        (MetricId::TimeSync, MetricValue::String(state)) => {
+            Some(if state.starts_with("unsynced") { AlertLevel::Warn } else { AlertLevel::Normal })
+        }
+        _ => None,
+    }
+}
+
+/// Sends the notification a rule would send at its highest configured level,
+/// so a new rule can be checked from the settings window.
+pub fn test_fire(metric: &str, thresholds: &Thresholds) {
+    let id = MetricId::from_str(metric).unwrap_or_else(|| MetricId::Custom(metric.to_string()));
+    let (limit, level) = match (thresholds.crit, thresholds.warn) {
+        (Some(crit), _) => (crit, AlertLevel::Crit),
+        (None, Some(warn)) => (warn, AlertLevel::Warn),
+        (None, None) => return,
+    };
+    notify(&id, &MetricValue::Float(limit), level);
+}
+
+fn notify(id: &MetricId, value: &MetricValue, level: AlertLevel) {
+    let shown = match value {
+        MetricValue::String(s) => s.clone(),
+        other => other.as_f64().map(|v| format!("{:.1}", v)).unwrap_or_default(),
+    };
+    desktop_notify(&format!("{} is {} ({:?})", id.label(), shown, level), level == AlertLevel::Crit);
+}
+
+/// Shows a "Matrix Overlay" desktop notification via notify-send.
+pub fn desktop_notify(body: &str, critical: bool) {
+    let urgency = if critical { "critical" } else { "normal" };
+    match Command::new("notify-send").args(["-u", urgency, "Matrix Overlay", body]).spawn() {
+        // Reaped off the caller's thread so finished notify-sends don't linger as zombies
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => log::warn!("Failed to send alert notification: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thresholds() -> Thresholds {
+        Thresholds {
+            warn: Some(70.0),
+            crit: Some(85.0),
+            warn_color: "#FFBF00".to_string(),
+            crit_color: "#FF3333".to_string(),
+            below: false,
+            notify: false,
+            debounce_secs: 300,
+        }
+    }
+
+    #[test]
+    fn test_classify_levels_with_hysteresis() {
+        let t = thresholds();
+        assert_eq!(classify(50.0, &t, 2.0, AlertLevel::Normal), AlertLevel::Normal);
+        assert_eq!(classify(86.0, &t, 2.0, AlertLevel::Normal), AlertLevel::Crit);
+        // Within the hysteresis band the level holds
+        assert_eq!(classify(84.0, &t, 2.0, AlertLevel::Crit), AlertLevel::Crit);
+        assert_eq!(classify(82.0, &t, 2.0, AlertLevel::Crit), AlertLevel::Warn);
+        assert_eq!(classify(69.0, &t, 2.0, AlertLevel::Warn), AlertLevel::Warn);
+        assert_eq!(classify(67.0, &t, 2.0, AlertLevel::Warn), AlertLevel::Normal);
+    }
+
+    #[test]
+    fn test_classify_below_thresholds() {
+        let t = Thresholds { warn: Some(20.0), crit: Some(10.0), below: true, ..thresholds() };
+        assert_eq!(classify(50.0, &t, 2.0, AlertLevel::Normal), AlertLevel::Normal);
+        assert_eq!(classify(15.0, &t, 2.0, AlertLevel::Normal), AlertLevel::Warn);
+        assert_eq!(classify(9.0, &t, 2.0, AlertLevel::Normal), AlertLevel::Crit);
+        // Recovery needs to clear the limit by the hysteresis band
+        assert_eq!(classify(11.0, &t, 2.0, AlertLevel::Crit), AlertLevel::Crit);
+        assert_eq!(classify(21.0, &t, 2.0, AlertLevel::Warn), AlertLevel::Warn);
+        assert_eq!(classify(23.0, &t, 2.0, AlertLevel::Warn), AlertLevel::Normal);
+    }
+
+    #[test]
+    fn test_failed_unit_is_critical() {
+        let unit = MetricId::Custom("service_docker.service".to_string());
+        assert_eq!(state_level(&unit, &MetricValue::String("failed".to_string())), Some(AlertLevel::Crit));
+        assert_eq!(state_level(&unit, &MetricValue::String("active".to_string())), Some(AlertLevel::Normal));
+        assert_eq!(state_level(&MetricId::CpuUsage, &MetricValue::Float(99.0)), None);
+
+        let ci = MetricId::Custom("ci_overlay".to_string());
+        assert_eq!(state_level(&ci, &MetricValue::String("fail".to_string())), Some(AlertLevel::Crit));
+        assert_eq!(state_level(&ci, &MetricValue::String("running".to_string())), Some(AlertLevel::Warn));
+        assert_eq!(state_level(&MetricId::TimeSync, &MetricValue::String("unsynced (chrony)".to_string())), Some(AlertLevel::Warn));
+    }
+}
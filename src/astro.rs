@@ -0,0 +1,155 @@
+//! Sun and moon calculations for the `sunrise`, `sunset`, `daylight_remaining` and
+//! `moon_phase` metrics, computed locally from the weather coordinates.
+//! Sun times use the NOAA sunrise equation (accurate to a minute or two away from the
+//! poles); the moon phase is the mean age since a known new moon.
+//! The astro collector also records whether the sun is up in `daytime()`, which the
+//! overlay uses to switch to `general.night_theme`.
+
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+/// Julian date of 2000-01-01 12:00 (J2000).
+const J2000: f64 = 2_451_545.0;
+
+/// Julian date of the Unix epoch.
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+
+/// Julian date of the new moon of 2000-01-06 18:14 UTC.
+const NEW_MOON_JD: f64 = 2_451_550.26;
+
+/// Mean length of a lunar cycle in days.
+const SYNODIC_MONTH: f64 = 29.530_588_853;
+
+/// Sunrise and sunset on one day at one place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SunTimes {
+    Rises { sunrise: DateTime<Utc>, sunset: DateTime<Utc> },
+    /// The sun stays above the horizon all day (polar day).
+    AlwaysUp,
+    /// The sun stays below the horizon all day (polar night).
+    AlwaysDown,
+}
+
+impl SunTimes {
+    /// Sunrise and sunset on `date` at `lat`/`lon` (degrees, east positive).
+    pub fn on(date: NaiveDate, lat: f64, lon: f64) -> Self {
+        let days = (date - NaiveDate::from_ymd_opt(2000, 1, 1).unwrap_or_default()).num_days() as f64;
+        let mean_noon = days + 0.0009 - lon / 360.0;
+        let anomaly = (357.5291 + 0.985_600_28 * mean_noon).rem_euclid(360.0).to_radians();
+        let center = 1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+        let ecliptic_lon = (anomaly.to_degrees() + center + 180.0 + 102.9372).rem_euclid(360.0).to_radians();
+        let transit = J2000 + mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_lon).sin();
+        let declination = (ecliptic_lon.sin() * 23.4397_f64.to_radians().sin()).asin();
+
+        // -0.833° accounts for refraction and the size of the sun's disc
+        let lat = lat.to_radians();
+        let cos_hour_angle =
+            ((-0.833_f64).to_radians().sin() - lat.sin() * declination.sin()) / (lat.cos() * declination.cos());
+        if cos_hour_angle > 1.0 {
+            return Self::AlwaysDown;
+        }
+        if cos_hour_angle < -1.0 {
+            return Self::AlwaysUp;
+        }
+        let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+        Self::Rises { sunrise: from_julian(transit - half_day), sunset: from_julian(transit + half_day) }
+    }
+
+    /// Whether the sun is up at `now`.
+    pub fn is_up(&self, now: DateTime<Utc>) -> bool {
+        match self {
+            Self::Rises { sunrise, sunset } => *sunrise <= now && now < *sunset,
+            Self::AlwaysUp => true,
+            Self::AlwaysDown => false,
+        }
+    }
+}
+
+fn from_julian(jd: f64) -> DateTime<Utc> {
+    let millis = ((jd - UNIX_EPOCH_JD) * 86_400_000.0).round() as i64;
+    Utc.timestamp_millis_opt(millis).single().unwrap_or_default()
+}
+
+fn to_julian(time: DateTime<Utc>) -> f64 {
+    time.timestamp_millis() as f64 / 86_400_000.0 + UNIX_EPOCH_JD
+}
+
+/// Moon phase at `now`: the fraction of the lunar cycle since new moon (0.0 to 1.0).
+pub fn moon_age(now: DateTime<Utc>) -> f64 {
+    ((to_julian(now) - NEW_MOON_JD) / SYNODIC_MONTH).rem_euclid(1.0)
+}
+
+/// Name of the phase at cycle fraction `age`, e.g. "Waxing Gibbous".
+pub fn moon_phase_name(age: f64) -> &'static str {
+    const NAMES: [&str; 8] = [
+        "New Moon",
+        "Waxing Crescent",
+        "First Quarter",
+        "Waxing Gibbous",
+        "Full Moon",
+        "Waning Gibbous",
+        "Last Quarter",
+        "Waning Crescent",
+    ];
+    NAMES[((age * 8.0).round() as usize) % 8]
+}
+
+/// Lit fraction of the moon's disc at cycle fraction `age`.
+pub fn moon_illumination(age: f64) -> f64 {
+    (1.0 - (2.0 * PI * age).cos()) / 2.0
+}
+
+/// Unknown until the astro collector first runs.
+const UNKNOWN: u8 = 0;
+const DAY: u8 = 1;
+const NIGHT: u8 = 2;
+
+static DAYTIME: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+/// Whether the sun was up at the astro collector's last run, `None` before it ran.
+pub fn daytime() -> Option<bool> {
+    match DAYTIME.load(Ordering::Relaxed) {
+        DAY => Some(true),
+        NIGHT => Some(false),
+        _ => None,
+    }
+}
+
+pub fn set_daytime(up: bool) {
+    DAYTIME.store(if up { DAY } else { NIGHT }, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sun_times_and_moon_phase() {
+        // London at midsummer: sunrise 03:43 UTC, sunset 20:21 UTC
+        let date = NaiveDate::from_ymd_opt(2026, 6, 21).unwrap();
+        let SunTimes::Rises { sunrise, sunset } = SunTimes::on(date, 51.5074, -0.1278) else {
+            panic!("the sun rises in London");
+        };
+        let expected_rise = Utc.with_ymd_and_hms(2026, 6, 21, 3, 43, 0).unwrap();
+        let expected_set = Utc.with_ymd_and_hms(2026, 6, 21, 20, 21, 0).unwrap();
+        assert!((sunrise - expected_rise).num_minutes().abs() <= 3, "sunrise {}", sunrise);
+        assert!((sunset - expected_set).num_minutes().abs() <= 3, "sunset {}", sunset);
+        assert!(SunTimes::on(date, 51.5074, -0.1278).is_up(Utc.with_ymd_and_hms(2026, 6, 21, 12, 0, 0).unwrap()));
+        assert!(!SunTimes::on(date, 51.5074, -0.1278).is_up(Utc.with_ymd_and_hms(2026, 6, 21, 23, 0, 0).unwrap()));
+
+        // Tromsø has midnight sun in June and polar night in December
+        assert_eq!(SunTimes::on(date, 69.65, 18.96), SunTimes::AlwaysUp);
+        assert_eq!(SunTimes::on(NaiveDate::from_ymd_opt(2026, 12, 21).unwrap(), 69.65, 18.96), SunTimes::AlwaysDown);
+
+        // Full moon on 2026-03-03, new moon on 2026-03-19
+        let full = moon_age(Utc.with_ymd_and_hms(2026, 3, 3, 11, 38, 0).unwrap());
+        assert_eq!(moon_phase_name(full), "Full Moon");
+        assert!(moon_illumination(full) > 0.97);
+        let new = moon_age(Utc.with_ymd_and_hms(2026, 3, 19, 1, 23, 0).unwrap());
+        assert_eq!(moon_phase_name(new), "New Moon");
+        assert!(moon_illumination(new) < 0.03);
+        assert_eq!(moon_phase_name(0.4), "Waxing Gibbous");
+    }
+}
@@ -0,0 +1,290 @@
+//! Audio-reactive rain (`cosmetics.audio_reactive`).
+//! A capture thread reads the default input device (under PipeWire or PulseAudio that is
+//! the selected source; pick a "Monitor of ..." source to follow what is playing), runs an
+//! FFT over each block and publishes per-band levels that `RainManager` reads every frame.
+//! Only the capture needs the `audio` feature; the band math is plain Rust.
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "audio")]
+use {
+    anyhow::{bail, Context, Result},
+    cpal::traits::{DeviceTrait, HostTrait, StreamTrait},
+    rustfft::{num_complex::Complex, Fft, FftPlanner},
+    std::sync::atomic::{AtomicBool, Ordering},
+    std::sync::Arc,
+    std::thread,
+};
+
+/// Frequency bands, spread across the screen from bass (left) to treble (right).
+pub const BANDS: usize = 8;
+
+/// Samples per FFT window; consecutive windows overlap by half.
+pub const FFT_SIZE: usize = 1024;
+
+const MIN_HZ: f64 = 40.0;
+const MAX_HZ: f64 = 16_000.0;
+
+/// Levels at or below this (relative to a full-scale sine) count as silence.
+const FLOOR_DB: f64 = -60.0;
+
+/// Share of a band's level kept per block once the sound drops, so beats fade out
+/// over a few frames instead of flickering.
+const DECAY: f64 = 0.85;
+
+/// Levels older than this read as silence (capture stopped or the device went away).
+const STALE_AFTER: Duration = Duration::from_millis(500);
+
+/// Wait before reopening the device after a failure, doubling up to `RETRY_MAX`.
+#[cfg(feature = "audio")]
+const RETRY_MIN: Duration = Duration::from_secs(2);
+#[cfg(feature = "audio")]
+const RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// Latest band levels (0.0-1.0) published by the capture thread.
+#[derive(Debug, Default)]
+pub struct AudioSpectrum {
+    levels: [f64; BANDS],
+    updated: Option<Instant>,
+}
+
+impl AudioSpectrum {
+    /// Process-wide instance written by the capture thread and read by the renderers.
+    pub fn shared() -> &'static Mutex<AudioSpectrum> {
+        static SHARED: OnceLock<Mutex<AudioSpectrum>> = OnceLock::new();
+        SHARED.get_or_init(|| Mutex::new(AudioSpectrum::default()))
+    }
+
+    /// Folds in one block's levels: a band jumps up at once and falls off gradually.
+    pub fn push(&mut self, bands: [f64; BANDS], now: Instant) {
+        for (level, new) in self.levels.iter_mut().zip(bands) {
+            *level = new.max(*level * DECAY);
+        }
+        self.updated = Some(now);
+    }
+
+    /// Current levels, all zero when nothing was captured recently.
+    pub fn levels(&self, now: Instant) -> [f64; BANDS] {
+        match self.updated {
+            Some(at) if now.saturating_duration_since(at) <= STALE_AFTER => self.levels,
+            _ => [0.0; BANDS],
+        }
+    }
+}
+
+/// Lower edge of band `i` in Hz. Bands are spaced logarithmically so each one covers a
+/// similar musical range.
+fn band_edge(i: usize) -> f64 {
+    MIN_HZ * (MAX_HZ / MIN_HZ).powf(i as f64 / BANDS as f64)
+}
+
+/// Per-band levels (0.0-1.0) from the magnitudes of the first `FFT_SIZE / 2` bins of a
+/// Hann-windowed FFT. Each band takes its loudest bin.
+pub fn band_levels(magnitudes: &[f64], sample_rate: u32) -> [f64; BANDS] {
+    let bin_hz = sample_rate as f64 / (magnitudes.len() * 2) as f64;
+    // A full-scale sine peaks at N/4 after the Hann window
+    let full_scale = magnitudes.len() as f64 / 2.0;
+    let mut levels = [0.0; BANDS];
+    for (i, level) in levels.iter_mut().enumerate() {
+        let lo = ((band_edge(i) / bin_hz).floor() as usize).min(magnitudes.len());
+        let hi = ((band_edge(i + 1) / bin_hz).ceil() as usize).clamp(lo, magnitudes.len());
+        let peak = magnitudes[lo..hi].iter().copied().fold(0.0, f64::max);
+        if peak > 0.0 {
+            let db = 20.0 * (peak / full_scale).log10();
+            *level = ((db - FLOOR_DB) / -FLOOR_DB).clamp(0.0, 1.0);
+        }
+    }
+    levels
+}
+
+/// Band that drives the rain column at `x` on a surface `width` pixels wide.
+pub fn band_for_column(x: f64, width: i32) -> usize {
+    if width <= 0 {
+        return 0;
+    }
+    ((x / width as f64 * BANDS as f64).max(0.0) as usize).min(BANDS - 1)
+}
+
+/// Starts the capture thread unless it is already running. `device` picks the first input
+/// whose name contains it; `None` uses the default input. When the device can't be opened
+/// or its stream fails (unplugged, sound server restarted), the thread retries with backoff.
+#[cfg(feature = "audio")]
+pub fn spawn_capture(device: Option<String>) -> Result<()> {
+    static RUNNING: AtomicBool = AtomicBool::new(false);
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let spawned = thread::Builder::new().name("audio-capture".into()).spawn(move || {
+        let mut backoff = RETRY_MIN;
+        loop {
+            let started = Instant::now();
+            let Err(e) = run_capture(device.as_deref());
+            // A stream that ran for a while starts over with a short wait
+            if started.elapsed() > RETRY_MAX {
+                backoff = RETRY_MIN;
+            }
+            log::warn!("Audio capture stopped: {:#}; retrying in {}s", e, backoff.as_secs());
+            thread::sleep(backoff);
+            backoff = (backoff * 2).min(RETRY_MAX);
+        }
+    });
+    if let Err(e) = spawned {
+        RUNNING.store(false, Ordering::SeqCst);
+        return Err(e).context("Failed to spawn audio capture thread");
+    }
+    Ok(())
+}
+
+/// Captures from the device until its stream reports an error; only returns on failure.
+#[cfg(feature = "audio")]
+fn run_capture(device_name: Option<&str>) -> Result<std::convert::Infallible> {
+    let host = cpal::default_host();
+    let device = match device_name {
+        Some(name) => host
+            .input_devices()?
+            .find(|d| d.name().is_ok_and(|n| n.contains(name)))
+            .with_context(|| format!("No audio input device matching {:?}", name))?,
+        None => host.default_input_device().context("No default audio input device")?,
+    };
+    let supported = device.default_input_config()?;
+    let sample_rate = supported.sample_rate().0;
+    let channels = supported.channels().max(1) as usize;
+    log::info!(
+        "Audio capture from {:?} ({} Hz, {} channels)",
+        device.name().unwrap_or_default(),
+        sample_rate,
+        channels
+    );
+
+    let mut analyzer = Analyzer::new(sample_rate);
+    let failed = Arc::new(AtomicBool::new(false));
+    let on_error = {
+        let failed = failed.clone();
+        let capture = thread::current();
+        move |e| {
+            log::warn!("Audio stream error: {}", e);
+            failed.store(true, Ordering::SeqCst);
+            capture.unpark();
+        }
+    };
+    let config = supported.config();
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &config,
+            move |data: &[f32], _: &_| analyzer.feed(data, channels, |s| s),
+            on_error,
+            None,
+        )?,
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &config,
+            move |data: &[i16], _: &_| analyzer.feed(data, channels, |s| s as f32 / 32768.0),
+            on_error,
+            None,
+        )?,
+        cpal::SampleFormat::U16 => device.build_input_stream(
+            &config,
+            move |data: &[u16], _: &_| analyzer.feed(data, channels, |s| (s as f32 - 32768.0) / 32768.0),
+            on_error,
+            None,
+        )?,
+        other => bail!("Unsupported sample format {:?}", other),
+    };
+    stream.play()?;
+
+    // The stream runs on cpal's own thread for as long as it is alive
+    while !failed.load(Ordering::SeqCst) {
+        thread::park();
+    }
+    drop(stream);
+    bail!("Audio stream failed")
+}
+
+/// Collects mono samples into overlapping windows and publishes the band levels of each.
+#[cfg(feature = "audio")]
+struct Analyzer {
+    sample_rate: u32,
+    fft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    samples: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    magnitudes: Vec<f64>,
+}
+
+#[cfg(feature = "audio")]
+impl Analyzer {
+    fn new(sample_rate: u32) -> Self {
+        let window = (0..FFT_SIZE)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FFT_SIZE as f32).cos())
+            .collect();
+        Self {
+            sample_rate,
+            fft: FftPlanner::new().plan_fft_forward(FFT_SIZE),
+            window,
+            samples: Vec::with_capacity(FFT_SIZE * 2),
+            spectrum: vec![Complex::default(); FFT_SIZE],
+            magnitudes: vec![0.0; FFT_SIZE / 2],
+        }
+    }
+
+    /// Mixes interleaved frames down to mono and analyzes every full window.
+    fn feed<T: Copy>(&mut self, data: &[T], channels: usize, to_f32: impl Fn(T) -> f32) {
+        for frame in data.chunks(channels) {
+            let sum: f32 = frame.iter().map(|&s| to_f32(s)).sum();
+            self.samples.push(sum / frame.len() as f32);
+        }
+        while self.samples.len() >= FFT_SIZE {
+            self.analyze();
+            self.samples.drain(..FFT_SIZE / 2);
+        }
+    }
+
+    fn analyze(&mut self) {
+        for ((bin, sample), weight) in self.spectrum.iter_mut().zip(&self.samples).zip(&self.window) {
+            *bin = Complex::new(sample * weight, 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+        for (magnitude, bin) in self.magnitudes.iter_mut().zip(&self.spectrum) {
+            *magnitude = bin.norm() as f64;
+        }
+        let levels = band_levels(&self.magnitudes, self.sample_rate);
+        if let Ok(mut shared) = AudioSpectrum::shared().lock() {
+            shared.push(levels, Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_band_levels() {
+        // A full-scale 1 kHz tone at 48 kHz lands in one band at full level
+        let mut magnitudes = vec![0.0; FFT_SIZE / 2];
+        let bin = (1000.0 / (48_000.0 / FFT_SIZE as f64)).round() as usize;
+        magnitudes[bin] = FFT_SIZE as f64 / 4.0;
+        let levels = band_levels(&magnitudes, 48_000);
+        let loud: Vec<usize> = (0..BANDS).filter(|&i| levels[i] > 0.0).collect();
+        assert_eq!(loud.len(), 1);
+        assert!((levels[loud[0]] - 1.0).abs() < 1e-9);
+        assert!(band_edge(loud[0]) <= 1000.0 && band_edge(loud[0] + 1) > 1000.0);
+
+        // -30 dB is halfway to the floor
+        magnitudes[bin] = FFT_SIZE as f64 / 4.0 * 10f64.powf(-30.0 / 20.0);
+        assert!((band_levels(&magnitudes, 48_000)[loud[0]] - 0.5).abs() < 1e-9);
+
+        assert_eq!(band_for_column(0.0, 1920), 0);
+        assert_eq!(band_for_column(1919.0, 1920), BANDS - 1);
+        assert_eq!(band_for_column(5000.0, 1920), BANDS - 1);
+
+        // Levels jump up, decay, and read as silence once capture stops
+        let start = Instant::now();
+        let mut spectrum = AudioSpectrum::default();
+        assert_eq!(spectrum.levels(start), [0.0; BANDS]);
+        spectrum.push([1.0; BANDS], start);
+        spectrum.push([0.0; BANDS], start);
+        assert_eq!(spectrum.levels(start)[0], DECAY);
+        assert_eq!(spectrum.levels(start + Duration::from_secs(1)), [0.0; BANDS]);
+    }
+}
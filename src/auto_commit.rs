@@ -0,0 +1,110 @@
+//! Auto-commit schedule status.
+//! The productivity thread records when the next check is due and how each repo
+//! fared in the last cycle; the `auto_commit_*` metrics and the tray's
+//! "Auto-Commit" submenu read it back through `shared()`.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Local};
+
+/// What the last cycle did with one repo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoOutcome {
+    Committed,
+    NoChanges,
+    BelowThreshold { lines: usize, threshold: u64 },
+    Skipped(String),
+    Failed(String),
+}
+
+impl fmt::Display for RepoOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Committed => write!(f, "committed"),
+            Self::NoChanges => write!(f, "no changes"),
+            Self::BelowThreshold { lines, threshold } => write!(f, "{}/{} lines", lines, threshold),
+            Self::Skipped(reason) => write!(f, "skipped: {}", reason),
+            Self::Failed(error) => write!(f, "failed: {}", error),
+        }
+    }
+}
+
+/// Schedule and last-cycle results of the auto-commit job.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScheduleStatus {
+    pub next_run: Option<DateTime<Local>>,
+    pub last_run: Option<DateTime<Local>>,
+    /// Outcome per configured repo path, in config order.
+    pub repos: Vec<(String, RepoOutcome)>,
+}
+
+impl ScheduleStatus {
+    /// Process-wide instance written by the productivity thread.
+    pub fn shared() -> &'static Mutex<ScheduleStatus> {
+        static SHARED: OnceLock<Mutex<ScheduleStatus>> = OnceLock::new();
+        SHARED.get_or_init(|| Mutex::new(ScheduleStatus::default()))
+    }
+
+    /// Short result of the last cycle ("1 committed, 2 skipped"), `None` before the first one.
+    pub fn last_result(&self) -> Option<String> {
+        self.last_run?;
+        let count = |pred: fn(&RepoOutcome) -> bool| self.repos.iter().filter(|(_, o)| pred(o)).count();
+        let parts: Vec<String> = [
+            (count(|o| matches!(o, RepoOutcome::Committed)), "committed"),
+            (count(|o| matches!(o, RepoOutcome::NoChanges | RepoOutcome::BelowThreshold { .. })), "unchanged"),
+            (count(|o| matches!(o, RepoOutcome::Skipped(_))), "skipped"),
+            (count(|o| matches!(o, RepoOutcome::Failed(_))), "failed"),
+        ]
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, what)| format!("{} {}", n, what))
+        .collect();
+        Some(if parts.is_empty() { "no repos".to_string() } else { parts.join(", ") })
+    }
+
+    /// Lines for the tray submenu.
+    pub fn menu_lines(&self) -> Vec<String> {
+        let mut lines = vec![match self.next_run {
+            Some(next) => format!("Next check: {}", next.format("%H:%M")),
+            None => "Next check: not scheduled".to_string(),
+        }];
+        match (self.last_run, self.last_result()) {
+            (Some(last), Some(result)) => lines.push(format!("Last run: {} ({})", last.format("%H:%M"), result)),
+            _ => lines.push("Last run: not yet".to_string()),
+        }
+        for (repo, outcome) in &self.repos {
+            lines.push(format!("{}: {}", repo_name(repo), outcome));
+        }
+        lines
+    }
+}
+
+/// Directory name of a repo path, as used in `auto_commit_<repo>` metric IDs.
+pub fn repo_name(repo: &str) -> String {
+    Path::new(repo).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| repo.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_status_summary() {
+        let mut status = ScheduleStatus { next_run: Some(Local.with_ymd_and_hms(2026, 3, 10, 15, 5, 0).unwrap()), ..Default::default() };
+        assert_eq!(status.last_result(), None);
+        assert_eq!(status.menu_lines(), vec!["Next check: 15:05", "Last run: not yet"]);
+
+        status.last_run = Some(Local.with_ymd_and_hms(2026, 3, 10, 14, 5, 0).unwrap());
+        status.repos = vec![
+            ("/home/me/src/overlay".to_string(), RepoOutcome::Committed),
+            ("/home/me/src/notes".to_string(), RepoOutcome::BelowThreshold { lines: 12, threshold: 1000 }),
+            ("/home/me/src/site".to_string(), RepoOutcome::Skipped("a rebase is in progress".to_string())),
+        ];
+        assert_eq!(status.last_result().unwrap(), "1 committed, 1 unchanged, 1 skipped");
+        assert_eq!(status.menu_lines()[1], "Last run: 14:05 (1 committed, 1 unchanged, 1 skipped)");
+        assert_eq!(status.menu_lines()[3], "notes: 12/1000 lines");
+    }
+}
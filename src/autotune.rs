@@ -0,0 +1,180 @@
+//! Adaptive density (`auto_tune.enabled`).
+//! Every few seconds the overlay thread measures its own CPU time and the time spent
+//! drawing frames. While either is over budget the tuner first thins the rain
+//! (`cosmetics.realism_scale`) and then stretches the frame interval (`general.update_ms`);
+//! once usage is well below the budget it steps back toward the configured values.
+//! Only the running copy of the config changes; the file is never rewritten.
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use crate::config::{AutoTune, Config};
+
+/// How often the tuner measures and adjusts.
+pub const SAMPLE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Drawing may take at most this share of the frame interval.
+const MAX_FRAME_SHARE: f64 = 0.5;
+
+/// Settings are only restored once usage is below this share of its limit, so the
+/// tuner doesn't flip back and forth around the budget.
+const RESTORE_RATIO: f64 = 0.6;
+
+/// Clock ticks per second in `/proc/self/stat` (USER_HZ, 100 on Linux).
+const USER_HZ: f64 = 100.0;
+
+#[derive(Debug)]
+pub struct DensityTuner {
+    limits: AutoTune,
+    base_realism: u32,
+    base_interval_ms: u64,
+    realism: u32,
+    interval_ms: u64,
+    window_start: Instant,
+    cpu_at_start: Option<f64>,
+    frame_time: Duration,
+    frames: u32,
+    last_cpu: Option<f64>,
+}
+
+impl DensityTuner {
+    /// Starts from the configured density and interval.
+    pub fn new(config: &Config) -> Self {
+        Self {
+            limits: config.auto_tune.clone(),
+            base_realism: config.cosmetics.realism_scale,
+            base_interval_ms: config.general.update_ms,
+            realism: config.cosmetics.realism_scale,
+            interval_ms: config.general.update_ms,
+            window_start: Instant::now(),
+            cpu_at_start: read_self_cpu_secs(),
+            frame_time: Duration::ZERO,
+            frames: 0,
+            last_cpu: None,
+        }
+    }
+
+    /// Adds the time taken to draw one frame on every monitor.
+    pub fn record_frame(&mut self, took: Duration) {
+        self.frame_time += took;
+        self.frames += 1;
+    }
+
+    /// Measures once per `SAMPLE_PERIOD` and returns the new `(realism_scale, update_ms)`
+    /// if this step changed them.
+    pub fn tick(&mut self, now: Instant) -> Option<(u32, u64)> {
+        let elapsed = now.duration_since(self.window_start);
+        if elapsed < SAMPLE_PERIOD {
+            return None;
+        }
+        let cpu_now = read_self_cpu_secs();
+        let cpu_percent = match (self.cpu_at_start, cpu_now) {
+            (Some(start), Some(end)) => (end - start) / elapsed.as_secs_f64() * 100.0,
+            _ => 0.0,
+        };
+        let frame_time = (self.frames > 0).then(|| self.frame_time / self.frames);
+        self.window_start = now;
+        self.cpu_at_start = cpu_now;
+        self.frame_time = Duration::ZERO;
+        self.frames = 0;
+        self.adjust(cpu_percent, frame_time)
+    }
+
+    /// One control step from the measured CPU usage (% of one core) and average frame time.
+    pub fn adjust(&mut self, cpu_percent: f64, frame_time: Option<Duration>) -> Option<(u32, u64)> {
+        self.last_cpu = Some(cpu_percent);
+        let frame_share = frame_time.map_or(0.0, |t| t.as_secs_f64() * 1000.0 / self.interval_ms.max(1) as f64);
+        let budget = self.limits.cpu_budget;
+        let before = (self.realism, self.interval_ms);
+
+        if cpu_percent > budget || frame_share > MAX_FRAME_SHARE {
+            if self.realism > self.limits.min_realism {
+                self.realism -= 1;
+            } else {
+                let ceiling = self.limits.max_interval_ms.max(self.base_interval_ms);
+                self.interval_ms = (self.interval_ms * 5 / 4).min(ceiling);
+            }
+        } else if cpu_percent < budget * RESTORE_RATIO && frame_share < MAX_FRAME_SHARE * RESTORE_RATIO {
+            if self.interval_ms > self.base_interval_ms {
+                self.interval_ms = (self.interval_ms * 4 / 5).max(self.base_interval_ms);
+            } else if self.realism < self.base_realism {
+                self.realism += 1;
+            }
+        }
+
+        let after = (self.realism, self.interval_ms);
+        (after != before).then_some(after)
+    }
+
+    /// Current `(realism_scale, update_ms)`.
+    pub fn current(&self) -> (u32, u64) {
+        (self.realism, self.interval_ms)
+    }
+
+    /// CPU usage measured at the last step, in percent of one core.
+    pub fn last_cpu(&self) -> Option<f64> {
+        self.last_cpu
+    }
+
+    pub fn cpu_budget(&self) -> f64 {
+        self.limits.cpu_budget
+    }
+}
+
+/// CPU time (user + system) the overlay process has used so far, in seconds.
+fn read_self_cpu_secs() -> Option<f64> {
+    parse_cpu_ticks(&fs::read_to_string("/proc/self/stat").ok()?).map(|ticks| ticks as f64 / USER_HZ)
+}
+
+/// utime + stime from a `/proc/<pid>/stat` line. The command name may contain spaces,
+/// so fields are counted from its closing parenthesis.
+fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+    let fields: Vec<&str> = stat.get(stat.rfind(')')? + 1..)?.split_whitespace().collect();
+    // fields[0] is the state (field 3); utime and stime are fields 14 and 15
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tuner_lowers_density_then_frame_rate() {
+        assert_eq!(
+            parse_cpu_ticks("4242 (matrix overlay) S 1 4242 4242 0 -1 4194560 812 0 0 0 150 37 0 0 20 0 9 0"),
+            Some(187)
+        );
+
+        let mut config = Config::default();
+        config.cosmetics.realism_scale = 4;
+        config.general.update_ms = 1000;
+        config.auto_tune.cpu_budget = 5.0;
+        config.auto_tune.min_realism = 2;
+        config.auto_tune.max_interval_ms = 1500;
+        let mut tuner = DensityTuner::new(&config);
+
+        // Over budget: density goes down first, then the interval grows up to the cap
+        assert_eq!(tuner.adjust(9.0, None), Some((3, 1000)));
+        assert_eq!(tuner.adjust(9.0, None), Some((2, 1000)));
+        assert_eq!(tuner.adjust(9.0, None), Some((2, 1250)));
+        assert_eq!(tuner.adjust(9.0, None), Some((2, 1500)));
+        assert_eq!(tuner.adjust(9.0, None), None);
+
+        // Slow frames count as over budget even with low CPU
+        let mut slow = DensityTuner::new(&config);
+        assert_eq!(slow.adjust(1.0, Some(Duration::from_millis(700))), Some((3, 1000)));
+
+        // Between the restore line and the budget nothing changes
+        assert_eq!(tuner.adjust(4.0, None), None);
+
+        // Well under budget: the interval comes back first, then density
+        assert_eq!(tuner.adjust(1.0, None), Some((2, 1200)));
+        assert_eq!(tuner.adjust(1.0, None), Some((2, 1000)));
+        assert_eq!(tuner.adjust(1.0, None), Some((3, 1000)));
+        assert_eq!(tuner.adjust(1.0, None), Some((4, 1000)));
+        assert_eq!(tuner.adjust(1.0, None), None);
+        assert_eq!(tuner.last_cpu(), Some(1.0));
+    }
+}
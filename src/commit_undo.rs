@@ -0,0 +1,110 @@
+//! Safety net for auto-commits.
+//! Before an auto-commit stages everything, the current HEAD and the staged tree are saved
+//! under `refs/matrix-overlay/`; `matrix-overlay undo-commit <repo>` puts both back,
+//! leaving the working tree untouched, as if the auto-commit never happened.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use git2::{Oid, Repository, ResetType};
+
+/// HEAD before the auto-commit.
+const PARENT_REF: &str = "refs/matrix-overlay/undo/parent";
+/// Tree of the index before everything was staged.
+const INDEX_REF: &str = "refs/matrix-overlay/undo/index";
+/// The auto-commit itself; undo refuses to run once HEAD has moved past it.
+const COMMIT_REF: &str = "refs/matrix-overlay/undo/commit";
+
+/// Saves HEAD and the index; call before staging.
+pub fn save_state(repo: &Repository) -> Result<()> {
+    let head = repo.head()?.peel_to_commit()?.id();
+    let index_tree = repo.index()?.write_tree()?;
+    repo.reference(PARENT_REF, head, true, "matrix-overlay: before auto-commit")?;
+    repo.reference(INDEX_REF, index_tree, true, "matrix-overlay: index before auto-commit")?;
+    // A stale commit ref from an earlier run must not be undoable against the new parent
+    if let Ok(mut stale) = repo.find_reference(COMMIT_REF) {
+        stale.delete()?;
+    }
+    Ok(())
+}
+
+/// Marks `commit` as the auto-commit that `undo` reverts.
+pub fn record_commit(repo: &Repository, commit: Oid) -> Result<()> {
+    repo.reference(COMMIT_REF, commit, true, "matrix-overlay: auto-commit")?;
+    Ok(())
+}
+
+/// Reverts the last auto-commit in `repo`: HEAD goes back to its parent and the index
+/// to what was staged before. Returns the commit that was undone.
+pub fn undo(repo: &Repository) -> Result<Oid> {
+    let target = |name: &str| -> Result<Oid> {
+        repo.find_reference(name).ok().and_then(|r| r.target()).context("No auto-commit to undo in this repository")
+    };
+    let (commit, parent, index_tree) = (target(COMMIT_REF)?, target(PARENT_REF)?, target(INDEX_REF)?);
+
+    let head = repo.head()?.peel_to_commit()?.id();
+    if head != commit {
+        bail!("HEAD has moved since the auto-commit {} (now at {}); undo it manually", commit, head);
+    }
+
+    repo.reset(repo.find_commit(parent)?.as_object(), ResetType::Soft, None)?;
+    let mut index = repo.index()?;
+    index.read_tree(&repo.find_tree(index_tree)?)?;
+    index.write()?;
+
+    for name in [COMMIT_REF, PARENT_REF, INDEX_REF] {
+        repo.find_reference(name)?.delete()?;
+    }
+    Ok(commit)
+}
+
+/// Entry point for `matrix-overlay undo-commit <repo>`.
+pub fn run_undo(args: &[String]) -> Result<()> {
+    let path = match args {
+        [path] => path,
+        _ => bail!("Usage: matrix-overlay undo-commit <repo>"),
+    };
+    let repo = Repository::open(Path::new(path)).with_context(|| format!("Could not open repo at {}", path))?;
+    let commit = undo(&repo)?;
+    println!("Undid auto-commit {} in {}; the changes are back in the working tree.", commit, path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_undo_restores_head_and_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let first = repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[]).unwrap();
+
+        // One file staged by hand, one left untracked
+        fs::write(dir.path().join("staged.txt"), "a").unwrap();
+        fs::write(dir.path().join("untracked.txt"), "b").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+
+        assert!(undo(&repo).is_err(), "nothing to undo yet");
+        save_state(&repo).unwrap();
+        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.find_commit(first).unwrap();
+        let auto = repo.commit(Some("HEAD"), &sig, &sig, "Auto-commit", &tree, &[&parent]).unwrap();
+        record_commit(&repo, auto).unwrap();
+
+        assert_eq!(undo(&repo).unwrap(), auto);
+        assert_eq!(repo.head().unwrap().target(), Some(first));
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("staged.txt"), 0).is_some());
+        assert!(index.get_path(Path::new("untracked.txt"), 0).is_none());
+        assert!(dir.path().join("untracked.txt").exists());
+        assert!(undo(&repo).is_err(), "undo is one-shot");
+    }
+}
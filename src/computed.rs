@@ -0,0 +1,282 @@
+//! Derived metrics.
+//! Parses and evaluates the `computed_metrics` expressions from config.json
+//! (e.g. `ram_total - ram_used`) against the values collected in a frame.
+
+use std::collections::HashMap;
+use anyhow::{bail, Result};
+
+use crate::config::ComputedMetric;
+use crate::metrics::{MetricId, MetricValue};
+
+/// Arithmetic operator supported in expressions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// Parsed expression tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Numeric literal.
+    Num(f64),
+    /// Reference to another metric (e.g. `ram_used` or `network_details.rx`).
+    Var(String),
+    /// Unary negation.
+    Neg(Box<Expr>),
+    /// Binary operation.
+    Bin(Box<Expr>, Op, Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluates the expression, resolving variables via `lookup`.
+    /// Returns `None` if any referenced metric is missing or the result is not finite.
+    pub fn eval(&self, lookup: &dyn Fn(&str) -> Option<f64>) -> Option<f64> {
+        let value = match self {
+            Expr::Num(v) => *v,
+            Expr::Var(name) => lookup(name)?,
+            Expr::Neg(inner) => -inner.eval(lookup)?,
+            Expr::Bin(lhs, op, rhs) => {
+                let a = lhs.eval(lookup)?;
+                let b = rhs.eval(lookup)?;
+                match op {
+                    Op::Add => a + b,
+                    Op::Sub => a - b,
+                    Op::Mul => a * b,
+                    Op::Div => a / b,
+                }
+            }
+        };
+        if value.is_finite() { Some(value) } else { None }
+    }
+
+    /// Collects the metric names referenced by this expression.
+    pub fn variables(&self, out: &mut Vec<String>) {
+        match self {
+            Expr::Num(_) => {}
+            Expr::Var(name) => out.push(name.clone()),
+            Expr::Neg(inner) => inner.variables(out),
+            Expr::Bin(lhs, _, rhs) => {
+                lhs.variables(out);
+                rhs.variables(out);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = src.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            match text.parse::<f64>() {
+                Ok(v) => tokens.push(Token::Num(v)),
+                Err(_) => bail!("Invalid number '{}'", text),
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if "+-*/".contains(c) {
+            tokens.push(Token::Op(c));
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::LParen);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::RParen);
+            i += 1;
+        } else {
+            bail!("Unexpected character '{}' in expression", c);
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser: expr := term (('+'|'-') term)*, term := factor (('*'|'/') factor)*.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut lhs = self.term()?;
+        while let Some(Token::Op(c)) = self.peek() {
+            let op = match c {
+                '+' => Op::Add,
+                '-' => Op::Sub,
+                _ => break,
+            };
+            self.pos += 1;
+            let rhs = self.term()?;
+            lhs = Expr::Bin(Box::new(lhs), op, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr> {
+        let mut lhs = self.factor()?;
+        while let Some(Token::Op(c)) = self.peek() {
+            let op = match c {
+                '*' => Op::Mul,
+                '/' => Op::Div,
+                _ => break,
+            };
+            self.pos += 1;
+            let rhs = self.factor()?;
+            lhs = Expr::Bin(Box::new(lhs), op, Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn factor(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Num(v)) => Ok(Expr::Num(v)),
+            Some(Token::Ident(name)) => Ok(Expr::Var(name)),
+            Some(Token::Op('-')) => Ok(Expr::Neg(Box::new(self.factor()?))),
+            Some(Token::LParen) => {
+                let inner = self.expr()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => bail!("Missing closing parenthesis"),
+                }
+            }
+            Some(tok) => bail!("Unexpected token {:?}", tok),
+            None => bail!("Unexpected end of expression"),
+        }
+    }
+}
+
+/// Parses an expression such as `ram_total - ram_used` or `(a + b) / 2`.
+pub fn parse(src: &str) -> Result<Expr> {
+    let mut parser = Parser { tokens: tokenize(src)?, pos: 0 };
+    let expr = parser.expr()?;
+    if parser.pos < parser.tokens.len() {
+        bail!("Trailing input in expression '{}'", src);
+    }
+    Ok(expr)
+}
+
+/// Resolves a variable name against collected values.
+///
+/// Plain names map to `MetricId`s; `<id>.rx` / `<id>.tx` select one direction
+/// of a network-style map (e.g. `network_details.rx`).
+fn lookup(values: &HashMap<MetricId, MetricValue>, name: &str) -> Option<f64> {
+    let (base, field) = match name.split_once('.') {
+        Some((b, f)) => (b, Some(f)),
+        None => (name, None),
+    };
+    let value = values.get(&MetricId::from_str(base)?)?;
+    match (value, field) {
+        (MetricValue::NetworkMap(map), Some("rx")) => Some(map.values().map(|(rx, _)| rx).sum()),
+        (MetricValue::NetworkMap(map), Some("tx")) => Some(map.values().map(|(_, tx)| tx).sum()),
+        (_, Some(_)) => None,
+        (v, None) => v.as_f64(),
+    }
+}
+
+/// Evaluates all computed metrics in order and inserts the results into `values`.
+///
+/// Later entries may reference earlier computed metrics. Invalid expressions
+/// are rejected by `Config::validate`, so parse failures here are only logged.
+pub fn apply(computed: &[ComputedMetric], values: &mut HashMap<MetricId, MetricValue>) {
+    for metric in computed {
+        let expr = match parse(&metric.expression) {
+            Ok(e) => e,
+            Err(e) => {
+                log::warn!("Computed metric '{}' has invalid expression: {}", metric.metric_id, e);
+                continue;
+            }
+        };
+        let result = expr.eval(&|name| lookup(values, name));
+        let value = match result {
+            Some(v) => MetricValue::String(format!("{:.*}{}", metric.precision, v, metric.unit)),
+            None => MetricValue::None,
+        };
+        values.insert(MetricId::Custom(metric.metric_id.clone()), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_precedence() {
+        let expr = parse("1 + 2 * 3").unwrap();
+        assert_eq!(expr.eval(&|_| None), Some(7.0));
+        let expr = parse("(1 + 2) * -3").unwrap();
+        assert_eq!(expr.eval(&|_| None), Some(-9.0));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("ram_total -").is_err());
+        assert!(parse("(1 + 2").is_err());
+        assert!(parse("1 $ 2").is_err());
+    }
+
+    #[test]
+    fn test_apply_ram_free() {
+        let mut values = HashMap::new();
+        values.insert(MetricId::RamTotal, MetricValue::String("16.0 GB".to_string()));
+        values.insert(MetricId::RamUsed, MetricValue::String("6.5 GB".to_string()));
+        let computed = vec![ComputedMetric {
+            metric_id: "ram_free".to_string(),
+            expression: "ram_total - ram_used".to_string(),
+            unit: " GB".to_string(),
+            precision: 1,
+        }];
+        apply(&computed, &mut values);
+        assert_eq!(
+            values.get(&MetricId::Custom("ram_free".to_string())),
+            Some(&MetricValue::String("9.5 GB".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_apply_missing_input_yields_none() {
+        let mut values = HashMap::new();
+        let computed = vec![ComputedMetric {
+            metric_id: "net_total".to_string(),
+            expression: "network_details.rx + network_details.tx".to_string(),
+            unit: String::new(),
+            precision: 0,
+        }];
+        apply(&computed, &mut values);
+        assert_eq!(values.get(&MetricId::Custom("net_total".to_string())), Some(&MetricValue::None));
+    }
+}
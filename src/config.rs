@@ -1,13 +1,15 @@
 //! Configuration management.
-//! Handles loading and parsing of config.json.
+//! Handles loading and parsing of config.json (or config.toml / config.yaml).
 
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct General {
     pub font_size: u32,
     #[serde(default = "default_metric_font_size")]
@@ -20,27 +22,270 @@ pub struct General {
     pub glow_passes: Vec<(f64, f64, f64)>,
     #[serde(default = "default_true")]
     pub show_monitor_label: bool,
+    /// Present frames through a server-side Pixmap + CopyArea (flicker-free).
+    #[serde(default = "default_true")]
+    pub pixmap_buffering: bool,
+    /// Rendering profile: "standard" or "low_power" (static high-contrast text,
+    /// no rain/glow/scrolling, 1-minute refresh; for always-on and e-ink panels).
+    #[serde(default = "default_profile")]
+    pub profile: String,
+    /// Theme used between sunset and sunrise at the weather location; `theme` is
+    /// then the daytime one. Unset keeps `theme` all day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub night_theme: Option<String>,
+    /// "metric" (°C, km/h, mm) or "imperial" (°F, mph, inches).
+    #[serde(default = "default_units")]
+    pub units: String,
+    /// Show network rates in bits per second (Mb/s) instead of bytes (MB/s).
+    #[serde(default)]
+    pub network_bits: bool,
+    /// Window layering strategy: "auto" (detected from the running window manager),
+    /// "gnome", "kde", "xfce" or "i3".
+    #[serde(default = "default_wm_profile")]
+    pub wm_profile: String,
 }
 
 fn default_metric_font_size() -> u32 { 14 }
 
+fn default_profile() -> String { "standard".to_string() }
+
+fn default_units() -> String { "metric".to_string() }
+
+fn default_wm_profile() -> String { "auto".to_string() }
+
+/// Minimum refresh interval under the low-power profile.
+pub const LOW_POWER_UPDATE_MS: u64 = 60_000;
+
 fn default_theme() -> String { "classic".to_string() }
 
+/// Per-screen render settings.
+///
+/// `x_offset`/`y_offset` are render-safe margins: nothing (metrics, header, rain)
+/// is drawn closer than this to the left/right and top/bottom monitor edges.
+/// `margins` overrides individual edges.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Screen {
     pub metrics: Vec<String>,
     pub x_offset: i32,
     pub y_offset: i32,
+    #[serde(default)]
+    pub margins: Margins,
+    /// Per-item display options, keyed by metric ID.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub item_options: HashMap<String, ItemOptions>,
+    /// Named weather location (from `weather.locations`) shown by this screen's weather metrics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather_location: Option<String>,
+    /// Areas of this monitor where rain never draws (e.g. over desktop icons).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rain_exclusions: Vec<RainExclusion>,
+    /// Background for this monitor, overriding `cosmetics.rain_mode`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rain_mode: Option<String>,
+    /// Number of side-by-side columns the metric list is split into; items pick theirs
+    /// with `item_options.<id>.column`.
+    #[serde(default = "default_columns", skip_serializing_if = "is_default_columns")]
+    pub columns: usize,
+    /// Pixels between list columns.
+    #[serde(default = "default_column_gap", skip_serializing_if = "is_default_column_gap")]
+    pub column_gap: i32,
+    /// Extra pixels between list rows.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub spacing: i32,
+}
+
+fn default_columns() -> usize { 1 }
+fn default_column_gap() -> i32 { 24 }
+fn is_default_columns(columns: &usize) -> bool { *columns == default_columns() }
+fn is_default_column_gap(gap: &i32) -> bool { *gap == default_column_gap() }
+
+/// Rectangle of a monitor kept free of rain, in monitor pixels. A missing `width` or
+/// `height` extends to the right or bottom edge, so `{ "height": 360 }` keeps the
+/// top 360px clear and `{ "x": 1600 }` everything right of x=1600.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RainExclusion {
+    #[serde(default)]
+    pub x: i32,
+    #[serde(default)]
+    pub y: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<i32>,
 }
 
+/// Display options for a single layout item.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ItemOptions {
+    /// "text" (default), "sparkline" (value plus a graph of recent samples)
+    /// or "bar" (value plus a horizontal gauge). The clock takes "digital" (same as
+    /// "text") or "analog" instead.
+    #[serde(default = "default_item_style")]
+    pub style: String,
+    /// Value that fills the bar gauge completely (percent metrics use 100).
+    #[serde(default = "default_bar_max")]
+    pub bar_max: f64,
+    /// Where to cut values that don't fit: "start", "middle" or "end".
+    /// Takes precedence over scrolling and shrink-to-fit when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ellipsize: Option<String>,
+    /// Template for the value text, e.g. "{value} ({label})" or "↓{rx} ↑{tx}".
+    /// Placeholders: {value}, {number}, {unit} and {label}; network metrics also have
+    /// {iface}, {rx} and {tx} (filled in per interface). Using {label} replaces the label.
+    /// For the digital clock this is a strftime format of the time of day instead
+    /// (default "%H:%M"; date and time zone specifiers are rejected).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Digits after the decimal point for the value's leading number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<usize>,
+    /// Replaces the unit after the value's leading number ("" drops it).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// Places the item freely instead of in the list: "top-left", "top-center",
+    /// "top-right", "middle-left", "center", "middle-right", "bottom-left",
+    /// "bottom-center" or "bottom-right" of the screen's margins. The side also sets the
+    /// alignment of the label and value within the item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+    /// Pixels the anchored item is moved right (negative: left).
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub x_offset: i32,
+    /// Pixels the anchored item is moved down (negative: up).
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub y_offset: i32,
+    /// Width of an anchored item (default a third of the screen).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<i32>,
+    /// List column of the item, from 0 (see `Screen::columns`).
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub column: usize,
+}
+
+fn is_zero<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+fn default_item_style() -> String { "text".to_string() }
+fn default_bar_max() -> f64 { 100.0 }
+
+/// Placeholders allowed in `ItemOptions::format`.
+const FORMAT_PLACEHOLDERS: [&str; 7] = ["value", "number", "unit", "label", "iface", "rx", "tx"];
+
+/// First `{name}` in a format template that is not a known placeholder.
+fn unknown_placeholder(template: &str) -> Option<&str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+        .find(|name| !FORMAT_PLACEHOLDERS.contains(name))
+}
+
+impl Default for ItemOptions {
+    fn default() -> Self {
+        Self {
+            style: default_item_style(),
+            bar_max: default_bar_max(),
+            ellipsize: None,
+            format: None,
+            precision: None,
+            unit: None,
+            anchor: None,
+            x_offset: 0,
+            y_offset: 0,
+            width: None,
+            column: 0,
+        }
+    }
+}
+
+/// Optional per-edge margin overrides in pixels.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Margins {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bottom: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left: Option<i32>,
+}
+
+impl Screen {
+    /// Resolved margins as (top, right, bottom, left), falling back to the offsets.
+    pub fn edge_margins(&self) -> (i32, i32, i32, i32) {
+        (
+            self.margins.top.unwrap_or(self.y_offset),
+            self.margins.right.unwrap_or(self.x_offset),
+            self.margins.bottom.unwrap_or(self.y_offset),
+            self.margins.left.unwrap_or(self.x_offset),
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Weather {
     pub lat: f64,
     pub lon: f64,
     pub enabled: bool,
+    /// Also fetch the daily forecast: `weather_high`, `weather_low`, `weather_rain_prob`
+    /// and `weather_forecast` (next three days).
+    #[serde(default)]
+    pub forecast: bool,
+    /// With lat/lon at 0.0, look up an approximate location from the public IP
+    /// (ipapi.co) at startup. Off by default since it sends the IP to a third party.
+    #[serde(default)]
+    pub geoip: bool,
+    /// Additional named locations, each fetched by its own collector.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<WeatherLocation>,
+}
+
+/// A named weather location (e.g. "office"), assigned to screens via `weather_location`
+/// or referenced directly as `weather_temp@office`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WeatherLocation {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Display name used in item labels (defaults to `name`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Weather {
+    /// Metric ID of a weather metric for a named location (`weather_temp@office`).
+    pub fn location_metric(metric: &str, location: &str) -> String {
+        format!("{}@{}", metric, location)
+    }
+
+    /// Maps a plain weather metric to the screen's assigned location, if any.
+    pub fn resolve_metric(&self, metric: &str, location: Option<&str>) -> String {
+        match location {
+            Some(loc) if metric.starts_with("weather_") && !metric.contains('@') => Self::location_metric(metric, loc),
+            _ => metric.to_string(),
+        }
+    }
+
+    /// Display label for a location name.
+    pub fn location_label(&self, name: &str) -> String {
+        self.locations
+            .iter()
+            .find(|l| l.name == name)
+            .and_then(|l| l.label.clone())
+            .unwrap_or_else(|| name.to_string())
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct CustomFile {
     pub name: String,      // Display label (e.g. "Server Log")
     pub path: String,      // Path to file (e.g. "/mnt/shared/status.txt")
@@ -49,10 +294,586 @@ pub struct CustomFile {
     pub tail: bool,        // If true, only display the last line of the file
 }
 
+/// A custom metric filled from a command's stdout.
+///
+/// `command` is the program and its arguments, run directly (no shell). A program given
+/// as a path must pass the same sandbox check as `custom_files`; bare names are looked
+/// up in `PATH`. Output is capped at 64KB.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CustomCommand {
+    /// ID to use in screen config (e.g. "backup_status").
+    pub metric_id: String,
+    pub command: Vec<String>,
+    #[serde(default = "default_command_interval")]
+    pub interval_secs: u64,
+    /// The command is killed after this long and the metric shows "TIMEOUT".
+    #[serde(default = "default_command_timeout")]
+    pub timeout_secs: u64,
+    /// If true, only display the last line of the output.
+    #[serde(default)]
+    pub tail: bool,
+}
+
+fn default_command_interval() -> u64 { 60 }
+fn default_command_timeout() -> u64 { 5 }
+
+/// A metric derived from other metrics via a simple arithmetic expression.
+///
+/// Evaluated in the metrics thread after collection, before values are published.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ComputedMetric {
+    /// ID to use in screen config (e.g. "ram_free").
+    pub metric_id: String,
+    /// Expression over other metric IDs using `+ - * /` and parentheses
+    /// (e.g. "ram_total - ram_used" or "network_details.rx + network_details.tx").
+    pub expression: String,
+    /// Suffix appended to the result (e.g. " GB").
+    #[serde(default)]
+    pub unit: String,
+    /// Number of decimal places to display.
+    #[serde(default = "default_precision")]
+    pub precision: usize,
+}
+
+fn default_precision() -> usize { 1 }
+
+/// Per-metric processing settings, keyed by metric ID in `metric_settings`.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct MetricSettings {
+    /// Exponential smoothing factor (0.0 - 1.0, lower is smoother). Off if unset.
+    #[serde(default)]
+    pub smoothing: Option<f64>,
+    /// Simple moving average over the last N samples. Off if unset.
+    #[serde(default)]
+    pub moving_average: Option<usize>,
+    /// Minimum change before a new value is shown (avoids flapping around a value).
+    /// Also used as the band a value must drop below a threshold before its level clears.
+    #[serde(default)]
+    pub hysteresis: f64,
+    /// Warning/critical levels for coloring and alerts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thresholds: Option<Thresholds>,
+    /// Display substitutions applied to the formatted value (first match wins).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub value_map: Vec<ValueMapping>,
+}
+
+/// Maps a raw value to display text, by exact value or numeric range.
+///
+/// e.g. `{"value": "1", "text": "ON"}` or `{"min": 30, "text": "🔥 {value}"}`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ValueMapping {
+    /// Exact (trimmed) value to match, e.g. "0" or "3".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Inclusive lower bound for the value's leading number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// Exclusive upper bound for the value's leading number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// Replacement text; `{value}` is substituted with the original value.
+    pub text: String,
+}
+
+impl ValueMapping {
+    fn matches(&self, raw: &str) -> bool {
+        if let Some(exact) = &self.value {
+            return raw.trim() == exact;
+        }
+        if self.min.is_none() && self.max.is_none() {
+            return false;
+        }
+        match crate::metrics::parse_leading_number(raw) {
+            Some(n) => self.min.is_none_or(|min| n >= min) && self.max.is_none_or(|max| n < max),
+            None => false,
+        }
+    }
+}
+
+/// Warn/crit levels for a metric (compared against its numeric value, e.g. 85 for "85°C").
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Thresholds {
+    #[serde(default)]
+    pub warn: Option<f64>,
+    #[serde(default)]
+    pub crit: Option<f64>,
+    #[serde(default = "default_warn_color")]
+    pub warn_color: String,
+    #[serde(default = "default_crit_color")]
+    pub crit_color: String,
+    /// Trigger when the value falls to or below the limits instead (e.g. battery, Wi-Fi signal).
+    #[serde(default)]
+    pub below: bool,
+    /// Send a desktop notification (notify-send) when the level rises.
+    #[serde(default)]
+    pub notify: bool,
+    /// Minimum time between notifications for the same metric.
+    #[serde(default = "default_alert_debounce")]
+    pub debounce_secs: u64,
+}
+
+impl Default for Thresholds {
+    /// No limits, default colors, no notifications.
+    fn default() -> Self {
+        Self {
+            warn: None,
+            crit: None,
+            warn_color: default_warn_color(),
+            crit_color: default_crit_color(),
+            below: false,
+            notify: false,
+            debounce_secs: default_alert_debounce(),
+        }
+    }
+}
+
+impl Thresholds {
+    /// Built-in thresholds for metrics with well-known limits, used unless `metric_settings` sets them.
+    pub fn builtin(metric: &str) -> Option<Self> {
+        let (warn, crit) = match metric {
+            // US AQI "unhealthy for sensitive groups" / "unhealthy"
+            "air_quality" => (Some(101.0), Some(151.0)),
+            // EPA PM breakpoints
+            "pm2_5" => (Some(35.5), Some(55.5)),
+            "pm10" => (Some(155.0), Some(255.0)),
+            // High / very high pollen
+            "pollen" => (Some(50.0), Some(200.0)),
+            // WHO "high" / "very high" UV
+            "uv_index" => (Some(6.0), Some(8.0)),
+            // Any failed systemd unit is critical
+            "failed_units" => (None, Some(1.0)),
+            // TLS and builds start failing a second off, well before Kerberos' five minutes
+            "clock_offset" => (Some(100.0), Some(1000.0)),
+            // Classified by state (see `alerts::state_level`), only the colors come from here
+            "time_sync" => (None, None),
+            // Any new kernel error or warning
+            "kernel_errors" => (Some(1.0), None),
+            // At 95% creating files starts failing soon
+            m if m == "inode_usage" || m.starts_with("inode_usage_") => (Some(80.0), Some(95.0)),
+            // Per-unit and CI states, classified like `time_sync`
+            m if m.starts_with("service_") || m.starts_with("ci_") => (None, None),
+            _ => return None,
+        };
+        Some(Self { warn, crit, ..Self::default() })
+    }
+}
+
+/// Thresholds for a metric: configured ones, else the built-in defaults.
+pub fn effective_thresholds(settings: &HashMap<String, MetricSettings>, metric: &str) -> Option<Thresholds> {
+    settings
+        .get(metric)
+        .and_then(|s| s.thresholds.clone())
+        .or_else(|| Thresholds::builtin(metric))
+}
+
+/// True for metrics fetched from Open-Meteo (only collected when weather is enabled).
+pub fn is_weather_metric(metric: &str) -> bool {
+    metric.starts_with("weather_") || matches!(metric, "air_quality" | "pm2_5" | "pm10" | "pollen" | "uv_index")
+}
+
+fn default_warn_color() -> String { "#FFBF00".to_string() }
+fn default_crit_color() -> String { "#FF3333".to_string() }
+fn default_alert_debounce() -> u64 { 300 }
+
+impl MetricSettings {
+    /// Returns true if any smoothing or hysteresis is configured.
+    pub fn is_active(&self) -> bool {
+        self.smoothing.is_some() || self.moving_average.is_some() || self.hysteresis > 0.0
+    }
+
+    /// Applies the first matching `value_map` entry to a formatted value.
+    pub fn map_value(&self, raw: &str) -> Option<String> {
+        self.value_map
+            .iter()
+            .find(|m| m.matches(raw))
+            .map(|m| m.text.replace("{value}", raw))
+    }
+}
+
+/// Top-process widget configuration (`top_processes` metric).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TopProcesses {
+    /// Number of processes to list.
+    #[serde(default = "default_top_count")]
+    pub count: usize,
+    /// Sort key: "cpu" or "memory".
+    #[serde(default = "default_top_sort")]
+    pub sort_by: String,
+}
+
+/// Notification mirror (`notifications` metric): recent desktop notifications, fading out.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationMirror {
+    /// Number of notifications shown.
+    #[serde(default = "default_notification_count")]
+    pub count: usize,
+    /// Seconds a notification stays on the overlay; it fades out over the second half.
+    #[serde(default = "default_notification_fade")]
+    pub fade_secs: u64,
+    /// Applications (as they name themselves, e.g. "Slack") whose notifications are not mirrored.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore_apps: Vec<String>,
+}
+
+fn default_notification_count() -> usize { 3 }
+fn default_notification_fade() -> u64 { 300 }
+
+impl Default for NotificationMirror {
+    fn default() -> Self {
+        Self { count: default_notification_count(), fade_secs: default_notification_fade(), ignore_apps: Vec::new() }
+    }
+}
+
+fn default_top_count() -> usize { 5 }
+fn default_top_sort() -> String { "cpu".to_string() }
+
+impl Default for TopProcesses {
+    fn default() -> Self {
+        Self { count: default_top_count(), sort_by: default_top_sort() }
+    }
+}
+
+/// Public IP lookup (`public_ip` metric). Only queried when the metric is on a screen.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PublicIp {
+    /// HTTPS endpoint returning the caller's IP as plain text.
+    #[serde(default = "default_public_ip_endpoint")]
+    pub endpoint: String,
+    /// Seconds between lookups.
+    #[serde(default = "default_public_ip_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_public_ip_endpoint() -> String { "https://api.ipify.org".to_string() }
+fn default_public_ip_interval() -> u64 { 300 }
+
+impl Default for PublicIp {
+    fn default() -> Self {
+        Self { endpoint: default_public_ip_endpoint(), interval_secs: default_public_ip_interval() }
+    }
+}
+
+/// Load limits for the `ResourceGuard`: the metrics loop pauses above `cpu_threshold`,
+/// and heavy collectors (git, nvidia-smi, weather, package checks) are deferred above
+/// their own threshold, e.g. `{"git_delta": 40}`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceLimits {
+    #[serde(default = "default_cpu_threshold")]
+    pub cpu_threshold: f32,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub collector_thresholds: HashMap<String, f32>,
+}
+
+fn default_cpu_threshold() -> f32 { 70.0 }
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self { cpu_threshold: default_cpu_threshold(), collector_thresholds: HashMap::new() }
+    }
+}
+
+/// Adaptive density (see `autotune`): thins the rain and then slows the frame rate
+/// while the overlay's own CPU usage is above `cpu_budget`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AutoTune {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Target CPU usage of the overlay process, in percent of one core.
+    #[serde(default = "default_cpu_budget")]
+    pub cpu_budget: f64,
+    /// Lowest `realism_scale` the tuner goes down to before slowing frames.
+    #[serde(default = "default_min_realism")]
+    pub min_realism: u32,
+    /// Longest frame interval the tuner may choose, in ms.
+    #[serde(default = "default_max_interval_ms")]
+    pub max_interval_ms: u64,
+}
+
+fn default_cpu_budget() -> f64 { 5.0 }
+fn default_min_realism() -> u32 { 2 }
+fn default_max_interval_ms() -> u64 { 5000 }
+
+impl Default for AutoTune {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cpu_budget: default_cpu_budget(),
+            min_realism: default_min_realism(),
+            max_interval_ms: default_max_interval_ms(),
+        }
+    }
+}
+
+/// Pending package updates (`updates_pending` metric).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Updates {
+    /// Shell command printing one line per pending update. When unset, the first available
+    /// of `checkupdates` (pacman), `dnf check-update -q` and `apt list --upgradable` is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Seconds between checks.
+    #[serde(default = "default_updates_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_updates_interval() -> u64 { 3600 }
+
+impl Default for Updates {
+    fn default() -> Self {
+        Self { command: None, interval_secs: default_updates_interval() }
+    }
+}
+
+/// GitHub account activity (`gh_notifications`, `gh_review_queue` metrics).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GitHub {
+    /// Personal access token (`notifications` and `repo` scopes). Falls back to the
+    /// `GITHUB_TOKEN` or `GH_TOKEN` environment variable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// API root; change for GitHub Enterprise (`https://github.example.com/api/v3`).
+    #[serde(default = "default_github_api_url")]
+    pub api_url: String,
+    /// Seconds between checks.
+    #[serde(default = "default_github_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_github_api_url() -> String { "https://api.github.com".to_string() }
+fn default_github_interval() -> u64 { 300 }
+
+impl Default for GitHub {
+    fn default() -> Self {
+        Self { token: None, api_url: default_github_api_url(), interval_secs: default_github_interval() }
+    }
+}
+
+impl GitHub {
+    /// `token`, else `$GITHUB_TOKEN`, else `$GH_TOKEN`.
+    pub fn resolved_token(&self) -> Option<String> {
+        self.token
+            .clone()
+            .or_else(|| env::var("GITHUB_TOKEN").ok())
+            .or_else(|| env::var("GH_TOKEN").ok())
+            .filter(|t| !t.trim().is_empty())
+    }
+}
+
+/// CI status of the `productivity.repos` (`ci_<repo>` metrics).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Ci {
+    /// Seconds between checks.
+    #[serde(default = "default_ci_interval")]
+    pub interval_secs: u64,
+    /// Status URLs for repos not built on GitHub Actions, keyed by repo directory name.
+    /// The response may be plain text ("passed") or JSON with a `status` or `state` field.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub status_urls: HashMap<String, String>,
+}
+
+fn default_ci_interval() -> u64 { 300 }
+
+impl Default for Ci {
+    fn default() -> Self {
+        Self { interval_secs: default_ci_interval(), status_urls: HashMap::new() }
+    }
+}
+
+/// Pending tasks (`tasks` metric), from Taskwarrior or a todo.txt file.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Tasks {
+    /// todo.txt file to read; Taskwarrior (`task export`) is used when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub todo_file: Option<String>,
+    /// Show the most urgent task after the count ("3 pending · Renew passport").
+    #[serde(default = "default_true")]
+    pub show_top: bool,
+    /// Seconds between checks.
+    #[serde(default = "default_tasks_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_tasks_interval() -> u64 { 300 }
+
+impl Default for Tasks {
+    fn default() -> Self {
+        Self { todo_file: None, show_top: true, interval_secs: default_tasks_interval() }
+    }
+}
+
+/// Calendars for the `next_event` metric.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Calendar {
+    /// ICS sources: http(s) URLs or file paths (under `$HOME`).
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Seconds between refreshes.
+    #[serde(default = "default_calendar_interval")]
+    pub interval_secs: u64,
+    /// Also show the next event under the day-of-week header.
+    #[serde(default)]
+    pub in_header: bool,
+}
+
+fn default_calendar_interval() -> u64 { 900 }
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Self { sources: Vec::new(), interval_secs: default_calendar_interval(), in_header: false }
+    }
+}
+
+/// Typing activity (`keystrokes_today`, `wpm_now`). Off unless enabled: only key
+/// press counts are kept, never which keys were pressed.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Typing {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Screen time per application (`top_app`, `focused_time`). Off unless enabled.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ScreenTime {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between samples of the focused window.
+    #[serde(default = "default_screen_time_sample")]
+    pub sample_secs: u64,
+}
+
+fn default_screen_time_sample() -> u64 { 5 }
+
+impl Default for ScreenTime {
+    fn default() -> Self {
+        Self { enabled: false, sample_secs: default_screen_time_sample() }
+    }
+}
+
+/// MQTT broker to read custom metrics from (e.g. Home Assistant sensors).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Mqtt {
+    pub host: String,
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+    #[serde(default = "default_mqtt_client_id")]
+    pub client_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    pub topics: Vec<MqttTopic>,
+}
+
+/// A subscribed topic and the custom metric its payload is shown as.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MqttTopic {
+    /// Topic filter; `+` and `#` wildcards are allowed.
+    pub topic: String,
+    pub metric_id: String,
+    /// JSON pointer into a JSON payload (e.g. "/temperature"); plain payloads are used as-is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_pointer: Option<String>,
+    /// Appended to numeric values (e.g. "°C").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+fn default_mqtt_port() -> u16 { 1883 }
+fn default_mqtt_client_id() -> String { "matrix-overlay".to_string() }
+
+/// A JSON REST endpoint polled for custom metrics (crypto prices, CI queue depth).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HttpJsonEndpoint {
+    pub url: String,
+    #[serde(default = "default_http_json_interval")]
+    pub interval_secs: u64,
+    pub fields: Vec<HttpJsonField>,
+}
+
+/// A value picked out of an endpoint's response and the custom metric it is shown as.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HttpJsonField {
+    /// JSON pointer into the response (e.g. "/bitcoin/usd", "/jobs/0/queued").
+    pub pointer: String,
+    pub metric_id: String,
+    /// Appended to numeric values (e.g. " USD").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+fn default_http_json_interval() -> u64 { 300 }
+
+/// An RSS or Atom feed whose latest headlines are shown, joined, as a custom metric.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RssFeed {
+    pub url: String,
+    pub metric_id: String,
+    /// Number of headlines shown, newest first.
+    #[serde(default = "default_rss_headlines")]
+    pub headlines: usize,
+    #[serde(default = "default_feed_interval")]
+    pub interval_secs: u64,
+    /// Text between headlines.
+    #[serde(default = "default_ticker_separator")]
+    pub separator: String,
+}
+
+fn default_rss_headlines() -> usize { 5 }
+
+/// Another machine running `matrix-overlay serve`; its metrics are shown here as
+/// `<name>.<metric_id>` (e.g. `nas.cpu_usage`).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteInstance {
+    pub name: String,
+    /// Base URL of its HTTP exporter, e.g. "http://nas:9184".
+    pub url: String,
+    #[serde(default = "default_remote_interval")]
+    pub interval_secs: u64,
+}
+
+fn default_remote_interval() -> u64 { 10 }
+
+impl RemoteInstance {
+    /// URL of the instance's status snapshot.
+    pub fn status_url(&self) -> String {
+        format!("{}/status.json", self.url.trim_end_matches('/'))
+    }
+
+    /// The remote metric ID in a local `<name>.<metric_id>`, if it belongs to this instance.
+    pub fn remote_id<'a>(&self, metric: &'a str) -> Option<&'a str> {
+        metric.split_once('.').filter(|(name, id)| *name == self.name && !id.is_empty()).map(|(_, id)| id)
+    }
+}
+
 /// Productivity tracking configuration.
 /// 
 /// Ties to Stage 0: Productivity Features (Git/AI).
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Productivity {
     /// List of local Git repository paths to monitor.
     #[serde(default)]
@@ -66,17 +887,97 @@ pub struct Productivity {
     /// Maximum number of repositories to scan per update cycle.
     #[serde(default = "default_batch_cap")]
     pub batch_cap: u32,
+    /// Limits for the `code_delta` scan, which runs on its own worker thread.
+    #[serde(default)]
+    pub git_scan: GitScan,
+    /// Send a desktop notification when auto-commit skips a repo mid-rebase/merge or on a detached HEAD.
+    #[serde(default)]
+    pub notify_skipped: bool,
+    /// Checks an Ollama-generated commit message must pass; otherwise a local summary is used.
+    #[serde(default)]
+    pub commit_message: CommitMessageRules,
+}
+
+/// Guardrails for AI-generated auto-commit messages.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CommitMessageRules {
+    /// Shortest acceptable message, in characters.
+    #[serde(default = "default_commit_min_length")]
+    pub min_length: usize,
+    /// Longest acceptable message, in characters (after any conventional prefix is added).
+    #[serde(default = "default_commit_max_length")]
+    pub max_length: usize,
+    /// Require a Conventional Commits type ("fix: ...", "feat(ui): ..."), adding "chore: " if missing.
+    #[serde(default)]
+    pub conventional: bool,
+    /// Extra words that reject a message, on top of the built-in list.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_words: Vec<String>,
+}
+
+fn default_commit_min_length() -> usize { 10 }
+fn default_commit_max_length() -> usize { 72 }
+
+impl Default for CommitMessageRules {
+    fn default() -> Self {
+        Self {
+            min_length: default_commit_min_length(),
+            max_length: default_commit_max_length(),
+            conventional: false,
+            blocked_words: Vec::new(),
+        }
+    }
 }
 
 fn default_commit_threshold() -> u64 { 1000 }
 fn default_batch_cap() -> u32 { 5 }
 
+impl Default for Productivity {
+    fn default() -> Self {
+        Self {
+            repos: Vec::new(),
+            auto_commit_threshold: 0,
+            ollama_enabled: false,
+            batch_cap: default_batch_cap(),
+            git_scan: GitScan::default(),
+            notify_skipped: false,
+            commit_message: CommitMessageRules::default(),
+        }
+    }
+}
+
+/// Concurrency and time limits for scanning `productivity.repos`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GitScan {
+    /// Repositories scanned at the same time.
+    #[serde(default = "default_git_max_parallel")]
+    pub max_parallel: usize,
+    /// Time one repository's history walk may take before it's cut short (partial counts are kept).
+    #[serde(default = "default_git_repo_budget_ms")]
+    pub repo_budget_ms: u64,
+}
+
+fn default_git_max_parallel() -> usize { 2 }
+fn default_git_repo_budget_ms() -> u64 { 2000 }
+
+impl Default for GitScan {
+    fn default() -> Self {
+        Self { max_parallel: default_git_max_parallel(), repo_budget_ms: default_git_repo_budget_ms() }
+    }
+}
+
 /// Cosmetic and animation configuration.
 /// 
 /// Ties to Stage 0: Matrix Aesthetics (<1% CPU goal).
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Cosmetics {
-    /// Rain mode: "fall" (classic), "pulse" (low-resource glow), or "off".
+    /// Rain mode: "fall" (classic), "reactive" (falling rain whose density, speed and
+    /// brightness follow CPU/GPU load), "pulse" (low-resource glow), "off", or one of the
+    /// ambient backgrounds "starfield", "plasma" and "life" (Conway's Game of Life).
+    /// Screens can override it with their own `rain_mode`.
     #[serde(default = "default_rain_mode")]
     pub rain_mode: String,
     /// Realism scale (0-10) affecting stream density and speed variance.
@@ -103,19 +1004,255 @@ pub struct Cosmetics {
     /// Opacity of the metric background box
     #[serde(default = "default_bg_opacity")]
     pub background_opacity: f64,
+    /// Fixed RNG seed for the rain, making it identical run to run (recordings, visual tests).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rain_seed: Option<u64>,
+    /// Pulse stream speed and lead-glyph brightness with sound from the audio input
+    /// (needs the `audio` feature).
+    #[serde(default)]
+    pub audio_reactive: bool,
+    /// Input device to capture for `audio_reactive`, matched by name substring
+    /// (e.g. "Monitor"); the default input when unset.
+    #[serde(default)]
+    pub audio_device: Option<String>,
+    /// Changed metric values resolve from random katakana into the real characters.
+    #[serde(default)]
+    pub decode_animation: bool,
+    /// Opacity of the whole overlay window (0.1 - 1.0), applied by the compositor through
+    /// `_NET_WM_WINDOW_OPACITY` on top of the per-element brightness settings.
+    #[serde(default = "default_window_opacity")]
+    pub window_opacity: f64,
+    /// How rain meets the metric boxes: "none" (drawn behind them), "fade" (glyphs dim
+    /// out just above a box) or "splash" (streams end at the box top with a small splash).
+    #[serde(default = "default_rain_collision")]
+    pub rain_collision: String,
+    /// Minutes without keyboard or mouse input after which the rain takes over the whole
+    /// screen, above all windows, until the next input; 0 disables the takeover.
+    #[serde(default)]
+    pub screensaver_after_min: u32,
+}
+
+fn default_rain_speed() -> f64 { 1.0 }
+fn default_brightness() -> f64 { 0.9 }
+fn default_border_color() -> String { "#00FF41".to_string() }
+fn default_bg_opacity() -> f64 { 0.7 }
+fn default_window_opacity() -> f64 { 1.0 }
+fn default_rain_collision() -> String { "none".to_string() }
+
+fn default_rain_mode() -> String { "fall".to_string() }
+
+/// Accepted values of `rain_mode`.
+const RAIN_MODES: [&str; 7] = ["fall", "reactive", "pulse", "off", "starfield", "plasma", "life"];
+fn default_realism() -> u32 { 10 }
+
+impl Default for Cosmetics {
+    fn default() -> Self {
+        Self {
+            rain_mode: default_rain_mode(),
+            realism_scale: default_realism(),
+            occlusion_enabled: true,
+            rain_speed: default_rain_speed(),
+            metrics_brightness: default_brightness(),
+            matrix_brightness: default_brightness(),
+            border_enabled: false,
+            border_color: default_border_color(),
+            background_opacity: default_bg_opacity(),
+            rain_seed: None,
+            audio_reactive: false,
+            audio_device: None,
+            decode_animation: false,
+            window_opacity: default_window_opacity(),
+            rain_collision: default_rain_collision(),
+            screensaver_after_min: 0,
+        }
+    }
+}
+fn default_true() -> bool { true }
+fn default_disks() -> Vec<String> { vec!["/".to_string()] }
+fn default_false() -> bool { false }
+
+/// Where rendered frames go.
+///
+/// `target` is "x11" (overlay windows, the default), "png" (numbered PNG files in
+/// `path`, for testing and headless capture), "framebuffer" (a Linux fbdev
+/// device such as /dev/fb0, for kiosks and SBCs without an X session) or "kms"
+/// (direct DRM/KMS mode-setting on `path`, default /dev/dri/card0; needs the `kms` feature).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Output {
+    #[serde(default = "default_output_target")]
+    pub target: String,
+    /// Output directory (png) or device (framebuffer: /dev/fb0, kms: /dev/dri/card0).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// KMS connector to drive (e.g. "HDMI-A-1"); the first connected one if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connector: Option<String>,
+    /// Frame size for the png target (the framebuffer reports its own size).
+    #[serde(default = "default_output_width")]
+    pub width: u16,
+    #[serde(default = "default_output_height")]
+    pub height: u16,
+    /// Number of PNG frames to keep before the oldest are deleted.
+    #[serde(default = "default_output_max_files")]
+    pub max_files: usize,
+}
+
+fn default_output_target() -> String { "x11".to_string() }
+fn default_output_width() -> u16 { 1920 }
+fn default_output_height() -> u16 { 1080 }
+fn default_output_max_files() -> usize { 120 }
+
+impl Default for Output {
+    fn default() -> Self {
+        Self {
+            target: default_output_target(),
+            path: None,
+            connector: None,
+            width: default_output_width(),
+            height: default_output_height(),
+            max_files: default_output_max_files(),
+        }
+    }
+}
+
+/// Panel mode: instead of covering the desktop, each monitor gets a slim metrics bar
+/// docked to one edge that reserves its space (`_NET_WM_STRUT_PARTIAL`), so maximized
+/// windows stop short of it. Only applies to the x11 output.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Panel {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Edge to dock to: "top", "bottom", "left" or "right".
+    #[serde(default = "default_panel_edge")]
+    pub edge: String,
+    /// Thickness of the bar in pixels (its height, or its width on the left and right edges).
+    #[serde(default = "default_panel_size")]
+    pub size: u16,
+}
+
+fn default_panel_edge() -> String { "top".to_string() }
+fn default_panel_size() -> u16 { 40 }
+
+impl Default for Panel {
+    fn default() -> Self {
+        Self { enabled: false, edge: default_panel_edge(), size: default_panel_size() }
+    }
+}
+
+/// Mini-HUD: a small always-on-top window with a few metrics, toggled with Ctrl+Alt+H
+/// or the tray independently of the desktop overlay.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Hud {
+    /// Show the HUD at startup.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_hud_metrics")]
+    pub metrics: Vec<String>,
+    /// Width in pixels; the height follows from the number of metrics.
+    #[serde(default = "default_hud_width")]
+    pub width: u16,
+    /// Initial position on the X screen; drag the HUD to move it.
+    #[serde(default = "default_hud_offset")]
+    pub x: i32,
+    #[serde(default = "default_hud_offset")]
+    pub y: i32,
+}
+
+fn default_hud_metrics() -> Vec<String> {
+    ["cpu_usage", "ram_usage", "cpu_temp", "gpu_temp"].iter().map(|s| s.to_string()).collect()
+}
+fn default_hud_width() -> u16 { 280 }
+fn default_hud_offset() -> i32 { 40 }
+
+/// Headless server mode (`matrix-overlay serve --headless`): the collectors only, with
+/// their values exported over HTTP and optionally MQTT.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Serve {
+    /// Address (IP:port) of the HTTP exporter: Prometheus text at `/metrics`, the
+    /// `ctl status` JSON at `/status.json`.
+    #[serde(default = "default_serve_listen")]
+    pub listen: String,
+    /// Publish every metric to `<prefix>/<metric_id>` on the `mqtt` broker after each update.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mqtt_prefix: Option<String>,
+}
+
+fn default_serve_listen() -> String { "127.0.0.1:9184".to_string() }
+
+/// The `ticker` layout item: a line scrolling along the bottom of a screen.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Ticker {
+    /// Metrics shown in the ticker, as "LABEL value".
+    #[serde(default)]
+    pub metrics: Vec<String>,
+    /// RSS or Atom feed whose latest headlines follow the metrics (`feed_headlines`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feed_url: Option<String>,
+    #[serde(default = "default_feed_interval")]
+    pub feed_interval_secs: u64,
+    /// Text between entries.
+    #[serde(default = "default_ticker_separator")]
+    pub separator: String,
+    /// Scroll speed in pixels per frame.
+    #[serde(default = "default_ticker_speed")]
+    pub speed: f64,
+}
+
+fn default_feed_interval() -> u64 { 900 }
+fn default_ticker_separator() -> String { "  ·  ".to_string() }
+fn default_ticker_speed() -> f64 { 1.0 }
+
+impl Default for Ticker {
+    fn default() -> Self {
+        Self {
+            metrics: Vec::new(),
+            feed_url: None,
+            feed_interval_secs: default_feed_interval(),
+            separator: default_ticker_separator(),
+            speed: default_ticker_speed(),
+        }
+    }
 }
 
-fn default_rain_speed() -> f64 { 1.0 }
-fn default_brightness() -> f64 { 0.9 }
-fn default_border_color() -> String { "#00FF41".to_string() }
-fn default_bg_opacity() -> f64 { 0.7 }
+impl Default for Serve {
+    fn default() -> Self {
+        Self { listen: default_serve_listen(), mqtt_prefix: None }
+    }
+}
 
-fn default_rain_mode() -> String { "fall".to_string() }
-fn default_realism() -> u32 { 10 }
-fn default_true() -> bool { true }
-fn default_false() -> bool { false }
+impl Default for Hud {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            metrics: default_hud_metrics(),
+            width: default_hud_width(),
+            x: default_hud_offset(),
+            y: default_hud_offset(),
+        }
+    }
+}
+
+impl Panel {
+    /// True for a bar along the top or bottom edge, where metrics sit side by side.
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self.edge.as_str(), "top" | "bottom")
+    }
+}
+
+impl Output {
+    /// True when frames are presented on X11 windows (the only target that needs an X server).
+    pub fn is_x11(&self) -> bool {
+        self.target == "x11"
+    }
+}
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Logging {
     pub enabled: bool,
     pub log_path: String,
@@ -147,17 +1284,86 @@ impl Default for Logging {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     pub general: General,
     pub screens: Vec<Screen>,
     pub weather: Weather,
     #[serde(default)]
     pub custom_files: Vec<CustomFile>,
+    /// Commands whose output is shown as custom metrics.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<CustomCommand>,
+    #[serde(default)]
+    pub computed_metrics: Vec<ComputedMetric>,
+    #[serde(default)]
+    pub metric_settings: HashMap<String, MetricSettings>,
+    #[serde(default)]
+    pub top_processes: TopProcesses,
+    #[serde(default)]
+    pub notifications: NotificationMirror,
+    #[serde(default)]
+    pub public_ip: PublicIp,
+    #[serde(default)]
+    pub updates: Updates,
+    #[serde(default)]
+    pub github: GitHub,
+    #[serde(default)]
+    pub ci: Ci,
+    #[serde(default)]
+    pub tasks: Tasks,
+    #[serde(default)]
+    pub calendar: Calendar,
+    #[serde(default)]
+    pub typing: Typing,
+    #[serde(default)]
+    pub screen_time: ScreenTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mqtt: Option<Mqtt>,
+    /// JSON endpoints whose fields are shown as custom metrics.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub http_json: Vec<HttpJsonEndpoint>,
+    /// Feeds whose headlines are shown as custom metrics.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rss_feeds: Vec<RssFeed>,
+    /// Other machines' `serve` endpoints whose metrics are shown as `<name>.<metric_id>`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remote_instances: Vec<RemoteInstance>,
+    #[serde(default)]
+    pub resource_guard: ResourceLimits,
+    #[serde(default)]
+    pub auto_tune: AutoTune,
+    /// Refresh interval overrides in ms, keyed by collector ID (e.g. "open_meteo", "git_delta").
+    /// Collectors not listed use their own default or `update_ms`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub collector_intervals: HashMap<String, u64>,
+    /// Mount points reported as `disk_usage_<mount>` ("/" -> `disk_usage_root`,
+    /// "/mnt/data" -> `disk_usage_mnt_data`) and together in the `disk_summary` row, and
+    /// likewise as `inode_usage_<mount>`.
+    #[serde(default = "default_disks")]
+    pub disks: Vec<String>,
+    /// systemd units to watch, e.g. ["docker.service", "sshd.service"]; each is shown as
+    /// the metric `service_<unit>`, alongside `failed_units` (all failed units on the system).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<String>,
+    /// Collector IDs that are switched off (tray "Collectors" menu), e.g. ["nvidia", "open_meteo"].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_collectors: Vec<String>,
     #[serde(default)]
     pub productivity: Productivity,
     #[serde(default)]
     pub cosmetics: Cosmetics,
     #[serde(default)]
+    pub output: Output,
+    #[serde(default)]
+    pub panel: Panel,
+    #[serde(default)]
+    pub hud: Hud,
+    #[serde(default)]
+    pub serve: Serve,
+    #[serde(default)]
+    pub ticker: Ticker,
+    #[serde(default)]
     pub logging: Logging,
 }
 
@@ -182,6 +1388,12 @@ impl Default for Config {
                 theme: "classic".to_string(),
                 glow_passes: default_glow_passes(),
                 show_monitor_label: true,
+                pixmap_buffering: true,
+                profile: default_profile(),
+                night_theme: None,
+                units: default_units(),
+                network_bits: false,
+                wm_profile: default_wm_profile(),
             },
             screens: vec![
                 Screen {
@@ -195,53 +1407,436 @@ impl Default for Config {
                     ],
                     x_offset: 20,
                     y_offset: 20,
+                    margins: Margins::default(),
+                    item_options: HashMap::new(),
+                    weather_location: None,
+                    rain_exclusions: Vec::new(),
+                    rain_mode: None,
+                    columns: default_columns(),
+                    column_gap: default_column_gap(),
+                    spacing: 0,
                 }
             ],
             weather: Weather {
                 lat: 0.0,
                 lon: 0.0,
                 enabled: false,
+                forecast: false,
+                geoip: false,
+                locations: Vec::new(),
             },
             custom_files: Vec::new(),
+            commands: Vec::new(),
+            computed_metrics: Vec::new(),
+            metric_settings: HashMap::new(),
+            top_processes: TopProcesses::default(),
+            notifications: NotificationMirror::default(),
+            public_ip: PublicIp::default(),
+            updates: Updates::default(),
+            github: GitHub::default(),
+            ci: Ci::default(),
+            tasks: Tasks::default(),
+            calendar: Calendar::default(),
+            typing: Typing::default(),
+            screen_time: ScreenTime::default(),
+            mqtt: None,
+            http_json: Vec::new(),
+            rss_feeds: Vec::new(),
+            remote_instances: Vec::new(),
+            resource_guard: ResourceLimits::default(),
+            auto_tune: AutoTune::default(),
+            collector_intervals: HashMap::new(),
+            disks: default_disks(),
+            services: Vec::new(),
+            disabled_collectors: Vec::new(),
             productivity: Productivity::default(),
             cosmetics: Cosmetics::default(),
+            output: Output::default(),
+            panel: Panel::default(),
+            hud: Hud::default(),
+            serve: Serve::default(),
+            ticker: Ticker::default(),
             logging: Logging::default(),
         }
     }
 }
 
+/// Levenshtein distance, for suggesting the metric ID a typo was meant to be.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The built-in or declared metric ID closest to `metric`, if one is close enough to be a typo.
+fn closest_metric(metric: &str, declared: &[&str]) -> Option<String> {
+    crate::metrics::MetricId::BUILTIN
+        .iter()
+        .chain(declared.iter())
+        .map(|known| (edit_distance(metric, known), *known))
+        .filter(|(distance, known)| *distance <= 2.max(known.len() / 4))
+        .min()
+        .map(|(_, known)| known.to_string())
+}
+
+/// Deep-merges `overlay` into `base`: objects merge key by key, any other value replaces.
+pub fn merge_values(base: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// The parts of `new` that differ from `old`, shaped like `new`. Objects are compared key
+/// by key, anything else as a whole; a key `new` no longer has comes out as `null`.
+fn changed_values(old: &serde_json::Value, new: serde_json::Value) -> Option<serde_json::Value> {
+    match (old, new) {
+        (serde_json::Value::Object(old), serde_json::Value::Object(new)) => {
+            let mut changes = serde_json::Map::new();
+            for key in old.keys().filter(|key| !new.contains_key(*key)) {
+                changes.insert(key.clone(), serde_json::Value::Null);
+            }
+            for (key, value) in new {
+                let change = match old.get(&key) {
+                    Some(existing) => changed_values(existing, value),
+                    None => Some(value),
+                };
+                if let Some(change) = change {
+                    changes.insert(key, change);
+                }
+            }
+            (!changes.is_empty()).then_some(serde_json::Value::Object(changes))
+        }
+        (old, new) => (*old != new).then_some(new),
+    }
+}
+
+/// Applies `changed_values` output to `base` like `merge_values`, removing keys set to `null`.
+fn patch_values(base: &mut serde_json::Value, changes: serde_json::Value) {
+    match (base, changes) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(changes)) => {
+            for (key, value) in changes {
+                if value.is_null() {
+                    base.remove(&key);
+                } else {
+                    patch_values(base.entry(key).or_insert_with(|| serde_json::json!({})), value);
+                }
+            }
+        }
+        (base, changes) => *base = changes,
+    }
+}
+
+/// On-disk config file format, detected from the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Candidate file names in order of precedence (hand-written formats win over JSON).
+    const FILE_NAMES: [&'static str; 4] = ["config.toml", "config.yaml", "config.yml", "config.json"];
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    pub fn parse(&self, content: &str) -> Result<Config> {
+        Ok(match self {
+            Self::Json => serde_json::from_str(content).context("Failed to parse JSON config")?,
+            Self::Toml => toml::from_str(content).context("Failed to parse TOML config")?,
+            Self::Yaml => serde_yaml::from_str(content).context("Failed to parse YAML config")?,
+        })
+    }
+
+    /// Parses `content` into an untyped tree, so partial files (drop-in fragments) can be merged.
+    pub fn parse_value(&self, content: &str) -> Result<serde_json::Value> {
+        Ok(match self {
+            Self::Json => serde_json::from_str(content).context("Failed to parse JSON config")?,
+            Self::Toml => toml::from_str(content).context("Failed to parse TOML config")?,
+            Self::Yaml => serde_yaml::from_str(content).context("Failed to parse YAML config")?,
+        })
+    }
+
+    pub fn serialize<T: Serialize>(&self, config: &T) -> Result<String> {
+        Ok(match self {
+            Self::Json => serde_json::to_string_pretty(config).context("Failed to serialize config as JSON")?,
+            Self::Toml => toml::to_string_pretty(config).context("Failed to serialize config as TOML")?,
+            Self::Yaml => serde_yaml::to_string(config).context("Failed to serialize config as YAML")?,
+        })
+    }
+}
+
 impl Config {
-    /// Loads configuration from `~/.config/matrix-overlay/config.json`.
+    /// Directory holding the config file (`~/.config/matrix-overlay`).
+    pub fn config_dir() -> Result<PathBuf> {
+        let home = env::var("HOME").context("HOME environment variable not set")?;
+        Ok(Path::new(&home).join(".config/matrix-overlay"))
+    }
+
+    /// Path of the active config file: the first existing of config.toml,
+    /// config.yaml, config.yml and config.json, or config.json if none exist.
+    pub fn config_path() -> Result<PathBuf> {
+        let dir = Self::config_dir()?;
+        let existing: Vec<PathBuf> = ConfigFormat::FILE_NAMES.iter().map(|n| dir.join(n)).filter(|p| p.exists()).collect();
+        if existing.len() > 1 {
+            log::warn!("Multiple config files found, using {:?} (ignoring {:?})", existing[0], &existing[1..]);
+        }
+        Ok(existing.into_iter().next().unwrap_or_else(|| dir.join("config.json")))
+    }
+
+    /// Directory of drop-in fragments merged over the main config (`~/.config/matrix-overlay/config.d`).
+    pub fn fragments_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("config.d"))
+    }
+
+    /// Loads configuration from `~/.config/matrix-overlay/config.{toml,yaml,yml,json}`,
+    /// with any fragments in `config.d/` merged on top.
     /// 
-    /// If the file does not exist, it creates a default configuration.
+    /// If no file exists, it creates a default config.json.
     /// Validates the loaded configuration before returning.
     pub fn load() -> Result<Self> {
-        let home = env::var("HOME").context("HOME environment variable not set")?;
-        let config_path = Path::new(&home).join(".config/matrix-overlay/config.json");
+        let config_path = Self::config_path()?;
 
         if !config_path.exists() {
             if let Some(parent) = config_path.parent() {
                 fs::create_dir_all(parent).context("Failed to create config directory")?;
             }
             let default_config = Config::default();
-            let json = serde_json::to_string_pretty(&default_config).context("Failed to serialize default config")?;
+            let json = ConfigFormat::Json.serialize(&default_config).context("Failed to serialize default config")?;
             fs::write(&config_path, json).context("Failed to write default config file")?;
-            return Ok(default_config);
         }
 
-        let content = fs::read_to_string(&config_path).context("Failed to read config file")?;
-        let config: Config = serde_json::from_str(&content).context("Failed to parse config.json")?;
-
+        let config = Self::load_merged(&config_path, &Self::fragments_dir()?)?;
         config.validate()?;
         Ok(config)
     }
 
-    /// Saves configuration to `~/.config/matrix-overlay/config.json`.
+    /// Parses `path` and deep-merges every `*.{json,toml,yaml,yml}` file in `fragments`
+    /// over it, in file name order (`10-theme.toml` before `20-alerts.json`).
+    ///
+    /// Tables merge key by key; anything else (numbers, strings, lists) in a later
+    /// fragment replaces the earlier value. A missing fragments directory is fine.
+    pub fn load_merged(path: &Path, fragments: &Path) -> Result<Self> {
+        let mut root = Self::read_value(path)?;
+
+        let mut files: Vec<PathBuf> = match fs::read_dir(fragments) {
+            Ok(entries) => entries.flatten().map(|e| e.path()).filter(|p| p.is_file() && ConfigFormat::from_path(p).is_some()).collect(),
+            Err(_) => Vec::new(),
+        };
+        files.sort();
+        for file in &files {
+            merge_values(&mut root, Self::read_value(file)?);
+        }
+        if !files.is_empty() {
+            log::info!("Merged {} config fragment(s) from {:?}", files.len(), fragments);
+        }
+
+        serde_json::from_value(root).with_context(|| format!("Invalid config after merging fragments over {:?}", path))
+    }
+
+    fn read_value(path: &Path) -> Result<serde_json::Value> {
+        let format = ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Json);
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read config file {:?}", path))?;
+        format.parse_value(&content).with_context(|| format!("Failed to parse {:?}", path))
+    }
+
+    /// Parses a config file, choosing the format by extension (JSON if unknown).
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let format = ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Json);
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read config file {:?}", path))?;
+        format.parse(&content).with_context(|| format!("Failed to parse {:?}", path))
+    }
+
+    /// Applies a single `key=value` override (as given to `--set`) on top of the loaded config.
+    ///
+    /// Keys are dotted paths into the config (`cosmetics.rain_mode`, `screens.0.x_offset`).
+    /// Values are parsed as JSON where possible (`8`, `true`, `["cpu_usage"]`), otherwise
+    /// taken as a plain string, so `--set cosmetics.rain_mode=pulse` works without quoting.
+    pub fn apply_override(&mut self, spec: &str) -> Result<()> {
+        let (key, raw) = match spec.split_once('=') {
+            Some((k, v)) if !k.trim().is_empty() => (k.trim(), v),
+            _ => bail!("Invalid override '{}': expected key=value", spec),
+        };
+        let value = serde_json::from_str(raw).unwrap_or_else(|_| serde_json::Value::String(raw.to_string()));
+
+        let mut root = serde_json::to_value(&*self).context("Failed to serialize config")?;
+        let mut target = &mut root;
+        for part in key.split('.') {
+            target = match target {
+                // Unset optional settings aren't serialized; deserializing rejects names that
+                // don't exist at all (every config struct denies unknown fields)
+                serde_json::Value::Object(map) => map.entry(part).or_insert_with(|| serde_json::json!({})),
+                serde_json::Value::Array(items) => {
+                    let index: usize = part.parse().with_context(|| format!("Expected an index in '{}'", key))?;
+                    match items.get_mut(index) {
+                        Some(v) => v,
+                        None => bail!("Index {} out of range in '{}'", index, key),
+                    }
+                }
+                _ => bail!("Config key '{}' does not name a nested setting", key),
+            };
+        }
+        *target = value;
+
+        *self = serde_json::from_value(root).with_context(|| format!("Invalid override '{}'", key))?;
+        Ok(())
+    }
+
+    /// Applies all `--set` overrides in order and re-validates the result.
+    pub fn apply_overrides(&mut self, overrides: &[String]) -> Result<()> {
+        for spec in overrides {
+            self.apply_override(spec)?;
+        }
+        if !overrides.is_empty() {
+            self.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the low-power rendering profile is selected.
+    pub fn is_low_power(&self) -> bool {
+        self.general.profile == "low_power"
+    }
+
+    /// Background mode of monitor `index`: its screen's `rain_mode`, else the global one.
+    pub fn rain_mode_for(&self, index: usize) -> &str {
+        self.screens.get(index).and_then(|s| s.rain_mode.as_deref()).unwrap_or(&self.cosmetics.rain_mode)
+    }
+
+    /// Returns the settings actually used at runtime, with the rendering profile applied.
+    ///
+    /// Kept separate from `load` so the GUI never saves profile-forced values back to disk.
+    pub fn with_profile(&self) -> Self {
+        let mut config = self.clone();
+        if config.is_low_power() {
+            config.general.update_ms = config.general.update_ms.max(LOW_POWER_UPDATE_MS);
+            config.general.glow_passes.clear();
+            config.general.theme = "high_contrast".to_string();
+            config.general.color = "#FFFFFF".to_string();
+            config.cosmetics.rain_mode = "off".to_string();
+            for screen in &mut config.screens {
+                screen.rain_mode = None;
+            }
+            config.cosmetics.metrics_brightness = 1.0;
+            config.cosmetics.background_opacity = 1.0;
+        }
+        config
+    }
+
+    /// Saves configuration back to the active config file, preserving its format.
     pub fn save(&self) -> Result<()> {
-        let home = env::var("HOME").context("HOME environment variable not set")?;
-        let config_path = Path::new(&home).join(".config/matrix-overlay/config.json");
-        let json = serde_json::to_string_pretty(self).context("Failed to serialize config")?;
-        fs::write(config_path, json).context("Failed to write config file")?;
+        self.save_to(&Self::config_path()?, &Self::fragments_dir()?)
+    }
+
+    /// Writes the settings that differ from what `path` and `fragments` currently load
+    /// into `path`'s own contents, so values that come from `config.d/` fragments (or
+    /// were left at their defaults) are not copied into the main file. A fragment that
+    /// sets a changed value still takes precedence on the next load.
+    pub fn save_to(&self, path: &Path, fragments: &Path) -> Result<()> {
+        let format = ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Json);
+        let content = if path.exists() {
+            let mut own = Self::read_value(path)?;
+            let loaded = serde_json::to_value(Self::load_merged(path, fragments)?)?;
+            if let Some(changes) = changed_values(&loaded, serde_json::to_value(self)?) {
+                patch_values(&mut own, changes);
+            }
+            format.serialize(&own)?
+        } else {
+            format.serialize(self)?
+        };
+        fs::write(path, content).context("Failed to write config file")?;
+        Ok(())
+    }
+
+    /// Metric IDs declared by this config: custom files, commands, computed metrics,
+    /// MQTT topics, HTTP JSON fields and RSS feeds.
+    fn declared_metrics(&self) -> Vec<&str> {
+        let mut declared: Vec<&str> = Vec::new();
+        declared.extend(self.custom_files.iter().map(|f| f.metric_id.as_str()));
+        declared.extend(self.commands.iter().map(|c| c.metric_id.as_str()));
+        declared.extend(self.computed_metrics.iter().map(|c| c.metric_id.as_str()));
+        declared.extend(self.mqtt.iter().flat_map(|m| m.topics.iter().map(|t| t.metric_id.as_str())));
+        declared.extend(self.http_json.iter().flat_map(|e| e.fields.iter().map(|f| f.metric_id.as_str())));
+        declared.extend(self.rss_feeds.iter().map(|f| f.metric_id.as_str()));
+        declared
+    }
+
+    fn is_known_metric(&self, metric: &str, declared: &[&str]) -> bool {
+        // Per-unit, per-mount, per-device and per-repo metrics are generated from other settings
+        const DYNAMIC_PREFIXES: [&str; 7] = ["service_", "disk_usage_", "inode_usage_", "disk_io_", "ci_", "git_status_", "auto_commit_"];
+        // Whatever a remote instance reports; only it knows what it collects
+        if self.remote_instances.iter().any(|remote| remote.remote_id(metric).is_some()) {
+            return true;
+        }
+        if let Some((base, location)) = metric.split_once('@') {
+            return base.starts_with("weather_")
+                && crate::metrics::MetricId::BUILTIN.contains(&base)
+                && self.weather.locations.iter().any(|l| l.name == location);
+        }
+        crate::metrics::MetricId::BUILTIN.contains(&metric)
+            || declared.contains(&metric)
+            || metric == "ai_insight"
+            || metric == "ticker"
+            || DYNAMIC_PREFIXES.iter().any(|p| metric.starts_with(p) && metric.len() > p.len())
+    }
+
+    /// Screen metrics that no collector produces, as (metric, screen index, closest known ID).
+    /// `MetricId::from_str` accepts any string as a custom metric, so a typo would
+    /// otherwise just render nothing.
+    pub fn unknown_metrics(&self) -> Vec<(String, usize, Option<String>)> {
+        let declared = self.declared_metrics();
+        let mut unknown = Vec::new();
+        for (index, screen) in self.screens.iter().enumerate() {
+            for metric in &screen.metrics {
+                if self.is_known_metric(metric, &declared) {
+                    continue;
+                }
+                unknown.push((metric.clone(), index, closest_metric(metric, &declared)));
+            }
+        }
+        unknown
+    }
+
+    /// Rejects a metric list outside the screens (`hud.metrics`, `ticker.metrics`) naming a
+    /// metric no collector produces, which would otherwise just be collected as an empty
+    /// custom metric and never shown.
+    fn check_metric_list(&self, setting: &str, metrics: &[String]) -> Result<()> {
+        let declared = self.declared_metrics();
+        for metric in metrics {
+            if self.is_known_metric(metric, &declared) {
+                continue;
+            }
+            match closest_metric(metric, &declared) {
+                Some(known) => bail!("{} names unknown metric '{}' (did you mean '{}'?)", setting, metric, known),
+                None => bail!("{} names unknown metric '{}'", setting, metric),
+            }
+        }
         Ok(())
     }
 
@@ -259,10 +1854,329 @@ impl Config {
         if self.general.update_ms < 500 {
             bail!("update_ms must be >= 500");
         }
+        if !matches!(self.general.profile.as_str(), "standard" | "low_power") {
+            bail!("profile must be \"standard\" or \"low_power\"");
+        }
+        if !matches!(self.general.units.to_ascii_lowercase().as_str(), "metric" | "imperial") {
+            bail!("units must be \"metric\" or \"imperial\"");
+        }
+        if self.panel.enabled {
+            if !matches!(self.panel.edge.as_str(), "top" | "bottom" | "left" | "right") {
+                bail!("panel.edge must be \"top\", \"bottom\", \"left\" or \"right\"");
+            }
+            // One line of metrics plus the padding around it
+            let min_size = (self.general.metric_font_size as f64 * 1.5) as i32 + 2 * crate::layout::PANEL_PADDING;
+            if (self.panel.size as i32) < min_size {
+                bail!("panel.size must be at least {} pixels for a metric_font_size of {}", min_size, self.general.metric_font_size);
+            }
+        }
+        if self.hud.metrics.is_empty() {
+            bail!("hud.metrics must list at least one metric");
+        }
+        self.check_metric_list("hud.metrics", &self.hud.metrics)?;
+        if self.hud.width < 120 {
+            bail!("hud.width must be at least 120 pixels");
+        }
+        if self.hud.x < 0 || self.hud.y < 0 {
+            bail!("hud.x and hud.y must be non-negative");
+        }
+        if self.serve.listen.parse::<std::net::SocketAddr>().is_err() {
+            bail!("serve.listen must be an IP address and port, e.g. \"0.0.0.0:9184\"");
+        }
+        if let Some(prefix) = &self.serve.mqtt_prefix {
+            if self.mqtt.is_none() {
+                bail!("serve.mqtt_prefix needs an mqtt broker to be configured");
+            }
+            if prefix.is_empty() || prefix.contains(['+', '#']) {
+                bail!("serve.mqtt_prefix must be a non-empty topic without wildcards");
+            }
+        }
+        self.check_metric_list("ticker.metrics", &self.ticker.metrics)?;
+        if !(0.1..=20.0).contains(&self.ticker.speed) {
+            bail!("ticker.speed must be between 0.1 and 20 pixels per frame");
+        }
+        if let Some(url) = &self.ticker.feed_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                bail!("ticker.feed_url must be an http(s) URL");
+            }
+            if self.ticker.feed_interval_secs < 60 {
+                bail!("ticker.feed_interval_secs must be >= 60");
+            }
+        }
+        if !RAIN_MODES.contains(&self.cosmetics.rain_mode.as_str()) {
+            bail!("cosmetics.rain_mode must be one of {}", RAIN_MODES.join(", "));
+        }
+        if !matches!(self.cosmetics.rain_collision.as_str(), "none" | "fade" | "splash") {
+            bail!("cosmetics.rain_collision must be \"none\", \"fade\" or \"splash\"");
+        }
+        if !matches!(self.general.wm_profile.as_str(), "auto" | "gnome" | "kde" | "xfce" | "i3") {
+            bail!("wm_profile must be \"auto\", \"gnome\", \"kde\", \"xfce\" or \"i3\"");
+        }
+        if !(crate::window::MIN_WINDOW_OPACITY..=1.0).contains(&self.cosmetics.window_opacity) {
+            bail!("cosmetics.window_opacity must be between {} and 1.0", crate::window::MIN_WINDOW_OPACITY);
+        }
         for (i, screen) in self.screens.iter().enumerate() {
             if screen.x_offset < 0 || screen.y_offset < 0 {
                 bail!("Screen {} offsets must be non-negative", i);
             }
+            let (top, right, bottom, left) = screen.edge_margins();
+            if top < 0 || right < 0 || bottom < 0 || left < 0 {
+                bail!("Screen {} margins must be non-negative", i);
+            }
+            if let Some(loc) = &screen.weather_location {
+                if !self.weather.locations.iter().any(|l| &l.name == loc) {
+                    bail!("Screen {} weather_location '{}' is not defined in weather.locations", i, loc);
+                }
+            }
+            if let Some(mode) = &screen.rain_mode {
+                if !RAIN_MODES.contains(&mode.as_str()) {
+                    bail!("Screen {} rain_mode must be one of {}", i, RAIN_MODES.join(", "));
+                }
+            }
+            for zone in &screen.rain_exclusions {
+                if zone.x < 0 || zone.y < 0 || zone.width.is_some_and(|w| w <= 0) || zone.height.is_some_and(|h| h <= 0) {
+                    bail!("Screen {} rain_exclusions: positions must be non-negative and sizes positive", i);
+                }
+            }
+            if !(1..=4).contains(&screen.columns) || screen.column_gap < 0 || screen.spacing < 0 {
+                bail!("Screen {}: columns must be between 1 and 4, column_gap and spacing non-negative", i);
+            }
+            for (id, options) in &screen.item_options {
+                if let Some(anchor) = &options.anchor {
+                    if !crate::layout::ANCHORS.contains(&anchor.as_str()) {
+                        bail!("Screen {} item '{}': anchor must be one of {}", i, id, crate::layout::ANCHORS.join(", "));
+                    }
+                }
+                if options.width.is_some_and(|w| w <= 0) {
+                    bail!("Screen {} item '{}': width must be positive", i, id);
+                }
+                if options.column >= screen.columns {
+                    bail!("Screen {} item '{}': column {} but the screen has {} column(s)", i, id, options.column, screen.columns);
+                }
+                if id == "clock" {
+                    if !matches!(options.style.as_str(), "text" | "digital" | "analog") {
+                        bail!("Screen {} clock: style must be \"digital\" or \"analog\"", i);
+                    }
+                    if let Some(format) = &options.format {
+                        // Parsing alone accepts specifiers a time of day can't fill (%a, %d, %Z)
+                        use std::fmt::Write;
+                        let mut probe = String::new();
+                        if write!(probe, "{}", chrono::NaiveTime::MIN.format(format)).is_err() {
+                            bail!("Screen {} clock: invalid strftime format '{}'", i, format);
+                        }
+                    }
+                    continue;
+                }
+                if !matches!(options.style.as_str(), "text" | "sparkline" | "bar") {
+                    bail!("Screen {} item '{}': unknown style '{}'", i, id, options.style);
+                }
+                if options.bar_max <= 0.0 {
+                    bail!("Screen {} item '{}': bar_max must be positive", i, id);
+                }
+                if let Some(mode) = &options.ellipsize {
+                    if !matches!(mode.as_str(), "start" | "middle" | "end") {
+                        bail!("Screen {} item '{}': ellipsize must be \"start\", \"middle\" or \"end\"", i, id);
+                    }
+                }
+                if let Some(template) = &options.format {
+                    if let Some(name) = unknown_placeholder(template) {
+                        bail!("Screen {} item '{}': unknown placeholder {{{}}} in format", i, id, name);
+                    }
+                }
+                if options.precision.is_some_and(|p| p > 6) {
+                    bail!("Screen {} item '{}': precision must be at most 6", i, id);
+                }
+            }
+        }
+
+        if self.screen_time.sample_secs == 0 {
+            bail!("screen_time.sample_secs must be positive");
+        }
+        let rules = &self.productivity.commit_message;
+        if rules.max_length == 0 || rules.min_length > rules.max_length {
+            bail!("productivity.commit_message: max_length must be positive and at least min_length");
+        }
+
+        for (id, settings) in &self.metric_settings {
+            if let Some(alpha) = settings.smoothing {
+                if !(0.0..=1.0).contains(&alpha) {
+                    bail!("metric_settings.{}.smoothing must be between 0.0 and 1.0", id);
+                }
+            }
+            if settings.hysteresis < 0.0 {
+                bail!("metric_settings.{}.hysteresis must be non-negative", id);
+            }
+            for mapping in &settings.value_map {
+                if mapping.value.is_none() && mapping.min.is_none() && mapping.max.is_none() {
+                    bail!("metric_settings.{}.value_map entries need a value or a min/max range", id);
+                }
+            }
+            if let Some(t) = &settings.thresholds {
+                if let (Some(warn), Some(crit)) = (t.warn, t.crit) {
+                    if !t.below && warn > crit {
+                        bail!("metric_settings.{}.thresholds: warn must not exceed crit", id);
+                    }
+                    if t.below && warn < crit {
+                        bail!("metric_settings.{}.thresholds: with below, warn must not be under crit", id);
+                    }
+                }
+                if !self.is_valid_hex(&t.warn_color) || !self.is_valid_hex(&t.crit_color) {
+                    bail!("metric_settings.{}.thresholds colors must be valid hex strings", id);
+                }
+            }
+        }
+        match self.output.target.as_str() {
+            "x11" | "framebuffer" | "kms" => {}
+            "png" => {
+                if self.output.path.is_none() {
+                    bail!("output.path (a directory) is required for the png target");
+                }
+                if self.output.width == 0 || self.output.height == 0 || self.output.max_files == 0 {
+                    bail!("output.width, output.height and output.max_files must be positive");
+                }
+            }
+            other => bail!("output.target must be \"x11\", \"png\", \"framebuffer\" or \"kms\" (got \"{}\")", other),
+        }
+        let mut location_names = std::collections::HashSet::new();
+        for location in &self.weather.locations {
+            if location.name.is_empty() || location.name.contains('@') || location.name.contains(char::is_whitespace) {
+                bail!("weather.locations: invalid location name '{}'", location.name);
+            }
+            if !location_names.insert(location.name.as_str()) {
+                bail!("weather.locations: duplicate location name '{}'", location.name);
+            }
+            if !(-90.0..=90.0).contains(&location.lat) || !(-180.0..=180.0).contains(&location.lon) {
+                bail!("weather.locations.{}: coordinates out of range", location.name);
+            }
+        }
+        if !self.public_ip.endpoint.starts_with("https://") && !self.public_ip.endpoint.starts_with("http://") {
+            bail!("public_ip.endpoint must be an http(s) URL");
+        }
+        if self.public_ip.interval_secs < 30 {
+            bail!("public_ip.interval_secs must be >= 30");
+        }
+        let thresholds = std::iter::once(("cpu_threshold", &self.resource_guard.cpu_threshold))
+            .chain(self.resource_guard.collector_thresholds.iter().map(|(id, t)| (id.as_str(), t)));
+        for (name, threshold) in thresholds {
+            if !(0.0..=100.0).contains(threshold) {
+                bail!("resource_guard.{} must be between 0 and 100", name);
+            }
+        }
+        if self.auto_tune.cpu_budget <= 0.0 {
+            bail!("auto_tune.cpu_budget must be positive");
+        }
+        if self.auto_tune.min_realism > 10 {
+            bail!("auto_tune.min_realism must be between 0 and 10");
+        }
+        for mount in &self.disks {
+            if !mount.starts_with('/') {
+                bail!("disks: '{}' must be an absolute mount point", mount);
+            }
+        }
+        for unit in &self.services {
+            if unit.is_empty() || unit.starts_with('-') || unit.chars().any(char::is_whitespace) {
+                bail!("services: invalid unit name '{}'", unit);
+            }
+        }
+        if let Some(mqtt) = &self.mqtt {
+            if mqtt.host.is_empty() || mqtt.client_id.is_empty() {
+                bail!("mqtt: host and client_id must not be empty");
+            }
+            for t in &mqtt.topics {
+                if t.topic.is_empty() || t.metric_id.is_empty() {
+                    bail!("mqtt.topics: each entry needs a topic and a metric_id");
+                }
+                if t.json_pointer.as_deref().is_some_and(|p| !p.starts_with('/')) {
+                    bail!("mqtt.topics.{}: json_pointer must start with '/'", t.metric_id);
+                }
+            }
+        }
+        for endpoint in &self.http_json {
+            if !endpoint.url.starts_with("http://") && !endpoint.url.starts_with("https://") {
+                bail!("http_json: '{}' is not an http(s) URL", endpoint.url);
+            }
+            if endpoint.interval_secs < 10 {
+                bail!("http_json: interval_secs for '{}' must be >= 10", endpoint.url);
+            }
+            for field in &endpoint.fields {
+                if field.metric_id.is_empty() {
+                    bail!("http_json.fields: each entry needs a metric_id");
+                }
+                if !field.pointer.is_empty() && !field.pointer.starts_with('/') {
+                    bail!("http_json.fields.{}: pointer must be empty or start with '/'", field.metric_id);
+                }
+            }
+        }
+        for feed in &self.rss_feeds {
+            if !feed.url.starts_with("http://") && !feed.url.starts_with("https://") {
+                bail!("rss_feeds.{}: '{}' is not an http(s) URL", feed.metric_id, feed.url);
+            }
+            if !(1..=crate::metrics::FEED_MAX_ITEMS).contains(&feed.headlines) {
+                bail!("rss_feeds.{}: headlines must be between 1 and {}", feed.metric_id, crate::metrics::FEED_MAX_ITEMS);
+            }
+            if feed.interval_secs < 60 {
+                bail!("rss_feeds.{}: interval_secs must be >= 60", feed.metric_id);
+            }
+        }
+        for (i, remote) in self.remote_instances.iter().enumerate() {
+            if remote.name.is_empty() || remote.name.contains(['.', '@', ' ']) {
+                bail!("remote_instances: name '{}' must be non-empty, without '.', '@' or spaces", remote.name);
+            }
+            if self.remote_instances[..i].iter().any(|other| other.name == remote.name) {
+                bail!("remote_instances: duplicate name '{}'", remote.name);
+            }
+            if !remote.url.starts_with("http://") && !remote.url.starts_with("https://") {
+                bail!("remote_instances.{}: '{}' is not an http(s) URL", remote.name, remote.url);
+            }
+            if remote.interval_secs < 2 {
+                bail!("remote_instances.{}: interval_secs must be >= 2", remote.name);
+            }
+        }
+        if self.github.interval_secs < 60 {
+            bail!("github.interval_secs must be >= 60");
+        }
+        if !self.github.api_url.starts_with("https://") && !self.github.api_url.starts_with("http://") {
+            bail!("github.api_url must be an http(s) URL");
+        }
+        if self.calendar.interval_secs < 60 {
+            bail!("calendar.interval_secs must be >= 60");
+        }
+        if self.tasks.interval_secs < 10 {
+            bail!("tasks.interval_secs must be >= 10");
+        }
+        if self.ci.interval_secs < 60 {
+            bail!("ci.interval_secs must be >= 60");
+        }
+        if self.updates.interval_secs < 300 {
+            bail!("updates.interval_secs must be >= 300");
+        }
+        for (id, ms) in &self.collector_intervals {
+            if *ms < 500 {
+                bail!("collector_intervals.{} must be >= 500 ms", id);
+            }
+        }
+        if self.top_processes.count == 0 || self.top_processes.count > 20 {
+            bail!("top_processes.count must be between 1 and 20");
+        }
+        if self.notifications.count == 0 || self.notifications.count > 10 {
+            bail!("notifications.count must be between 1 and 10");
+        }
+        if self.notifications.fade_secs < 10 {
+            bail!("notifications.fade_secs must be >= 10");
+        }
+        if !matches!(self.top_processes.sort_by.as_str(), "cpu" | "memory") {
+            bail!("top_processes.sort_by must be \"cpu\" or \"memory\"");
+        }
+        for computed in &self.computed_metrics {
+            crate::computed::parse(&computed.expression)
+                .with_context(|| format!("Invalid expression for computed metric '{}'", computed.metric_id))?;
+        }
+
+        for (metric, screen, suggestion) in self.unknown_metrics() {
+            match suggestion {
+                Some(known) => log::warn!("Unknown metric '{}' on screen {} (did you mean '{}'?)", metric, screen + 1, known),
+                None => log::warn!("Unknown metric '{}' on screen {}; nothing will be shown for it", metric, screen + 1),
+            }
         }
 
         // Security Path Validation
@@ -271,6 +2185,28 @@ impl Config {
                 log::warn!("Security Warning: Unsafe path detected in custom_files: {}", file.path);
             }
         }
+        for source in &self.calendar.sources {
+            let is_url = source.starts_with("http://") || source.starts_with("https://");
+            if !is_url && !crate::path_utils::is_safe_path(std::path::Path::new(source)) {
+                log::warn!("Security Warning: Unsafe path detected in calendar.sources: {}", source);
+            }
+        }
+        if let Some(todo) = &self.tasks.todo_file {
+            if !crate::path_utils::is_safe_path(std::path::Path::new(todo)) {
+                log::warn!("Security Warning: Unsafe path detected in tasks.todo_file: {}", todo);
+            }
+        }
+        for cmd in &self.commands {
+            if cmd.metric_id.is_empty() || cmd.command.is_empty() {
+                bail!("commands: each entry needs a metric_id and a command");
+            }
+            if cmd.interval_secs == 0 || cmd.timeout_secs == 0 {
+                bail!("commands.{}: interval_secs and timeout_secs must be at least 1", cmd.metric_id);
+            }
+            if cmd.command[0].contains('/') && !crate::path_utils::is_safe_path(std::path::Path::new(&cmd.command[0])) {
+                log::warn!("Security Warning: Unsafe command path for '{}': {}", cmd.metric_id, cmd.command[0]);
+            }
+        }
         for repo in &self.productivity.repos {
             if !crate::path_utils::is_safe_path(std::path::Path::new(repo)) {
                 log::warn!("Security Warning: Unsafe Git repo path: {}", repo);
@@ -304,7 +2240,7 @@ impl From<&Config> for MetricsConfig {
         let mut metrics = std::collections::HashSet::new();
         for screen in &config.screens {
             for m in &screen.metrics {
-                if !config.weather.enabled && (m == "weather_temp" || m == "weather_condition") {
+                if !config.weather.enabled && is_weather_metric(m) {
                     continue;
                 }
                 metrics.insert(m.clone());
@@ -0,0 +1,313 @@
+//! Ambient background effects drawn behind the metrics.
+//!
+//! The classic rain (`RainManager`) and the alternatives here ("starfield", "plasma" and
+//! "life") share the `BackgroundEffect` trait, so the renderer can hold whichever one the
+//! monitor's `rain_mode` selects. All of them draw in bounds-local coordinates.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use cairo::Context as CairoContext;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::Config;
+use crate::metrics::MetricData;
+use crate::render::Palette;
+
+/// What a background runs with on one monitor: the config plus the rain settings that
+/// can differ per screen (its `rain_mode`, and the boost of a screensaver takeover).
+pub struct EffectSettings<'a> {
+    pub config: &'a Config,
+    pub mode: &'a str,
+    pub realism_scale: u32,
+    pub brightness: f64,
+    pub speed: f64,
+}
+
+impl<'a> EffectSettings<'a> {
+    /// The settings as configured, before any per-monitor override.
+    pub fn new(config: &'a Config) -> Self {
+        Self {
+            config,
+            mode: &config.cosmetics.rain_mode,
+            realism_scale: config.cosmetics.realism_scale,
+            brightness: config.cosmetics.matrix_brightness,
+            speed: config.cosmetics.rain_speed,
+        }
+    }
+}
+
+/// An animated background.
+pub trait BackgroundEffect {
+    /// Advances the animation by `dt` for a `width` x `height` area.
+    fn update(&mut self, dt: Duration, width: i32, height: i32, settings: &EffectSettings, metrics: &MetricData);
+
+    /// Draws the current state into `cr`.
+    fn draw(&self, cr: &CairoContext, width: f64, height: f64, frame_count: u64, settings: &EffectSettings, palette: &Palette) -> Result<()>;
+}
+
+/// True for modes that move every frame and want the fast frame rate (all but "pulse" and "off").
+pub fn is_animated(mode: &str) -> bool {
+    matches!(mode, "fall" | "reactive" | "starfield" | "plasma" | "life")
+}
+
+/// The effect for one of the ambient modes; `None` for the rain modes, "pulse" and "off",
+/// which the renderer handles itself.
+pub fn ambient_effect(mode: &str, seed: Option<u64>) -> Option<Box<dyn BackgroundEffect>> {
+    let rng = || match seed {
+        Some(seed) => SmallRng::seed_from_u64(seed),
+        None => SmallRng::from_entropy(),
+    };
+    match mode {
+        "starfield" => Some(Box::new(Starfield::new(rng()))),
+        "plasma" => Some(Box::new(Plasma::default())),
+        "life" => Some(Box::new(Life::new(rng()))),
+        _ => None,
+    }
+}
+
+/// Stars flying out from the center of the screen.
+pub struct Starfield {
+    /// Position relative to the center (-1.0..1.0) and depth (0.0 is the viewer, 1.0 the far plane).
+    stars: Vec<(f64, f64, f64)>,
+    rng: SmallRng,
+}
+
+/// Depth units a star covers per second at `rain_speed` 1.0.
+const STAR_SPEED: f64 = 0.25;
+
+impl Starfield {
+    fn new(rng: SmallRng) -> Self {
+        Self { stars: Vec::new(), rng }
+    }
+
+    fn spawn(rng: &mut SmallRng) -> (f64, f64, f64) {
+        (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(0.2..1.0))
+    }
+}
+
+impl BackgroundEffect for Starfield {
+    fn update(&mut self, dt: Duration, _width: i32, _height: i32, settings: &EffectSettings, _metrics: &MetricData) {
+        // Same density scale as the rain, capped like it
+        let count = (settings.realism_scale as usize * 30).min(500);
+        let rng = &mut self.rng;
+        self.stars.resize_with(count, || Self::spawn(rng));
+
+        let dz = STAR_SPEED * dt.as_secs_f64() * settings.speed;
+        for star in &mut self.stars {
+            star.2 -= dz;
+            // Past the viewer or off screen: respawn at the far plane
+            if star.2 <= 0.05 || (star.0 / star.2).abs() > 1.0 || (star.1 / star.2).abs() > 1.0 {
+                *star = (rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 1.0);
+            }
+        }
+    }
+
+    fn draw(&self, cr: &CairoContext, width: f64, height: f64, _frame_count: u64, settings: &EffectSettings, palette: &Palette) -> Result<()> {
+        let (cx, cy) = (width / 2.0, height / 2.0);
+        let (r, g, b) = palette.lead;
+        cr.save()?;
+        for &(x, y, z) in &self.stars {
+            // Nearer stars are bigger and brighter
+            let closeness = 1.0 - z;
+            let size = 1.0 + 2.0 * closeness;
+            cr.set_source_rgba(r, g, b, (0.2 + 0.8 * closeness) * settings.brightness);
+            cr.rectangle(cx + x / z * cx, cy + y / z * cy, size, size);
+            cr.fill()?;
+        }
+        cr.restore()?;
+        Ok(())
+    }
+}
+
+/// Slowly drifting interference of sine waves, drawn on a coarse grid.
+#[derive(Default)]
+pub struct Plasma {
+    /// Animation time in seconds (scaled by `rain_speed`).
+    time: f64,
+}
+
+/// Size in pixels of a plasma cell; coarse enough to stay cheap on large monitors.
+const PLASMA_CELL: f64 = 32.0;
+
+impl Plasma {
+    /// Intensity (0.0-1.0) at cell (`x`, `y`) at `time`.
+    fn intensity(x: f64, y: f64, time: f64) -> f64 {
+        let v = (x * 0.35 + time).sin()
+            + (y * 0.3 - time * 0.7).sin()
+            + ((x + y) * 0.2 + time * 0.5).sin()
+            + ((x * x + y * y).sqrt() * 0.25 - time).sin();
+        (v / 4.0 + 1.0) / 2.0
+    }
+}
+
+impl BackgroundEffect for Plasma {
+    fn update(&mut self, dt: Duration, _width: i32, _height: i32, settings: &EffectSettings, _metrics: &MetricData) {
+        self.time += dt.as_secs_f64() * settings.speed;
+    }
+
+    fn draw(&self, cr: &CairoContext, width: f64, height: f64, _frame_count: u64, settings: &EffectSettings, palette: &Palette) -> Result<()> {
+        let (r, g, b) = palette.rain;
+        let columns = (width / PLASMA_CELL).ceil() as usize;
+        let rows = (height / PLASMA_CELL).ceil() as usize;
+        cr.save()?;
+        for row in 0..rows {
+            for column in 0..columns {
+                let value = Self::intensity(column as f64, row as f64, self.time);
+                // Kept dim: the plasma is a backdrop for the metrics, not competition
+                cr.set_source_rgba(r, g, b, value * value * 0.35 * settings.brightness);
+                cr.rectangle(column as f64 * PLASMA_CELL, row as f64 * PLASMA_CELL, PLASMA_CELL, PLASMA_CELL);
+                cr.fill()?;
+            }
+        }
+        cr.restore()?;
+        Ok(())
+    }
+}
+
+/// Conway's Game of Life on a wrapping grid, reseeded when it dies out or settles.
+pub struct Life {
+    cells: Vec<bool>,
+    /// Cells that came alive in the latest generation (drawn brighter).
+    born: Vec<bool>,
+    columns: usize,
+    rows: usize,
+    /// Time carried over towards the next generation.
+    pending: Duration,
+    /// Generations since the last reseed.
+    generation: u32,
+    rng: SmallRng,
+}
+
+/// Size in pixels of a life cell.
+const LIFE_CELL: f64 = 16.0;
+/// Time between generations at `rain_speed` 1.0.
+const LIFE_GENERATION: Duration = Duration::from_millis(250);
+/// Share of live cells in a fresh grid.
+const LIFE_SEED_DENSITY: f64 = 0.25;
+/// Generations after which the grid is reseeded even if it is still changing, since
+/// most soups end in a handful of oscillators long before this.
+const LIFE_MAX_GENERATIONS: u32 = 600;
+
+impl Life {
+    fn new(rng: SmallRng) -> Self {
+        Self { cells: Vec::new(), born: Vec::new(), columns: 0, rows: 0, pending: Duration::ZERO, generation: 0, rng }
+    }
+
+    fn reseed(&mut self, columns: usize, rows: usize) {
+        self.columns = columns;
+        self.rows = rows;
+        let rng = &mut self.rng;
+        self.cells = (0..columns * rows).map(|_| rng.gen_bool(LIFE_SEED_DENSITY)).collect();
+        self.born = self.cells.clone();
+        self.generation = 0;
+    }
+
+    fn live_neighbours(&self, column: usize, row: usize) -> usize {
+        let mut count = 0;
+        for dy in [self.rows - 1, 0, 1] {
+            for dx in [self.columns - 1, 0, 1] {
+                if (dx, dy) != (0, 0) && self.cells[(row + dy) % self.rows * self.columns + (column + dx) % self.columns] {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Advances one generation; returns false if nothing changed.
+    fn step(&mut self) -> bool {
+        let next: Vec<bool> = (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let neighbours = self.live_neighbours(column, row);
+                matches!((self.cells[row * self.columns + column], neighbours), (true, 2) | (_, 3))
+            })
+            .collect();
+        let changed = next != self.cells;
+        self.born = next.iter().zip(&self.cells).map(|(&now, &before)| now && !before).collect();
+        self.cells = next;
+        self.generation += 1;
+        changed
+    }
+}
+
+impl BackgroundEffect for Life {
+    fn update(&mut self, dt: Duration, width: i32, height: i32, settings: &EffectSettings, _metrics: &MetricData) {
+        let columns = ((width as f64 / LIFE_CELL) as usize).max(3);
+        let rows = ((height as f64 / LIFE_CELL) as usize).max(3);
+        if (columns, rows) != (self.columns, self.rows) {
+            self.reseed(columns, rows);
+        }
+        if settings.speed <= 0.0 {
+            return;
+        }
+        self.pending += dt.mul_f64(settings.speed);
+        while self.pending >= LIFE_GENERATION {
+            self.pending -= LIFE_GENERATION;
+            let population = self.cells.iter().filter(|&&alive| alive).count();
+            if !self.step() || population * 50 < self.cells.len() || self.generation >= LIFE_MAX_GENERATIONS {
+                self.reseed(columns, rows);
+            }
+        }
+    }
+
+    fn draw(&self, cr: &CairoContext, _width: f64, _height: f64, _frame_count: u64, settings: &EffectSettings, palette: &Palette) -> Result<()> {
+        let brightness = settings.brightness;
+        cr.save()?;
+        for (i, (&alive, &born)) in self.cells.iter().zip(&self.born).enumerate() {
+            if !alive {
+                continue;
+            }
+            let (r, g, b) = if born { palette.lead } else { palette.rain };
+            cr.set_source_rgba(r, g, b, 0.45 * brightness);
+            let (column, row) = (i % self.columns, i / self.columns);
+            cr.rectangle(column as f64 * LIFE_CELL + 1.0, row as f64 * LIFE_CELL + 1.0, LIFE_CELL - 2.0, LIFE_CELL - 2.0);
+            cr.fill()?;
+        }
+        cr.restore()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_life_blinker_oscillates() {
+        let mut life = Life::new(SmallRng::seed_from_u64(1));
+        life.reseed(5, 5);
+        life.cells = vec![false; 25];
+        for column in 1..4 {
+            life.cells[2 * 5 + column] = true;
+        }
+        let horizontal = life.cells.clone();
+
+        assert!(life.step());
+        let vertical: Vec<usize> = (0..25).filter(|&i| life.cells[i]).collect();
+        assert_eq!(vertical, vec![7, 12, 17]);
+        assert!(life.born[7] && life.born[17] && !life.born[12]);
+
+        assert!(life.step());
+        assert_eq!(life.cells, horizontal);
+    }
+
+    #[test]
+    fn test_ambient_effects_by_mode() {
+        assert!(ambient_effect("starfield", Some(1)).is_some());
+        assert!(ambient_effect("plasma", None).is_some());
+        assert!(ambient_effect("life", Some(1)).is_some());
+        assert!(ambient_effect("fall", None).is_none());
+        assert!(ambient_effect("pulse", None).is_none());
+
+        assert!(is_animated("life") && is_animated("reactive"));
+        assert!(!is_animated("pulse") && !is_animated("off"));
+
+        for x in 0..20 {
+            let value = Plasma::intensity(x as f64, 3.0, 1.5);
+            assert!((0.0..=1.0).contains(&value));
+        }
+    }
+}
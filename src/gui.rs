@@ -1,20 +1,33 @@
-use gtk::prelude::*;
-use gtk::{Window, WindowType, Notebook, Box, Orientation, Label, CheckButton, SpinButton, ComboBoxText, Button, Entry, ListBox};
-use std::sync::Arc;
-use crossbeam_channel::Sender;
-use crate::config::Config;
+// Everything but `GuiEvent` needs the `gui` feature (GTK)
+#[cfg(feature = "gui")]
+use {
+    gtk::prelude::*,
+    gtk::{glib, Window, WindowType, Notebook, Box, Orientation, Label, CheckButton, SpinButton, ComboBoxText, Button, Entry, EntryIconPosition, ListBox},
+    std::cell::RefCell,
+    std::rc::Rc,
+    std::sync::Arc,
+    crossbeam_channel::Sender,
+    crate::config::{Config, Thresholds},
+    crate::metrics::{search_locations, GeoLocation},
+    crate::stats::UsageStats,
+};
 
 pub enum GuiEvent {
     Reload,
     PurgeLogs,
     OpenConfig,
+    ViewLogs,
+    /// Swap the renderers' palettes to this theme without a full config reload.
+    SetTheme(String),
 }
 
+#[cfg(feature = "gui")]
 pub struct ConfigWindow {
     config: Arc<Config>,
     event_tx: Sender<GuiEvent>,
 }
 
+#[cfg(feature = "gui")]
 impl ConfigWindow {
     pub fn new(config: Config, event_tx: Sender<GuiEvent>) -> Self {
         Self {
@@ -41,6 +54,15 @@ impl ConfigWindow {
         theme_combo.set_active_id(Some(&self.config.general.theme));
         vbox_gen.pack_start(&theme_combo, false, false, 0);
 
+        vbox_gen.pack_start(&Label::new(Some("Night Theme (after sunset at the weather location)")), false, false, 0);
+        let night_theme_combo = ComboBoxText::new();
+        night_theme_combo.append(Some("none"), "same as day");
+        for theme in ["classic", "calm", "alert"] {
+            night_theme_combo.append(Some(theme), theme);
+        }
+        night_theme_combo.set_active_id(Some(self.config.general.night_theme.as_deref().unwrap_or("none")));
+        vbox_gen.pack_start(&night_theme_combo, false, false, 0);
+
         vbox_gen.pack_start(&Label::new(Some("Matrix Font Size (Rain)")), false, false, 0);
         let font_spin = SpinButton::with_range(12.0, 72.0, 1.0);
         font_spin.set_value(self.config.general.font_size as f64);
@@ -56,10 +78,25 @@ impl ConfigWindow {
         update_spin.set_value(self.config.general.update_ms as f64);
         vbox_gen.pack_start(&update_spin, false, false, 0);
 
+        let check_low_power = CheckButton::with_label("Low-Power Profile (static text, 1 min refresh, for e-ink)");
+        check_low_power.set_active(self.config.is_low_power());
+        vbox_gen.pack_start(&check_low_power, false, false, 0);
+
         let check_monitor_label = CheckButton::with_label("Show Monitor Labels (e.g., Monitor 1)");
         check_monitor_label.set_active(self.config.general.show_monitor_label);
         vbox_gen.pack_start(&check_monitor_label, false, false, 0);
 
+        vbox_gen.pack_start(&Label::new(Some("Units")), false, false, 0);
+        let units_combo = ComboBoxText::new();
+        units_combo.append(Some("metric"), "Metric (°C, km/h, mm)");
+        units_combo.append(Some("imperial"), "Imperial (°F, mph, in)");
+        units_combo.set_active_id(Some(if self.config.general.units == "imperial" { "imperial" } else { "metric" }));
+        vbox_gen.pack_start(&units_combo, false, false, 0);
+
+        let check_network_bits = CheckButton::with_label("Network Rates in Bits (Mb/s)");
+        check_network_bits.set_active(self.config.general.network_bits);
+        vbox_gen.pack_start(&check_network_bits, false, false, 0);
+
         notebook.append_page(&vbox_gen, Some(&Label::new(Some("General"))));
 
         // --- 2. Metrics Tab (REORDERABLE) ---
@@ -75,12 +112,59 @@ impl ConfigWindow {
             ("ram_usage", "RAM Usage (%)"),
             ("gpu_temp", "GPU Temperature"),
             ("gpu_util", "GPU Utilization"),
+            ("gpu_vram", "GPU VRAM Used/Total"),
+            ("gpu_power", "GPU Power Draw"),
+            ("gpu_clock", "GPU Clock Speed"),
+            ("gpu_state", "GPU Power State (on/off)"),
             ("disk_usage", "Disk Usage (%)"),
+            ("disk_summary", "Disk Usage (All Mounts)"),
+            ("disk_io", "Disk I/O (Read/Write)"),
             ("uptime", "System Uptime"),
             ("network_details", "Network Details"),
             ("weather_temp", "Weather Temperature"),
+            ("weather_humidity", "Humidity"),
+            ("weather_wind", "Wind Speed/Direction"),
+            ("weather_precipitation", "Precipitation"),
+            ("weather_high", "Forecast High"),
+            ("weather_low", "Forecast Low"),
+            ("weather_rain_prob", "Chance of Rain"),
+            ("weather_forecast", "3-Day Forecast"),
+            ("air_quality", "Air Quality Index (US AQI)"),
+            ("pm2_5", "Fine Particulates (PM2.5)"),
+            ("pm10", "Coarse Particulates (PM10)"),
+            ("pollen", "Pollen (Europe)"),
+            ("uv_index", "UV Index"),
+            ("sunrise", "Sunrise"),
+            ("sunset", "Sunset"),
+            ("daylight_remaining", "Daylight Remaining"),
+            ("moon_phase", "Moon Phase"),
+            ("public_ip", "Public IP Address"),
+            ("vpn_status", "VPN Status"),
+            ("wifi_ssid", "Wi-Fi Network (SSID)"),
+            ("wifi_signal", "Wi-Fi Signal Strength"),
+            ("now_playing", "Now Playing (MPRIS)"),
+            ("audio_volume", "Audio Volume (%)"),
+            ("audio_mute", "Audio Mute State"),
+            ("audio_device", "Audio Output Device"),
+            ("updates_pending", "Pending Package Updates"),
+            ("failed_units", "Failed systemd Units"),
+            ("gh_notifications", "GitHub Notifications"),
+            ("gh_review_queue", "GitHub Review Queue"),
             ("code_delta", "Git Code Delta (+/-)"),
+            ("git_status", "Git Branch & Working State"),
+            ("auto_commits_today", "Auto-Commits Today"),
+            ("auto_commit_next", "Next Auto-Commit Check"),
+            ("auto_commit_last", "Last Auto-Commit Result"),
+            ("keystrokes_today", "Keystrokes Today"),
+            ("wpm_now", "Typing Speed (WPM)"),
+            ("top_app", "Top App Today"),
+            ("focused_time", "Focused Time Today"),
+            ("tasks", "Pending Tasks (Taskwarrior/todo.txt)"),
+            ("next_event", "Next Calendar Event"),
             ("fan_speed", "Fan Speed (RPM)"),
+            ("top_processes", "Top Processes"),
+            ("overlay_memory", "Overlay Memory (RSS)"),
+            ("overlay_render", "Renderer State"),
         ];
 
         // Current order from config, followed by any missing ones
@@ -224,7 +308,21 @@ impl ConfigWindow {
         check_weather_enabled.set_active(self.config.weather.enabled);
         vbox_weath.pack_start(&check_weather_enabled, false, false, 0);
 
-        vbox_weath.pack_start(&Label::new(Some("Location (0.0/0.0 triggers Geo-IP Auto)")), false, false, 0);
+        vbox_weath.pack_start(&Label::new(Some("Location (0.0/0.0 uses Geo-IP if enabled below)")), false, false, 0);
+
+        // City search (Open-Meteo geocoding) filling in the coordinates below
+        let hbox_search = Box::new(Orientation::Horizontal, 5);
+        let search_entry = Entry::new();
+        search_entry.set_placeholder_text(Some("City name"));
+        let btn_search = Button::with_label("Search");
+        hbox_search.pack_start(&search_entry, true, true, 0);
+        hbox_search.pack_start(&btn_search, false, false, 0);
+        vbox_weath.pack_start(&hbox_search, false, false, 0);
+        let results_combo = ComboBoxText::new();
+        vbox_weath.pack_start(&results_combo, false, false, 0);
+        let search_status = Label::new(None);
+        vbox_weath.pack_start(&search_status, false, false, 0);
+
         vbox_weath.pack_start(&Label::new(Some("Latitude")), false, false, 0);
         let lat_spin = SpinButton::with_range(-90.0, 90.0, 0.0001);
         lat_spin.set_value(self.config.weather.lat);
@@ -235,9 +333,129 @@ impl ConfigWindow {
         lon_spin.set_value(self.config.weather.lon);
         vbox_weath.pack_start(&lon_spin, false, false, 0);
 
+        let found: Rc<RefCell<Vec<GeoLocation>>> = Rc::new(RefCell::new(Vec::new()));
+        {
+            let (found, lat_spin, lon_spin) = (found.clone(), lat_spin.clone(), lon_spin.clone());
+            results_combo.connect_changed(move |combo| {
+                let index = combo.active().map(|i| i as usize);
+                if let Some(place) = index.and_then(|i| found.borrow().get(i).cloned()) {
+                    lat_spin.set_value(place.latitude);
+                    lon_spin.set_value(place.longitude);
+                }
+            });
+        }
+        {
+            let (search_entry, results_combo, search_status) = (search_entry.clone(), results_combo.clone(), search_status.clone());
+            let search = move |button: &Button| {
+                let name = search_entry.text().trim().to_string();
+                if name.is_empty() {
+                    return;
+                }
+                button.set_sensitive(false);
+                search_status.set_text("Searching...");
+
+                // The lookup blocks, so run it off the GTK thread and hand the result back
+                let (tx, rx) = glib::MainContext::channel(glib::PRIORITY_DEFAULT);
+                std::thread::spawn(move || {
+                    let _ = tx.send(search_locations(&name));
+                });
+                let (button, found, results_combo, search_status) = (button.clone(), found.clone(), results_combo.clone(), search_status.clone());
+                rx.attach(None, move |result| {
+                    button.set_sensitive(true);
+                    results_combo.remove_all();
+                    match result {
+                        Ok(places) if places.is_empty() => search_status.set_text("No matching places"),
+                        Ok(places) => {
+                            search_status.set_text(&format!("{} result(s); pick one to fill in the coordinates", places.len()));
+                            *found.borrow_mut() = places;
+                            for place in found.borrow().iter() {
+                                results_combo.append_text(&place.display_name());
+                            }
+                        }
+                        Err(e) => {
+                            log::warn!("Location search failed: {:#}", e);
+                            search_status.set_text("Search failed (offline?)");
+                        }
+                    }
+                    glib::Continue(false)
+                });
+            };
+            btn_search.connect_clicked(search);
+        }
+        let btn = btn_search.clone();
+        search_entry.connect_activate(move |_| btn.clicked());
+
+        let check_geoip = CheckButton::with_label("Geo-IP location when 0.0/0.0 (sends your IP to ipapi.co)");
+        check_geoip.set_active(self.config.weather.geoip);
+        vbox_weath.pack_start(&check_geoip, false, false, 0);
+
         notebook.append_page(&vbox_weath, Some(&Label::new(Some("Weather"))));
 
-        // --- 6. Advanced Tab ---
+        // --- 6. Alerts Tab ---
+        let vbox_alerts = Box::new(Orientation::Vertical, 10);
+        vbox_alerts.set_border_width(10);
+        vbox_alerts.pack_start(&Label::new(Some("Alert Rules (leave Warn/Crit empty to skip a level)")), false, false, 0);
+
+        // Metrics the active collectors produce (everything on a screen or the HUD, and computed
+        // metrics), plus any already with rules
+        let mut alert_metrics: Vec<String> = Vec::new();
+        let shown = self.config.screens.iter().flat_map(|s| s.metrics.iter()).chain(self.config.hud.metrics.iter());
+        for id in shown.chain(self.config.computed_metrics.iter().map(|c| &c.metric_id)) {
+            if id != "day_of_week" && !alert_metrics.contains(id) {
+                alert_metrics.push(id.clone());
+            }
+        }
+        let mut existing_rules: Vec<(String, Thresholds)> = self
+            .config
+            .metric_settings
+            .iter()
+            .filter_map(|(id, settings)| settings.thresholds.clone().map(|t| (id.clone(), t)))
+            .collect();
+        existing_rules.sort_by(|a, b| a.0.cmp(&b.0));
+        for (id, _) in &existing_rules {
+            if !alert_metrics.contains(id) {
+                alert_metrics.push(id.clone());
+            }
+        }
+
+        let rules_vbox = Box::new(Orientation::Vertical, 5);
+        let rules: Rc<RefCell<Vec<AlertRuleRow>>> = Rc::new(RefCell::new(Vec::new()));
+        let add_rule = {
+            let (rules_vbox, rules) = (rules_vbox.clone(), rules.clone());
+            move |metric: Option<&str>, thresholds: Option<&Thresholds>| {
+                let row = AlertRuleRow::new(&alert_metrics, metric, thresholds);
+                rules_vbox.pack_start(&row.container, false, false, 0);
+                row.container.show_all();
+
+                let (rules_remove, rules_vbox_remove, container) = (rules.clone(), rules_vbox.clone(), row.container.clone());
+                row.btn_remove.connect_clicked(move |_| {
+                    rules_remove.borrow_mut().retain(|r| r.container != container);
+                    rules_vbox_remove.remove(&container);
+                });
+                let row_test = row.clone();
+                row.btn_test.connect_clicked(move |_| {
+                    if let Ok(Some((metric, thresholds))) = row_test.rule() {
+                        crate::alerts::test_fire(&metric, &thresholds);
+                    }
+                });
+                rules.borrow_mut().push(row);
+            }
+        };
+        for (id, thresholds) in &existing_rules {
+            add_rule(Some(id), Some(thresholds));
+        }
+        vbox_alerts.pack_start(&rules_vbox, false, false, 0);
+
+        let btn_add_rule = Button::with_label("Add Rule");
+        btn_add_rule.connect_clicked(move |_| add_rule(None, None));
+        vbox_alerts.pack_start(&btn_add_rule, false, false, 0);
+        let alert_error = Label::new(None);
+        alert_error.set_halign(gtk::Align::Start);
+        vbox_alerts.pack_start(&alert_error, false, false, 0);
+
+        let alerts_page = notebook.append_page(&vbox_alerts, Some(&Label::new(Some("Alerts"))));
+
+        // --- 7. Advanced Tab ---
         let vbox_adv = Box::new(Orientation::Vertical, 10);
         vbox_adv.set_border_width(10);
         
@@ -247,6 +465,42 @@ impl ConfigWindow {
         
         notebook.append_page(&vbox_adv, Some(&Label::new(Some("Advanced"))));
 
+        // --- 8. About Tab ---
+        let vbox_about = Box::new(Orientation::Vertical, 6);
+        vbox_about.set_border_width(10);
+        vbox_about.pack_start(&Label::new(Some("Matrix Overlay v2")), false, false, 0);
+        for (name, value) in crate::version::build_info() {
+            let row = Label::new(Some(&format!("{}: {}", name, value)));
+            row.set_halign(gtk::Align::Start);
+            row.set_selectable(true);
+            vbox_about.pack_start(&row, false, false, 0);
+        }
+        notebook.append_page(&vbox_about, Some(&Label::new(Some("About"))));
+
+        // --- 9. Stats Tab ---
+        // Local counters only (see `stats`); they help pick an update interval and density
+        let vbox_stats = Box::new(Orientation::Vertical, 6);
+        vbox_stats.set_border_width(10);
+        let stats_label = Label::new(None);
+        stats_label.set_halign(gtk::Align::Start);
+        stats_label.set_selectable(true);
+        let update_ms = self.config.general.update_ms;
+        let show_stats = {
+            let stats_label = stats_label.clone();
+            move || {
+                if let Ok(stats) = UsageStats::shared().lock() {
+                    stats_label.set_text(&stats.summary(update_ms).join("\n"));
+                }
+            }
+        };
+        show_stats();
+        vbox_stats.pack_start(&stats_label, false, false, 0);
+        let btn_stats_refresh = Button::with_label("Refresh");
+        btn_stats_refresh.connect_clicked(move |_| show_stats());
+        vbox_stats.pack_start(&btn_stats_refresh, false, false, 0);
+        vbox_stats.pack_start(&Label::new(Some("Kept in memory for this session only; nothing is saved or sent.")), false, false, 0);
+        notebook.append_page(&vbox_stats, Some(&Label::new(Some("Stats"))));
+
         // --- Bottom Actions ---
         let main_vbox = Box::new(Orientation::Vertical, 10);
         main_vbox.pack_start(&notebook, true, true, 5);
@@ -261,15 +515,30 @@ impl ConfigWindow {
         // Wiring logic
         let tx = self.event_tx.clone();
         let config_arc = self.config.clone();
+        let notebook_save = notebook.clone();
         btn_save.connect_clicked(move |_| {
+            // A limit that isn't a number would silently drop its rule; nothing is saved until it's fixed
+            let alert_rules: Vec<(String, Thresholds)> = match rules.borrow().iter().map(AlertRuleRow::rule).collect::<Result<Vec<_>, _>>() {
+                Ok(rows) => rows.into_iter().flatten().collect(),
+                Err(e) => {
+                    alert_error.set_markup(&format!("<span foreground=\"red\">{}</span>", glib::markup_escape_text(&e)));
+                    notebook_save.set_current_page(Some(alerts_page));
+                    return;
+                }
+            };
+            alert_error.set_text("");
             let mut new_config = (*config_arc).clone();
             
             // General
             new_config.general.theme = theme_combo.active_text().map(|s| s.to_string()).unwrap_or_else(|| "classic".to_string());
+            new_config.general.night_theme = night_theme_combo.active_id().map(|s| s.to_string()).filter(|s| s != "none");
             new_config.general.font_size = font_spin.value() as u32;
             new_config.general.metric_font_size = metric_font_spin.value() as u32;
             new_config.general.update_ms = update_spin.value() as u64;
             new_config.general.show_monitor_label = check_monitor_label.is_active();
+            new_config.general.profile = if check_low_power.is_active() { "low_power" } else { "standard" }.to_string();
+            new_config.general.units = units_combo.active_id().map(|s| s.to_string()).unwrap_or_else(|| "metric".to_string());
+            new_config.general.network_bits = check_network_bits.is_active();
             
             // Cosmetics
             new_config.cosmetics.rain_speed = speed_spin.value();
@@ -293,6 +562,15 @@ impl ConfigWindow {
             new_config.weather.enabled = check_weather_enabled.is_active();
             new_config.weather.lat = lat_spin.value();
             new_config.weather.lon = lon_spin.value();
+            new_config.weather.geoip = check_geoip.is_active();
+
+            // Alert rules replace all previous thresholds
+            for settings in new_config.metric_settings.values_mut() {
+                settings.thresholds = None;
+            }
+            for (metric, thresholds) in alert_rules {
+                new_config.metric_settings.entry(metric).or_default().thresholds = Some(thresholds);
+            }
 
             // Metrics Selection & Order (Extracted from UI order)
             let mut active_metrics = Vec::new();
@@ -320,7 +598,12 @@ impl ConfigWindow {
             if let Err(e) = new_config.save() {
                 log::error!("Failed to save config: {}", e);
             }
-            let _ = tx.send(GuiEvent::Reload);
+            // A theme-only change skips the full reload (layout, metrics thread restart)
+            let mut unchanged = new_config.clone();
+            unchanged.general.theme = config_arc.general.theme.clone();
+            let theme_only = new_config.general.theme != config_arc.general.theme
+                && serde_json::to_value(&unchanged).ok() == serde_json::to_value(&*config_arc).ok();
+            let _ = tx.send(if theme_only { GuiEvent::SetTheme(new_config.general.theme.clone()) } else { GuiEvent::Reload });
         });
 
         let tx_purge = self.event_tx.clone();
@@ -337,3 +620,99 @@ impl ConfigWindow {
         window.show_all();
     }
 }
+
+/// One editable row of the Alerts tab: metric, comparison, warn/crit limits and actions.
+#[cfg(feature = "gui")]
+#[derive(Clone)]
+struct AlertRuleRow {
+    container: Box,
+    metric: ComboBoxText,
+    comparison: ComboBoxText,
+    warn: Entry,
+    crit: Entry,
+    notify: CheckButton,
+    btn_test: Button,
+    btn_remove: Button,
+    /// Colors and debounce aren't editable here; kept so saving doesn't reset them.
+    base: Option<Thresholds>,
+}
+
+#[cfg(feature = "gui")]
+impl AlertRuleRow {
+    fn new(metrics: &[String], metric: Option<&str>, thresholds: Option<&Thresholds>) -> Self {
+        let container = Box::new(Orientation::Horizontal, 5);
+
+        let metric_combo = ComboBoxText::with_entry();
+        for id in metrics {
+            metric_combo.append(Some(id), id);
+        }
+        if let Some(entry) = metric_combo.child().and_then(|c| c.downcast::<Entry>().ok()) {
+            entry.set_text(metric.unwrap_or(""));
+            entry.set_placeholder_text(Some("metric"));
+        }
+
+        let comparison = ComboBoxText::new();
+        comparison.append(Some("above"), "≥");
+        comparison.append(Some("below"), "≤");
+        comparison.set_active_id(Some(if thresholds.is_some_and(|t| t.below) { "below" } else { "above" }));
+
+        let limit_entry = |value: Option<f64>, placeholder: &str| {
+            let entry = Entry::new();
+            entry.set_width_chars(6);
+            entry.set_placeholder_text(Some(placeholder));
+            entry.set_text(&value.map(|v| v.to_string()).unwrap_or_default());
+            // Flag a limit that isn't a number while it's typed
+            entry.connect_changed(|entry| {
+                let invalid = Self::limit(entry).is_err();
+                entry.set_icon_from_icon_name(EntryIconPosition::Secondary, invalid.then_some("dialog-error"));
+                entry.set_icon_tooltip_text(EntryIconPosition::Secondary, invalid.then_some("Not a number"));
+            });
+            entry
+        };
+        let warn = limit_entry(thresholds.and_then(|t| t.warn), "Warn");
+        let crit = limit_entry(thresholds.and_then(|t| t.crit), "Crit");
+
+        let notify = CheckButton::with_label("Notify");
+        notify.set_active(thresholds.is_some_and(|t| t.notify));
+        let btn_test = Button::with_label("Test");
+        let btn_remove = Button::with_label("✕");
+
+        container.pack_start(&metric_combo, true, true, 0);
+        container.pack_start(&comparison, false, false, 0);
+        container.pack_start(&warn, false, false, 0);
+        container.pack_start(&crit, false, false, 0);
+        container.pack_start(&notify, false, false, 0);
+        container.pack_start(&btn_test, false, false, 0);
+        container.pack_start(&btn_remove, false, false, 0);
+
+        Self { container, metric: metric_combo, comparison, warn, crit, notify, btn_test, btn_remove, base: thresholds.cloned() }
+    }
+
+    /// A limit entry's value: `None` if empty, an error if it isn't a number.
+    fn limit(entry: &Entry) -> Result<Option<f64>, String> {
+        let text = entry.text();
+        match text.trim() {
+            "" => Ok(None),
+            text => text.parse::<f64>().map(Some).map_err(|_| format!("'{}' is not a number", text)),
+        }
+    }
+
+    /// The rule as entered: `None` if it has no metric or no limit, an error if a limit
+    /// isn't a number.
+    fn rule(&self) -> Result<Option<(String, Thresholds)>, String> {
+        let Some(metric) = self.metric.active_text().map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) else {
+            return Ok(None);
+        };
+        let invalid = |e: String| format!("Alert rule for {}: {}", metric, e);
+        let (warn, crit) = (Self::limit(&self.warn).map_err(invalid)?, Self::limit(&self.crit).map_err(invalid)?);
+        if warn.is_none() && crit.is_none() {
+            return Ok(None);
+        }
+        let mut thresholds = self.base.clone().or_else(|| Thresholds::builtin(&metric)).unwrap_or_default();
+        thresholds.warn = warn;
+        thresholds.crit = crit;
+        thresholds.below = self.comparison.active_id().is_some_and(|id| id == "below");
+        thresholds.notify = self.notify.is_active();
+        Ok(Some((metric, thresholds)))
+    }
+}
@@ -0,0 +1,152 @@
+//! Shared HTTP client for network collectors.
+//! Every collector goes through one `reqwest` client (one connection pool) and a
+//! response cache keyed by URL and request headers, so collectors asking for the same resource within
+//! its TTL (several weather locations, Geo-IP lookups, forced refreshes) share a
+//! single outbound request.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+
+/// Timeout for a single request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Entries older than this are dropped when the cache is written to, whatever their TTL.
+const MAX_ENTRY_AGE: Duration = Duration::from_secs(24 * 3600);
+
+#[derive(Debug)]
+struct CachedResponse {
+    body: String,
+    fetched: Instant,
+}
+
+/// HTTP client with a response cache keyed by URL and headers. Only successful responses
+/// are cached.
+#[derive(Debug)]
+pub struct HttpCache {
+    client: reqwest::blocking::Client,
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl HttpCache {
+    pub fn new() -> Self {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("matrix-overlay/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_else(|_| reqwest::blocking::Client::new());
+        Self { client, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Process-wide instance used by the collectors.
+    pub fn shared() -> &'static HttpCache {
+        static SHARED: OnceLock<HttpCache> = OnceLock::new();
+        SHARED.get_or_init(HttpCache::new)
+    }
+
+    /// Body of `url`, served from the cache if it was fetched less than `ttl` ago.
+    pub fn get_text(&self, url: &str, ttl: Duration) -> Result<String> {
+        self.get_text_with_headers(url, &[], ttl)
+    }
+
+    /// Like `get_text`, sending extra request headers (e.g. `Authorization`). Requests with
+    /// different headers are cached apart, so one token's response never serves another.
+    pub fn get_text_with_headers(&self, url: &str, headers: &[(&str, &str)], ttl: Duration) -> Result<String> {
+        let key = cache_key(url, headers);
+        if let Some(body) = self.cached(&key, ttl) {
+            log::trace!("HTTP cache hit: {}", url);
+            return Ok(body);
+        }
+
+        let mut request = self.client.get(url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        // The lock isn't held across the request; a concurrent miss may fetch twice
+        let body = request
+            .send()
+            .and_then(|resp| resp.error_for_status())
+            .and_then(|resp| resp.text())
+            .with_context(|| format!("Request to {} failed", url))?;
+
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|_, entry| entry.fetched.elapsed() < MAX_ENTRY_AGE);
+            entries.insert(key, CachedResponse { body: body.clone(), fetched: Instant::now() });
+        }
+        Ok(body)
+    }
+
+    /// Like `get_text`, parsing the body as JSON.
+    pub fn get_json<T: DeserializeOwned>(&self, url: &str, ttl: Duration) -> Result<T> {
+        self.get_json_with_headers(url, &[], ttl)
+    }
+
+    /// Like `get_text_with_headers`, parsing the body as JSON.
+    pub fn get_json_with_headers<T: DeserializeOwned>(&self, url: &str, headers: &[(&str, &str)], ttl: Duration) -> Result<T> {
+        let body = self.get_text_with_headers(url, headers, ttl)?;
+        serde_json::from_str(&body).with_context(|| format!("Malformed response from {}", url))
+    }
+
+    fn cached(&self, key: &str, ttl: Duration) -> Option<String> {
+        let entries = self.entries.lock().ok()?;
+        entries.get(key).filter(|entry| entry.fetched.elapsed() < ttl).map(|entry| entry.body.clone())
+    }
+}
+
+/// The URL followed by the headers (names lowercased, sorted), one per line.
+fn cache_key(url: &str, headers: &[(&str, &str)]) -> String {
+    let mut headers: Vec<String> = headers.iter().map(|(name, value)| format!("{}: {}", name.to_ascii_lowercase(), value)).collect();
+    headers.sort();
+    std::iter::once(url.to_string()).chain(headers).collect::<Vec<_>>().join("\n")
+}
+
+impl Default for HttpCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_responses_are_shared_within_ttl() {
+        let mut server = mockito::Server::new();
+        let ok = server.mock("GET", "/ip").with_body("203.0.113.7\n").expect(2).create();
+        let missing = server.mock("GET", "/missing").with_status(404).expect(2).create();
+
+        let cache = HttpCache::new();
+        let url = format!("{}/ip", server.url());
+        assert_eq!(cache.get_text(&url, Duration::from_secs(60)).unwrap(), "203.0.113.7\n");
+        assert_eq!(cache.get_text(&url, Duration::from_secs(60)).unwrap(), "203.0.113.7\n");
+        // A zero TTL always goes to the network
+        cache.get_text(&url, Duration::ZERO).unwrap();
+        ok.assert();
+
+        // Errors are not cached
+        let url = format!("{}/missing", server.url());
+        assert!(cache.get_text(&url, Duration::from_secs(60)).is_err());
+        assert!(cache.get_text(&url, Duration::from_secs(60)).is_err());
+        missing.assert();
+    }
+
+    #[test]
+    fn test_cache_is_keyed_by_headers() {
+        let mut server = mockito::Server::new();
+        let alice = server.mock("GET", "/user").match_header("authorization", "Bearer a").with_body("alice").expect(1).create();
+        let bob = server.mock("GET", "/user").match_header("authorization", "Bearer b").with_body("bob").expect(1).create();
+
+        let cache = HttpCache::new();
+        let url = format!("{}/user", server.url());
+        let ttl = Duration::from_secs(60);
+        assert_eq!(cache.get_text_with_headers(&url, &[("Authorization", "Bearer a")], ttl).unwrap(), "alice");
+        assert_eq!(cache.get_text_with_headers(&url, &[("Authorization", "Bearer b")], ttl).unwrap(), "bob");
+        assert_eq!(cache.get_text_with_headers(&url, &[("authorization", "Bearer a")], ttl).unwrap(), "alice");
+        alice.assert();
+        bob.assert();
+    }
+}
@@ -0,0 +1,277 @@
+//! Mini-HUD: a small always-on-top window with a few key metrics.
+//!
+//! The desktop overlay sits below every window, so it is hidden while working fullscreen.
+//! The HUD floats above instead, shows only `hud.metrics` (no rain or header), and is
+//! toggled on its own with Ctrl+Alt+H or the tray. It has no decorations; dragging it
+//! anywhere with the left button asks the window manager to move it.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use xcb::x;
+
+use crate::config::{Config, Panel, Screen};
+use crate::layout;
+use crate::metrics::SharedMetrics;
+use crate::render::Renderer;
+use crate::window::{self, X11Present};
+
+/// `_NET_WM_MOVERESIZE` direction for moving the window with the pointer.
+const NET_WM_MOVERESIZE_MOVE: u32 = 8;
+
+pub struct MiniHud {
+    window: x::Window,
+    renderer: Renderer,
+    /// Renderer slot after the overlay's monitors, so its stats and seed don't collide with theirs.
+    index: usize,
+    visible: bool,
+    /// `view_config` of the current configuration, rebuilt on reload and theme changes
+    /// rather than cloned for every frame.
+    view: Config,
+}
+
+/// Configuration the HUD renderer draws with: the HUD's metrics as the only screen, no
+/// background, and laid out like a left-edge panel (stacked, no header or icon safe zone).
+fn view_config(config: &Config) -> Config {
+    let mut view = config.clone();
+    view.cosmetics.rain_mode = "off".to_string();
+    view.panel = Panel { enabled: true, edge: "left".to_string(), size: config.hud.width };
+    view.screens = vec![Screen {
+        metrics: config.hud.metrics.clone(),
+        x_offset: 0,
+        y_offset: 0,
+        margins: Default::default(),
+        item_options: config.screens.first().map(|s| s.item_options.clone()).unwrap_or_default(),
+        weather_location: None,
+        rain_exclusions: Vec::new(),
+        rain_mode: None,
+        columns: 1,
+        column_gap: 0,
+        spacing: 0,
+    }];
+    view
+}
+
+/// Height in pixels that fits `count` metric rows.
+fn height_for(count: usize, config: &Config) -> u16 {
+    let line_height = (config.general.metric_font_size as f64 * 1.5) as usize;
+    (count.max(1) * line_height + 2 * layout::PANEL_PADDING as usize) as u16
+}
+
+impl MiniHud {
+    /// Creates and shows the HUD window at `hud.x`, `hud.y`.
+    pub fn create(conn: &Arc<xcb::Connection>, config: &Config, index: usize) -> Result<Self> {
+        let screen = conn.get_setup().roots().next().context("No screen found")?;
+        let (root, depth, visual) = (screen.root(), screen.root_depth(), screen.root_visual());
+        let (width, height) = (config.hud.width, height_for(config.hud.metrics.len(), config));
+
+        let window: x::Window = conn.generate_id();
+        conn.send_and_check_request(&x::CreateWindow {
+            depth,
+            wid: window,
+            parent: root,
+            x: config.hud.x as i16,
+            y: config.hud.y as i16,
+            width,
+            height,
+            border_width: 0,
+            class: x::WindowClass::InputOutput,
+            visual,
+            value_list: &[
+                x::Cw::BackPixel(0),
+                x::Cw::EventMask(x::EventMask::EXPOSURE | x::EventMask::BUTTON_PRESS),
+            ],
+        })
+        .context("Failed to create the mini-HUD window")?;
+        setup_hud_properties(conn, window, config, width, height)?;
+
+        let view = view_config(config);
+        let layout = layout::compute(&view.screens[0], width, height, &view);
+        let target = X11Present::new(conn.clone(), window, width, height, config.general.pixmap_buffering).with_depth(depth);
+        let renderer = Renderer::new(width, height, index, layout, &view, Box::new(target))?;
+
+        conn.send_request(&x::MapWindow { window });
+        window::set_window_opacity(conn, window, config.cosmetics.window_opacity)?;
+        conn.flush()?;
+        log::info!("Mini-HUD shown at ({}, {}), {}x{}", config.hud.x, config.hud.y, width, height);
+        Ok(Self { window, renderer, index, visible: true, view })
+    }
+
+    pub fn window(&self) -> x::Window {
+        self.window
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Shows or hides the HUD; it keeps its position while hidden.
+    pub fn set_visible(&mut self, conn: &xcb::Connection, visible: bool) {
+        self.visible = visible;
+        if visible {
+            conn.send_request(&x::MapWindow { window: self.window });
+        } else {
+            conn.send_request(&x::UnmapWindow { window: self.window });
+        }
+        let _ = conn.flush();
+    }
+
+    pub fn draw(&mut self, shared: &SharedMetrics) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        self.renderer.draw(&self.view, shared)
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.visible && self.renderer.is_animating()
+    }
+
+    pub fn draw_animation_frame(&mut self, shared: &SharedMetrics) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        self.renderer.draw_animation_frame(&self.view, shared)
+    }
+
+    pub fn set_theme(&mut self, config: &Config) {
+        self.view = view_config(config);
+        self.renderer.set_theme(config);
+    }
+
+    /// Applies a reloaded configuration: new metrics, size and opacity. The window stays
+    /// where it was dragged to.
+    pub fn update_config(&mut self, conn: &Arc<xcb::Connection>, config: &Config) -> Result<()> {
+        let (width, height) = (config.hud.width, height_for(config.hud.metrics.len(), config));
+        conn.send_request(&x::ConfigureWindow {
+            window: self.window,
+            value_list: &[x::ConfigWindow::Width(width as u32), x::ConfigWindow::Height(height as u32)],
+        });
+        let view = view_config(config);
+        let layout = layout::compute(&view.screens[0], width, height, &view);
+        let depth = conn.get_setup().roots().next().map_or(24, |s| s.root_depth());
+        let target = X11Present::new(conn.clone(), self.window, width, height, config.general.pixmap_buffering).with_depth(depth);
+        self.renderer.release();
+        self.renderer = Renderer::new(width, height, self.index, layout, &view, Box::new(target))?;
+        self.view = view;
+        window::set_window_opacity(conn, self.window, config.cosmetics.window_opacity)?;
+        conn.flush()?;
+        Ok(())
+    }
+
+    /// Starts a window-manager move when the HUD is pressed with the left button.
+    pub fn begin_drag(&self, conn: &xcb::Connection, ev: &x::ButtonPressEvent) -> Result<()> {
+        if ev.event() != self.window || ev.detail() != 1 {
+            return Ok(());
+        }
+        let root = conn.get_setup().roots().next().context("No screen found")?.root();
+        let moveresize = conn
+            .wait_for_reply(conn.send_request(&x::InternAtom { only_if_exists: false, name: b"_NET_WM_MOVERESIZE" }))?
+            .atom();
+        // The WM has to take over the implicit grab from the press
+        conn.send_request(&x::UngrabPointer { time: x::CURRENT_TIME });
+        let data = [ev.root_x() as u32, ev.root_y() as u32, NET_WM_MOVERESIZE_MOVE, 1, 1];
+        conn.send_request(&x::SendEvent {
+            propagate: false,
+            destination: x::SendEventDest::Window(root),
+            event_mask: x::EventMask::SUBSTRUCTURE_REDIRECT | x::EventMask::SUBSTRUCTURE_NOTIFY,
+            event: &x::ClientMessageEvent::new(self.window, moveresize, x::ClientMessageData::Data32(data)),
+        });
+        conn.flush()?;
+        Ok(())
+    }
+
+    /// Frees the renderer and destroys the window.
+    pub fn destroy(mut self, conn: &xcb::Connection) {
+        self.renderer.release();
+        conn.send_request(&x::DestroyWindow { window: self.window });
+        let _ = conn.flush();
+    }
+}
+
+/// Undecorated, always on top, on every desktop and out of the taskbar, placed where configured.
+fn setup_hud_properties(conn: &xcb::Connection, win: x::Window, config: &Config, width: u16, height: u16) -> Result<()> {
+    let atom_names = [
+        "_NET_WM_WINDOW_TYPE",
+        "_NET_WM_WINDOW_TYPE_UTILITY",
+        "_NET_WM_STATE",
+        "_NET_WM_STATE_ABOVE",
+        "_NET_WM_STATE_STICKY",
+        "_NET_WM_STATE_SKIP_TASKBAR",
+        "_NET_WM_STATE_SKIP_PAGER",
+        "_MOTIF_WM_HINTS",
+    ];
+    let cookies: Vec<_> = atom_names
+        .iter()
+        .map(|name| conn.send_request(&x::InternAtom { only_if_exists: false, name: name.as_bytes() }))
+        .collect();
+    let mut atoms = Vec::with_capacity(atom_names.len());
+    for cookie in cookies {
+        atoms.push(conn.wait_for_reply(cookie)?.atom());
+    }
+
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: win,
+        property: atoms[0],
+        r#type: x::ATOM_ATOM,
+        data: &[atoms[1]],
+    });
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: win,
+        property: atoms[2],
+        r#type: x::ATOM_ATOM,
+        data: &atoms[3..7],
+    });
+    // flags = MWM_HINTS_DECORATIONS, decorations = 0
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: win,
+        property: atoms[7],
+        r#type: atoms[7],
+        data: &[2u32, 0, 0, 0, 0],
+    });
+    // WM_NORMAL_HINTS with USPosition | USSize, so the WM keeps the configured position
+    let mut size_hints = [0u32; 18];
+    size_hints[0] = 1 | 2;
+    size_hints[1] = config.hud.x as u32;
+    size_hints[2] = config.hud.y as u32;
+    size_hints[3] = width as u32;
+    size_hints[4] = height as u32;
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: win,
+        property: x::ATOM_WM_NORMAL_HINTS,
+        r#type: x::ATOM_WM_SIZE_HINTS,
+        data: &size_hints,
+    });
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: win,
+        property: x::ATOM_WM_NAME,
+        r#type: x::ATOM_STRING,
+        data: b"Matrix Overlay HUD",
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hud_view_lays_out_only_hud_metrics() {
+        let mut config = Config::default();
+        config.hud.metrics = vec!["cpu_usage".to_string(), "ram_usage".to_string()];
+        let view = view_config(&config);
+        assert_eq!(view.rain_mode_for(0), "off");
+        assert!(view.validate().is_ok());
+
+        let height = height_for(config.hud.metrics.len(), &config);
+        let layout = layout::compute(&view.screens[0], config.hud.width, height, &view);
+        let ids: Vec<&str> = layout.items.iter().map(|item| item.metric_id.as_str()).collect();
+        assert_eq!(ids, ["cpu_usage", "ram_usage"]);
+        assert!(layout.items.iter().all(|item| item.y + 21 <= height as i32));
+    }
+}
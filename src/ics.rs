@@ -0,0 +1,269 @@
+//! Minimal iCalendar (RFC 5545) reader for the `next_event` metric.
+//! Handles line folding, `VEVENT` start times (UTC, floating, `TZID=` and all-day dates)
+//! and simple `DAILY`/`WEEKLY` recurrence with `INTERVAL`, `COUNT`, `UNTIL` and `BYDAY`.
+//! There is no time zone database: `TZID` times are taken as local time, and
+//! `EXDATE`/`RDATE` and other rules are ignored. An occurrence that falls into a
+//! daylight-saving gap is skipped. Properties of nested components (`VALARM`) are ignored.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+
+/// How far ahead recurring events are expanded.
+const RECURRENCE_HORIZON_DAYS: i64 = 400;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub summary: String,
+    pub start: DateTime<Local>,
+    pub all_day: bool,
+    rule: Option<Recurrence>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Recurrence {
+    weekly: bool,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<DateTime<Local>>,
+    by_day: Vec<Weekday>,
+}
+
+/// A `VEVENT` being read.
+#[derive(Default)]
+struct PendingEvent {
+    summary: String,
+    start: Option<(DateTime<Local>, bool)>,
+    rrule: Option<String>,
+    /// Components (`VALARM`) open inside the event; their properties aren't the event's.
+    nested: usize,
+}
+
+/// Parses all `VEVENT`s in an ICS document; events without a usable start are skipped.
+pub fn parse(ics: &str) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut current: Option<PendingEvent> = None;
+
+    for line in unfold(ics) {
+        let (name, params, value) = match split_property(&line) {
+            Some(parts) => parts,
+            None => continue,
+        };
+        match (name.as_str(), &mut current) {
+            ("BEGIN", None) if value == "VEVENT" => current = Some(PendingEvent::default()),
+            ("BEGIN", Some(event)) => event.nested += 1,
+            ("END", Some(event)) if event.nested > 0 => event.nested -= 1,
+            ("END", Some(_)) if value == "VEVENT" => {
+                if let Some(PendingEvent { summary, start: Some((start, all_day)), rrule, .. }) = current.take() {
+                    let rule = rrule.and_then(|r| parse_rrule(&r));
+                    events.push(Event { summary, start, all_day, rule });
+                }
+            }
+            (_, Some(event)) if event.nested > 0 => {}
+            ("SUMMARY", Some(event)) => event.summary = unescape(&value),
+            ("DTSTART", Some(event)) => event.start = parse_date_time(&params, &value),
+            ("RRULE", Some(event)) => event.rrule = Some(value),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// The first event starting after `now`, with its (possibly recurring) start time.
+pub fn next_event(events: &[Event], now: DateTime<Local>) -> Option<(DateTime<Local>, &Event)> {
+    events.iter().filter_map(|e| e.next_start(now).map(|start| (start, e))).min_by_key(|(start, _)| *start)
+}
+
+impl Event {
+    /// Next start of this event strictly after `now`.
+    pub fn next_start(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let rule = match &self.rule {
+            None => return (self.start > now).then_some(self.start),
+            Some(rule) => rule,
+        };
+
+        let first_day = self.start.date_naive();
+        let time = self.start.time();
+        let by_day = if rule.by_day.is_empty() { vec![first_day.weekday()] } else { rule.by_day.clone() };
+        let horizon = now.date_naive() + Duration::days(RECURRENCE_HORIZON_DAYS);
+
+        // Walk the rule's periods (days, or weeks starting on Monday) `interval` at a time
+        let (mut period, period_days) = if rule.weekly {
+            (first_day - Duration::days(first_day.weekday().num_days_from_monday() as i64), 7)
+        } else {
+            (first_day, 1)
+        };
+        let mut seen = 0;
+        while period <= horizon {
+            let days = (0..period_days)
+                .map(|offset| period + Duration::days(offset))
+                .filter(|day| *day >= first_day && (!rule.weekly || by_day.contains(&day.weekday())));
+            for day in days {
+                seen += 1;
+                if rule.count.is_some_and(|count| seen > count) {
+                    return None;
+                }
+                // A start inside a daylight-saving gap doesn't exist; skip that occurrence
+                let Some(start) = Local.from_local_datetime(&day.and_time(time)).earliest() else {
+                    continue;
+                };
+                if rule.until.is_some_and(|until| start > until) {
+                    return None;
+                }
+                if start > now {
+                    return Some(start);
+                }
+            }
+            period += Duration::days(period_days * rule.interval as i64);
+        }
+        None
+    }
+}
+
+/// Joins folded lines (continuations start with a space or tab).
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// "DTSTART;TZID=Europe/Berlin:20261016T140000" -> ("DTSTART", "TZID=Europe/Berlin", "20261016T140000").
+fn split_property(line: &str) -> Option<(String, String, String)> {
+    let (head, value) = line.split_once(':')?;
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.to_ascii_uppercase(), params.to_ascii_uppercase(), value.trim().to_string()))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", " ").replace("\\N", " ").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+fn parse_date_time(params: &str, value: &str) -> Option<(DateTime<Local>, bool)> {
+    if (params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME")) || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some((Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest()?, true));
+    }
+    let utc = value.ends_with('Z');
+    let naive = NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S").ok()?;
+    let start = if utc {
+        Utc.from_utc_datetime(&naive).with_timezone(&Local)
+    } else {
+        Local.from_local_datetime(&naive).earliest()?
+    };
+    Some((start, false))
+}
+
+fn parse_rrule(rule: &str) -> Option<Recurrence> {
+    let mut recurrence = Recurrence { weekly: false, interval: 1, count: None, until: None, by_day: Vec::new() };
+    let mut supported = false;
+    for part in rule.split(';') {
+        let (key, value) = part.split_once('=')?;
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => match value.to_ascii_uppercase().as_str() {
+                "DAILY" => supported = true,
+                "WEEKLY" => {
+                    supported = true;
+                    recurrence.weekly = true;
+                }
+                _ => return None,
+            },
+            "INTERVAL" => recurrence.interval = value.parse().ok().filter(|i| *i > 0)?,
+            "COUNT" => recurrence.count = value.parse().ok(),
+            "UNTIL" => recurrence.until = parse_date_time("", value).map(|(until, _)| until),
+            "BYDAY" => {
+                recurrence.by_day = value
+                    .split(',')
+                    .filter_map(|d| match d.trim().to_ascii_uppercase().as_str() {
+                        "MO" => Some(Weekday::Mon),
+                        "TU" => Some(Weekday::Tue),
+                        "WE" => Some(Weekday::Wed),
+                        "TH" => Some(Weekday::Thu),
+                        "FR" => Some(Weekday::Fri),
+                        "SA" => Some(Weekday::Sat),
+                        "SU" => Some(Weekday::Sun),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            _ => {}
+        }
+    }
+    supported.then_some(recurrence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(s: &str) -> DateTime<Local> {
+        Local.from_local_datetime(&NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()).unwrap()
+    }
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Dentist\\, downtown\r\n\
+DTSTART:20261020T093000\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Team stand\r\n\
+\x20up\r\n\
+DTSTART;TZID=Europe/Berlin:20261001T100000\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=10\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Holiday\r\n\
+DTSTART;VALUE=DATE:20261225\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    #[test]
+    fn test_parse_and_next_event() {
+        let events = parse(CALENDAR);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].summary, "Dentist, downtown");
+        assert_eq!(events[1].summary, "Team standup");
+        assert!(events[2].all_day);
+
+        // Fri Oct 16: the Monday standup on the 19th comes before the dentist
+        let (start, event) = next_event(&events, local("2026-10-16 12:00")).unwrap();
+        assert_eq!((start, event.summary.as_str()), (local("2026-10-19 10:00"), "Team standup"));
+
+        // COUNT=10 from Thu Oct 1 runs out on Wed Nov 4
+        let (start, event) = next_event(&events, local("2026-11-04 11:00")).unwrap();
+        assert_eq!((start, event.summary.as_str()), (local("2026-12-25 00:00"), "Holiday"));
+        assert_eq!(events[1].next_start(local("2026-11-02 11:00")), Some(local("2026-11-04 10:00")));
+    }
+
+    #[test]
+    fn test_alarm_properties_and_intervals() {
+        let events = parse(
+            "BEGIN:VEVENT\r\n\
+SUMMARY:Gym\r\n\
+DTSTART:20261001T070000\r\n\
+RRULE:FREQ=DAILY;INTERVAL=3;COUNT=5\r\n\
+BEGIN:VALARM\r\n\
+SUMMARY:Reminder\r\n\
+TRIGGER:-PT15M\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Review\r\n\
+DTSTART:20261001T150000\r\n\
+RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=TH,FR\r\n\
+END:VEVENT\r\n",
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].summary, "Gym");
+
+        // Oct 1, 4, 7, 10, 13 and no more
+        assert_eq!(events[0].next_start(local("2026-10-05 12:00")), Some(local("2026-10-07 07:00")));
+        assert_eq!(events[0].next_start(local("2026-10-13 07:00")), None);
+        // Thu Oct 1 and Fri Oct 2, then every other week
+        assert_eq!(events[1].next_start(local("2026-10-02 16:00")), Some(local("2026-10-15 15:00")));
+        assert_eq!(events[1].next_start(local("2026-10-15 16:00")), Some(local("2026-10-16 15:00")));
+    }
+}
@@ -0,0 +1,327 @@
+//! Local control interface.
+//! A line-based Unix socket (`$XDG_RUNTIME_DIR/matrix-overlay.sock`) that lets
+//! scripts query the running overlay via `matrix-overlay ctl <command>`.
+//! `ctl status` returns an `OverlaySnapshot` of the whole overlay state.
+//! With the `dbus` feature the same data is also published on the session bus.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use crossbeam_channel::Sender;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::metrics::{MetricValue, MetricsCommand, SharedMetrics};
+use crate::scheduler::CollectorHealth;
+
+/// An overlay window as exposed to external scripts (picom rules, xdotool, ...).
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowInfo {
+    /// Index of the monitor/screen config this window renders.
+    pub index: usize,
+    /// X11 window ID.
+    pub xid: u32,
+    /// RandR output name (e.g. "eDP-1").
+    pub monitor: String,
+    pub x: i16,
+    pub y: i16,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Serializable view of the running overlay, returned by `ctl status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverlaySnapshot {
+    pub visible: bool,
+    /// Rendering profile ("standard", "low_power").
+    pub profile: String,
+    /// Effective theme (the low-power profile forces "high_contrast").
+    pub theme: String,
+    pub windows: Vec<WindowInfo>,
+    pub collectors: Vec<CollectorHealth>,
+    /// Age of the last published metrics frame.
+    pub metrics_age_ms: u64,
+    /// Last value of every metric, keyed by metric ID.
+    pub metrics: BTreeMap<String, MetricValue>,
+}
+
+/// State published by the overlay for IPC queries.
+#[derive(Debug, Default)]
+pub struct IpcState {
+    pub windows: Mutex<Vec<WindowInfo>>,
+    /// Command channel of the metrics thread, once it is running.
+    metrics_tx: Mutex<Option<Sender<MetricsCommand>>>,
+    /// Latest metrics published by the metrics thread.
+    metrics: Mutex<Option<Arc<Mutex<SharedMetrics>>>>,
+    /// Overlay windows are unmapped (Ctrl+Alt+W).
+    hidden: AtomicBool,
+    /// Active (profile, theme).
+    appearance: Mutex<(String, String)>,
+}
+
+impl IpcState {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn set_windows(&self, windows: Vec<WindowInfo>) {
+        if let Ok(mut w) = self.windows.lock() {
+            *w = windows;
+        }
+    }
+
+    pub fn set_metrics_sender(&self, tx: Sender<MetricsCommand>) {
+        if let Ok(mut slot) = self.metrics_tx.lock() {
+            *slot = Some(tx);
+        }
+    }
+
+    pub fn set_metrics(&self, metrics: Arc<Mutex<SharedMetrics>>) {
+        if let Ok(mut slot) = self.metrics.lock() {
+            *slot = Some(metrics);
+        }
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        self.hidden.store(!visible, Ordering::Relaxed);
+    }
+
+    /// Records the profile and theme of the (runtime) config; call again after reloads.
+    pub fn set_config(&self, config: &Config) {
+        if let Ok(mut appearance) = self.appearance.lock() {
+            *appearance = (config.general.profile.clone(), config.general.theme.clone());
+        }
+    }
+
+    /// Current state of the overlay.
+    pub fn snapshot(&self) -> OverlaySnapshot {
+        let (profile, theme) = self.appearance.lock().map(|a| a.clone()).unwrap_or_default();
+        let mut snapshot = OverlaySnapshot {
+            visible: !self.hidden.load(Ordering::Relaxed),
+            profile,
+            theme,
+            windows: self.windows.lock().map(|w| w.clone()).unwrap_or_default(),
+            collectors: Vec::new(),
+            metrics_age_ms: 0,
+            metrics: BTreeMap::new(),
+        };
+        let metrics = self.metrics.lock().ok().and_then(|slot| slot.clone());
+        if let Some(Ok(shared)) = metrics.as_ref().map(|m| m.lock()) {
+            snapshot.collectors = shared.health.clone();
+            snapshot.metrics_age_ms = shared.timestamp.elapsed().as_millis() as u64;
+            snapshot.metrics = shared.data.values.iter().map(|(id, v)| (id.as_str().to_string(), v.clone())).collect();
+        }
+        snapshot
+    }
+
+    fn status_json(&self) -> String {
+        serde_json::to_string(&self.snapshot()).unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() }).to_string())
+    }
+
+    /// Asks the metrics thread for an immediate collection cycle.
+    fn request_refresh(&self) -> bool {
+        match self.metrics_tx.lock() {
+            Ok(slot) => slot.as_ref().is_some_and(|tx| tx.send(MetricsCommand::ForceRefresh).is_ok()),
+            Err(_) => false,
+        }
+    }
+
+    fn windows_json(&self) -> String {
+        let windows = self.windows.lock().map(|w| w.clone()).unwrap_or_default();
+        serde_json::to_string(&windows).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+/// Path of the control socket.
+pub fn socket_path() -> PathBuf {
+    match std::env::var("XDG_RUNTIME_DIR") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir).join("matrix-overlay.sock"),
+        _ => {
+            let user = std::env::var("USER").unwrap_or_else(|_| "default".to_string());
+            PathBuf::from(format!("/tmp/matrix-overlay-{}.sock", user))
+        }
+    }
+}
+
+/// Executes a single command and returns the JSON reply.
+fn handle_command(line: &str, state: &IpcState) -> String {
+    let mut parts = line.split_whitespace();
+    match parts.next() {
+        Some("windows") => state.windows_json(),
+        Some("status") => state.status_json(),
+        Some("ping") => r#"{"ok":true}"#.to_string(),
+        Some("refresh") if state.request_refresh() => r#"{"ok":true}"#.to_string(),
+        Some("refresh") => serde_json::json!({ "error": "metrics thread is not running" }).to_string(),
+        Some(other) => serde_json::json!({ "error": format!("unknown command '{}'", other) }).to_string(),
+        None => serde_json::json!({ "error": "empty command" }).to_string(),
+    }
+}
+
+fn handle_client(stream: UnixStream, state: &IpcState) -> Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let reply = handle_command(line.trim(), state);
+    let mut stream = stream;
+    writeln!(stream, "{}", reply)?;
+    Ok(())
+}
+
+/// Binds the control socket and serves requests on a background thread.
+pub fn spawn_server(state: Arc<IpcState>) -> Result<thread::JoinHandle<()>> {
+    let path = socket_path();
+    if path.exists() {
+        // Only one instance runs at a time (see version::print_startup_info), so this is stale.
+        let _ = std::fs::remove_file(&path);
+    }
+    let listener = UnixListener::bind(&path).with_context(|| format!("Failed to bind control socket {:?}", path))?;
+    log::info!("Control socket listening at {:?}", path);
+
+    Ok(thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = handle_client(stream, &state) {
+                        log::warn!("Control socket client error: {}", e);
+                    }
+                }
+                Err(e) => log::warn!("Control socket accept failed: {}", e),
+            }
+        }
+    }))
+}
+
+/// Sends a command to the running overlay and returns its reply.
+pub fn send_command(command: &str) -> Result<String> {
+    let path = socket_path();
+    let mut stream = UnixStream::connect(&path)
+        .with_context(|| format!("Overlay is not running (no socket at {:?})", path))?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    writeln!(stream, "{}", command)?;
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    Ok(reply.trim_end().to_string())
+}
+
+/// Entry point for `matrix-overlay ctl <command> [args...]`.
+pub fn run_ctl(args: &[String]) -> Result<()> {
+    if args.is_empty() {
+        bail!("Usage: matrix-overlay ctl <status|windows|refresh|ping>");
+    }
+    let reply = send_command(&args.join(" "))?;
+    println!("{}", reply);
+    Ok(())
+}
+
+/// Session bus object exposing the same state as the control socket.
+#[cfg(feature = "dbus")]
+struct OverlayBus {
+    state: Arc<IpcState>,
+}
+
+#[cfg(feature = "dbus")]
+#[zbus::dbus_interface(name = "org.matrixoverlay.Overlay1")]
+impl OverlayBus {
+    /// Overlay windows as a JSON array (same format as `ctl windows`).
+    #[dbus_interface(property)]
+    fn windows(&self) -> String {
+        self.state.windows_json()
+    }
+
+    /// Full overlay state as JSON (same format as `ctl status`).
+    fn status(&self) -> String {
+        self.state.status_json()
+    }
+
+    /// Triggers an immediate metrics refresh (same as `ctl refresh`).
+    fn refresh(&self) -> zbus::fdo::Result<()> {
+        if self.state.request_refresh() {
+            Ok(())
+        } else {
+            Err(zbus::fdo::Error::Failed("metrics thread is not running".to_string()))
+        }
+    }
+}
+
+/// Publishes `org.matrixoverlay.Overlay` on the session bus.
+#[cfg(feature = "dbus")]
+pub fn spawn_dbus(state: Arc<IpcState>) -> Result<thread::JoinHandle<()>> {
+    let connection = zbus::blocking::ConnectionBuilder::session()?
+        .name("org.matrixoverlay.Overlay")?
+        .serve_at("/org/matrixoverlay/Overlay", OverlayBus { state })?
+        .build()
+        .context("Failed to register on the D-Bus session bus")?;
+    log::info!("D-Bus interface registered as org.matrixoverlay.Overlay");
+
+    // zbus serves requests on its own executor; keep the connection alive for the process lifetime.
+    Ok(thread::spawn(move || {
+        let _connection = connection;
+        loop {
+            thread::park();
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_command_lists_windows() {
+        let state = IpcState::new();
+        state.set_windows(vec![WindowInfo {
+            index: 0,
+            xid: 0x1c00001,
+            monitor: "eDP-1".to_string(),
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+        }]);
+        let reply = handle_command("windows", &state);
+        let parsed: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(parsed[0]["xid"], 0x1c00001);
+        assert_eq!(parsed[0]["monitor"], "eDP-1");
+        assert!(handle_command("bogus", &state).contains("error"));
+    }
+
+    #[test]
+    fn test_status_reports_state_and_metrics() {
+        use crate::metrics::MetricId;
+
+        let state = IpcState::new();
+        let mut config = Config::default();
+        config.general.profile = "low_power".to_string();
+        state.set_config(&config.with_profile());
+        state.set_visible(false);
+
+        let shared = Arc::new(Mutex::new(SharedMetrics::new()));
+        shared.lock().unwrap().data.values.insert(MetricId::CpuUsage, MetricValue::Float(12.5));
+        state.set_metrics(shared);
+
+        let parsed: serde_json::Value = serde_json::from_str(&handle_command("status", &state)).unwrap();
+        assert_eq!(parsed["visible"], false);
+        assert_eq!(parsed["profile"], "low_power");
+        assert_eq!(parsed["theme"], "high_contrast");
+        assert_eq!(parsed["metrics"]["cpu_usage"], 12.5);
+    }
+
+    #[test]
+    fn test_refresh_command_reaches_metrics_thread() {
+        let state = IpcState::new();
+        assert!(handle_command("refresh", &state).contains("error"));
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        state.set_metrics_sender(tx);
+        assert_eq!(handle_command("refresh", &state), r#"{"ok":true}"#);
+        assert!(matches!(rx.try_recv(), Ok(MetricsCommand::ForceRefresh)));
+    }
+}
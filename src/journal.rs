@@ -0,0 +1,112 @@
+//! Auto-commit journal.
+//! Every auto-commit is appended as one JSON line to
+//! `$XDG_DATA_HOME/matrix-overlay/auto-commits.jsonl` (default `~/.local/share`), so the
+//! feature's activity can be audited afterwards (e.g. with `jq`) and counted for the
+//! `auto_commits_today` metric.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// `$XDG_DATA_HOME/matrix-overlay`, falling back to `~/.local/share/matrix-overlay`.
+pub fn data_dir() -> Result<PathBuf> {
+    let base = match std::env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(&std::env::var("HOME").context("HOME environment variable not set")?).join(".local/share"),
+    };
+    Ok(base.join("matrix-overlay"))
+}
+
+/// One auto-commit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommitRecord {
+    pub time: DateTime<Local>,
+    pub repo: String,
+    /// Lines added plus deleted.
+    pub lines: usize,
+    pub message: String,
+    /// Whether the message came from Ollama (false: the fixed fallback message).
+    pub ai_generated: bool,
+}
+
+/// Append-only JSON Lines file of `CommitRecord`s.
+#[derive(Debug, Clone)]
+pub struct CommitJournal {
+    path: PathBuf,
+}
+
+impl CommitJournal {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// `$XDG_DATA_HOME/matrix-overlay/auto-commits.jsonl`, falling back to `~/.local/share`.
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(data_dir()?.join("auto-commits.jsonl"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, record: &CommitRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context("Failed to create journal directory")?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open commit journal {:?}", self.path))?;
+        writeln!(file, "{}", serde_json::to_string(record)?).context("Failed to write commit journal")?;
+        Ok(())
+    }
+
+    /// All records, oldest first. A missing journal is empty; unreadable lines are skipped.
+    pub fn records(&self) -> Result<Vec<CommitRecord>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read commit journal {:?}", self.path)),
+        };
+        Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+    }
+
+    /// Number of auto-commits made on `day` (local time).
+    pub fn count_on(&self, day: NaiveDate) -> Result<usize> {
+        Ok(self.records()?.iter().filter(|r| r.time.date_naive() == day).count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_round_trip_and_count_by_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = CommitJournal::new(dir.path().join("nested/auto-commits.jsonl"));
+        assert_eq!(journal.count_on(Local::now().date_naive()).unwrap(), 0);
+
+        let record = |time: DateTime<Local>, ai_generated: bool| CommitRecord {
+            time,
+            repo: "/home/me/src/overlay".to_string(),
+            lines: 1200,
+            message: "Auto-commit (Matrix Overlay)".to_string(),
+            ai_generated,
+        };
+        let now = Local::now();
+        journal.append(&record(now - chrono::Duration::days(2), false)).unwrap();
+        journal.append(&record(now, true)).unwrap();
+        journal.append(&record(now, false)).unwrap();
+
+        let records = journal.records().unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records[1].ai_generated);
+        assert_eq!(journal.count_on(now.date_naive()).unwrap(), 2);
+    }
+}
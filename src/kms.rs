@@ -0,0 +1,161 @@
+//! DRM/KMS output.
+//! Drives a display directly through the kernel mode-setting API (no X server,
+//! no fbdev emulation): picks a connected connector, sets its preferred mode on
+//! a CRTC and scans out a dumb buffer that each frame is copied into.
+//! Requires DRM master, i.e. no other compositor/X server running on the card.
+
+use std::fs::{File, OpenOptions};
+use std::os::unix::io::{AsFd, BorrowedFd};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use cairo::ImageSurface;
+use drm::buffer::{Buffer, DrmFourcc};
+use drm::control::{connector, crtc, dumbbuffer::DumbBuffer, framebuffer, Device as ControlDevice, Mode};
+
+use crate::present::Present;
+
+/// An opened DRM device node (e.g. /dev/dri/card0).
+struct Card(File);
+
+impl AsFd for Card {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl drm::Device for Card {}
+impl ControlDevice for Card {}
+
+/// Scans out frames on one connector via a dumb buffer.
+pub struct KmsOutput {
+    card: Card,
+    connector: connector::Handle,
+    crtc: crtc::Handle,
+    mode: Mode,
+    buffer: Option<DumbBuffer>,
+    fb: Option<framebuffer::Handle>,
+    /// CRTC state before we took over, restored on release.
+    saved_crtc: crtc::Info,
+}
+
+impl KmsOutput {
+    /// Opens `device` and sets up the first connected connector (or the one named
+    /// `connector_name`, e.g. "HDMI-A-1") at its preferred mode.
+    pub fn open(device: &Path, connector_name: Option<&str>) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(device)
+            .with_context(|| format!("Failed to open DRM device {:?}", device))?;
+        let card = Card(file);
+
+        let resources = card.resource_handles().context("Failed to query DRM resources")?;
+        let connectors: Vec<connector::Info> = resources
+            .connectors()
+            .iter()
+            .filter_map(|&c| card.get_connector(c, true).ok())
+            .filter(|info| info.state() == connector::State::Connected)
+            .collect();
+        let con = match connector_name {
+            Some(name) => connectors.iter().find(|info| info.to_string() == name),
+            None => connectors.first(),
+        }
+        .with_context(|| match connector_name {
+            Some(name) => format!("Connector {} is not connected", name),
+            None => "No connected display found".to_string(),
+        })?;
+
+        // The first mode is the connector's preferred one
+        let mode = *con.modes().first().with_context(|| format!("Connector {} reports no modes", con))?;
+
+        // Keep the CRTC currently driving this connector if there is one
+        let current = con
+            .current_encoder()
+            .and_then(|e| card.get_encoder(e).ok())
+            .and_then(|e| e.crtc());
+        let crtc = match current {
+            Some(c) => c,
+            None => con
+                .encoders()
+                .iter()
+                .filter_map(|&e| card.get_encoder(e).ok())
+                .flat_map(|e| resources.filter_crtcs(e.possible_crtcs()))
+                .next()
+                .with_context(|| format!("No CRTC available for connector {}", con))?,
+        };
+        let saved_crtc = card.get_crtc(crtc).context("Failed to read CRTC state")?;
+
+        let (width, height) = mode.size();
+        let mut buffer = card
+            .create_dumb_buffer((width as u32, height as u32), DrmFourcc::Xrgb8888, 32)
+            .context("Failed to allocate scanout buffer")?;
+        {
+            let mut map = card.map_dumb_buffer(&mut buffer).context("Failed to map scanout buffer")?;
+            map.as_mut().fill(0);
+        }
+        let fb = card.add_framebuffer(&buffer, 24, 32).context("Failed to create DRM framebuffer")?;
+        card.set_crtc(crtc, Some(fb), (0, 0), &[con.handle()], Some(mode))
+            .context("Failed to set display mode (is another compositor or X server running?)")?;
+
+        log::info!("KMS output on {} at {}x{}@{}Hz", con, width, height, mode.vrefresh());
+        Ok(Self {
+            card,
+            connector: con.handle(),
+            crtc,
+            mode,
+            buffer: Some(buffer),
+            fb: Some(fb),
+            saved_crtc,
+        })
+    }
+
+    /// Frame size (the connector's mode).
+    pub fn size(&self) -> (u16, u16) {
+        self.mode.size()
+    }
+}
+
+impl Present for KmsOutput {
+    fn present(&mut self, frame: &mut ImageSurface) -> Result<()> {
+        let buffer = match self.buffer.as_mut() {
+            Some(b) => b,
+            None => return Ok(()),
+        };
+        let dst_pitch = buffer.pitch() as usize;
+        let (buf_w, buf_h) = buffer.size();
+
+        frame.flush();
+        let src_stride = frame.stride() as usize;
+        let width = (frame.width().max(0) as usize).min(buf_w as usize);
+        let height = (frame.height().max(0) as usize).min(buf_h as usize);
+        let data = frame.data().map_err(|e| anyhow::anyhow!("Failed to get surface data: {}", e))?;
+
+        // Cairo's native-endian ARGB32 matches XRGB8888, so rows copy straight across
+        let mut map = self.card.map_dumb_buffer(buffer).context("Failed to map scanout buffer")?;
+        let dst = map.as_mut();
+        for y in 0..height {
+            let src_row = &data[y * src_stride..y * src_stride + width * 4];
+            dst[y * dst_pitch..y * dst_pitch + width * 4].copy_from_slice(src_row);
+        }
+        Ok(())
+    }
+
+    /// Restores the previous CRTC configuration and frees the scanout buffer.
+    fn release(&mut self) {
+        let saved = &self.saved_crtc;
+        if let Err(e) = self.card.set_crtc(self.crtc, saved.framebuffer(), saved.position(), &[self.connector], saved.mode()) {
+            log::warn!("Failed to restore previous display mode: {}", e);
+        }
+        if let Some(fb) = self.fb.take() {
+            let _ = self.card.destroy_framebuffer(fb);
+        }
+        if let Some(buffer) = self.buffer.take() {
+            let _ = self.card.destroy_dumb_buffer(buffer);
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "kms"
+    }
+}
@@ -8,6 +8,40 @@ use std::collections::HashSet;
 #[derive(Debug, Clone)]
 pub struct Layout {
     pub items: Vec<LayoutItem>,
+    /// Render-safe area inside the screen margins; everything is drawn within it.
+    pub bounds: Bounds,
+    /// Rain-free areas, relative to `bounds` (the coordinate space the rain lives in).
+    pub rain_exclusions: Vec<Bounds>,
+}
+
+/// Rectangle in window coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Bounds {
+    /// The area of a `width` x `height` monitor left after applying the screen's margins.
+    pub fn from_margins(screen: &Screen, width: u16, height: u16) -> Self {
+        let (top, right, bottom, left) = screen.edge_margins();
+        Self {
+            x: left,
+            y: top,
+            width: (width as i32 - left - right).max(0),
+            height: (height as i32 - top - bottom).max(0),
+        }
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64 && x < (self.x + self.width) as f64 && y >= self.y as f64 && y < self.bottom() as f64
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -17,8 +51,22 @@ pub struct LayoutItem {
     pub x: i32,
     pub y: i32,
     pub max_width: i32,
+    /// "left" spreads label and value across the item; "center" and "right" keep them
+    /// together in the middle or at the right edge.
     pub alignment: String,
     pub clip: bool,
+    /// Render style from `item_options` ("text", "sparkline", "bar").
+    pub style: String,
+    /// Full-scale value for the bar style.
+    pub bar_max: f64,
+    /// Ellipsize mode for overflowing values ("start", "middle", "end").
+    pub ellipsize: Option<String>,
+    /// Value template from `item_options` (e.g. "{value} ({label})").
+    pub format: Option<String>,
+    /// Digits after the decimal point for the value's number.
+    pub precision: Option<usize>,
+    /// Replacement for the unit after the value's number.
+    pub unit: Option<String>,
 }
 
 /// Validates the configuration for logical consistency and uniqueness.
@@ -57,41 +105,225 @@ pub fn validate_config(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Layout rows taken by the clock, digital or analog.
+pub const CLOCK_ROWS: i32 = 3;
+
+/// Layout rows taken by the month calendar: title, weekday names and up to six weeks.
+pub const CALENDAR_ROWS: i32 = 8;
+
+/// Number of layout rows a metric occupies (tables reserve one row per entry below the label).
+pub fn row_span(metric_id: &str, config: &Config) -> i32 {
+    match metric_id {
+        "top_processes" => 1 + config.top_processes.count as i32,
+        "notifications" => 1 + config.notifications.count as i32,
+        "clock" => CLOCK_ROWS,
+        "calendar" => CALENDAR_ROWS,
+        _ => 1,
+    }
+}
+
+/// Display label of a (weather-resolved) metric ID, e.g. "WEATHER TEMP (Office)", or of
+/// a remote instance's metric, e.g. "CPU USAGE (nas)".
+fn item_label(resolved_id: &str, config: &Config) -> String {
+    if let Some((remote, id)) = config.remote_instances.iter().find_map(|r| r.remote_id(resolved_id).map(|id| (r, id))) {
+        return format!("{} ({})", id.replace("_", " ").to_uppercase(), remote.name);
+    }
+    match resolved_id.split_once('@') {
+        Some((base, loc)) => format!("{} ({})", base.replace("_", " ").to_uppercase(), config.weather.location_label(loc)),
+        None => resolved_id.replace("_", " ").to_uppercase(),
+    }
+}
+
+/// Anchors an item can be placed at with `item_options.<id>.anchor`.
+pub const ANCHORS: [&str; 9] = [
+    "top-left",
+    "top-center",
+    "top-right",
+    "middle-left",
+    "center",
+    "middle-right",
+    "bottom-left",
+    "bottom-center",
+    "bottom-right",
+];
+
+/// Top-left corner of a `width` x `height` item at `anchor` of `bounds`, moved by the
+/// offsets and kept inside `bounds`, with the alignment the anchor's side implies.
+pub fn anchored_position(anchor: &str, bounds: Bounds, width: i32, height: i32, x_offset: i32, y_offset: i32) -> (i32, i32, &'static str) {
+    let (vertical, horizontal) = match anchor {
+        "center" => ("middle", "center"),
+        _ => anchor.split_once('-').unwrap_or(("top", "left")),
+    };
+    let (x, alignment) = match horizontal {
+        "center" => (bounds.x + (bounds.width - width) / 2, "center"),
+        "right" => (bounds.x + bounds.width - width, "right"),
+        _ => (bounds.x, "left"),
+    };
+    let y = match vertical {
+        "middle" => bounds.y + (bounds.height - height) / 2,
+        "bottom" => bounds.bottom() - height,
+        _ => bounds.y,
+    };
+    let x = (x + x_offset).clamp(bounds.x, (bounds.x + bounds.width - width).max(bounds.x));
+    let y = (y + y_offset).clamp(bounds.y, (bounds.bottom() - height).max(bounds.y));
+    (x, y, alignment)
+}
+
+/// Padding in pixels between a panel bar's edges and its metrics.
+pub const PANEL_PADDING: i32 = 8;
+
 /// Computes the layout for a specific monitor based on its dimensions and config.
-pub fn compute(screen: &Screen, width: u16, _height: u16, global_font_size: f64) -> Layout {
+pub fn compute(screen: &Screen, width: u16, height: u16, config: &Config) -> Layout {
+    if config.panel.enabled {
+        return compute_panel(screen, width, height, config);
+    }
+    let global_font_size = config.general.font_size as f64;
     let mut items = Vec::new();
     
-    // Use screen offsets from config
-    let left = screen.x_offset;
-    let top = screen.y_offset;
+    // Screen margins define the render-safe area
+    let bounds = Bounds::from_margins(screen, width, height);
     
     // Icon Avoidance: Fixed top safe zone of 180px for desktop icons and header
     let safe_top = 180;
-    let start_y = std::cmp::max(top, safe_top);
+    let start_y = std::cmp::max(bounds.y, safe_top);
     
-    let mut cursor_y = start_y;
     // Approximate line height: font size + padding
     let line_height = (global_font_size * 1.5) as i32; 
 
-    for metric_id in &screen.metrics {
-        // Simple vertical list layout
-        let x = left;
-        let y = cursor_y;
-        cursor_y += line_height;
+    // The list fills its columns top-down, each with its own cursor
+    let columns = screen.columns.max(1) as i32;
+    let column_width = ((bounds.width - screen.column_gap * (columns - 1)) / columns).max(0);
+    let mut cursors = vec![start_y; columns as usize];
+
+    // The ticker runs along the bottom margin; the list stops above it
+    let ticker = screen.metrics.iter().any(|m| m == "ticker");
+    let list_bottom = if ticker { bounds.bottom() - line_height } else { bounds.bottom() };
 
-        // Calculate max width for clipping (simple bounds check against screen edges)
-        let max_width = (width as i32) - left * 2;
+    for metric_id in screen.metrics.iter().filter(|m| *m != "ticker") {
+        let options = screen.item_options.get(metric_id).cloned().unwrap_or_default();
+        let height = line_height * row_span(metric_id, config);
+
+        let (x, y, max_width, alignment) = match &options.anchor {
+            // Anchored items sit wherever they were put, outside the list
+            Some(anchor) => {
+                let width = options.width.unwrap_or(bounds.width / 3).min(bounds.width);
+                let (x, y, alignment) = anchored_position(anchor, bounds, width, height, options.x_offset, options.y_offset);
+                (x, y, width, alignment)
+            }
+            None => {
+                let column = options.column.min(columns as usize - 1);
+                let x = bounds.x + column as i32 * (column_width + screen.column_gap);
+                let y = cursors[column];
+                cursors[column] += height + screen.spacing;
+
+                if y + height > list_bottom {
+                    log::debug!("Metric '{}' does not fit above the bottom margin, skipping", metric_id);
+                    continue;
+                }
+                // Clip to the column on both sides
+                (x, y, column_width, "left")
+            }
+        };
+
+        // Weather metrics follow the screen's assigned location (weather_temp -> weather_temp@office)
+        let resolved_id = config.weather.resolve_metric(metric_id, screen.weather_location.as_deref());
+        let label = item_label(&resolved_id, config);
 
         items.push(LayoutItem {
-            metric_id: metric_id.clone(),
-            label: metric_id.replace("_", " ").to_uppercase(),
+            metric_id: resolved_id,
+            label,
             x,
             y,
             max_width,
-            alignment: "left".to_string(),
+            alignment: alignment.to_string(),
             clip: false,
+            style: options.style.clone(),
+            bar_max: options.bar_max,
+            ellipsize: options.ellipsize.clone(),
+            format: options.format.clone(),
+            precision: options.precision,
+            unit: options.unit.clone(),
+        });
+    }
+
+    if ticker {
+        let options = screen.item_options.get("ticker").cloned().unwrap_or_default();
+        items.push(LayoutItem {
+            metric_id: "ticker".to_string(),
+            label: String::new(),
+            x: bounds.x,
+            y: list_bottom,
+            max_width: bounds.width,
+            alignment: "left".to_string(),
+            clip: true,
+            style: options.style,
+            bar_max: options.bar_max,
+            ellipsize: None,
+            format: None,
+            precision: None,
+            unit: None,
+        });
+    }
+
+    // Exclusions are configured in monitor pixels; open-ended sides run to the monitor edge
+    let rain_exclusions = screen
+        .rain_exclusions
+        .iter()
+        .map(|zone| Bounds {
+            x: zone.x - bounds.x,
+            y: zone.y - bounds.y,
+            width: zone.width.unwrap_or((width as i32 - zone.x).max(0)),
+            height: zone.height.unwrap_or((height as i32 - zone.y).max(0)),
+        })
+        .collect();
+
+    Layout { items, bounds, rain_exclusions }
+}
+
+/// Layout for a panel bar of `width` x `height`: metrics side by side in equal slots along
+/// a top or bottom bar, or stacked down a left or right one. Screen margins, the icon safe
+/// zone and rain exclusions are for the full-desktop overlay and don't apply.
+fn compute_panel(screen: &Screen, width: u16, height: u16, config: &Config) -> Layout {
+    let bounds = Bounds {
+        x: PANEL_PADDING,
+        y: 0,
+        width: (width as i32 - 2 * PANEL_PADDING).max(0),
+        height: height as i32,
+    };
+    let line_height = (config.general.metric_font_size as f64 * 1.5) as i32;
+    // Multi-row metrics (top processes) don't fit a bar
+    let metrics: Vec<&String> = screen.metrics.iter().filter(|id| row_span(id, config) == 1).collect();
+    let slot = bounds.width / metrics.len().max(1) as i32;
+
+    let mut items = Vec::new();
+    for (i, metric_id) in metrics.into_iter().enumerate() {
+        let (x, y, max_width) = if config.panel.is_horizontal() {
+            (bounds.x + i as i32 * slot, (bounds.height - line_height) / 2, slot - PANEL_PADDING)
+        } else {
+            (bounds.x, PANEL_PADDING + i as i32 * line_height, bounds.width)
+        };
+        if y + line_height > bounds.bottom() {
+            log::debug!("Metric '{}' does not fit in the panel, skipping", metric_id);
+            continue;
+        }
+        let options = screen.item_options.get(metric_id).cloned().unwrap_or_default();
+        let resolved_id = config.weather.resolve_metric(metric_id, screen.weather_location.as_deref());
+        items.push(LayoutItem {
+            label: item_label(&resolved_id, config),
+            metric_id: resolved_id,
+            x,
+            y,
+            max_width,
+            alignment: "left".to_string(),
+            clip: true,
+            style: options.style.clone(),
+            bar_max: options.bar_max,
+            ellipsize: options.ellipsize.clone(),
+            format: options.format.clone(),
+            precision: options.precision,
+            unit: options.unit.clone(),
         });
     }
 
-    Layout { items }
+    Layout { items, bounds, rain_exclusions: Vec::new() }
 }
\ No newline at end of file
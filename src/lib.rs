@@ -7,6 +7,38 @@ pub mod window;
 pub mod timer;
 pub mod path_utils;
 pub mod logging;
+pub mod log_viewer;
 pub mod version;
 pub mod build_logger;
-pub mod gui;
\ No newline at end of file
+pub mod gui;
+pub mod computed;
+pub mod smoothing;
+pub mod scheduler;
+pub mod stats;
+pub mod autotune;
+pub mod audio;
+pub mod effects;
+pub mod hud;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod ics;
+pub mod ipc;
+pub mod alerts;
+pub mod journal;
+pub mod commit_message;
+#[cfg(feature = "git")]
+pub mod commit_undo;
+#[cfg(feature = "git")]
+pub mod repo_state;
+pub mod auto_commit;
+pub mod typing;
+pub mod screen_time;
+pub mod astro;
+pub mod units;
+pub mod present;
+pub mod screensaver;
+pub mod serve;
+#[cfg(feature = "kms")]
+pub mod kms;
\ No newline at end of file
@@ -0,0 +1,201 @@
+//! "View Logs" window: tails `matrix_overlay.log` in the logging directory, filtered by
+//! level, with a button that copies what is shown (for pasting into bug reports).
+//! Only the window needs the `gui` feature; the tail and filter helpers are plain Rust.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use log::{Level, LevelFilter};
+
+#[cfg(feature = "gui")]
+use {
+    gtk::prelude::*,
+    gtk::{gdk, glib, Box, Button, ComboBoxText, Label, Orientation, ScrolledWindow, TextView, Window, WindowType},
+    std::cell::{Cell, RefCell},
+    std::rc::Rc,
+    std::time::Duration,
+};
+
+/// How much of the end of the log file the viewer reads.
+const TAIL_BYTES: u64 = 256 * 1024;
+
+/// The log file written when `logging.enabled` is set.
+pub fn log_file(log_dir: &str) -> PathBuf {
+    Path::new(log_dir).join("matrix_overlay.log")
+}
+
+/// The last `TAIL_BYTES` of `path`, starting at a line boundary.
+pub fn read_tail(path: &Path) -> Result<String> {
+    let mut file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let text = String::from_utf8_lossy(&bytes).into_owned();
+    // Drop the partial first line when starting mid-file
+    Ok(match (start > 0, text.find('\n')) {
+        (true, Some(newline)) => text[newline + 1..].to_string(),
+        _ => text,
+    })
+}
+
+/// Level of a log line ("12:00:01 [WARN] ..."), `None` for continuation lines.
+pub fn line_level(line: &str) -> Option<Level> {
+    let open = line.find('[')?;
+    let close = open + line[open..].find(']')?;
+    // The level sits right after the timestamp, so ignore brackets further into the message
+    if open > 32 {
+        return None;
+    }
+    Level::from_str(line[open + 1..close].trim()).ok()
+}
+
+/// Lines at or above `min` severity; continuation lines follow the line they belong to.
+pub fn filter_lines(text: &str, min: LevelFilter) -> String {
+    let mut keep = true;
+    let mut out = String::with_capacity(text.len());
+    for line in text.lines() {
+        if let Some(level) = line_level(line) {
+            keep = level <= min;
+        }
+        if keep {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(feature = "gui")]
+pub struct LogViewer {
+    path: PathBuf,
+}
+
+#[cfg(feature = "gui")]
+impl LogViewer {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn show(&self) {
+        let window = Window::new(WindowType::Toplevel);
+        window.set_title("Matrix Overlay Logs");
+        window.set_default_size(820, 500);
+
+        let vbox = Box::new(Orientation::Vertical, 6);
+        vbox.set_border_width(8);
+
+        let toolbar = Box::new(Orientation::Horizontal, 6);
+        toolbar.pack_start(&Label::new(Some("Level")), false, false, 0);
+        let level_combo = ComboBoxText::new();
+        level_combo.append(Some("error"), "Errors");
+        level_combo.append(Some("warn"), "Warnings and up");
+        level_combo.append(Some("info"), "Info and up");
+        level_combo.append(Some("trace"), "Everything");
+        level_combo.set_active_id(Some("trace"));
+        toolbar.pack_start(&level_combo, false, false, 0);
+        let btn_copy = Button::with_label("Copy");
+        toolbar.pack_start(&btn_copy, false, false, 0);
+        let path_label = Label::new(Some(&self.path.display().to_string()));
+        path_label.set_selectable(true);
+        toolbar.pack_end(&path_label, false, false, 0);
+        vbox.pack_start(&toolbar, false, false, 0);
+
+        let text_view = TextView::new();
+        text_view.set_editable(false);
+        text_view.set_monospace(true);
+        let scroll = ScrolledWindow::new(None::<&gtk::Adjustment>, None::<&gtk::Adjustment>);
+        scroll.add(&text_view);
+        vbox.pack_start(&scroll, true, true, 0);
+        window.add(&vbox);
+
+        // Re-reads the file and shows it if anything changed, keeping the view at the end
+        let shown = Rc::new(RefCell::new(String::new()));
+        let refresh = {
+            let path = self.path.clone();
+            let (level_combo, text_view, shown) = (level_combo.clone(), text_view.clone(), shown.clone());
+            Rc::new(move || {
+                let min = level_combo
+                    .active_id()
+                    .and_then(|id| LevelFilter::from_str(&id).ok())
+                    .unwrap_or(LevelFilter::Trace);
+                let text = match read_tail(&path) {
+                    Ok(raw) => filter_lines(&raw, min),
+                    Err(e) => format!("{:#}\n\nFile logging is on when `logging.enabled` is set in the config.", e),
+                };
+                if *shown.borrow() == text {
+                    return;
+                }
+                if let Some(buffer) = text_view.buffer() {
+                    buffer.set_text(&text);
+                    let mut end = buffer.end_iter();
+                    text_view.scroll_to_iter(&mut end, 0.0, false, 0.0, 1.0);
+                }
+                *shown.borrow_mut() = text;
+            })
+        };
+
+        let refresh_level = refresh.clone();
+        level_combo.connect_changed(move |_| refresh_level());
+
+        let shown_copy = shown.clone();
+        btn_copy.connect_clicked(move |_| {
+            gtk::Clipboard::get(&gdk::SELECTION_CLIPBOARD).set_text(&shown_copy.borrow());
+        });
+
+        let closed = Rc::new(Cell::new(false));
+        let closed_flag = closed.clone();
+        window.connect_destroy(move |_| closed_flag.set(true));
+
+        refresh();
+        glib::timeout_add_local(Duration::from_secs(1), move || {
+            if closed.get() {
+                return glib::Continue(false);
+            }
+            refresh();
+            glib::Continue(true)
+        });
+
+        window.show_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_log_tail_and_level_filter() {
+        let log = "12:00:01 [INFO] Metrics thread initialized with 9 collectors.\n\
+                   12:00:02 [WARN] Weather fetch failed: timeout\n\
+                   caused by: connection reset\n\
+                   12:00:03 [ERROR] Auto-commit cycle failed: [repo] locked\n\
+                   12:00:04 [DEBUG] Drawing metric CpuUsage at y=40\n";
+        assert_eq!(line_level("12:00:02 [WARN] x"), Some(Level::Warn));
+        assert_eq!(line_level("caused by: [x] y"), None);
+
+        assert_eq!(
+            filter_lines(log, LevelFilter::Warn),
+            "12:00:02 [WARN] Weather fetch failed: timeout\ncaused by: connection reset\n12:00:03 [ERROR] Auto-commit cycle failed: [repo] locked\n"
+        );
+        assert_eq!(filter_lines(log, LevelFilter::Info).lines().count(), 4);
+        assert_eq!(filter_lines(log, LevelFilter::Trace), log);
+
+        let dir = tempdir().unwrap();
+        let path = log_file(dir.path().to_str().unwrap());
+        let mut file = File::create(&path).unwrap();
+        for i in 0..20_000 {
+            writeln!(file, "12:00:00 [INFO] line {}", i).unwrap();
+        }
+        let tail = read_tail(&path).unwrap();
+        assert!(tail.len() as u64 <= TAIL_BYTES);
+        assert!(tail.starts_with("12:00:00 [INFO] line "));
+        assert!(tail.ends_with("line 19999\n"));
+    }
+}
@@ -2,36 +2,97 @@
 #![allow(unused_imports)]
 
 use anyhow::{bail, Context, Result};
-use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
 use std::process::Command;
 use std::thread;
 use std::time::{Duration, Instant};
 use std::env;
 use std::fs;
 use std::path::Path;
+#[cfg(feature = "git")]
 use git2::Repository;
-use crossbeam_channel::{unbounded, bounded, select, after, Receiver};
-use tray_icon::menu::MenuEvent;
-use tray_icon::TrayIconEvent;
+use crossbeam_channel::{unbounded, bounded, select, after, never, Receiver};
 use simplelog::{WriteLogger, TermLogger, Config as LogConfig, LevelFilter, TerminalMode, ColorChoice};
 use chrono::Local;
 use xcb::x;
+use xcb::Xid;
 
-use matrix_overlay::config::Config;
-use matrix_overlay::window::create_all_windows;
-use matrix_overlay::metrics::{MetricData, MetricId, MetricValue, MetricsCommand, spawn_metrics_thread};
-use matrix_overlay::render::Renderer;
+use matrix_overlay::config::{CommitMessageRules, Config};
+use matrix_overlay::window::{self, create_all_windows, WindowManager, X11Present, WINDOW_OPACITY_STEP};
+use matrix_overlay::present;
+use matrix_overlay::serve;
+use matrix_overlay::screensaver::{self, idle_time, takeover_due, TAKEOVER_FRAME};
+use matrix_overlay::metrics::{MetricData, MetricId, MetricValue, MetricsCommand, SharedMetrics, spawn_metrics_thread};
+use matrix_overlay::hud::MiniHud;
+use matrix_overlay::render::{Renderer, ANIMATION_FRAME};
 use matrix_overlay::layout::{self, Layout};
 use matrix_overlay::logging;
+use matrix_overlay::log_viewer;
 use matrix_overlay::version;
 use matrix_overlay::build_logger;
 use matrix_overlay::path_utils;
-use matrix_overlay::tray::{SystemTray, MENU_QUIT_ID, MENU_RELOAD_ID, MENU_EDIT_ID, MENU_THEME_CLASSIC, MENU_THEME_CALM, MENU_THEME_ALERT, MENU_TOGGLE_AUTO_COMMIT, MENU_TOGGLE_OLLAMA, MENU_CONFIG_GUI_ID, MENU_CONFIG_JSON_ID};
-use matrix_overlay::gui::{GuiEvent, ConfigWindow};
+use matrix_overlay::journal::{CommitJournal, CommitRecord};
+#[cfg(feature = "git")]
+use matrix_overlay::commit_undo;
+use matrix_overlay::commit_message;
+#[cfg(feature = "git")]
+use matrix_overlay::repo_state;
+use matrix_overlay::auto_commit::{RepoOutcome, ScheduleStatus};
+use matrix_overlay::alerts;
+use matrix_overlay::astro;
+use matrix_overlay::autotune::DensityTuner;
+#[cfg(feature = "audio")]
+use matrix_overlay::audio;
+use matrix_overlay::stats::{TunedSettings, UsageStats};
+use matrix_overlay::tray::{self, MENU_QUIT_ID, MENU_RELOAD_ID, MENU_REFRESH_ID, MENU_VIEW_LOGS_ID, MENU_EDIT_ID, MENU_THEME_CLASSIC, MENU_THEME_CALM, MENU_THEME_ALERT, MENU_OPACITY_UP, MENU_OPACITY_DOWN, MENU_TOGGLE_HUD, MENU_TOGGLE_AUTO_COMMIT, MENU_TOGGLE_OLLAMA, MENU_CONFIG_GUI_ID, MENU_CONFIG_JSON_ID, MENU_COLLECTOR_PREFIX};
+#[cfg(feature = "tray")]
+use matrix_overlay::tray::SystemTray;
+use matrix_overlay::gui::GuiEvent;
+#[cfg(feature = "gui")]
+use matrix_overlay::gui::ConfigWindow;
+use matrix_overlay::ipc::{self, IpcState, WindowInfo};
 
 fn main() -> Result<()> {
-    // 1. Load Config First (to determine logging)
+    // `ctl` talks to the running instance; it must not go through startup (which kills other instances)
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(|a| a == "ctl").unwrap_or(false) {
+        return ipc::run_ctl(&args[2..]);
+    }
+    if args.get(1).map(|a| a == "undo-commit").unwrap_or(false) {
+        #[cfg(feature = "git")]
+        return commit_undo::run_undo(&args[2..]);
+        #[cfg(not(feature = "git"))]
+        bail!("undo-commit requires building with --features git");
+    }
+    if args.iter().any(|a| a == "--version" || a == "-V") {
+        version::print_version(args.iter().any(|a| a == "--verbose" || a == "-v"));
+        return Ok(());
+    }
+
+    // 1. Load Config First (to determine logging), then apply `--set key=value` overrides
+    let overrides = parse_set_overrides(&args)?;
     let mut config = Config::load().context("Failed to load configuration")?;
+    config.apply_overrides(&overrides).context("Invalid --set override")?;
+
+    // Headless server: collectors and exporters only, no X connection. Also skips startup,
+    // so it can run next to a desktop overlay
+    if args.get(1).map(|a| a == "serve").unwrap_or(false) {
+        env_logger::init();
+        if !args.iter().any(|a| a == "--headless") {
+            log::info!("serve always runs headless; pass --headless to make that explicit");
+        }
+        return serve::run(&config.with_profile());
+    }
+
+    // Screensaver hack (xscreensaver/xsecurelock): draw into the daemon's window.
+    // Like `ctl`, this must not go through startup, which would kill the running overlay.
+    if let Some(xid) = screensaver::target_window(&args) {
+        env_logger::init();
+        let config = config.with_profile();
+        let (metrics, shutdown, _metrics_handle, _metrics_tx) = spawn_metrics_thread(&config);
+        start_audio(&config);
+        return screensaver::run(&config, &metrics, &shutdown, xid);
+    }
     
     // 2. Init Logger
     version::print_startup_info();
@@ -51,7 +112,7 @@ fn main() -> Result<()> {
         let _ = WriteLogger::init(
             LevelFilter::Info,
             LogConfig::default(),
-            fs::File::create(log_dir.join("matrix_overlay.log")).context("Failed to create log file")?
+            fs::File::create(log_viewer::log_file(&config.logging.log_path)).context("Failed to create log file")?
         );
         println!("Logging enabled. Directory: {}", config.logging.log_path);
     } else {
@@ -59,12 +120,16 @@ fn main() -> Result<()> {
     }
     log::info!("Initializing Matrix Overlay... v0.1.3-FORCE_REBUILD");
 
-    // FORCE OVERRIDE: Ensure rain is enabled for verification
-    config.cosmetics.rain_mode = "fall".to_string();
-    // FORCE OVERRIDE: Max density to ensure visibility (Fixes "No streams to draw")
-    config.cosmetics.realism_scale = 8;
+    // Rendering profile (e.g. low_power) adjusts the runtime copy only; the file stays untouched
+    config = config.with_profile();
+    if config.is_low_power() {
+        log::info!("Low-power profile active: static text, refresh every {} ms", config.general.update_ms);
+    }
 
     log::info!("Configuration loaded successfully.");
+    if !overrides.is_empty() {
+        log::info!("Command-line overrides: {:?}", overrides);
+    }
     for (i, screen) in config.screens.iter().enumerate() {
         log::info!("Monitor {}: Configured metrics: {:?}", i, screen.metrics);
     }
@@ -76,8 +141,28 @@ fn main() -> Result<()> {
         log::info!("Weather disabled (Privacy Mode active)");
     }
 
+    // 2b. Control socket (and D-Bus, if enabled) for external scripts
+    let ipc_state = IpcState::new();
+    ipc_state.set_config(&config);
+    if let Err(e) = ipc::spawn_server(ipc_state.clone()) {
+        log::warn!("Failed to start control socket: {}", e);
+    }
+    #[cfg(feature = "dbus")]
+    if let Err(e) = ipc::spawn_dbus(ipc_state.clone()) {
+        log::warn!("Failed to start D-Bus interface: {}", e);
+    }
+
+    start_audio(&config);
+
     // 3. Spawn Metrics Thread
     let (metrics, shutdown, _metrics_handle, metrics_tx) = spawn_metrics_thread(&config);
+    ipc_state.set_metrics_sender(metrics_tx.clone());
+    ipc_state.set_metrics(metrics.clone());
+
+    // Non-X11 outputs (PNG sequence, framebuffer) render without connecting to an X server
+    if !config.output.is_x11() {
+        return run_headless(&config, &metrics, &shutdown);
+    }
 
     // 4. Setup XCB Connection
     let (conn, screen_num) = xcb::Connection::connect(None).context("Failed to connect to X server")?;
@@ -111,8 +196,27 @@ fn main() -> Result<()> {
 
     grab_key_combinations(&conn, root, keycode_q, x::ModMask::CONTROL | x::ModMask::N1)?;
 
+    // 'r' keysym is 0x0072
+    let keycode_r = find_keycode(&conn, 0x0072)?.context("Could not find keycode for 'r'")?;
+
+    grab_key_combinations(&conn, root, keycode_r, x::ModMask::CONTROL | x::ModMask::N1)?;
+
+    // '=' (0x003d) and '-' (0x002d) step the window opacity
+    for keysym in [KEYSYM_EQUAL, KEYSYM_MINUS] {
+        match find_keycode(&conn, keysym)? {
+            Some(keycode) => grab_key_combinations(&conn, root, keycode, x::ModMask::CONTROL | x::ModMask::N1)?,
+            None => log::warn!("No keycode for keysym {:#06x}; opacity hotkey unavailable", keysym),
+        }
+    }
+
+    // 'h' (0x0068) toggles the mini-HUD
+    match find_keycode(&conn, KEYSYM_H)? {
+        Some(keycode) => grab_key_combinations(&conn, root, keycode, x::ModMask::CONTROL | x::ModMask::N1)?,
+        None => log::warn!("No keycode for 'h'; mini-HUD hotkey unavailable"),
+    }
+
     conn.flush()?;
-    log::info!("Grabbed hotkeys: Ctrl+Alt+W (Toggle), Ctrl+Alt+Q (Quit)");
+    log::info!("Grabbed hotkeys: Ctrl+Alt+W (Toggle), Ctrl+Alt+Q (Quit), Ctrl+Alt+R (Refresh), Ctrl+Alt+=/- (Opacity), Ctrl+Alt+H (Mini-HUD)");
 
     // 7. Test Mode Check
     if env::args().any(|a| a == "--test-layering") {
@@ -129,7 +233,7 @@ fn main() -> Result<()> {
     }
 
     // 7b. Initialize GTK (Required for Tray Icon on Linux)
-    #[cfg(target_os = "linux")]
+    #[cfg(all(target_os = "linux", feature = "gui"))]
     {
         if let Err(e) = gtk::init() {
             log::warn!("Failed to initialize GTK: {}", e);
@@ -137,7 +241,8 @@ fn main() -> Result<()> {
     }
 
     // 7b. Initialize System Tray
-    let _tray = match SystemTray::new(&config) {
+    #[cfg(feature = "tray")]
+    let tray = match SystemTray::new(&config) {
         Ok(t) => Some(t),
         Err(e) => {
             log::warn!("Failed to initialize system tray: {}", e);
@@ -180,7 +285,9 @@ fn main() -> Result<()> {
     let control_tx_overlay = control_tx.clone();
     let interval_tx_overlay = interval_tx.clone();
     let metrics_tx_overlay = metrics_tx.clone();
-    let menu_channel = MenuEvent::receiver();
+    let menu_clicks = tray::menu_clicks();
+    let ipc_state_overlay = ipc_state.clone();
+    let overrides_overlay = overrides.clone();
 
     thread::spawn(move || {
         log::info!("Overlay logic thread started.");
@@ -195,11 +302,24 @@ fn main() -> Result<()> {
             }
         };
 
+        apply_window_opacity(&conn_arc, &wm, config_overlay.cosmetics.window_opacity);
+
+        ipc_state_overlay.set_windows(wm.monitors.iter().enumerate().map(|(i, ctx)| WindowInfo {
+            index: i,
+            xid: ctx.window.resource_id(),
+            monitor: ctx.monitor.name.clone(),
+            x: ctx.monitor.x,
+            y: ctx.monitor.y,
+            width: ctx.monitor.width,
+            height: ctx.monitor.height,
+        }).collect());
+
         let mut renderers = Vec::new();
         for (i, ctx) in wm.monitors.iter().enumerate() {
             let screen_config = config_overlay.screens.get(i).unwrap_or(&config_overlay.screens[0]);
-            let layout = layout::compute(screen_config, ctx.monitor.width, ctx.monitor.height, config_overlay.general.font_size as f64);
-            if let Ok(renderer) = Renderer::new(ctx.monitor.width, ctx.monitor.height, i, layout, &config_overlay) {
+            let layout = layout::compute(screen_config, ctx.monitor.width, ctx.monitor.height, &config_overlay);
+            let target = X11Present::new(conn_arc.clone(), ctx.window, ctx.monitor.width, ctx.monitor.height, config_overlay.general.pixmap_buffering);
+            if let Ok(renderer) = Renderer::new(ctx.monitor.width, ctx.monitor.height, i, layout, &config_overlay, Box::new(target)) {
                 renderers.push(renderer);
             }
         }
@@ -224,10 +344,26 @@ fn main() -> Result<()> {
 
         let keycode_w = find_keycode(&conn_arc, 0x0077).unwrap_or(Some(0)).unwrap_or(0);
         let keycode_q = find_keycode(&conn_arc, 0x0071).unwrap_or(Some(0)).unwrap_or(0);
+        let keycode_r = find_keycode(&conn_arc, 0x0072).unwrap_or(Some(0)).unwrap_or(0);
+        let keycode_equal = find_keycode(&conn_arc, KEYSYM_EQUAL).unwrap_or(Some(0)).unwrap_or(0);
+        let keycode_minus = find_keycode(&conn_arc, KEYSYM_MINUS).unwrap_or(Some(0)).unwrap_or(0);
+        let keycode_h = find_keycode(&conn_arc, KEYSYM_H).unwrap_or(Some(0)).unwrap_or(0);
         let mut visible = true;
+        let mut takeover = false;
+        let mut hud = None;
+        if config_overlay.hud.enabled {
+            toggle_hud(&conn_arc, &mut hud, &config_overlay, renderers.len());
+        }
+        // The configured theme; `general.night_theme` replaces it between sunset and sunrise
+        let mut day_theme = config_overlay.general.theme.clone();
+        let mut tuner = new_tuner(&config_overlay);
 
         loop {
             if shutdown_arc.load(Ordering::Relaxed) { break; }
+            // Extra frames while a value decodes or a theme fades in
+            let animating = (visible && renderers.iter().any(|r| r.is_animating())) || hud.as_ref().is_some_and(MiniHud::is_animating);
+            let animation_tick = if animating { after(ANIMATION_FRAME) } else { never() };
+            let takeover_tick = if takeover { after(TAKEOVER_FRAME) } else { never() };
 
             select! {
                 recv(xcb_rx_overlay) -> event_res => {
@@ -235,7 +371,9 @@ fn main() -> Result<()> {
                         match event {
                             xcb::Event::X(x::Event::KeyPress(ev)) => {
                                 if ev.detail() == keycode_w {
+                                    set_takeover(&conn_arc, &wm, &mut renderers, &mut takeover, false);
                                     visible = !visible;
+                                    ipc_state_overlay.set_visible(visible);
                                     for ctx in &wm.monitors {
                                         if visible { let _ = conn_arc.send_request(&x::MapWindow { window: ctx.window }); }
                                         else { let _ = conn_arc.send_request(&x::UnmapWindow { window: ctx.window }); }
@@ -244,6 +382,25 @@ fn main() -> Result<()> {
                                 } else if ev.detail() == keycode_q {
                                     shutdown_arc.store(true, Ordering::Relaxed);
                                     break;
+                                } else if ev.detail() == keycode_r {
+                                    let _ = metrics_tx_overlay.send(MetricsCommand::ForceRefresh);
+                                } else if ev.detail() == keycode_equal || ev.detail() == keycode_minus {
+                                    let delta = if ev.detail() == keycode_equal { WINDOW_OPACITY_STEP } else { -WINDOW_OPACITY_STEP };
+                                    step_window_opacity(&conn_arc, &wm, &mut config_overlay, delta);
+                                } else if ev.detail() == keycode_h {
+                                    toggle_hud(&conn_arc, &mut hud, &config_overlay, renderers.len());
+                                }
+                            },
+                            xcb::Event::X(x::Event::ButtonPress(ev)) => {
+                                if let Some(hud) = &hud {
+                                    if let Err(e) = hud.begin_drag(&conn_arc, &ev) {
+                                        log::warn!("Failed to move the mini-HUD: {}", e);
+                                    }
+                                }
+                            },
+                            xcb::Event::X(x::Event::Expose(ev)) if hud.as_ref().is_some_and(|h| h.window() == ev.window()) => {
+                                if let (Some(hud), Ok(shared)) = (&mut hud, metrics_arc.lock()) {
+                                    let _ = hud.draw(&shared);
                                 }
                             },
                             xcb::Event::X(x::Event::Expose(ev)) => {
@@ -251,7 +408,7 @@ fn main() -> Result<()> {
                                     if let Some(idx) = wm.monitors.iter().position(|m| m.window == ev.window()) {
                                         if let Some(renderer) = renderers.get_mut(idx) {
                                             if let Ok(shared) = metrics_arc.lock() {
-                                                let _ = renderer.draw(&conn_arc, ev.window(), &config_overlay, &shared.data);
+                                                let _ = renderer.draw(&config_overlay, &shared);
                                             }
                                         }
                                     }
@@ -261,35 +418,138 @@ fn main() -> Result<()> {
                         }
                     }
                 },
+                recv(animation_tick) -> _ => {
+                    if let Ok(shared) = metrics_arc.lock() {
+                        for renderer in renderers.iter_mut() {
+                            let _ = renderer.draw_animation_frame(&config_overlay, &shared);
+                        }
+                        if let Some(hud) = &mut hud {
+                            let _ = hud.draw_animation_frame(&shared);
+                        }
+                    }
+                },
+                recv(takeover_tick) -> _ => {
+                    // Any input ends the takeover
+                    if !takeover_due(idle_time(&conn_arc).unwrap_or_default(), &config_overlay) {
+                        set_takeover(&conn_arc, &wm, &mut renderers, &mut takeover, false);
+                    }
+                    if let Ok(shared) = metrics_arc.lock() {
+                        for renderer in renderers.iter_mut() {
+                            let _ = renderer.draw(&config_overlay, &shared);
+                        }
+                    }
+                },
                 recv(tick_thread_rx) -> _ => {
-                    if visible {
+                    if visible && !takeover && takeover_allowed(&config_overlay) {
+                        match idle_time(&conn_arc) {
+                            Ok(idle) if takeover_due(idle, &config_overlay) => {
+                                set_takeover(&conn_arc, &wm, &mut renderers, &mut takeover, true);
+                            }
+                            Ok(_) => {}
+                            Err(e) => log::debug!("Idle check failed: {}", e),
+                        }
+                    }
+                    if apply_daylight_theme(&mut config_overlay, &day_theme) {
+                        log::info!("Sun {}: switching to the {} theme", if astro::daytime() == Some(true) { "rose" } else { "set" }, config_overlay.general.theme);
+                        ipc_state_overlay.set_config(&config_overlay);
+                        for renderer in &mut renderers { renderer.set_theme(&config_overlay); }
+                        if let Some(hud) = &mut hud { hud.set_theme(&config_overlay); }
+                    }
+                    // The takeover tick draws (and advances the rain) on its own while active
+                    if visible && !takeover {
                         if let Ok(shared) = metrics_arc.lock() {
-                            for (i, renderer) in renderers.iter_mut().enumerate() {
-                                if let Some(ctx) = wm.monitors.get(i) {
-                                    let _ = renderer.draw(&conn_arc, ctx.window, &config_overlay, &shared.data);
-                                }
+                            let started = Instant::now();
+                            for renderer in renderers.iter_mut() {
+                                let _ = renderer.draw(&config_overlay, &shared);
+                            }
+                            if let Some(tuner) = &mut tuner {
+                                tuner.record_frame(started.elapsed());
                             }
                         }
                     }
+                    if let (Some(hud), Ok(shared)) = (&mut hud, metrics_arc.lock()) {
+                        let _ = hud.draw(&shared);
+                    }
+                    if let Some(tuner) = &mut tuner {
+                        if let Some((realism, update_ms)) = tuner.tick(Instant::now()) {
+                            log::info!(
+                                "Auto-tune: overlay CPU {:.1}% (budget {:.1}%), now density {} and update interval {} ms",
+                                tuner.last_cpu().unwrap_or_default(), tuner.cpu_budget(), realism, update_ms
+                            );
+                            config_overlay.cosmetics.realism_scale = realism;
+                            config_overlay.general.update_ms = update_ms;
+                            let _ = interval_tx_overlay.send(Duration::from_millis(update_ms));
+                        }
+                        if let (Some(cpu_percent), Ok(mut stats)) = (tuner.last_cpu(), UsageStats::shared().lock()) {
+                            let (realism_scale, update_ms) = tuner.current();
+                            stats.record_auto_tune(TunedSettings { realism_scale, update_ms, cpu_percent, cpu_budget: tuner.cpu_budget() });
+                        }
+                    }
                 },
-                recv(MenuEvent::receiver()) -> event_res => {
-                    if let Ok(event) = event_res {
-                        if event.id.as_ref() == MENU_QUIT_ID {
+                recv(menu_clicks) -> click => {
+                    if let Ok(id) = click {
+                        if id == MENU_QUIT_ID {
                             shutdown_arc.store(true, Ordering::Relaxed);
                             break;
                         }
-                        if event.id.as_ref() == MENU_RELOAD_ID {
+                        if id == MENU_RELOAD_ID {
                             let _ = Command::new("notify-send").args(&["-t", "1000", "Matrix Overlay", "Reloading Configuration..."]).spawn();
-                            if let Ok(new_config) = Config::load() {
-                                config_overlay = new_config.clone();
+                            if let Some(new_config) = reload_config(&overrides_overlay) {
+                                config_overlay = new_config;
+                                day_theme = config_overlay.general.theme.clone();
+                                tuner = new_tuner(&config_overlay);
+                                start_audio(&config_overlay);
+                                apply_window_opacity(&conn_arc, &wm, config_overlay.cosmetics.window_opacity);
+                                ipc_state_overlay.set_config(&config_overlay);
                                 let _ = interval_tx_overlay.send(Duration::from_millis(config_overlay.general.update_ms));
+                                set_takeover(&conn_arc, &wm, &mut renderers, &mut takeover, false);
                                 for renderer in &mut renderers { renderer.update_config(config_overlay.clone()); }
+                                if let Some(hud) = &mut hud {
+                                    if let Err(e) = hud.update_config(&conn_arc, &config_overlay) {
+                                        log::warn!("Failed to update the mini-HUD: {}", e);
+                                    }
+                                }
                                 let _ = metrics_tx_overlay.send(MetricsCommand::UpdateConfig(config_overlay.clone()));
                             }
                         }
-                        if event.id.as_ref() == MENU_CONFIG_GUI_ID {
+                        if id == MENU_REFRESH_ID {
+                            let _ = metrics_tx_overlay.send(MetricsCommand::ForceRefresh);
+                        }
+                        if id == MENU_CONFIG_GUI_ID {
                             let _ = control_tx_overlay.send(GuiEvent::OpenConfig);
                         }
+                        if id == MENU_VIEW_LOGS_ID {
+                            let _ = control_tx_overlay.send(GuiEvent::ViewLogs);
+                        }
+                        if id == MENU_TOGGLE_HUD {
+                            toggle_hud(&conn_arc, &mut hud, &config_overlay, renderers.len());
+                        }
+                        if id == MENU_OPACITY_UP || id == MENU_OPACITY_DOWN {
+                            let delta = if id == MENU_OPACITY_UP { WINDOW_OPACITY_STEP } else { -WINDOW_OPACITY_STEP };
+                            step_window_opacity(&conn_arc, &wm, &mut config_overlay, delta);
+                        }
+                        let picked_theme = match id.as_str() {
+                            MENU_THEME_CLASSIC => Some("classic"),
+                            MENU_THEME_CALM => Some("calm"),
+                            MENU_THEME_ALERT => Some("alert"),
+                            _ => None,
+                        };
+                        if let Some(theme) = picked_theme {
+                            switch_theme(&mut config_overlay, &mut day_theme, theme, &mut renderers);
+                            if let Some(hud) = &mut hud { hud.set_theme(&config_overlay); }
+                            ipc_state_overlay.set_config(&config_overlay);
+                        }
+                        if let Some(collector_id) = id.strip_prefix(MENU_COLLECTOR_PREFIX) {
+                            let enable = config_overlay.disabled_collectors.iter().any(|d| d == collector_id);
+                            config_overlay.disabled_collectors.retain(|d| d != collector_id);
+                            if !enable {
+                                config_overlay.disabled_collectors.push(collector_id.to_string());
+                            }
+                            let _ = metrics_tx_overlay.send(MetricsCommand::SetCollectorEnabled(collector_id.to_string(), enable));
+                            if let Err(e) = save_disabled_collectors(&config_overlay.disabled_collectors) {
+                                log::warn!("Failed to save collector state: {}", e);
+                            }
+                        }
                     }
                 },
                 recv(gui_rx) -> event_res => {
@@ -297,13 +557,29 @@ fn main() -> Result<()> {
                         match event {
                             GuiEvent::Reload => {
                                 let _ = Command::new("notify-send").args(&["-t", "1000", "Matrix Overlay", "Changes Applied Successfully"]).spawn();
-                                if let Ok(new_config) = Config::load() {
-                                    config_overlay = new_config.clone();
+                                if let Some(new_config) = reload_config(&overrides_overlay) {
+                                    config_overlay = new_config;
+                                    day_theme = config_overlay.general.theme.clone();
+                                    tuner = new_tuner(&config_overlay);
+                                    start_audio(&config_overlay);
+                                    apply_window_opacity(&conn_arc, &wm, config_overlay.cosmetics.window_opacity);
+                                    ipc_state_overlay.set_config(&config_overlay);
                                     let _ = interval_tx_overlay.send(Duration::from_millis(config_overlay.general.update_ms));
+                                    set_takeover(&conn_arc, &wm, &mut renderers, &mut takeover, false);
                                     for renderer in &mut renderers { renderer.update_config(config_overlay.clone()); }
+                                    if let Some(hud) = &mut hud {
+                                        if let Err(e) = hud.update_config(&conn_arc, &config_overlay) {
+                                            log::warn!("Failed to update the mini-HUD: {}", e);
+                                        }
+                                    }
                                     let _ = metrics_tx_overlay.send(MetricsCommand::UpdateConfig(config_overlay.clone()));
                                 }
                             },
+                            GuiEvent::SetTheme(theme) => {
+                                switch_theme(&mut config_overlay, &mut day_theme, &theme, &mut renderers);
+                                if let Some(hud) = &mut hud { hud.set_theme(&config_overlay); }
+                                ipc_state_overlay.set_config(&config_overlay);
+                            },
                             GuiEvent::PurgeLogs => {
                                 let _ = logging::Logger::purge_debug_logs("/tmp/matrix_overlay_logs");
                             },
@@ -314,23 +590,34 @@ fn main() -> Result<()> {
             }
         }
         log::info!("Overlay logic thread stopping. Cleaning up windows...");
+        for renderer in &mut renderers { renderer.release(); }
+        if let Some(hud) = hud.take() { hud.destroy(&conn_arc); }
         let _ = wm.cleanup(&conn_arc);
     });
 
     // 7c. Spawn Productivity Thread (Auto-Commits & AI Insights)
+    #[cfg(not(feature = "git"))]
+    if !config.productivity.repos.is_empty() {
+        log::warn!("Auto-commit requires building with --features git; productivity.repos is ignored");
+    }
+    #[cfg(feature = "git")]
     let productivity_config = config.clone();
+    #[cfg(feature = "git")]
     let productivity_shutdown = shutdown.clone();
+    #[cfg(feature = "git")]
     thread::spawn(move || {
         log::info!("Productivity thread started.");
         let mut last_commit_check = Instant::now();
+        set_next_auto_commit_run(AUTO_COMMIT_INTERVAL);
         
         while !productivity_shutdown.load(Ordering::Relaxed) {
             // Run commit check every hour
-            if last_commit_check.elapsed() >= Duration::from_secs(3600) {
+            if last_commit_check.elapsed() >= AUTO_COMMIT_INTERVAL {
                 last_commit_check = Instant::now();
                 if let Err(e) = run_auto_commit_cycle(&productivity_config) {
                     log::error!("Auto-commit cycle failed: {}", e);
                 }
+                set_next_auto_commit_run(AUTO_COMMIT_INTERVAL);
             }
             
             thread::sleep(Duration::from_secs(60));
@@ -339,15 +626,32 @@ fn main() -> Result<()> {
     });
 
     // Start GTK Main Loop on main thread
-    #[cfg(target_os = "linux")]
+    #[cfg(all(target_os = "linux", feature = "gui"))]
     {
         log::info!("GTK dedicated thread active (60 FPS GUI).");
+        let log_file = log_viewer::log_file(&config.logging.log_path);
         loop {
             if shutdown.load(Ordering::Relaxed) { break; }
             while gtk::events_pending() {
                 gtk::main_iteration();
             }
             
+            // Keep the tray's "Collectors" submenu in step with the metrics thread
+            #[cfg(feature = "tray")]
+            {
+                let statuses = metrics.try_lock().ok().map(|shared| shared.collectors.clone());
+                if let (Some(tray), Some(statuses)) = (&tray, statuses) {
+                    if let Err(e) = tray.sync_collectors(&statuses) {
+                        log::warn!("Failed to update tray collectors menu: {}", e);
+                    }
+                }
+                if let (Some(tray), Ok(status)) = (&tray, ScheduleStatus::shared().try_lock()) {
+                    if let Err(e) = tray.sync_auto_commit(&status) {
+                        log::warn!("Failed to update tray auto-commit menu: {}", e);
+                    }
+                }
+            }
+
             // Watch for GUI events that need to be handled on the main thread (like opening a window)
             while let Ok(event) = control_rx.try_recv() {
                 match event {
@@ -357,6 +661,9 @@ fn main() -> Result<()> {
                             window.show();
                         }
                     },
+                    GuiEvent::ViewLogs => {
+                        log_viewer::LogViewer::new(log_file.clone()).show();
+                    },
                     _ => {}
                 }
             }
@@ -365,13 +672,30 @@ fn main() -> Result<()> {
         }
     }
 
+    // Without GTK the main thread only waits for shutdown
+    #[cfg(not(all(target_os = "linux", feature = "gui")))]
+    while !shutdown.load(Ordering::Relaxed) {
+        if let Ok(GuiEvent::OpenConfig | GuiEvent::ViewLogs) = control_rx.try_recv() {
+            log::warn!("The settings and log windows require building with --features gui");
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
     log::info!("Shutting down main...");
     
     // Ungrab key (Optional as thread does it, but safer here if thread crashes)
     let keycode_w = find_keycode(&conn, 0x0077)?.unwrap_or(0);
     let keycode_q = find_keycode(&conn, 0x0071)?.unwrap_or(0);
+    let keycode_r = find_keycode(&conn, 0x0072)?.unwrap_or(0);
+    let keycode_equal = find_keycode(&conn, KEYSYM_EQUAL)?.unwrap_or(0);
+    let keycode_minus = find_keycode(&conn, KEYSYM_MINUS)?.unwrap_or(0);
+    let keycode_h = find_keycode(&conn, KEYSYM_H)?.unwrap_or(0);
     let _ = conn.send_request(&x::UngrabKey { key: keycode_w, grab_window: root, modifiers: x::ModMask::ANY });
     let _ = conn.send_request(&x::UngrabKey { key: keycode_q, grab_window: root, modifiers: x::ModMask::ANY });
+    let _ = conn.send_request(&x::UngrabKey { key: keycode_r, grab_window: root, modifiers: x::ModMask::ANY });
+    let _ = conn.send_request(&x::UngrabKey { key: keycode_equal, grab_window: root, modifiers: x::ModMask::ANY });
+    let _ = conn.send_request(&x::UngrabKey { key: keycode_minus, grab_window: root, modifiers: x::ModMask::ANY });
+    let _ = conn.send_request(&x::UngrabKey { key: keycode_h, grab_window: root, modifiers: x::ModMask::ANY });
     let _ = conn.flush();
 
     shutdown.store(true, Ordering::Relaxed);
@@ -379,6 +703,192 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Renders the first screen's layout to a PNG sequence or framebuffer until shutdown.
+///
+/// Used on kiosks and SBCs without an X session; the rain and metric pipeline is
+/// the same as for overlay windows, only the presentation target differs.
+fn run_headless(config: &Config, metrics: &Arc<Mutex<SharedMetrics>>, shutdown: &Arc<AtomicBool>) -> Result<()> {
+    let (width, height, target) = present::open_target(&config.output)?;
+    log::info!("Rendering {}x{} frames to the {} output", width, height, target.name());
+
+    let layout = layout::compute(&config.screens[0], width, height, config);
+    let mut renderer = Renderer::new(width, height, 0, layout, config, target)?;
+    let interval = Duration::from_millis(config.general.update_ms);
+
+    while !shutdown.load(Ordering::Relaxed) {
+        let start = Instant::now();
+        if let Ok(shared) = metrics.lock() {
+            if let Err(e) = renderer.draw(config, &shared) {
+                log::warn!("Failed to present frame: {:#}", e);
+            }
+        }
+        let elapsed = start.elapsed();
+        if elapsed < interval { thread::sleep(interval - elapsed); }
+    }
+
+    renderer.release();
+    shutdown.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Persists the tray's collector toggles to the config file.
+///
+/// Loads the file fresh so `--set` overrides and the active profile are not written back.
+fn save_disabled_collectors(disabled: &[String]) -> Result<()> {
+    let mut config = Config::load()?;
+    config.disabled_collectors = disabled.to_vec();
+    config.save()
+}
+
+/// Collects `--set key=value` (or `--set=key=value`) arguments in order.
+fn parse_set_overrides(args: &[String]) -> Result<Vec<String>> {
+    let mut overrides = Vec::new();
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == "--set" {
+            match iter.next() {
+                Some(spec) => overrides.push(spec.clone()),
+                None => bail!("--set requires a key=value argument"),
+            }
+        } else if let Some(spec) = arg.strip_prefix("--set=") {
+            overrides.push(spec.to_string());
+        }
+    }
+    Ok(overrides)
+}
+
+/// The adaptive density tuner, if `auto_tune` is on. The low-power profile already
+/// draws static frames at a slow rate, so it is left alone.
+fn new_tuner(config: &Config) -> Option<DensityTuner> {
+    (config.auto_tune.enabled && !config.is_low_power()).then(|| DensityTuner::new(config))
+}
+
+/// Starts audio capture for `cosmetics.audio_reactive`. The capture keeps running if the
+/// option is turned off later; the rain just stops reading it.
+fn start_audio(config: &Config) {
+    if !config.cosmetics.audio_reactive {
+        return;
+    }
+    #[cfg(feature = "audio")]
+    if let Err(e) = audio::spawn_capture(config.cosmetics.audio_device.clone()) {
+        log::warn!("Failed to start audio capture: {}", e);
+    }
+    #[cfg(not(feature = "audio"))]
+    log::warn!("cosmetics.audio_reactive requires building with --features audio");
+}
+
+/// Keysyms of the opacity hotkeys (Ctrl+Alt+= and Ctrl+Alt+-).
+const KEYSYM_EQUAL: u32 = 0x003d;
+const KEYSYM_MINUS: u32 = 0x002d;
+
+/// The takeover needs full-screen overlay windows, so not in panel mode, and the
+/// low-power profile never animates.
+fn takeover_allowed(config: &Config) -> bool {
+    config.cosmetics.screensaver_after_min > 0 && !config.panel.enabled && !config.is_low_power()
+}
+
+/// Starts or ends the screensaver takeover on every monitor; a no-op if already in that state.
+fn set_takeover(conn: &xcb::Connection, wm: &WindowManager, renderers: &mut [Renderer], takeover: &mut bool, on: bool) {
+    if *takeover == on {
+        return;
+    }
+    *takeover = on;
+    log::info!("Screensaver takeover {}", if on { "started" } else { "ended" });
+    for renderer in renderers.iter_mut() {
+        renderer.set_takeover(on);
+    }
+    if let Err(e) = wm.set_takeover(conn, on) {
+        log::warn!("Failed to restack the overlay windows: {}", e);
+    }
+}
+
+/// Keysym of the mini-HUD hotkey (Ctrl+Alt+H).
+const KEYSYM_H: u32 = 0x0068;
+
+/// Shows or hides the mini-HUD, creating it on first use. `index` is the renderer slot
+/// after the overlay's monitors.
+fn toggle_hud(conn: &Arc<xcb::Connection>, hud: &mut Option<MiniHud>, config: &Config, index: usize) {
+    match hud {
+        Some(hud) => {
+            let visible = !hud.is_visible();
+            hud.set_visible(conn, visible);
+            log::info!("Mini-HUD {}", if visible { "shown" } else { "hidden" });
+        }
+        None => match MiniHud::create(conn, config, index) {
+            Ok(created) => *hud = Some(created),
+            Err(e) => log::warn!("Failed to create the mini-HUD: {}", e),
+        },
+    }
+}
+
+fn apply_window_opacity(conn: &xcb::Connection, wm: &WindowManager, opacity: f64) {
+    if let Err(e) = wm.set_opacity(conn, opacity) {
+        log::warn!("Failed to set window opacity: {}", e);
+    }
+}
+
+/// Changes the overlay opacity from the tray or a hotkey. Like a theme picked in the
+/// tray, the change lasts until the next reload.
+fn step_window_opacity(conn: &xcb::Connection, wm: &WindowManager, config: &mut Config, delta: f64) {
+    let opacity = window::step_opacity(config.cosmetics.window_opacity, delta);
+    if opacity == config.cosmetics.window_opacity {
+        return;
+    }
+    config.cosmetics.window_opacity = opacity;
+    log::info!("Window opacity {:.0}%", opacity * 100.0);
+    apply_window_opacity(conn, wm, opacity);
+}
+
+/// Applies a theme picked in the tray or settings window. Only the renderers' palettes
+/// change (with a fade); the layout, rain and metrics thread are left alone. The low-power
+/// profile keeps its high-contrast theme and `night_theme` still applies after sunset.
+fn switch_theme(config: &mut Config, day_theme: &mut String, theme: &str, renderers: &mut [Renderer]) {
+    *day_theme = theme.to_string();
+    if config.is_low_power() {
+        log::info!("Theme {} applies once the low-power profile is off", theme);
+        return;
+    }
+    config.general.theme = theme.to_string();
+    apply_daylight_theme(config, day_theme);
+    log::info!("Switched to the {} theme", config.general.theme);
+    for renderer in renderers.iter_mut() {
+        renderer.set_theme(config);
+    }
+}
+
+/// Switches the runtime theme between `day_theme` and `general.night_theme` once the
+/// astro collector knows whether the sun is up. Returns true if the theme changed.
+fn apply_daylight_theme(config: &mut Config, day_theme: &str) -> bool {
+    let (Some(night_theme), Some(up)) = (&config.general.night_theme, astro::daytime()) else {
+        return false;
+    };
+    if config.is_low_power() {
+        return false; // The profile's high-contrast theme wins
+    }
+    let wanted = if up { day_theme.to_string() } else { night_theme.clone() };
+    if config.general.theme == wanted {
+        return false;
+    }
+    config.general.theme = wanted;
+    true
+}
+
+/// Reloads the config file for a live reload, re-applying CLI overrides and the rendering profile.
+fn reload_config(overrides: &[String]) -> Option<Config> {
+    let mut config = match Config::load() {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Failed to reload configuration: {:#}", e);
+            return None;
+        }
+    };
+    if let Err(e) = config.apply_overrides(overrides) {
+        log::error!("Failed to re-apply --set overrides: {:#}", e);
+        return None;
+    }
+    Some(config.with_profile())
+}
+
 fn setup_autostart() -> Result<()> {
     let home = env::var("HOME").context("HOME environment variable not set")?;
     let autostart_dir = Path::new(&home).join(".config/autostart");
@@ -449,35 +959,67 @@ fn grab_key_combinations(conn: &xcb::Connection, root: x::Window, keycode: u8, b
     Ok(())
 }
 
+/// How often the productivity thread runs an auto-commit cycle.
+const AUTO_COMMIT_INTERVAL: Duration = Duration::from_secs(3600);
+
+fn set_next_auto_commit_run(after: Duration) {
+    if let Ok(mut status) = ScheduleStatus::shared().lock() {
+        status.next_run = Some(Local::now() + chrono::Duration::from_std(after).unwrap_or_default());
+    }
+}
+
+#[cfg(feature = "git")]
 fn run_auto_commit_cycle(config: &Config) -> Result<()> {
     log::info!("Starting auto-commit cycle for {} repos...", config.productivity.repos.len());
     
+    let mut outcomes = Vec::new();
     for repo_path in &config.productivity.repos {
         let path = Path::new(repo_path);
         if !path_utils::is_safe_path(path) {
             log::warn!("Skipping unsafe repo path: {}", repo_path);
+            outcomes.push((repo_path.clone(), RepoOutcome::Skipped("unsafe path".to_string())));
             continue;
         }
 
-        match Repository::open(path) {
-            Ok(repo) => {
-                if let Err(e) = handle_repo_auto_commit(&repo, config) {
+        let outcome = match Repository::open(path) {
+            Ok(repo) => match handle_repo_auto_commit(&repo, config) {
+                Ok(outcome) => outcome,
+                Err(e) => {
                     log::error!("Failed to auto-commit in {}: {}", repo_path, e);
+                    RepoOutcome::Failed(e.to_string())
                 }
+            },
+            Err(e) => {
+                log::warn!("Could not open repo at {}: {}", repo_path, e);
+                RepoOutcome::Failed(e.message().to_string())
             }
-            Err(e) => log::warn!("Could not open repo at {}: {}", repo_path, e),
-        }
+        };
+        outcomes.push((repo_path.clone(), outcome));
+    }
+
+    if let Ok(mut status) = ScheduleStatus::shared().lock() {
+        status.last_run = Some(Local::now());
+        status.repos = outcomes;
     }
-    
     Ok(())
 }
 
-fn handle_repo_auto_commit(repo: &Repository, config: &Config) -> Result<()> {
+#[cfg(feature = "git")]
+fn handle_repo_auto_commit(repo: &Repository, config: &Config) -> Result<RepoOutcome> {
+    if let Some(reason) = repo_state::auto_commit_blocker(repo) {
+        let repo_dir = repo.workdir().unwrap_or_else(|| repo.path());
+        log::info!("Skipping auto-commit in {}: {}", repo_dir.display(), reason);
+        if config.productivity.notify_skipped {
+            alerts::desktop_notify(&format!("Auto-commit skipped in {}: {}", repo_dir.display(), reason), false);
+        }
+        return Ok(RepoOutcome::Skipped(reason));
+    }
+
     let mut index = repo.index()?;
     let statuses = repo.statuses(None)?;
     
     if statuses.is_empty() {
-        return Ok(());
+        return Ok(RepoOutcome::NoChanges);
     }
 
     // Check line count threshold
@@ -490,9 +1032,12 @@ fn handle_repo_auto_commit(repo: &Repository, config: &Config) -> Result<()> {
 
     if total_diff_lines < config.productivity.auto_commit_threshold as usize {
         log::debug!("Skipping auto-commit: {} lines < {} threshold", total_diff_lines, config.productivity.auto_commit_threshold);
-        return Ok(());
+        return Ok(RepoOutcome::BelowThreshold { lines: total_diff_lines, threshold: config.productivity.auto_commit_threshold });
     }
 
+    // Keep HEAD and what was staged so `undo-commit` can revert this
+    commit_undo::save_state(repo)?;
+
     // Stage all changes
     index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
     index.write()?;
@@ -502,19 +1047,73 @@ fn handle_repo_auto_commit(repo: &Repository, config: &Config) -> Result<()> {
     let parent_commit = repo.head()?.peel_to_commit()?;
     let sig = repo.signature()?;
 
-    let message = if config.productivity.ollama_enabled {
-        generate_ai_commit_message(repo).unwrap_or_else(|_| "Auto-commit (Matrix Overlay)".to_string())
+    let rules = &config.productivity.commit_message;
+    #[cfg(feature = "ollama")]
+    let ai_message = if config.productivity.ollama_enabled {
+        generate_ai_commit_message(repo, rules)
+            .and_then(|raw| commit_message::sanitize(&raw, rules))
+            .map_err(|e| log::info!("Using a locally generated commit message: {:#}", e))
+            .ok()
     } else {
-        "Auto-commit (Matrix Overlay)".to_string()
+        None
+    };
+    #[cfg(not(feature = "ollama"))]
+    let ai_message: Option<String> = {
+        if config.productivity.ollama_enabled {
+            log::info!("Ollama commit messages require building with --features ollama; using a local summary");
+        }
+        None
+    };
+    let ai_generated = ai_message.is_some();
+    let message = match ai_message {
+        Some(message) => message,
+        None => local_commit_message(repo, &parent_commit.tree()?, &tree, rules.max_length)
+            .unwrap_or_else(|_| "Auto-commit (Matrix Overlay)".to_string()),
     };
 
-    repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &[&parent_commit])?;
-    log::info!("Auto-committed to {}: {}", repo.path().display(), message);
+    let commit = repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &[&parent_commit])?;
+    commit_undo::record_commit(repo, commit)?;
+    let repo_dir = repo.workdir().unwrap_or_else(|| repo.path());
+    log::info!("Auto-committed to {}: {}", repo_dir.display(), message);
 
-    Ok(())
+    let record = CommitRecord {
+        time: chrono::Local::now(),
+        repo: repo_dir.display().to_string(),
+        lines: total_diff_lines,
+        message,
+        ai_generated,
+    };
+    if let Err(e) = CommitJournal::default_path().and_then(|path| CommitJournal::new(path).append(&record)) {
+        log::warn!("Failed to record auto-commit in the journal: {:#}", e);
+    }
+
+    Ok(RepoOutcome::Committed)
+}
+
+#[cfg(feature = "git")]
+/// Summary of the staged changes built without Ollama (file names or types, line counts).
+fn local_commit_message(repo: &Repository, old: &git2::Tree, new: &git2::Tree, max_length: usize) -> Result<String> {
+    let diff = repo.diff_tree_to_tree(Some(old), Some(new), None)?;
+    let mut changes = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let kind = match delta.status() {
+            git2::Delta::Added => commit_message::ChangeKind::Added,
+            git2::Delta::Deleted => commit_message::ChangeKind::Deleted,
+            _ => commit_message::ChangeKind::Modified,
+        };
+        let path = delta.new_file().path().or_else(|| delta.old_file().path()).map(|p| p.display().to_string()).unwrap_or_default();
+        let (_, insertions, deletions) = match git2::Patch::from_diff(&diff, idx)? {
+            Some(patch) => patch.line_stats()?,
+            None => (0, 0, 0),
+        };
+        changes.push(commit_message::FileChange { path, kind, insertions, deletions });
+    }
+    Ok(commit_message::local_summary(&changes, max_length))
 }
 
-fn generate_ai_commit_message(repo: &Repository) -> Result<String> {
+/// Raw model output; `commit_message::sanitize` decides whether it's usable.
+#[cfg(all(feature = "git", feature = "ollama"))]
+fn generate_ai_commit_message(repo: &Repository, rules: &CommitMessageRules) -> Result<String> {
     // Basic diff for Ollama
     let diff = repo.diff_index_to_workdir(None, None)?;
     let mut diff_text = Vec::new();
@@ -530,9 +1129,10 @@ fn generate_ai_commit_message(repo: &Repository) -> Result<String> {
         diff_str.to_string()
     };
 
+    let style = if rules.conventional { " in Conventional Commits style (e.g. \"fix: ...\")" } else { "" };
     let prompt = format!(
-        "Generate a concise one-line git commit message for the following diff:\n\n{}",
-        truncated_diff
+        "Generate a concise one-line git commit message{} of at most {} characters for the following diff. Reply with the message only.\n\n{}",
+        style, rules.max_length, truncated_diff
     );
 
     // Use reqwest blocking to call Ollama
@@ -549,7 +1149,7 @@ fn generate_ai_commit_message(repo: &Repository) -> Result<String> {
         .json::<serde_json::Value>()?;
 
     if let Some(msg) = res["response"].as_str() {
-        Ok(msg.trim().trim_matches('"').to_string())
+        Ok(msg.to_string())
     } else {
         bail!("Failed to get message from Ollama")
     }
@@ -1,9 +1,9 @@
 //! System metrics collection.
-//! Uses sysinfo and nvml-wrapper to gather CPU, RAM, and GPU statistics.
+//! Uses sysinfo and nvml-wrapper (with an nvidia-smi fallback) to gather CPU, RAM, and GPU statistics.
 
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
-use std::time::{Duration, Instant};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU32, Ordering}};
+use std::time::{Duration, Instant, SystemTime};
 use std::thread;
 use std::fs;
 use std::fmt::Debug;
@@ -11,12 +11,21 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use chrono::{Datelike, Local};
 use crate::config::Config;
-use sysinfo::{System, SystemExt, CpuExt};
+use sysinfo::{System, SystemExt, CpuExt, ProcessExt};
 use sysinfo::DiskExt;
+use serde::Serialize;
+#[cfg(feature = "weather")]
 use serde::Deserialize;
+#[cfg(feature = "git")]
 use git2::Repository;
 use crossbeam_channel::{unbounded, Sender};
 use crate::path_utils;
+#[cfg(feature = "http")]
+use anyhow::Context;
+#[cfg(feature = "http")]
+use crate::http::HttpCache;
+use crate::scheduler::CollectorScheduler;
+use crate::units::UnitSystem;
 use std::io::Read;
     
 
@@ -24,6 +33,8 @@ use std::io::Read;
 pub enum MetricsCommand {
     UpdateConfig(Config),
     ForceRefresh,
+    /// Switches a collector (by ID) on or off without rebuilding the scheduler.
+    SetCollectorEnabled(String, bool),
 }
 
 /// Unique identifier for metrics.
@@ -47,6 +58,12 @@ pub enum MetricId {
     NetworkDetails,
     /// Disk space usage percentage.
     DiskUsage,
+    /// Usage of every configured mount on one row.
+    DiskSummary,
+    /// Inode usage percentage of `/`.
+    InodeUsage,
+    /// Aggregate disk read/write throughput.
+    DiskIo,
     /// CPU core temperature (via hwmon).
     CpuTemp,
     /// System fan speed (RPM).
@@ -55,19 +72,198 @@ pub enum MetricId {
     GpuTemp,
     /// NVIDIA GPU utilization percentage.
     GpuUtil,
+    /// GPU VRAM used / total.
+    GpuVram,
+    /// GPU power draw (Watts).
+    GpuPower,
+    /// GPU graphics clock (MHz).
+    GpuClock,
+    /// Whether the NVIDIA GPU is responding ("on") or powered off/unavailable ("off").
+    GpuState,
     /// Current weather temperature.
     WeatherTemp,
     /// Current weather description (e.g. "Clear").
     WeatherCondition,
+    /// Relative humidity at 2m (%).
+    WeatherHumidity,
+    /// Wind speed (km/h or mph) and compass direction at 10m.
+    WeatherWind,
+    /// Precipitation over the preceding hour (mm or inches).
+    WeatherPrecipitation,
+    /// Today's forecast high (needs `weather.forecast`).
+    WeatherHigh,
+    /// Today's forecast low.
+    WeatherLow,
+    /// Today's highest chance of precipitation.
+    WeatherRainProb,
+    /// Compact forecast for the next three days.
+    WeatherForecast,
+    /// US air quality index (Open-Meteo air-quality API).
+    AirQuality,
+    /// Fine particulate matter (µg/m³).
+    Pm25,
+    /// Coarse particulate matter (µg/m³).
+    Pm10,
+    /// Highest pollen count and its source (Europe only).
+    Pollen,
+    /// UV index with its WHO exposure category.
+    UvIndex,
+    /// Today's sunrise (local time), computed from the weather location.
+    Sunrise,
+    /// Today's sunset (local time).
+    Sunset,
+    /// Time left until sunset.
+    DaylightRemaining,
+    /// Moon phase name and lit fraction.
+    MoonPhase,
+    /// Public IP address as seen by an external endpoint.
+    PublicIp,
+    /// Whether a VPN/WireGuard/tun interface is up.
+    VpnStatus,
+    /// SSID of the connected Wi-Fi network.
+    WifiSsid,
+    /// Wi-Fi signal strength (dBm) and link quality.
+    WifiSignal,
+    /// Currently playing track from an MPRIS media player.
+    NowPlaying,
+    /// Recent desktop notifications, as rows of (received unix time, app, summary).
+    Notifications,
+    /// Default output volume (%).
+    AudioVolume,
+    /// Default output mute state.
+    AudioMute,
+    /// Default output device description.
+    AudioDevice,
+    /// Number of pending package updates.
+    UpdatesPending,
+    /// Number of failed systemd units.
+    FailedUnits,
+    /// Whether the system clock is NTP-synchronized, and by which daemon.
+    TimeSync,
+    /// How far the system clock is off NTP time (ms, fast or slow).
+    ClockOffset,
+    /// Kernel errors and warnings logged since the overlay started.
+    KernelErrors,
+    /// Unread GitHub notifications.
+    GhNotifications,
+    /// Open pull requests awaiting my review on GitHub.
+    GhReviewQueue,
     /// Current day of week for header display.
     DayOfWeek,
+    /// Local time; drawn by the renderer as a large digital or analog clock.
+    Clock,
+    /// Current month; drawn by the renderer as a month grid with today highlighted.
+    Calendar,
     /// Git code delta (added/deleted lines in 24h).
     CodeDelta,
+    /// Auto-commits made today (from the auto-commit journal).
+    AutoCommitsToday,
+    /// Time of the next auto-commit check.
+    AutoCommitNext,
+    /// Time and result of the last auto-commit cycle.
+    AutoCommitLast,
+    /// Key presses today (opt-in typing tracker).
+    KeystrokesToday,
+    /// Typing rate over the last minute, in words per minute.
+    WpmNow,
+    /// Application with the most focused time today, with that time (opt-in screen-time tracker).
+    TopApp,
+    /// Total focused-window time today.
+    FocusedTime,
+    /// Pending tasks (Taskwarrior or todo.txt), optionally with the most urgent one.
+    Tasks,
+    /// Next upcoming calendar event (time and title).
+    NextEvent,
+    /// Latest headlines of `ticker.feed_url`, joined by the ticker separator.
+    FeedHeadlines,
+    /// Branch, ahead/behind and dirty file count of every configured repo on one row.
+    GitStatus,
+    /// Resident memory of the overlay process itself.
+    OverlayMemory,
+    /// Renderer-held state (scroll offsets, item states, surfaces).
+    OverlayRender,
+    /// Top processes by CPU or memory (table).
+    TopProcesses,
     /// Generic custom metric.
     Custom(String),
 }
 
 impl MetricId {
+    /// IDs of the built-in metrics, as written in screen config.
+    pub const BUILTIN: [&'static str; 71] = [
+        "cpu_usage",
+        "ram_usage",
+        "ram_used",
+        "ram_total",
+        "load_avg",
+        "uptime",
+        "network_details",
+        "disk_usage",
+        "disk_summary",
+        "inode_usage",
+        "disk_io",
+        "cpu_temp",
+        "fan_speed",
+        "gpu_temp",
+        "gpu_util",
+        "gpu_vram",
+        "gpu_power",
+        "gpu_clock",
+        "gpu_state",
+        "weather_temp",
+        "weather_condition",
+        "weather_humidity",
+        "weather_wind",
+        "weather_precipitation",
+        "weather_high",
+        "weather_low",
+        "weather_rain_prob",
+        "weather_forecast",
+        "air_quality",
+        "pm2_5",
+        "pm10",
+        "pollen",
+        "uv_index",
+        "sunrise",
+        "sunset",
+        "daylight_remaining",
+        "moon_phase",
+        "public_ip",
+        "vpn_status",
+        "wifi_ssid",
+        "wifi_signal",
+        "now_playing",
+        "notifications",
+        "audio_volume",
+        "audio_mute",
+        "audio_device",
+        "updates_pending",
+        "failed_units",
+        "time_sync",
+        "clock_offset",
+        "kernel_errors",
+        "gh_notifications",
+        "gh_review_queue",
+        "day_of_week",
+        "clock",
+        "calendar",
+        "code_delta",
+        "auto_commits_today",
+        "auto_commit_next",
+        "auto_commit_last",
+        "keystrokes_today",
+        "wpm_now",
+        "top_app",
+        "focused_time",
+        "tasks",
+        "next_event",
+        "feed_headlines",
+        "git_status",
+        "overlay_memory",
+        "overlay_render",
+        "top_processes",
+    ];
+
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "cpu_usage" => Some(Self::CpuUsage),
@@ -78,14 +274,69 @@ impl MetricId {
             "uptime" => Some(Self::Uptime),
             "network_details" => Some(Self::NetworkDetails),
             "disk_usage" => Some(Self::DiskUsage),
+            "disk_summary" => Some(Self::DiskSummary),
+            "inode_usage" => Some(Self::InodeUsage),
+            "disk_io" => Some(Self::DiskIo),
             "cpu_temp" => Some(Self::CpuTemp),
             "fan_speed" => Some(Self::FanSpeed),
             "gpu_temp" => Some(Self::GpuTemp),
             "gpu_util" => Some(Self::GpuUtil),
+            "gpu_vram" => Some(Self::GpuVram),
+            "gpu_power" => Some(Self::GpuPower),
+            "gpu_clock" => Some(Self::GpuClock),
+            "gpu_state" => Some(Self::GpuState),
             "weather_temp" => Some(Self::WeatherTemp),
             "weather_condition" => Some(Self::WeatherCondition),
+            "weather_humidity" => Some(Self::WeatherHumidity),
+            "weather_wind" => Some(Self::WeatherWind),
+            "weather_precipitation" => Some(Self::WeatherPrecipitation),
+            "weather_high" => Some(Self::WeatherHigh),
+            "weather_low" => Some(Self::WeatherLow),
+            "weather_rain_prob" => Some(Self::WeatherRainProb),
+            "weather_forecast" => Some(Self::WeatherForecast),
+            "air_quality" => Some(Self::AirQuality),
+            "pm2_5" => Some(Self::Pm25),
+            "pm10" => Some(Self::Pm10),
+            "pollen" => Some(Self::Pollen),
+            "uv_index" => Some(Self::UvIndex),
+            "sunrise" => Some(Self::Sunrise),
+            "sunset" => Some(Self::Sunset),
+            "daylight_remaining" => Some(Self::DaylightRemaining),
+            "moon_phase" => Some(Self::MoonPhase),
+            "public_ip" => Some(Self::PublicIp),
+            "vpn_status" => Some(Self::VpnStatus),
+            "wifi_ssid" => Some(Self::WifiSsid),
+            "wifi_signal" => Some(Self::WifiSignal),
+            "now_playing" => Some(Self::NowPlaying),
+            "notifications" => Some(Self::Notifications),
+            "audio_volume" => Some(Self::AudioVolume),
+            "audio_mute" => Some(Self::AudioMute),
+            "audio_device" => Some(Self::AudioDevice),
+            "updates_pending" => Some(Self::UpdatesPending),
+            "failed_units" => Some(Self::FailedUnits),
+            "time_sync" => Some(Self::TimeSync),
+            "clock_offset" => Some(Self::ClockOffset),
+            "kernel_errors" => Some(Self::KernelErrors),
+            "gh_notifications" => Some(Self::GhNotifications),
+            "gh_review_queue" => Some(Self::GhReviewQueue),
             "day_of_week" => Some(Self::DayOfWeek),
+            "clock" => Some(Self::Clock),
+            "calendar" => Some(Self::Calendar),
             "code_delta" => Some(Self::CodeDelta),
+            "auto_commits_today" => Some(Self::AutoCommitsToday),
+            "auto_commit_next" => Some(Self::AutoCommitNext),
+            "auto_commit_last" => Some(Self::AutoCommitLast),
+            "keystrokes_today" => Some(Self::KeystrokesToday),
+            "wpm_now" => Some(Self::WpmNow),
+            "top_app" => Some(Self::TopApp),
+            "focused_time" => Some(Self::FocusedTime),
+            "tasks" => Some(Self::Tasks),
+            "next_event" => Some(Self::NextEvent),
+            "feed_headlines" => Some(Self::FeedHeadlines),
+            "git_status" => Some(Self::GitStatus),
+            "overlay_memory" => Some(Self::OverlayMemory),
+            "overlay_render" => Some(Self::OverlayRender),
+            "top_processes" => Some(Self::TopProcesses),
             other => Some(Self::Custom(other.to_string())),
         }
     }
@@ -100,14 +351,69 @@ impl MetricId {
             Self::Uptime => "uptime",
             Self::NetworkDetails => "network_details",
             Self::DiskUsage => "disk_usage",
+            Self::DiskSummary => "disk_summary",
+            Self::InodeUsage => "inode_usage",
+            Self::DiskIo => "disk_io",
             Self::CpuTemp => "cpu_temp",
             Self::FanSpeed => "fan_speed",
             Self::GpuTemp => "gpu_temp",
             Self::GpuUtil => "gpu_util",
+            Self::GpuVram => "gpu_vram",
+            Self::GpuPower => "gpu_power",
+            Self::GpuClock => "gpu_clock",
+            Self::GpuState => "gpu_state",
             Self::WeatherTemp => "weather_temp",
             Self::WeatherCondition => "weather_condition",
+            Self::WeatherHumidity => "weather_humidity",
+            Self::WeatherWind => "weather_wind",
+            Self::WeatherPrecipitation => "weather_precipitation",
+            Self::WeatherHigh => "weather_high",
+            Self::WeatherLow => "weather_low",
+            Self::WeatherRainProb => "weather_rain_prob",
+            Self::WeatherForecast => "weather_forecast",
+            Self::AirQuality => "air_quality",
+            Self::Pm25 => "pm2_5",
+            Self::Pm10 => "pm10",
+            Self::Pollen => "pollen",
+            Self::UvIndex => "uv_index",
+            Self::Sunrise => "sunrise",
+            Self::Sunset => "sunset",
+            Self::DaylightRemaining => "daylight_remaining",
+            Self::MoonPhase => "moon_phase",
+            Self::PublicIp => "public_ip",
+            Self::VpnStatus => "vpn_status",
+            Self::WifiSsid => "wifi_ssid",
+            Self::WifiSignal => "wifi_signal",
+            Self::NowPlaying => "now_playing",
+            Self::Notifications => "notifications",
+            Self::AudioVolume => "audio_volume",
+            Self::AudioMute => "audio_mute",
+            Self::AudioDevice => "audio_device",
+            Self::UpdatesPending => "updates_pending",
+            Self::FailedUnits => "failed_units",
+            Self::TimeSync => "time_sync",
+            Self::ClockOffset => "clock_offset",
+            Self::KernelErrors => "kernel_errors",
+            Self::GhNotifications => "gh_notifications",
+            Self::GhReviewQueue => "gh_review_queue",
             Self::DayOfWeek => "day_of_week",
+            Self::Clock => "clock",
+            Self::Calendar => "calendar",
             Self::CodeDelta => "code_delta",
+            Self::AutoCommitsToday => "auto_commits_today",
+            Self::AutoCommitNext => "auto_commit_next",
+            Self::AutoCommitLast => "auto_commit_last",
+            Self::KeystrokesToday => "keystrokes_today",
+            Self::WpmNow => "wpm_now",
+            Self::TopApp => "top_app",
+            Self::FocusedTime => "focused_time",
+            Self::Tasks => "tasks",
+            Self::NextEvent => "next_event",
+            Self::FeedHeadlines => "feed_headlines",
+            Self::GitStatus => "git_status",
+            Self::OverlayMemory => "overlay_memory",
+            Self::OverlayRender => "overlay_render",
+            Self::TopProcesses => "top_processes",
             Self::Custom(s) => s.as_str(),
         }
     }
@@ -122,20 +428,79 @@ impl MetricId {
             Self::Uptime => "Uptime",
             Self::NetworkDetails => "Network",
             Self::DiskUsage => "Disk",
+            Self::DiskSummary => "Disks",
+            Self::InodeUsage => "Inodes",
+            Self::DiskIo => "Disk I/O",
             Self::CpuTemp => "CPU Temp",
             Self::FanSpeed => "Fan",
             Self::GpuTemp => "GPU Temp",
             Self::GpuUtil => "GPU Util",
+            Self::GpuVram => "VRAM",
+            Self::GpuPower => "GPU Power",
+            Self::GpuClock => "GPU Clock",
+            Self::GpuState => "GPU State",
             Self::WeatherTemp => "Temp",
             Self::WeatherCondition => "Weather",
+            Self::WeatherHumidity => "Humidity",
+            Self::WeatherWind => "Wind",
+            Self::WeatherPrecipitation => "Precip",
+            Self::WeatherHigh => "High",
+            Self::WeatherLow => "Low",
+            Self::WeatherRainProb => "Rain",
+            Self::WeatherForecast => "Forecast",
+            Self::AirQuality => "AQI",
+            Self::Pm25 => "PM2.5",
+            Self::Pm10 => "PM10",
+            Self::Pollen => "Pollen",
+            Self::UvIndex => "UV",
+            Self::Sunrise => "Sunrise",
+            Self::Sunset => "Sunset",
+            Self::DaylightRemaining => "Daylight",
+            Self::MoonPhase => "Moon",
+            Self::PublicIp => "Public IP",
+            Self::VpnStatus => "VPN",
+            Self::WifiSsid => "Wi-Fi",
+            Self::WifiSignal => "Signal",
+            Self::NowPlaying => "Playing",
+            Self::Notifications => "Notifications",
+            Self::AudioVolume => "Volume",
+            Self::AudioMute => "Mute",
+            Self::AudioDevice => "Output",
+            Self::UpdatesPending => "Updates",
+            Self::FailedUnits => "Failed Units",
+            Self::TimeSync => "Time Sync",
+            Self::KernelErrors => "dmesg",
+            Self::ClockOffset => "Clock Offset",
+            Self::GhNotifications => "GH Notifs",
+            Self::GhReviewQueue => "GH Reviews",
             Self::DayOfWeek => "Day",
+            Self::Clock => "Time",
+            Self::Calendar => "Month",
             Self::CodeDelta => "Delta",
-            Self::Custom(s) => s.as_str(),
+            Self::AutoCommitsToday => "Auto-Commits",
+            Self::AutoCommitNext => "Next Commit",
+            Self::AutoCommitLast => "Last Commit",
+            Self::KeystrokesToday => "Keys",
+            Self::WpmNow => "WPM",
+            Self::TopApp => "Top App",
+            Self::FocusedTime => "Focused",
+            Self::Tasks => "Tasks",
+            Self::NextEvent => "Next",
+            Self::FeedHeadlines => "News",
+            Self::GitStatus => "Git",
+            Self::OverlayMemory => "Overlay RSS",
+            Self::OverlayRender => "Renderer",
+            Self::TopProcesses => "Top",
+            // "service_docker.service" -> "docker"
+            Self::Custom(s) => match s.strip_prefix("service_") {
+                Some(unit) => unit.trim_end_matches(".service"),
+                None => s.as_str(),
+            },
         }.to_string()
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct MetricData {
     pub values: HashMap<MetricId, MetricValue>,
 }
@@ -149,6 +514,7 @@ impl MetricData {
         let sample: String = entries.iter().take(3).map(|(k, v)| {
             match v {
                 MetricValue::NetworkMap(_) => format!("{:?}: <Map>", k),
+                MetricValue::Table(rows) => format!("{:?}: <Table {} rows>", k, rows.len()),
                 MetricValue::Float(f) => format!("{:?}: {:.1}", k, f),
                 MetricValue::Int(i) => format!("{:?}: {}", k, i),
                 MetricValue::String(s) => format!("{:?}: \"{}\"", k, s),
@@ -160,28 +526,91 @@ impl MetricData {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Serializes without a tag (`12.5`, `"eth0"`, `null`, ...) for status queries.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum MetricValue {
     Float(f64),
     Int(i64),
     String(String),
-    NetworkMap(HashMap<String, (u64, u64)>),
+    /// Per-interface (rx, tx) rates in bytes per second.
+    NetworkMap(HashMap<String, (f64, f64)>),
+    /// Rows of pre-formatted cells (e.g. top processes), drawn as a multi-row table.
+    Table(Vec<Vec<String>>),
     None,
 }
 
+impl MetricValue {
+    /// Best-effort numeric view of the value.
+    ///
+    /// Formatted strings (e.g. "42.0%", "6.5 GB", "45°C") yield their leading number;
+    /// network maps yield the total of all rx + tx rates (bytes/sec).
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            MetricValue::Float(v) => Some(*v),
+            MetricValue::Int(v) => Some(*v as f64),
+            MetricValue::String(s) => parse_leading_number(s),
+            MetricValue::NetworkMap(map) => Some(map.values().map(|(rx, tx)| rx + tx).sum()),
+            MetricValue::Table(_) | MetricValue::None => None,
+        }
+    }
+}
+
+/// Parses the numeric prefix of a formatted metric string ("+12 / -3" -> 12.0).
+pub fn parse_leading_number(s: &str) -> Option<f64> {
+    let s = s.trim_start();
+    let end = s
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && (c == '-' || c == '+'))))
+        .map(|(i, _)| i)
+        .unwrap_or(s.len());
+    s[..end].parse::<f64>().ok()
+}
+
 #[derive(Debug)]
 pub struct SharedMetrics {
     pub data: MetricData,
     pub timestamp: Instant,
     pub day_of_week: String,
+    /// Recent numeric samples per metric (oldest first), for sparklines.
+    pub history: HashMap<MetricId, VecDeque<f64>>,
+    /// Metrics currently above a warn/crit threshold.
+    pub levels: HashMap<MetricId, crate::alerts::AlertLevel>,
+    /// Registered collectors and whether they are enabled (for the tray menu).
+    pub collectors: Vec<crate::scheduler::CollectorStatus>,
+    /// Runtime state of each collector (for `ctl status`).
+    pub health: Vec<crate::scheduler::CollectorHealth>,
 }
 
 impl SharedMetrics {
+    /// Number of samples kept per metric.
+    pub const HISTORY_LEN: usize = 60;
+
     pub fn new() -> Self {
         Self {
             data: MetricData { values: HashMap::new() },
             timestamp: Instant::now(),
             day_of_week: "Unknown".to_string(),
+            history: HashMap::new(),
+            levels: HashMap::new(),
+            collectors: Vec::new(),
+            health: Vec::new(),
+        }
+    }
+
+    /// Appends the current numeric values to the history ring buffers.
+    /// Metrics that are no longer collected are dropped.
+    pub fn record_history(&mut self) {
+        let values = &self.data.values;
+        self.history.retain(|id, _| values.contains_key(id));
+        for (id, value) in values {
+            if let Some(v) = value.as_f64() {
+                let samples = self.history.entry(id.clone()).or_insert_with(|| VecDeque::with_capacity(Self::HISTORY_LEN));
+                if samples.len() == Self::HISTORY_LEN {
+                    samples.pop_front();
+                }
+                samples.push_back(v);
+            }
         }
     }
 }
@@ -190,31 +619,107 @@ impl SharedMetrics {
 /// 
 /// Ties to Stage 0: <1% CPU target. Ensures that background metrics collection
 /// does not compete with higher-priority rendering or system tasks.
-#[derive(Debug, Clone)]
+///
+/// One guard is shared (via `Arc`) between the `CpuCollector`, which feeds it the
+/// samples it already takes, the metrics loop, which samples itself whenever the
+/// collector didn't (it may not be displayed, or be toggled off), and the scheduler,
+/// which defers heavy collectors using per-collector thresholds. Decisions use an exponential moving
+/// average so a single busy tick doesn't pause anything.
+#[derive(Debug)]
 pub struct ResourceGuard {
     /// CPU usage percentage threshold (0.0 - 100.0)
     pub cpu_threshold: f32,
+    /// Per-collector thresholds, keyed by collector ID; others use `cpu_threshold`.
+    thresholds: HashMap<String, f32>,
+    /// Moving average of global CPU usage, stored as f32 bits (NaN until the first sample).
+    cpu_average: AtomicU32,
+    /// Whether `record` was called since the metrics loop last sampled.
+    recorded: AtomicBool,
 }
 
+/// Weight of the newest sample in the CPU moving average (~3 samples to follow a step halfway).
+const CPU_EMA_ALPHA: f32 = 0.2;
+
 impl ResourceGuard {
     /// Creates a new ResourceGuard with the given CPU threshold.
     pub fn new(threshold: f32) -> Self {
-        Self { cpu_threshold: threshold }
+        Self {
+            cpu_threshold: threshold,
+            thresholds: HashMap::new(),
+            cpu_average: AtomicU32::new(f32::NAN.to_bits()),
+            recorded: AtomicBool::new(false),
+        }
     }
 
-    /// Returns true if the current global CPU usage exceeds the threshold.
-    ///
-    /// Refreshes the CPU stats in the provided SysinfoManager.
-    pub fn should_throttle(&self, sys_manager: &mut SysinfoManager) -> bool {
-        sys_manager.system.refresh_cpu();
-        sys_manager.system.global_cpu_info().cpu_usage() > self.cpu_threshold
+    /// Builds the guard from the `resource_guard` config section.
+    pub fn from_config(limits: &crate::config::ResourceLimits) -> Self {
+        Self { thresholds: limits.collector_thresholds.clone(), ..Self::new(limits.cpu_threshold) }
+    }
+
+    /// Folds a global CPU usage sample (%) into the moving average.
+    pub fn record(&self, cpu_usage: f32) {
+        let _ = self.cpu_average.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+            let average = f32::from_bits(bits);
+            let next = if average.is_nan() { cpu_usage } else { average + CPU_EMA_ALPHA * (cpu_usage - average) };
+            Some(next.to_bits())
+        });
+        self.recorded.store(true, Ordering::Relaxed);
+    }
+
+    /// Averaged global CPU usage (%); 0 before the first sample.
+    pub fn cpu_usage(&self) -> f32 {
+        let average = f32::from_bits(self.cpu_average.load(Ordering::Relaxed));
+        if average.is_nan() { 0.0 } else { average }
+    }
+
+    /// Whether the collector `id` should put off heavy work at the averaged load.
+    pub fn should_defer(&self, id: &str) -> bool {
+        self.cpu_usage() > self.thresholds.get(id).copied().unwrap_or(self.cpu_threshold)
+    }
+
+    /// Returns true if the averaged global CPU usage exceeds the threshold.
+    pub fn should_throttle(&self) -> bool {
+        self.cpu_usage() > self.cpu_threshold
+    }
+
+    /// Called once per metrics-loop iteration: records the usage `read` returns unless the
+    /// `CpuCollector` recorded a sample since the last call (refreshing the CPU counters
+    /// twice a tick would shorten the collector's measuring window).
+    pub fn sample_with(&self, read: impl FnOnce() -> f32) {
+        if !self.recorded.swap(false, Ordering::Relaxed) {
+            self.record(read());
+            self.recorded.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// `sample_with` reading global CPU usage from sysinfo.
+    pub fn sample(&self, sys_manager: &mut SysinfoManager) {
+        self.sample_with(|| {
+            sys_manager.system.refresh_cpu();
+            sys_manager.system.global_cpu_info().cpu_usage()
+        });
     }
 }
 
 pub trait MetricCollector: Send + Sync + Debug {
-    fn id(&self) -> &'static str;
+    /// Key for `disabled_collectors`, `collector_intervals` and `collector_thresholds`;
+    /// collectors configured once per entry (commands, feeds, remote instances) include the entry in it.
+    fn id(&self) -> &str;
     fn collect(&mut self) -> HashMap<MetricId, MetricValue>;
     fn label(&self) -> &'static str;
+    /// Preferred refresh interval; `None` runs every `update_ms` tick.
+    /// Overridable per collector ID via `collector_intervals` in config.
+    fn interval(&self) -> Option<Duration> {
+        None
+    }
+    /// Called on a wake event (resume from suspend, forced refresh) so collectors
+    /// that backed off from an unavailable device try again.
+    fn wake(&mut self) {}
+    /// Heavy collectors (subprocesses, network requests, repository scans) are
+    /// deferred by the scheduler's `ResourceGuard` while the CPU is busy.
+    fn heavy(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug)]
@@ -249,11 +754,18 @@ impl Debug for SysinfoManager {
 #[derive(Debug)]
 pub struct CpuCollector {
     sys: Arc<Mutex<SysinfoManager>>,
+    guard: Option<Arc<ResourceGuard>>,
 }
 
 impl CpuCollector {
     pub fn new(sys: Arc<Mutex<SysinfoManager>>) -> Self {
-        Self { sys }
+        Self { sys, guard: None }
+    }
+
+    /// Feeds each sample into `guard`, so throttling needs no refresh of its own.
+    pub fn with_guard(mut self, guard: Arc<ResourceGuard>) -> Self {
+        self.guard = Some(guard);
+        self
     }
 }
 
@@ -266,6 +778,9 @@ impl MetricCollector for CpuCollector {
             Ok(mut manager) => {
                 manager.system.refresh_cpu();
                 let global = manager.system.global_cpu_info().cpu_usage();
+                if let Some(guard) = &self.guard {
+                    guard.record(global);
+                }
                 map.insert(MetricId::CpuUsage, MetricValue::String(format!("{:.1}%", global)));
                 
                 // Note: Per-core metrics are collected but MetricId enum is static.
@@ -280,7 +795,7 @@ impl MetricCollector for CpuCollector {
     }
 }
 
-/// Collector for Date/Time (Day of Week).
+/// Collector for Date/Time (Day of Week, the clock and the calendar month).
 #[derive(Debug)]
 pub struct DateCollector;
 
@@ -292,37 +807,79 @@ impl MetricCollector for DateCollector {
         let day = Local::now().format("%A").to_string();
         log::debug!("Collected DayOfWeek: {}", day);
         map.insert(MetricId::DayOfWeek, MetricValue::String(day));
+        // The renderer reads the time itself when drawing the clock; this is for the status outputs
+        map.insert(MetricId::Clock, MetricValue::String(Local::now().format("%H:%M").to_string()));
+        map.insert(MetricId::Calendar, MetricValue::String(Local::now().format("%B %Y").to_string()));
         map
     }
 }
 
+#[cfg(feature = "weather")]
 #[derive(Deserialize)]
 struct OpenMeteoResponse {
     current: CurrentWeather,
+    /// Present when `daily=` was requested.
+    #[serde(default)]
+    daily: Option<DailyWeather>,
+}
+
+/// Per-day columns; Open-Meteo returns null where a model has no value.
+#[cfg(feature = "weather")]
+#[derive(Deserialize)]
+struct DailyWeather {
+    time: Vec<chrono::NaiveDate>,
+    #[serde(default)]
+    weather_code: Vec<Option<i64>>,
+    #[serde(default)]
+    temperature_2m_max: Vec<Option<f64>>,
+    #[serde(default)]
+    temperature_2m_min: Vec<Option<f64>>,
+    #[serde(default)]
+    precipitation_probability_max: Vec<Option<f64>>,
 }
 
+#[cfg(feature = "weather")]
 #[derive(Deserialize)]
 struct CurrentWeather {
     temperature_2m: f64,
     weather_code: i64,
+    #[serde(default)]
+    relative_humidity_2m: Option<f64>,
+    #[serde(default)]
+    wind_speed_10m: Option<f64>,
+    #[serde(default)]
+    wind_direction_10m: Option<f64>,
+    #[serde(default)]
+    precipitation: Option<f64>,
 }
 
 /// Collector for Weather data from Open-Meteo.
+#[cfg(feature = "weather")]
 #[derive(Debug)]
 pub struct OpenMeteoCollector {
     lat: f64,
     lon: f64,
     enabled: bool,
     url_base: String,
+    /// Named location; its values are published as `weather_temp@<name>` etc.
+    location: Option<String>,
+    /// Also fetch the daily forecast (`weather_high`, `weather_low`, ...).
+    forecast: bool,
 }
 
+#[cfg(feature = "weather")]
 impl OpenMeteoCollector {
+    /// Open-Meteo refreshes current conditions every 15 minutes.
+    const RESPONSE_TTL: Duration = Duration::from_secs(300);
+
     pub fn new(lat: f64, lon: f64, enabled: bool) -> Self {
         Self {
             lat,
             lon,
             enabled,
             url_base: "https://api.open-meteo.com".to_string(),
+            location: None,
+            forecast: false,
         }
     }
 
@@ -332,9 +889,55 @@ impl OpenMeteoCollector {
             lon,
             enabled: true,
             url_base: url,
+            location: None,
+            forecast: false,
+        }
+    }
+
+    /// Requests today's and the next three days' forecast along with current conditions.
+    pub fn with_forecast(mut self, forecast: bool) -> Self {
+        self.forecast = forecast;
+        self
+    }
+
+    /// Collector for a named location from `weather.locations`.
+    pub fn for_location(location: &crate::config::WeatherLocation) -> Self {
+        Self { location: Some(location.name.clone()), ..Self::new(location.lat, location.lon, true) }
+    }
+
+    /// Key under which a weather value is published (location-qualified for named locations).
+    fn key(&self, id: MetricId) -> MetricId {
+        match &self.location {
+            Some(loc) => MetricId::Custom(crate::config::Weather::location_metric(id.as_str(), loc)),
+            None => id,
         }
     }
 
+    /// "Wed 18°/9° Rain · Thu 15°/8° Partly cloudy · ..." for the days after today.
+    fn forecast_strip(daily: &DailyWeather) -> String {
+        let at = |column: &[Option<f64>], i: usize| column.get(i).copied().flatten();
+        (1..daily.time.len().min(4))
+            .map(|i| {
+                let temps = match (at(&daily.temperature_2m_max, i), at(&daily.temperature_2m_min, i)) {
+                    (Some(high), Some(low)) => {
+                        let units = UnitSystem::current();
+                        format!(" {:.0}°/{:.0}°", units.degrees(high), units.degrees(low))
+                    }
+                    _ => String::new(),
+                };
+                let condition = daily.weather_code.get(i).copied().flatten().map(|code| format!(" {}", Self::weather_code_str(code)));
+                format!("{}{}{}", daily.time[i].format("%a"), temps, condition.unwrap_or_default())
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+
+    /// 8-point compass direction for a wind bearing in degrees (direction the wind comes from).
+    fn compass_point(degrees: f64) -> &'static str {
+        const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+        POINTS[((degrees.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+    }
+
     fn weather_code_str(code: i64) -> String {
         match code {
             0 => "Clear sky",
@@ -355,247 +958,2353 @@ impl OpenMeteoCollector {
     }
 }
 
+/// A place returned by the Open-Meteo geocoding API.
+#[cfg(feature = "weather")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeoLocation {
+    pub name: String,
+    /// State or region.
+    #[serde(default)]
+    pub admin1: Option<String>,
+    #[serde(default)]
+    pub country: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[cfg(feature = "weather")]
+impl GeoLocation {
+    /// "Springfield, Illinois, United States", skipping parts the API didn't return.
+    pub fn display_name(&self) -> String {
+        [Some(&self.name), self.admin1.as_ref(), self.country.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Looks up places matching a city name (best matches first, at most 10).
+#[cfg(feature = "weather")]
+pub fn search_locations(name: &str) -> anyhow::Result<Vec<GeoLocation>> {
+    search_locations_at("https://geocoding-api.open-meteo.com", name)
+}
+
+#[cfg(feature = "weather")]
+pub fn search_locations_at(url_base: &str, name: &str) -> anyhow::Result<Vec<GeoLocation>> {
+    #[derive(Deserialize)]
+    struct SearchResponse {
+        // Absent when nothing matches
+        #[serde(default)]
+        results: Vec<GeoLocation>,
+    }
+    let url = reqwest::Url::parse_with_params(
+        &format!("{}/v1/search", url_base),
+        &[("name", name.trim()), ("count", "10"), ("language", "en"), ("format", "json")],
+    )?;
+    Ok(HttpCache::shared().get_json::<SearchResponse>(url.as_str(), Duration::from_secs(3600))?.results)
+}
+
+/// Geo-IP service used when `weather.geoip` is set and no coordinates are configured.
+#[cfg(feature = "weather")]
+pub const GEO_IP_URL: &str = "https://ipapi.co/json/";
+
+/// How long a Geo-IP result saved on disk is reused before looking it up again.
+#[cfg(feature = "weather")]
+const GEO_IP_MAX_AGE: chrono::Duration = chrono::Duration::days(7);
+
+/// A Geo-IP result as saved in `geoip.json`.
+#[cfg(feature = "weather")]
+#[derive(Debug, Serialize, Deserialize)]
+struct GeoIpCache {
+    lat: f64,
+    lon: f64,
+    fetched: chrono::DateTime<Local>,
+}
+
+/// Approximate coordinates of this machine's public IP, from `url` (ipapi.co format).
+/// With `cache`, a result saved there in the last week is used instead of a request.
+#[cfg(feature = "weather")]
+pub fn geoip_location(url: &str, cache: Option<&Path>) -> anyhow::Result<(f64, f64)> {
+    if let Some(saved) = cache
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<GeoIpCache>(&content).ok())
+        .filter(|saved| Local::now() - saved.fetched < GEO_IP_MAX_AGE)
+    {
+        return Ok((saved.lat, saved.lon));
+    }
+
+    #[derive(Deserialize)]
+    struct IpApiResponse { latitude: f64, longitude: f64 }
+    let geo = HttpCache::shared().get_json::<IpApiResponse>(url, Duration::from_secs(6 * 3600))?;
+    if let Some(path) = cache {
+        let saved = GeoIpCache { lat: geo.latitude, lon: geo.longitude, fetched: Local::now() };
+        let written = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(path, serde_json::to_string(&saved)?));
+        if let Err(e) = written {
+            log::warn!("Failed to save Geo-IP location to {:?}: {}", path, e);
+        }
+    }
+    Ok((geo.latitude, geo.longitude))
+}
+
+/// Coordinates for the main weather location: the configured ones, or with
+/// `weather.geoip` and 0.0/0.0, a one-off Geo-IP lookup (saved for a week).
+fn weather_coordinates(weather: &crate::config::Weather) -> (f64, f64) {
+    if weather.lat != 0.0 || weather.lon != 0.0 {
+        return (weather.lat, weather.lon);
+    }
+    if !weather.geoip {
+        log::warn!("Weather location is 0.0/0.0: set weather.lat/lon (or search for a city in the Weather tab), or enable weather.geoip");
+        return (weather.lat, weather.lon);
+    }
+    #[cfg(not(feature = "weather"))]
+    {
+        log::warn!("weather.geoip requires building with --features weather; set weather.lat/lon instead");
+        (weather.lat, weather.lon)
+    }
+    #[cfg(feature = "weather")]
+    {
+        log::info!("Privacy notice: weather.geoip is enabled, so your public IP address is sent to {} to find an approximate location. Set weather.lat/lon to avoid this.", GEO_IP_URL);
+        let cache = crate::journal::data_dir().map(|dir| dir.join("geoip.json")).ok();
+        match geoip_location(GEO_IP_URL, cache.as_deref()) {
+            Ok((lat, lon)) => {
+                log::info!("Weather location from Geo-IP: ({:.2}, {:.2})", lat, lon);
+                (lat, lon)
+            }
+            Err(e) => {
+                log::warn!("Geo-IP lookup failed: {:#}", e);
+                (weather.lat, weather.lon)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "weather")]
 impl MetricCollector for OpenMeteoCollector {
     fn id(&self) -> &'static str { "open_meteo" }
     fn label(&self) -> &'static str { "Weather" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(600)) }
+    fn heavy(&self) -> bool { true }
     fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
         let mut map = HashMap::new();
         if !self.enabled {
             return map;
         }
 
-        // Privacy Auto-Adjust: If lat/lon are 0.0, attempt one-time Geo-IP lookup
-        if self.lat == 0.0 && self.lon == 0.0 {
-             if let Ok(resp) = reqwest::blocking::get("http://ip-api.com/json") {
-                 #[derive(Deserialize)]
-                 struct IpApiResponse { lat: f64, lon: f64 }
-                 if let Ok(geo) = resp.json::<IpApiResponse>() {
-                     log::info!("Geo-IP Privacy Auto-Adjust: Detected Location ({}, {})", geo.lat, geo.lon);
-                     self.lat = geo.lat;
-                     self.lon = geo.lon;
-                 }
-             }
+        let mut url = format!("{}/v1/forecast?latitude={}&longitude={}&current=temperature_2m,weather_code,relative_humidity_2m,wind_speed_10m,wind_direction_10m,precipitation", self.url_base, self.lat, self.lon);
+        if self.forecast {
+            url.push_str("&daily=weather_code,temperature_2m_max,temperature_2m_min,precipitation_probability_max&timezone=auto&forecast_days=4");
         }
 
-        let url = format!("{}/v1/forecast?latitude={}&longitude={}&current=temperature_2m,weather_code", self.url_base, self.lat, self.lon);
-
-        match reqwest::blocking::Client::new().get(&url).timeout(std::time::Duration::from_secs(5)).send() {
-            Ok(resp) => {
-                if let Ok(json) = resp.json::<OpenMeteoResponse>() {
-                    map.insert(MetricId::WeatherTemp, MetricValue::String(format!("{:.1}°C", json.current.temperature_2m)));
-                    map.insert(MetricId::WeatherCondition, MetricValue::String(Self::weather_code_str(json.current.weather_code)));
+        let units = UnitSystem::current();
+        match HttpCache::shared().get_json::<OpenMeteoResponse>(&url, Self::RESPONSE_TTL) {
+            Ok(json) => {
+                map.insert(self.key(MetricId::WeatherTemp), MetricValue::String(units.temperature(json.current.temperature_2m, 1)));
+                map.insert(self.key(MetricId::WeatherCondition), MetricValue::String(Self::weather_code_str(json.current.weather_code)));
+                let current = &json.current;
+                if let Some(humidity) = current.relative_humidity_2m {
+                    map.insert(self.key(MetricId::WeatherHumidity), MetricValue::String(format!("{:.0}%", humidity)));
+                }
+                if let Some(speed) = current.wind_speed_10m {
+                    let wind = match current.wind_direction_10m {
+                        Some(dir) => format!("{} {}", units.speed(speed), Self::compass_point(dir)),
+                        None => units.speed(speed),
+                    };
+                    map.insert(self.key(MetricId::WeatherWind), MetricValue::String(wind));
+                }
+                if let Some(precip) = current.precipitation {
+                    map.insert(self.key(MetricId::WeatherPrecipitation), MetricValue::String(units.precipitation(precip)));
+                }
+                if let Some(daily) = &json.daily {
+                    let today = |column: &[Option<f64>]| column.first().copied().flatten();
+                    if let Some(high) = today(&daily.temperature_2m_max) {
+                        map.insert(self.key(MetricId::WeatherHigh), MetricValue::String(units.temperature(high, 1)));
+                    }
+                    if let Some(low) = today(&daily.temperature_2m_min) {
+                        map.insert(self.key(MetricId::WeatherLow), MetricValue::String(units.temperature(low, 1)));
+                    }
+                    if let Some(prob) = today(&daily.precipitation_probability_max) {
+                        map.insert(self.key(MetricId::WeatherRainProb), MetricValue::String(format!("{:.0}%", prob)));
+                    }
+                    map.insert(self.key(MetricId::WeatherForecast), MetricValue::String(Self::forecast_strip(daily)));
                 }
             },
             Err(e) => {
-                log::warn!("Weather fetch failed: {}", e);
-                map.insert(MetricId::WeatherTemp, MetricValue::String("N/A".to_string()));
+                log::warn!("Weather fetch failed: {:#}", e);
+                map.insert(self.key(MetricId::WeatherTemp), MetricValue::String("N/A".to_string()));
             }
         }
         map
     }
 }
 
-/// Collector for Network usage (Bytes/sec).
-/// Reads /proc/net/dev directly to avoid sysinfo locking contention and ensure independent delta tracking.
-#[derive(Debug)]
-pub struct NetworkCollector {
-    last_snapshot: HashMap<String, (u64, u64)>, // iface -> (rx_bytes, tx_bytes)
-    last_collection_time: Instant,
+#[cfg(feature = "weather")]
+#[derive(Deserialize)]
+struct AirQualityResponse {
+    current: CurrentAirQuality,
 }
 
-impl NetworkCollector {
-    pub fn new() -> Self {
-        Self {
-            last_snapshot: HashMap::new(),
-            last_collection_time: Instant::now(),
-        }
-    }
-
-    fn read_proc_net_dev(&self) -> HashMap<String, (u64, u64)> {
-        let mut map = HashMap::new();
-        if let Ok(content) = fs::read_to_string("/proc/net/dev") {
-            for line in content.lines().skip(2) {
-                let line = line.trim();
-                if let Some(colon_idx) = line.find(':') {
-                    let iface = &line[..colon_idx];
-                    let stats_str = &line[colon_idx+1..];
-                    let stats: Vec<&str> = stats_str.split_whitespace().collect();
-                    if stats.len() >= 9 {
-                        if let (Ok(rx), Ok(tx)) = (stats[0].parse::<u64>(), stats[8].parse::<u64>()) {
-                            map.insert(iface.to_string(), (rx, tx));
-                        }
-                    }
-                }
-            }
-        }
-        map
-    }
+#[cfg(feature = "weather")]
+#[derive(Deserialize)]
+struct CurrentAirQuality {
+    us_aqi: Option<f64>,
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    alder_pollen: Option<f64>,
+    birch_pollen: Option<f64>,
+    grass_pollen: Option<f64>,
+    mugwort_pollen: Option<f64>,
+    olive_pollen: Option<f64>,
+    ragweed_pollen: Option<f64>,
+    #[serde(default)]
+    uv_index: Option<f64>,
+}
 
-    #[allow(dead_code)]
-    fn format_rate(bytes_sec: f64) -> String {
-        if bytes_sec >= 1_073_741_824.0 {
-            format!("{:.1} GB/s", bytes_sec / 1_073_741_824.0)
-        } else if bytes_sec >= 1_048_576.0 {
-            format!("{:.1} MB/s", bytes_sec / 1_048_576.0)
-        } else if bytes_sec >= 1024.0 {
-            format!("{:.1} KB/s", bytes_sec / 1024.0)
-        } else {
-            format!("{:.0} B/s", bytes_sec)
-        }
+#[cfg(feature = "weather")]
+impl CurrentAirQuality {
+    /// Highest pollen count as "34 (Grass)"; `None` where pollen data is unavailable.
+    fn dominant_pollen(&self) -> Option<String> {
+        [
+            ("Alder", self.alder_pollen),
+            ("Birch", self.birch_pollen),
+            ("Grass", self.grass_pollen),
+            ("Mugwort", self.mugwort_pollen),
+            ("Olive", self.olive_pollen),
+            ("Ragweed", self.ragweed_pollen),
+        ]
+        .into_iter()
+        .filter_map(|(name, v)| v.map(|v| (name, v)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(name, v)| format!("{:.0} ({})", v, name))
     }
 }
 
-impl MetricCollector for NetworkCollector {
-    fn id(&self) -> &'static str { "network" }
-    fn label(&self) -> &'static str { "Net" }
-    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
-        let now = Instant::now();
-        let current_snapshot = self.read_proc_net_dev();
-        let duration = now.duration_since(self.last_collection_time).as_secs_f64();
-        let duration = if duration < 0.001 { 1.0 } else { duration };
-
-        let mut results = HashMap::new();
-        let mut details_map = HashMap::new();
-
-        for (iface, (curr_rx, curr_tx)) in &current_snapshot {
-            if iface == "lo" { continue; }
-            if let Some((last_rx, last_tx)) = self.last_snapshot.get(iface) {
-                let delta_rx = if *curr_rx >= *last_rx { curr_rx - last_rx } else { 0 };
-                let delta_tx = if *curr_tx >= *last_tx { curr_tx - last_tx } else { 0 };
-
-                let _rx_rate = delta_rx as f64 / duration;
-                let _tx_rate = delta_tx as f64 / duration;
-
-                // We store raw bytes in the map for now, or formatted strings?
-                // MetricValue::NetworkMap expects u64.
-                details_map.insert(iface.clone(), (delta_rx, delta_tx));
-            }
-        }
-
-        results.insert(MetricId::NetworkDetails, MetricValue::NetworkMap(details_map));
-        self.last_snapshot = current_snapshot;
-        self.last_collection_time = now;
-
-        results
-    }
+/// UV index with its WHO exposure category, e.g. "6.2 (High)".
+#[cfg(feature = "weather")]
+fn format_uv_index(uv: f64) -> String {
+    let category = match uv {
+        uv if uv < 3.0 => "Low",
+        uv if uv < 6.0 => "Moderate",
+        uv if uv < 8.0 => "High",
+        uv if uv < 11.0 => "Very High",
+        _ => "Extreme",
+    };
+    format!("{:.1} ({})", uv, category)
 }
 
-/// Collector for Memory usage.
+/// Collector for air quality, pollen and UV index from Open-Meteo's air-quality API.
+#[cfg(feature = "weather")]
 #[derive(Debug)]
-pub struct MemoryCollector {
-    sys: Arc<Mutex<SysinfoManager>>,
+pub struct AirQualityCollector {
+    lat: f64,
+    lon: f64,
+    url_base: String,
 }
 
-impl MemoryCollector {
-    pub fn new(sys: Arc<Mutex<SysinfoManager>>) -> Self {
-        Self { sys }
+#[cfg(feature = "weather")]
+impl AirQualityCollector {
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self::new_with_url(lat, lon, "https://air-quality-api.open-meteo.com".to_string())
+    }
+
+    pub fn new_with_url(lat: f64, lon: f64, url_base: String) -> Self {
+        Self { lat, lon, url_base }
     }
 }
 
-impl MetricCollector for MemoryCollector {
-    fn id(&self) -> &'static str { "memory" }
-    fn label(&self) -> &'static str { "RAM" }
+#[cfg(feature = "weather")]
+impl MetricCollector for AirQualityCollector {
+    fn id(&self) -> &'static str { "air_quality" }
+    fn label(&self) -> &'static str { "Air Quality" }
+    // Open-Meteo updates air quality hourly
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(1800)) }
+    fn heavy(&self) -> bool { true }
     fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
         let mut map = HashMap::new();
-        match self.sys.lock() {
-            Ok(mut manager) => {
-                manager.system.refresh_memory();
-                let used = manager.system.used_memory();
-                let total = manager.system.total_memory();
-                
-                let used_gb = used as f64 / 1024.0 / 1024.0 / 1024.0;
-                let percent = if total > 0 { (used as f64 / total as f64) * 100.0 } else { 0.0 };
-                
-                map.insert(MetricId::RamUsed, MetricValue::String(format!("{:.1} GB", used_gb)));
-                map.insert(MetricId::RamUsage, MetricValue::String(format!("{:.0}%", percent)));
-            },
+        let url = format!(
+            "{}/v1/air-quality?latitude={}&longitude={}&current=us_aqi,pm2_5,pm10,alder_pollen,birch_pollen,grass_pollen,mugwort_pollen,olive_pollen,ragweed_pollen,uv_index",
+            self.url_base, self.lat, self.lon
+        );
+
+        match HttpCache::shared().get_json::<AirQualityResponse>(&url, Duration::from_secs(900)) {
+            Ok(json) => {
+                let current = json.current;
+                let value = |v: Option<f64>, unit: &str| match v {
+                    Some(v) => MetricValue::String(format!("{:.1}{}", v, unit)),
+                    None => MetricValue::None,
+                };
+                map.insert(MetricId::AirQuality, current.us_aqi.map_or(MetricValue::None, |v| MetricValue::Int(v.round() as i64)));
+                map.insert(MetricId::Pm25, value(current.pm2_5, " µg/m³"));
+                map.insert(MetricId::Pm10, value(current.pm10, " µg/m³"));
+                map.insert(MetricId::Pollen, current.dominant_pollen().map_or(MetricValue::None, MetricValue::String));
+                map.insert(MetricId::UvIndex, current.uv_index.map_or(MetricValue::None, |v| MetricValue::String(format_uv_index(v))));
+            }
             Err(e) => {
-                log::error!("MemoryCollector lock failed: {}", e);
-                map.insert(MetricId::RamUsage, MetricValue::String("ERR".to_string()));
+                log::warn!("Air quality fetch failed: {:#}", e);
+                map.insert(MetricId::AirQuality, MetricValue::String("N/A".to_string()));
             }
         }
         map
     }
 }
 
-/// Collector for Uptime and Load Average.
+/// Collector for the public IP address, queried from a plain-text endpoint (e.g. api.ipify.org).
+#[cfg(feature = "http")]
 #[derive(Debug)]
-pub struct UptimeLoadCollector {
-    sys: Arc<Mutex<SysinfoManager>>,
+pub struct PublicIpCollector {
+    endpoint: String,
+    interval: Duration,
 }
 
-impl UptimeLoadCollector {
-    pub fn new(sys: Arc<Mutex<SysinfoManager>>) -> Self {
-        Self { sys }
+#[cfg(feature = "http")]
+impl PublicIpCollector {
+    pub fn new(endpoint: &str, interval_secs: u64) -> Self {
+        Self { endpoint: endpoint.to_string(), interval: Duration::from_secs(interval_secs) }
     }
 }
 
-impl MetricCollector for UptimeLoadCollector {
-    fn id(&self) -> &'static str { "uptime_load" }
-    fn label(&self) -> &'static str { "System" }
+#[cfg(feature = "http")]
+impl MetricCollector for PublicIpCollector {
+    fn id(&self) -> &'static str { "public_ip" }
+    fn label(&self) -> &'static str { "Public IP" }
+    fn heavy(&self) -> bool { true }
+    fn interval(&self) -> Option<Duration> { Some(self.interval) }
     fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
         let mut map = HashMap::new();
-        match self.sys.lock() {
-            Ok(manager) => {
-                let uptime_secs = manager.system.uptime();
-                let days = uptime_secs / 86400;
-                let hours = (uptime_secs % 86400) / 3600;
-                let mins = (uptime_secs % 3600) / 60;
-                
-                let uptime_str = if days > 0 {
-                    format!("{} days {}:{:02}", days, hours, mins)
-                } else {
-                    format!("{}:{:02}", hours, mins)
-                };
-                
-                map.insert(MetricId::Uptime, MetricValue::String(uptime_str));
-                
-                let load = manager.system.load_average();
-                map.insert(MetricId::LoadAvg, MetricValue::String(format!("{:.2}", load.one)));
+        // Short TTL: only de-duplicates forced refreshes, the address can change at any time
+        let ip = HttpCache::shared().get_text(&self.endpoint, Duration::from_secs(30));
+        let value = match ip {
+            // Only accept something that parses as an address, never arbitrary response text
+            Ok(body) => match body.trim().parse::<std::net::IpAddr>() {
+                Ok(addr) => MetricValue::String(addr.to_string()),
+                Err(_) => {
+                    log::warn!("Public IP endpoint returned an unexpected response");
+                    MetricValue::String("N/A".to_string())
+                }
             },
             Err(e) => {
-                log::error!("UptimeLoadCollector lock failed: {}", e);
-                map.insert(MetricId::Uptime, MetricValue::String("ERR".to_string()));
+                log::warn!("Public IP lookup failed: {:#}", e);
+                MetricValue::String("Offline".to_string())
             }
-        }
+        };
+        map.insert(MetricId::PublicIp, value);
         map
     }
 }
 
-/// Collector for Disk usage.
+/// Collector for VPN status: reports whether a WireGuard, tun/tap or PPP interface is up.
 #[derive(Debug)]
-pub struct DiskCollector {
-    sys: Arc<Mutex<SysinfoManager>>,
+pub struct VpnStatusCollector {
+    net_path: PathBuf,
 }
 
-impl DiskCollector {
-    pub fn new(sys: Arc<Mutex<SysinfoManager>>) -> Self {
-        Self { sys }
+impl VpnStatusCollector {
+    /// Interface name prefixes commonly used by VPN clients.
+    const VPN_PREFIXES: [&'static str; 8] = ["wg", "tun", "tap", "ppp", "nordlynx", "proton", "tailscale", "mullvad"];
+
+    pub fn new() -> Self {
+        Self::new_with_path(PathBuf::from("/sys/class/net"))
+    }
+
+    pub fn new_with_path(net_path: PathBuf) -> Self {
+        Self { net_path }
+    }
+
+    fn is_vpn_interface(&self, name: &str) -> bool {
+        if Self::VPN_PREFIXES.iter().any(|p| name.starts_with(p)) {
+            return true;
+        }
+        // ARPHRD_NONE (65534): point-to-point tunnels such as WireGuard and tun devices
+        fs::read_to_string(self.net_path.join(name).join("type"))
+            .map(|t| t.trim() == "65534")
+            .unwrap_or(false)
+    }
+
+    fn is_up(&self, name: &str) -> bool {
+        // Tunnels without carrier detection report "unknown" while active
+        fs::read_to_string(self.net_path.join(name).join("operstate"))
+            .map(|s| matches!(s.trim(), "up" | "unknown"))
+            .unwrap_or(false)
     }
 }
 
-impl MetricCollector for DiskCollector {
-    fn id(&self) -> &'static str { "disk" }
-    fn label(&self) -> &'static str { "Disk" }
+impl Default for VpnStatusCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricCollector for VpnStatusCollector {
+    fn id(&self) -> &'static str { "vpn_status" }
+    fn label(&self) -> &'static str { "VPN" }
     fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut active: Vec<String> = fs::read_dir(&self.net_path)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .filter(|name| self.is_vpn_interface(name) && self.is_up(name))
+                    .collect()
+            })
+            .unwrap_or_default();
+        active.sort();
+
+        let status = if active.is_empty() { "OFF".to_string() } else { format!("ON ({})", active.join(", ")) };
         let mut map = HashMap::new();
-        if let Ok(mut manager) = self.sys.lock() {
-            manager.system.refresh_disks_list();
-            manager.system.refresh_disks();
-            for disk in manager.system.disks() {
-                if disk.mount_point() == std::path::Path::new("/") {
-                     let used = disk.total_space() - disk.available_space();
-                     let total = disk.total_space();
-                     let percent = if total > 0 { (used as f64 / total as f64) * 100.0 } else { 0.0 };
-                     map.insert(MetricId::DiskUsage, MetricValue::String(format!("{:.1}%", percent)));
-                }
-            }
-        }
+        map.insert(MetricId::VpnStatus, MetricValue::String(status));
         map
     }
 }
 
-/// Collector for Hardware Monitor sensors (Temperature, Fans).
+/// Collector for the Wi-Fi link: signal and quality from /proc/net/wireless,
+/// SSID from `iw dev <iface> link` (which /proc does not expose).
+#[derive(Debug)]
+pub struct WifiCollector {
+    wireless_path: PathBuf,
+    iw_command: String,
+    /// Interface and SSID of the current link; `iw` runs again only once the link drops
+    /// or moves to another interface.
+    ssid: Option<(String, String)>,
+}
+
+/// One interface line of /proc/net/wireless.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WirelessLink {
+    pub interface: String,
+    /// Link quality, usually out of 70.
+    pub quality: f64,
+    pub level_dbm: f64,
+}
+
+impl WifiCollector {
+    /// Maximum link quality reported by most drivers (cfg80211 scales to 70).
+    const MAX_QUALITY: f64 = 70.0;
+
+    pub fn new() -> Self {
+        Self::new_with_path(PathBuf::from("/proc/net/wireless"), "iw".to_string())
+    }
+
+    pub fn new_with_path(wireless_path: PathBuf, iw_command: String) -> Self {
+        Self { wireless_path, iw_command, ssid: None }
+    }
+
+    /// Parses /proc/net/wireless, skipping the two header lines.
+    pub fn parse_wireless(contents: &str) -> Vec<WirelessLink> {
+        contents
+            .lines()
+            .skip(2)
+            .filter_map(|line| {
+                let (iface, rest) = line.split_once(':')?;
+                let fields: Vec<&str> = rest.split_whitespace().collect();
+                // status, link, level, noise, ... (values carry a trailing '.' when updated)
+                let num = |i: usize| fields.get(i)?.trim_end_matches('.').parse::<f64>().ok();
+                let quality = num(1)?;
+                let mut level_dbm = num(2)?;
+                // Some drivers report the level as an unsigned byte (e.g. 200 for -56 dBm)
+                if level_dbm > 0.0 {
+                    level_dbm -= 256.0;
+                }
+                Some(WirelessLink { interface: iface.trim().to_string(), quality, level_dbm })
+            })
+            .collect()
+    }
+
+    /// Extracts the SSID from `iw dev <iface> link` output.
+    pub fn parse_iw_ssid(output: &str) -> Option<String> {
+        output
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("SSID:"))
+            .map(|ssid| ssid.trim().to_string())
+            .filter(|ssid| !ssid.is_empty())
+    }
+
+    fn ssid(&self, interface: &str) -> Option<String> {
+        let output = Command::new(&self.iw_command).args(["dev", interface, "link"]).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::parse_iw_ssid(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+impl Default for WifiCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricCollector for WifiCollector {
+    fn id(&self) -> &'static str { "wifi" }
+    fn label(&self) -> &'static str { "Wi-Fi" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(10)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let link = fs::read_to_string(&self.wireless_path)
+            .ok()
+            .and_then(|contents| Self::parse_wireless(&contents).into_iter().next());
+
+        match link {
+            Some(link) => {
+                if self.ssid.as_ref().is_none_or(|(interface, _)| *interface != link.interface) {
+                    self.ssid = self.ssid(&link.interface).map(|ssid| (link.interface.clone(), ssid));
+                }
+                let ssid = self.ssid.as_ref().map_or_else(|| link.interface.clone(), |(_, ssid)| ssid.clone());
+                let percent = (link.quality / Self::MAX_QUALITY * 100.0).clamp(0.0, 100.0);
+                map.insert(MetricId::WifiSsid, MetricValue::String(ssid));
+                map.insert(MetricId::WifiSignal, MetricValue::String(format!("{:.0} dBm ({:.0}%)", link.level_dbm, percent)));
+            }
+            None => {
+                self.ssid = None;
+                map.insert(MetricId::WifiSsid, MetricValue::String("Disconnected".to_string()));
+                map.insert(MetricId::WifiSignal, MetricValue::String("N/A".to_string()));
+            }
+        }
+        map
+    }
+}
+
+/// Formats an MPRIS track as "▶ Artist – Title 1:23/3:45" (positions in microseconds).
+pub fn format_now_playing(status: &str, artist: Option<&str>, title: Option<&str>, position_us: Option<i64>, length_us: Option<i64>) -> String {
+    let icon = match status {
+        "Playing" => "▶",
+        "Paused" => "⏸",
+        _ => "■",
+    };
+    let track = match (artist.filter(|a| !a.is_empty()), title.filter(|t| !t.is_empty())) {
+        (Some(artist), Some(title)) => format!("{} – {}", artist, title),
+        (None, Some(title)) => title.to_string(),
+        (Some(artist), None) => artist.to_string(),
+        (None, None) => "Unknown track".to_string(),
+    };
+    let clock = |us: i64| {
+        let secs = us.max(0) / 1_000_000;
+        format!("{}:{:02}", secs / 60, secs % 60)
+    };
+    match (position_us, length_us.filter(|l| *l > 0)) {
+        (Some(pos), Some(len)) => format!("{} {} {}/{}", icon, track, clock(pos), clock(len)),
+        (Some(pos), None) => format!("{} {} {}", icon, track, clock(pos)),
+        _ => format!("{} {}", icon, track),
+    }
+}
+
+/// Collector for the current track of MPRIS media players on the session bus.
+/// A playing player wins over paused ones; nothing is published when no player is running.
+#[cfg(feature = "dbus")]
+pub struct MprisCollector {
+    connection: Option<zbus::blocking::Connection>,
+}
+
+#[cfg(feature = "dbus")]
+impl Debug for MprisCollector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MprisCollector").field("connected", &self.connection.is_some()).finish()
+    }
+}
+
+#[cfg(feature = "dbus")]
+impl MprisCollector {
+    const BUS_PREFIX: &'static str = "org.mpris.MediaPlayer2.";
+
+    pub fn new() -> Self {
+        Self { connection: None }
+    }
+
+    fn connection(&mut self) -> Option<&zbus::blocking::Connection> {
+        if self.connection.is_none() {
+            match zbus::blocking::Connection::session() {
+                Ok(conn) => self.connection = Some(conn),
+                Err(e) => log::warn!("MPRIS: session bus unavailable: {}", e),
+            }
+        }
+        self.connection.as_ref()
+    }
+
+    /// Returns (status, formatted track) for one player.
+    fn query_player(conn: &zbus::blocking::Connection, bus_name: &str) -> zbus::Result<(String, String)> {
+        use zbus::zvariant::Value;
+
+        let proxy = zbus::blocking::Proxy::new(conn, bus_name, "/org/mpris/MediaPlayer2", "org.mpris.MediaPlayer2.Player")?;
+        let status: String = proxy.get_property("PlaybackStatus")?;
+        let metadata: HashMap<String, zbus::zvariant::OwnedValue> = proxy.get_property("Metadata")?;
+        // Position is optional in the spec (streams often omit it)
+        let position: Option<i64> = proxy.get_property("Position").ok();
+
+        let title = metadata.get("xesam:title").and_then(|v| match &**v {
+            Value::Str(s) => Some(s.to_string()),
+            _ => None,
+        });
+        let artist = metadata.get("xesam:artist").and_then(|v| match &**v {
+            Value::Array(a) => {
+                let names: Vec<String> = a.get().iter().filter_map(|n| match n {
+                    Value::Str(s) => Some(s.to_string()),
+                    _ => None,
+                }).collect();
+                Some(names.join(", "))
+            }
+            Value::Str(s) => Some(s.to_string()),
+            _ => None,
+        });
+        let length = metadata.get("mpris:length").and_then(|v| match &**v {
+            Value::I64(l) => Some(*l),
+            Value::U64(l) => i64::try_from(*l).ok(),
+            _ => None,
+        });
+
+        let text = format_now_playing(&status, artist.as_deref(), title.as_deref(), position, length);
+        Ok((status, text))
+    }
+}
+
+#[cfg(feature = "dbus")]
+impl Default for MprisCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "dbus")]
+impl MetricCollector for MprisCollector {
+    fn id(&self) -> &'static str { "mpris" }
+    fn label(&self) -> &'static str { "Now Playing" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let conn = match self.connection() {
+            Some(c) => c.clone(),
+            None => return map,
+        };
+        let names = match zbus::blocking::fdo::DBusProxy::new(&conn).and_then(|dbus| Ok(dbus.list_names()?)) {
+            Ok(names) => names,
+            Err(e) => {
+                log::warn!("MPRIS: failed to list bus names: {}", e);
+                self.connection = None;
+                return map;
+            }
+        };
+
+        let mut players: Vec<(String, String)> = names
+            .iter()
+            .filter(|n| n.as_str().starts_with(Self::BUS_PREFIX))
+            .filter_map(|n| Self::query_player(&conn, n.as_str()).ok())
+            .collect();
+        players.sort_by_key(|(status, _)| match status.as_str() {
+            "Playing" => 0,
+            "Paused" => 1,
+            _ => 2,
+        });
+        if let Some((_, text)) = players.into_iter().next() {
+            map.insert(MetricId::NowPlaying, MetricValue::String(text));
+        }
+        map
+    }
+}
+
+/// Notifications remembered for the mirror, however many are shown.
+#[cfg(feature = "dbus")]
+const NOTIFICATION_HISTORY: usize = 20;
+
+/// A desktop notification seen on the session bus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MirroredNotification {
+    /// Unix time it was sent.
+    pub received: i64,
+    pub app: String,
+    pub summary: String,
+}
+
+/// Rows of the `notifications` table: the newest `count` notifications that haven't faded
+/// out yet, newest first, as (received unix time, app, summary).
+pub fn notification_rows(
+    recent: &VecDeque<MirroredNotification>,
+    now: i64,
+    mirror: &crate::config::NotificationMirror,
+) -> Vec<Vec<String>> {
+    recent
+        .iter()
+        .rev()
+        .filter(|n| now - n.received < mirror.fade_secs as i64)
+        .take(mirror.count)
+        .map(|n| vec![n.received.to_string(), n.app.clone(), n.summary.clone()])
+        .collect()
+}
+
+/// Collector for the notification mirror. A session-bus monitor records every `Notify`
+/// call to `org.freedesktop.Notifications`; each cycle publishes the recent ones. The
+/// monitor thread exits once the collector is dropped (at the latest with the next
+/// message it sees, since it blocks on the bus).
+#[cfg(feature = "dbus")]
+#[derive(Debug)]
+pub struct NotificationCollector {
+    mirror: crate::config::NotificationMirror,
+    recent: Arc<Mutex<VecDeque<MirroredNotification>>>,
+    stop: Arc<AtomicBool>,
+}
+
+#[cfg(feature = "dbus")]
+impl NotificationCollector {
+    pub fn new(mirror: crate::config::NotificationMirror) -> Self {
+        let recent = Arc::new(Mutex::new(VecDeque::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (recent_thread, stop_thread, ignore_apps) = (recent.clone(), stop.clone(), mirror.ignore_apps.clone());
+        thread::spawn(move || {
+            while !stop_thread.load(Ordering::Relaxed) {
+                if let Err(e) = Self::monitor(&recent_thread, &ignore_apps, &stop_thread) {
+                    log::warn!("Notification mirror: {}; retrying in 30s", e);
+                }
+                // Sleep in short steps so a config reload doesn't wait for the retry
+                let until = Instant::now() + Duration::from_secs(30);
+                while Instant::now() < until && !stop_thread.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(200));
+                }
+            }
+            log::debug!("Notification mirror stopped");
+        });
+        Self { mirror, recent, stop }
+    }
+
+    /// Turns a fresh session-bus connection into a monitor of `Notify` calls and records
+    /// them until the connection drops or `stop` is set. A monitor connection can't send
+    /// anything else.
+    fn monitor(recent: &Mutex<VecDeque<MirroredNotification>>, ignore_apps: &[String], stop: &AtomicBool) -> zbus::Result<()> {
+        let conn = zbus::blocking::Connection::session()?;
+        let rule = "type='method_call',interface='org.freedesktop.Notifications',member='Notify'";
+        conn.call_method(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            Some("org.freedesktop.DBus.Monitoring"),
+            "BecomeMonitor",
+            &(vec![rule], 0u32),
+        )?;
+        log::info!("Mirroring desktop notifications");
+        for message in zbus::blocking::MessageIterator::from(&conn) {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            let message = message?;
+            if message.member().as_deref() != Some("Notify") {
+                continue;
+            }
+            // (app_name, replaces_id, app_icon, summary, body, actions, hints, expire_timeout)
+            type Notify = (String, u32, String, String, String, Vec<String>, HashMap<String, zbus::zvariant::OwnedValue>, i32);
+            let Ok((app, _, _, summary, body, _, _, _)) = message.body::<Notify>() else {
+                continue;
+            };
+            if ignore_apps.iter().any(|ignored| ignored.eq_ignore_ascii_case(&app)) {
+                continue;
+            }
+            let summary = if summary.trim().is_empty() { body } else { summary };
+            let mut recent = recent.lock().unwrap_or_else(|e| e.into_inner());
+            recent.push_back(MirroredNotification {
+                received: chrono::Utc::now().timestamp(),
+                app,
+                summary: summary.split_whitespace().collect::<Vec<_>>().join(" "),
+            });
+            while recent.len() > NOTIFICATION_HISTORY {
+                recent.pop_front();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "dbus")]
+impl MetricCollector for NotificationCollector {
+    fn id(&self) -> &'static str { "notifications" }
+    fn label(&self) -> &'static str { "Notifications" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let rows = notification_rows(&recent, chrono::Utc::now().timestamp(), &self.mirror);
+        HashMap::from([(MetricId::Notifications, MetricValue::Table(rows))])
+    }
+}
+
+#[cfg(feature = "dbus")]
+impl Drop for NotificationCollector {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// A PulseAudio/PipeWire sink as reported by `pactl list sinks`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SinkInfo {
+    pub name: String,
+    pub description: String,
+    /// Average of the channel volumes (%).
+    pub volume: i64,
+    pub muted: bool,
+}
+
+/// Collector for the default audio output via `pactl` (works with PulseAudio and pipewire-pulse).
+#[derive(Debug)]
+pub struct AudioCollector {
+    command: String,
+    /// Name of the default sink and when it was looked up.
+    default_sink: Option<(String, Instant)>,
+}
+
+impl AudioCollector {
+    /// How long the default sink name is reused before `pactl get-default-sink` runs again
+    /// (sooner when that sink disappears from the list).
+    const DEFAULT_SINK_REFRESH: Duration = Duration::from_secs(60);
+
+    pub fn new() -> Self {
+        Self::new_with_command("pactl".to_string())
+    }
+
+    pub fn new_with_command(command: String) -> Self {
+        Self { command, default_sink: None }
+    }
+
+    fn default_sink(&mut self) -> Option<String> {
+        match &self.default_sink {
+            Some((name, at)) if at.elapsed() < Self::DEFAULT_SINK_REFRESH => Some(name.clone()),
+            _ => {
+                let name = self.run(&["get-default-sink"])?.trim().to_string();
+                self.default_sink = Some((name.clone(), Instant::now()));
+                Some(name)
+            }
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Option<String> {
+        // pactl localizes its output; force the C locale for parsing
+        let output = Command::new(&self.command).args(args).env("LC_ALL", "C").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Finds the sink called `default_name` in `pactl list sinks` output.
+    pub fn parse_sinks(list_output: &str, default_name: &str) -> Option<SinkInfo> {
+        let mut current: Option<SinkInfo> = None;
+        let mut found = None;
+        for line in list_output.lines() {
+            let line = line.trim();
+            if line.starts_with("Sink #") {
+                if let Some(sink) = current.take().filter(|s| s.name == default_name) {
+                    found = Some(sink);
+                }
+                current = Some(SinkInfo { name: String::new(), description: String::new(), volume: 0, muted: false });
+                continue;
+            }
+            let sink = match current.as_mut() {
+                Some(s) => s,
+                None => continue,
+            };
+            if let Some(name) = line.strip_prefix("Name:") {
+                sink.name = name.trim().to_string();
+            } else if let Some(desc) = line.strip_prefix("Description:") {
+                sink.description = desc.trim().to_string();
+            } else if let Some(mute) = line.strip_prefix("Mute:") {
+                sink.muted = mute.trim() == "yes";
+            } else if let Some(volume) = line.strip_prefix("Volume:") {
+                // "front-left: 39321 /  60% / -13.31 dB,   front-right: 39321 /  60% / -13.31 dB"
+                let percents: Vec<i64> = volume
+                    .split('/')
+                    .filter_map(|part| part.trim().strip_suffix('%'))
+                    .filter_map(|p| p.trim().parse().ok())
+                    .collect();
+                if !percents.is_empty() {
+                    sink.volume = percents.iter().sum::<i64>() / percents.len() as i64;
+                }
+            }
+        }
+        found.or_else(|| current.filter(|s| s.name == default_name))
+    }
+}
+
+impl Default for AudioCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricCollector for AudioCollector {
+    fn id(&self) -> &'static str { "audio" }
+    fn label(&self) -> &'static str { "Audio" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(5)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let Some(default_sink) = self.default_sink() else {
+            log::debug!("pactl unavailable, no audio metrics");
+            return map;
+        };
+        let list = self.run(&["list", "sinks"]);
+        let mut sink = list.as_deref().and_then(|list| Self::parse_sinks(list, &default_sink));
+        if sink.is_none() {
+            // The default may have changed since it was looked up (e.g. headphones unplugged)
+            self.default_sink = None;
+            if let (Some(list), Some(default_sink)) = (list.as_deref(), self.default_sink()) {
+                sink = Self::parse_sinks(list, &default_sink);
+            }
+        }
+        match sink {
+            Some(sink) => {
+                map.insert(MetricId::AudioVolume, MetricValue::Int(sink.volume));
+                map.insert(MetricId::AudioMute, MetricValue::String(if sink.muted { "muted" } else { "on" }.to_string()));
+                let device = if sink.description.is_empty() { sink.name } else { sink.description };
+                map.insert(MetricId::AudioDevice, MetricValue::String(device));
+            }
+            None => {
+                map.insert(MetricId::AudioDevice, MetricValue::String("No output".to_string()));
+            }
+        }
+        map
+    }
+}
+
+/// Collector for pending package updates. Checks run every `refresh` (typically an hour)
+/// and are postponed while the CPU is busy, since they refresh package metadata.
+#[derive(Debug)]
+pub struct UpdatesCollector {
+    command: Option<String>,
+    refresh: Duration,
+    last_check: Option<Instant>,
+    cached: Option<i64>,
+}
+
+impl UpdatesCollector {
+    /// Package manager checks tried in order when no command is configured.
+    const CANDIDATES: [(&'static str, &'static str); 3] = [
+        ("checkupdates", "checkupdates"),
+        ("dnf", "dnf check-update -q"),
+        ("apt", "apt list --upgradable"),
+    ];
+    pub fn new(command: Option<String>, refresh_secs: u64) -> Self {
+        let command = command.or_else(Self::detect_command);
+        match &command {
+            Some(cmd) => log::info!("Package updates: using '{}'", cmd),
+            None => log::warn!("Package updates: no supported package manager found; set updates.command"),
+        }
+        Self { command, refresh: Duration::from_secs(refresh_secs), last_check: None, cached: None }
+    }
+
+    fn detect_command() -> Option<String> {
+        let path = std::env::var_os("PATH")?;
+        Self::CANDIDATES
+            .iter()
+            .find(|(bin, _)| std::env::split_paths(&path).any(|dir| dir.join(bin).is_file()))
+            .map(|(_, cmd)| cmd.to_string())
+    }
+
+    /// Counts update lines, skipping the headers apt and dnf print.
+    pub fn count_updates(output: &str) -> i64 {
+        output
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .filter(|l| !l.starts_with("Listing...") && !l.starts_with("Last metadata expiration") && !l.starts_with("Obsoleting"))
+            .count() as i64
+    }
+
+    fn check(&self, command: &str) -> Option<i64> {
+        let output = Command::new("sh").args(["-c", command]).env("LC_ALL", "C").output().ok()?;
+        // checkupdates exits 2 and dnf check-update exits 100 when there is nothing to do / updates exist
+        match output.status.code() {
+            Some(0) | Some(2) | Some(100) => Some(Self::count_updates(&String::from_utf8_lossy(&output.stdout))),
+            _ => {
+                log::warn!("Package update check '{}' failed with {}", command, output.status);
+                None
+            }
+        }
+    }
+}
+
+impl MetricCollector for UpdatesCollector {
+    fn id(&self) -> &'static str { "updates" }
+    fn label(&self) -> &'static str { "Updates" }
+    // Polls often enough to retry soon after a postponed check; the real work runs every `refresh`
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(60)) }
+    // The scheduler postpones heavy collectors while the CPU is busy
+    fn heavy(&self) -> bool { true }
+    fn wake(&mut self) { self.last_check = None; }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let due = self.last_check.is_none_or(|t| t.elapsed() >= self.refresh);
+        if let (true, Some(command)) = (due, self.command.as_deref()) {
+            self.last_check = Some(Instant::now());
+            if let Some(count) = self.check(command) {
+                self.cached = Some(count);
+            }
+        }
+        if let Some(count) = self.cached {
+            map.insert(MetricId::UpdatesPending, MetricValue::Int(count));
+        }
+        map
+    }
+}
+
+/// Collector for GitHub: unread notifications (`gh_notifications`) and open pull
+/// requests requesting my review (`gh_review_queue`), checked every `github.interval_secs`.
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct GitHubCollector {
+    token: Option<String>,
+    api_url: String,
+    refresh: Duration,
+}
+
+#[cfg(feature = "http")]
+impl GitHubCollector {
+    /// The notifications API returns at most this many threads per page; more show as "50+".
+    const PAGE_SIZE: usize = 50;
+
+    pub fn new(config: &crate::config::GitHub) -> Self {
+        let token = config.resolved_token();
+        if token.is_none() {
+            log::warn!("GitHub: no token in github.token, $GITHUB_TOKEN or $GH_TOKEN");
+        }
+        Self { token, api_url: config.api_url.trim_end_matches('/').to_string(), refresh: Duration::from_secs(config.interval_secs) }
+    }
+
+    fn get(&self, token: &str, path: &str) -> anyhow::Result<serde_json::Value> {
+        let auth = format!("Bearer {}", token);
+        let headers = [("Authorization", auth.as_str()), ("Accept", "application/vnd.github+json")];
+        HttpCache::shared().get_json_with_headers(&format!("{}{}", self.api_url, path), &headers, self.refresh / 2)
+    }
+
+    fn notifications(&self, token: &str) -> anyhow::Result<MetricValue> {
+        let threads = self.get(token, &format!("/notifications?per_page={}", Self::PAGE_SIZE))?;
+        let count = threads.as_array().context("Unexpected notifications response")?.len();
+        Ok(if count >= Self::PAGE_SIZE {
+            MetricValue::String(format!("{}+", Self::PAGE_SIZE))
+        } else {
+            MetricValue::Int(count as i64)
+        })
+    }
+
+    fn review_queue(&self, token: &str) -> anyhow::Result<MetricValue> {
+        let results = self.get(token, "/search/issues?q=is:open+is:pr+archived:false+review-requested:@me&per_page=1")?;
+        let total = results.get("total_count").and_then(|v| v.as_i64()).context("Unexpected search response")?;
+        Ok(MetricValue::Int(total))
+    }
+}
+
+#[cfg(feature = "http")]
+impl MetricCollector for GitHubCollector {
+    fn id(&self) -> &'static str { "github" }
+    fn label(&self) -> &'static str { "GitHub" }
+    fn interval(&self) -> Option<Duration> { Some(self.refresh) }
+    fn heavy(&self) -> bool { true }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let token = match &self.token {
+            Some(token) => token,
+            None => {
+                map.insert(MetricId::GhNotifications, MetricValue::String("NO TOKEN".to_string()));
+                map.insert(MetricId::GhReviewQueue, MetricValue::String("NO TOKEN".to_string()));
+                return map;
+            }
+        };
+        for (id, result) in [(MetricId::GhNotifications, self.notifications(token)), (MetricId::GhReviewQueue, self.review_queue(token))] {
+            let value = result.unwrap_or_else(|e| {
+                log::warn!("GitHub {} check failed: {:#}", id.as_str(), e);
+                MetricValue::String("Offline".to_string())
+            });
+            map.insert(id, value);
+        }
+        map
+    }
+}
+
+/// Collector for CI status: the latest run on the default branch of each
+/// `productivity.repos` entry, as `ci_<repo dir>` = "pass", "fail", "running" or "none".
+/// GitHub Actions is used for repos with a GitHub `origin`; `ci.status_urls` covers the rest.
+#[cfg(all(feature = "http", feature = "git"))]
+#[derive(Debug)]
+pub struct CiCollector {
+    repos: Vec<String>,
+    status_urls: HashMap<String, String>,
+    token: Option<String>,
+    api_url: String,
+    refresh: Duration,
+}
+
+#[cfg(all(feature = "http", feature = "git"))]
+impl CiCollector {
+    /// Default branches rarely change.
+    const BRANCH_TTL: Duration = Duration::from_secs(24 * 3600);
+
+    pub fn new(repos: Vec<String>, ci: &crate::config::Ci, github: &crate::config::GitHub) -> Self {
+        Self {
+            repos,
+            status_urls: ci.status_urls.clone(),
+            token: github.resolved_token(),
+            api_url: github.api_url.trim_end_matches('/').to_string(),
+            refresh: Duration::from_secs(ci.interval_secs),
+        }
+    }
+
+    /// Metric ID for a repo path (its directory name).
+    pub fn repo_metric(repo: &str) -> MetricId {
+        let name = Path::new(repo).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| repo.to_string());
+        MetricId::Custom(format!("ci_{}", name))
+    }
+
+    /// `owner/name` of a GitHub remote URL (https or ssh form).
+    pub fn github_slug(remote: &str) -> Option<String> {
+        let path = remote
+            .strip_prefix("https://github.com/")
+            .or_else(|| remote.strip_prefix("git@github.com:"))
+            .or_else(|| remote.strip_prefix("ssh://git@github.com/"))?;
+        let path = path.trim_end_matches('/').trim_end_matches(".git");
+        match path.split('/').collect::<Vec<_>>()[..] {
+            [owner, name] if !owner.is_empty() && !name.is_empty() => Some(path.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Maps a CI status word (GitHub conclusions or common generic ones) to pass/fail/running.
+    pub fn normalize_state(state: &str) -> String {
+        match state.trim().to_lowercase().as_str() {
+            "success" | "succeeded" | "passed" | "pass" | "ok" | "green" | "neutral" | "skipped" => "pass",
+            "failure" | "failed" | "fail" | "error" | "red" | "timed_out" | "startup_failure" | "cancelled" | "action_required" => "fail",
+            "in_progress" | "queued" | "pending" | "running" | "requested" | "waiting" | "started" => "running",
+            _ => "unknown",
+        }
+        .to_string()
+    }
+
+    fn github_get(&self, path: &str, ttl: Duration) -> anyhow::Result<serde_json::Value> {
+        let auth = self.token.as_ref().map(|t| format!("Bearer {}", t));
+        let mut headers = vec![("Accept", "application/vnd.github+json")];
+        if let Some(auth) = &auth {
+            headers.push(("Authorization", auth.as_str()));
+        }
+        HttpCache::shared().get_json_with_headers(&format!("{}{}", self.api_url, path), &headers, ttl)
+    }
+
+    fn github_state(&self, repo_path: &str) -> anyhow::Result<String> {
+        if !path_utils::is_safe_path(Path::new(repo_path)) {
+            anyhow::bail!("unsafe repo path");
+        }
+        let repo = Repository::open(repo_path)?;
+        let remote = repo.find_remote("origin")?;
+        let slug = remote.url().and_then(Self::github_slug).context("origin is not a GitHub remote")?;
+
+        // origin/HEAD names the default branch when the clone recorded it; otherwise ask the API
+        let branch = match repo.find_reference("refs/remotes/origin/HEAD").ok().and_then(|r| r.symbolic_target().map(str::to_string)) {
+            Some(target) => target.trim_start_matches("refs/remotes/origin/").to_string(),
+            None => {
+                let info = self.github_get(&format!("/repos/{}", slug), Self::BRANCH_TTL)?;
+                info.get("default_branch").and_then(|b| b.as_str()).context("No default branch")?.to_string()
+            }
+        };
+
+        let runs = self.github_get(&format!("/repos/{}/actions/runs?branch={}&per_page=1", slug, branch), self.refresh / 2)?;
+        let latest = match runs.get("workflow_runs").and_then(|r| r.get(0)) {
+            Some(run) => run,
+            None => return Ok("none".to_string()),
+        };
+        Ok(match latest.get("status").and_then(|s| s.as_str()) {
+            Some("completed") => Self::normalize_state(latest.get("conclusion").and_then(|c| c.as_str()).unwrap_or("")),
+            _ => "running".to_string(),
+        })
+    }
+
+    fn url_state(&self, url: &str) -> anyhow::Result<String> {
+        let body = HttpCache::shared().get_text(url, self.refresh / 2)?;
+        let state = match serde_json::from_str::<serde_json::Value>(&body) {
+            Ok(json) => json.get("status").or_else(|| json.get("state")).and_then(|s| s.as_str()).unwrap_or("").to_string(),
+            Err(_) => body,
+        };
+        Ok(Self::normalize_state(&state))
+    }
+}
+
+#[cfg(all(feature = "http", feature = "git"))]
+impl MetricCollector for CiCollector {
+    fn id(&self) -> &'static str { "ci" }
+    fn label(&self) -> &'static str { "CI" }
+    fn interval(&self) -> Option<Duration> { Some(self.refresh) }
+    fn heavy(&self) -> bool { true }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        for repo in &self.repos {
+            let id = Self::repo_metric(repo);
+            let name = id.as_str().trim_start_matches("ci_").to_string();
+            let state = match self.status_urls.get(&name) {
+                Some(url) => self.url_state(url),
+                None => self.github_state(repo),
+            };
+            let value = state.unwrap_or_else(|e| {
+                log::warn!("CI status for {} unavailable: {:#}", repo, e);
+                "N/A".to_string()
+            });
+            map.insert(id, MetricValue::String(value));
+        }
+        map
+    }
+}
+
+/// Collector for MQTT topics (config `mqtt`). A background thread holds the broker
+/// connection and keeps the latest value per topic; `collect` just reads them. The
+/// thread reconnects with backoff, showing "Offline" for every topic meanwhile, and
+/// exits when the collector is dropped.
+#[cfg(feature = "mqtt")]
+#[derive(Debug)]
+pub struct MqttCollector {
+    latest: Arc<Mutex<HashMap<MetricId, MetricValue>>>,
+    stop: Arc<AtomicBool>,
+}
+
+#[cfg(feature = "mqtt")]
+impl MqttCollector {
+    pub fn new(config: crate::config::Mqtt) -> Self {
+        let latest = Arc::new(Mutex::new(HashMap::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (latest_thread, stop_thread) = (latest.clone(), stop.clone());
+        thread::spawn(move || Self::run(config, latest_thread, stop_thread));
+        Self { latest, stop }
+    }
+
+    fn run(config: crate::config::Mqtt, latest: Arc<Mutex<HashMap<MetricId, MetricValue>>>, stop: Arc<AtomicBool>) {
+        let options = crate::mqtt::ConnectOptions {
+            client_id: config.client_id.clone(),
+            username: config.username.clone(),
+            password: config.password.clone(),
+        };
+        let filters: Vec<String> = config.topics.iter().map(|t| t.topic.clone()).collect();
+        let mut backoff = Duration::from_secs(1);
+
+        while !stop.load(Ordering::Relaxed) {
+            let mut client = match crate::mqtt::MqttClient::connect(&config.host, config.port, &options, &filters) {
+                Ok(client) => {
+                    log::info!("MQTT: connected to {}:{}, {} topic(s)", config.host, config.port, filters.len());
+                    backoff = Duration::from_secs(1);
+                    client
+                }
+                Err(e) => {
+                    log::warn!("MQTT: {:#}; retrying in {:?}", e, backoff);
+                    Self::mark_offline(&config, &latest);
+                    // Sleep in short steps so a config reload doesn't wait for the backoff
+                    let until = Instant::now() + backoff;
+                    while Instant::now() < until && !stop.load(Ordering::Relaxed) {
+                        thread::sleep(Duration::from_millis(200));
+                    }
+                    backoff = (backoff * 2).min(Duration::from_secs(60));
+                    continue;
+                }
+            };
+
+            while !stop.load(Ordering::Relaxed) {
+                match client.poll() {
+                    Ok(Some(message)) => {
+                        for topic in config.topics.iter().filter(|t| crate::mqtt::topic_matches(&t.topic, &message.topic)) {
+                            if let Some(value) = Self::payload_value(topic, &message.payload) {
+                                if let Ok(mut latest) = latest.lock() {
+                                    latest.insert(MetricId::Custom(topic.metric_id.clone()), value);
+                                }
+                            }
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        log::warn!("MQTT: connection lost: {:#}", e);
+                        Self::mark_offline(&config, &latest);
+                        break;
+                    }
+                }
+            }
+        }
+        log::debug!("MQTT: collector stopped");
+    }
+
+    /// Replaces every topic's last value, which may be long stale, while there is no connection.
+    fn mark_offline(config: &crate::config::Mqtt, latest: &Mutex<HashMap<MetricId, MetricValue>>) {
+        if let Ok(mut latest) = latest.lock() {
+            for topic in &config.topics {
+                latest.insert(MetricId::Custom(topic.metric_id.clone()), MetricValue::String("Offline".to_string()));
+            }
+        }
+    }
+
+    /// Value of a payload: the `json_pointer` field if set, numbers with the topic's unit.
+    pub fn payload_value(topic: &crate::config::MqttTopic, payload: &[u8]) -> Option<MetricValue> {
+        let text = String::from_utf8_lossy(payload).trim().to_string();
+        let raw = match &topic.json_pointer {
+            Some(pointer) => json_field_text(&serde_json::from_str(&text).ok()?, pointer)?,
+            None => text,
+        };
+        Some(unit_value(raw, topic.unit.as_deref()))
+    }
+}
+
+/// The value at `pointer` as display text; strings lose their quotes, null counts as missing.
+#[cfg(any(feature = "http", feature = "mqtt"))]
+fn json_field_text(doc: &serde_json::Value, pointer: &str) -> Option<String> {
+    match doc.pointer(pointer)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+/// Numbers become floats (or text with `unit` appended, one decimal); anything else stays text.
+#[cfg(any(feature = "http", feature = "mqtt"))]
+fn unit_value(raw: String, unit: Option<&str>) -> MetricValue {
+    match (raw.parse::<f64>(), unit) {
+        (Ok(v), Some(unit)) => MetricValue::String(format!("{:.1}{}", v, unit)),
+        (Ok(v), None) => MetricValue::Float(v),
+        (Err(_), _) => MetricValue::String(raw),
+    }
+}
+
+/// Collector for one `http_json` endpoint: fetches the URL on its own interval and shows
+/// each configured JSON pointer as a custom metric ("N/A" if the field is missing,
+/// "Offline" for every field if the request fails).
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct HttpJsonCollector {
+    endpoint: crate::config::HttpJsonEndpoint,
+}
+
+#[cfg(feature = "http")]
+impl HttpJsonCollector {
+    pub fn new(endpoint: crate::config::HttpJsonEndpoint) -> Self {
+        Self { endpoint }
+    }
+}
+
+#[cfg(feature = "http")]
+impl MetricCollector for HttpJsonCollector {
+    fn id(&self) -> &'static str { "http_json" }
+    fn label(&self) -> &'static str { "HTTP JSON" }
+    fn heavy(&self) -> bool { true }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(self.endpoint.interval_secs)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        // Half the interval: shares one request between entries with the same
+        // URL without ever serving the previous poll's response
+        let ttl = Duration::from_secs(self.endpoint.interval_secs) / 2;
+        let doc = HttpCache::shared().get_json::<serde_json::Value>(&self.endpoint.url, ttl);
+        if let Err(e) = &doc {
+            log::warn!("HTTP JSON request to {} failed: {:#}", self.endpoint.url, e);
+        }
+
+        let mut map = HashMap::new();
+        for field in &self.endpoint.fields {
+            let value = match &doc {
+                Ok(doc) => match json_field_text(doc, &field.pointer) {
+                    Some(raw) => unit_value(raw, field.unit.as_deref()),
+                    None => MetricValue::String("N/A".to_string()),
+                },
+                Err(_) => MetricValue::String("Offline".to_string()),
+            };
+            map.insert(MetricId::Custom(field.metric_id.clone()), value);
+        }
+        map
+    }
+}
+
+/// A value from a remote instance's `/status.json`, which serializes `MetricValue`
+/// untagged; integers stay integers so they display the same as on the remote.
+#[cfg(feature = "http")]
+fn remote_value(json: &serde_json::Value) -> MetricValue {
+    use serde_json::Value;
+    match json {
+        Value::Number(n) => n.as_i64().map(MetricValue::Int).unwrap_or_else(|| MetricValue::Float(n.as_f64().unwrap_or(0.0))),
+        Value::String(s) => MetricValue::String(s.clone()),
+        Value::Null => MetricValue::None,
+        Value::Array(rows) => MetricValue::Table(
+            rows.iter()
+                .map(|row| row.as_array().map(|cells| cells.iter().map(|c| c.as_str().map_or_else(|| c.to_string(), str::to_string)).collect()).unwrap_or_default())
+                .collect(),
+        ),
+        Value::Object(map) => MetricValue::NetworkMap(
+            map.iter()
+                .filter_map(|(iface, rates)| Some((iface.clone(), (rates.get(0)?.as_f64()?, rates.get(1)?.as_f64()?))))
+                .collect(),
+        ),
+        Value::Bool(b) => MetricValue::String(b.to_string()),
+    }
+}
+
+/// Most headlines kept from a feed.
+pub const FEED_MAX_ITEMS: usize = 10;
+
+/// Titles of the items of an RSS feed or the entries of an Atom feed, newest first as
+/// published. A plain scan rather than an XML parser: feeds are flat and only the titles matter.
+pub fn parse_feed_titles(xml: &str, max: usize) -> Vec<String> {
+    let entry_tag = if xml.contains("<entry") { "<entry" } else { "<item" };
+    xml.split(entry_tag)
+        .skip(1)
+        .filter_map(|entry| {
+            let start = entry.find("<title")?;
+            let after = &entry[start..];
+            let open_end = after.find('>')? + 1;
+            let close = after.find("</title>")?;
+            let raw = after.get(open_end..close)?.trim();
+            let text = raw.strip_prefix("<![CDATA[").and_then(|t| t.strip_suffix("]]>")).unwrap_or(raw);
+            let title = text
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&#39;", "'")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&");
+            let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+            (!title.is_empty()).then_some(title)
+        })
+        .take(max)
+        .collect()
+}
+
+/// Collector for one of `rss_feeds` (or `ticker.feed_url`, as `feed_headlines`): the
+/// latest headlines joined by the feed's separator, for a scrolling value or the ticker.
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct RssCollector {
+    feed: crate::config::RssFeed,
+    metric: MetricId,
+    /// `rss:<metric_id>`, so each feed can be toggled and tuned on its own.
+    id: String,
+}
+
+#[cfg(feature = "http")]
+impl RssCollector {
+    pub fn new(feed: crate::config::RssFeed) -> Self {
+        let metric = MetricId::from_str(&feed.metric_id).unwrap_or_else(|| MetricId::Custom(feed.metric_id.clone()));
+        let id = format!("rss:{}", feed.metric_id);
+        Self { feed, metric, id }
+    }
+
+    /// Collector for the ticker's own feed.
+    pub fn for_ticker(ticker: &crate::config::Ticker) -> Self {
+        Self::new(crate::config::RssFeed {
+            url: ticker.feed_url.clone().unwrap_or_default(),
+            metric_id: MetricId::FeedHeadlines.as_str().to_string(),
+            headlines: FEED_MAX_ITEMS,
+            interval_secs: ticker.feed_interval_secs,
+            separator: ticker.separator.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "http")]
+impl MetricCollector for RssCollector {
+    fn id(&self) -> &str { &self.id }
+    fn label(&self) -> &'static str { "RSS Feeds" }
+    fn heavy(&self) -> bool { true }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(self.feed.interval_secs)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let value = match HttpCache::shared().get_text(&self.feed.url, Duration::from_secs(self.feed.interval_secs / 2)) {
+            Ok(xml) => {
+                let titles = parse_feed_titles(&xml, self.feed.headlines);
+                if titles.is_empty() {
+                    MetricValue::String("N/A".to_string())
+                } else {
+                    MetricValue::String(titles.join(&self.feed.separator))
+                }
+            }
+            Err(e) => {
+                log::warn!("Feed {} unavailable: {:#}", self.feed.url, e);
+                MetricValue::String("Offline".to_string())
+            }
+        };
+        map.insert(self.metric.clone(), value);
+        map
+    }
+}
+
+/// Collector for one of `remote_instances`: polls its `/status.json` and shows every
+/// metric it reports as `<name>.<metric_id>`. The displayed ones read "Offline" while
+/// the instance can't be reached.
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct RemoteCollector {
+    remote: crate::config::RemoteInstance,
+    /// Local IDs of this instance's metrics on the screens.
+    displayed: Vec<String>,
+    /// `remote:<name>`, so each instance can be toggled and tuned on its own.
+    id: String,
+}
+
+#[cfg(feature = "http")]
+impl RemoteCollector {
+    pub fn new(remote: crate::config::RemoteInstance, displayed: Vec<String>) -> Self {
+        let id = format!("remote:{}", remote.name);
+        Self { remote, displayed, id }
+    }
+
+    /// Namespaced metrics from a status snapshot.
+    pub fn parse_status(name: &str, status: &serde_json::Value) -> HashMap<MetricId, MetricValue> {
+        status
+            .get("metrics")
+            .and_then(|m| m.as_object())
+            .map(|metrics| metrics.iter().map(|(id, value)| (MetricId::Custom(format!("{}.{}", name, id)), remote_value(value))).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "http")]
+impl MetricCollector for RemoteCollector {
+    fn id(&self) -> &str { &self.id }
+    fn label(&self) -> &'static str { "Remote Instances" }
+    fn heavy(&self) -> bool { true }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(self.remote.interval_secs)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let ttl = Duration::from_secs(self.remote.interval_secs) / 2;
+        match HttpCache::shared().get_json::<serde_json::Value>(&self.remote.status_url(), ttl) {
+            Ok(status) => Self::parse_status(&self.remote.name, &status),
+            Err(e) => {
+                log::warn!("Remote instance '{}' unreachable: {:#}", self.remote.name, e);
+                self.displayed.iter().map(|id| (MetricId::Custom(id.clone()), MetricValue::String("Offline".to_string()))).collect()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "mqtt")]
+impl Drop for MqttCollector {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "mqtt")]
+impl MetricCollector for MqttCollector {
+    fn id(&self) -> &'static str { "mqtt" }
+    fn label(&self) -> &'static str { "MQTT" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        self.latest.lock().map(|latest| latest.clone()).unwrap_or_default()
+    }
+}
+
+/// Collector for systemd units: `service_<unit>` per configured unit ("active", "failed", ...)
+/// and `failed_units`, the number of failed units on the whole system.
+#[derive(Debug)]
+pub struct SystemdCollector {
+    units: Vec<String>,
+    command: String,
+}
+
+impl SystemdCollector {
+    pub fn new(units: Vec<String>) -> Self {
+        Self::new_with_command(units, "systemctl".to_string())
+    }
+
+    pub fn new_with_command(units: Vec<String>, command: String) -> Self {
+        Self { units, command }
+    }
+
+    /// Metric ID for a unit's state.
+    pub fn unit_metric(unit: &str) -> MetricId {
+        MetricId::Custom(format!("service_{}", unit))
+    }
+
+    fn run(&self, args: &[&str]) -> Option<String> {
+        // is-active exits non-zero when any unit is inactive; the states are still printed
+        let output = Command::new(&self.command).args(args).env("LC_ALL", "C").output().ok()?;
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+impl MetricCollector for SystemdCollector {
+    fn id(&self) -> &'static str { "systemd" }
+    fn label(&self) -> &'static str { "Services" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(10)) }
+    fn heavy(&self) -> bool { true }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+
+        if !self.units.is_empty() {
+            let mut args = vec!["is-active", "--"];
+            args.extend(self.units.iter().map(String::as_str));
+            if let Some(stdout) = self.run(&args) {
+                // One state per unit, in argument order
+                for (unit, state) in self.units.iter().zip(stdout.lines()) {
+                    map.insert(Self::unit_metric(unit), MetricValue::String(state.trim().to_string()));
+                }
+            }
+        }
+
+        if let Some(stdout) = self.run(&["list-units", "--state=failed", "--no-legend", "--plain"]) {
+            let failed = stdout.lines().filter(|l| !l.trim().is_empty()).count();
+            map.insert(MetricId::FailedUnits, MetricValue::Int(failed as i64));
+        }
+        map
+    }
+}
+
+/// Collector for clock synchronization: `time_sync` ("synced (chrony)", "unsynced
+/// (timesyncd)", ...) and `clock_offset`. Asks chrony first and falls back to
+/// systemd-timesyncd through timedatectl.
+#[derive(Debug)]
+pub struct TimeSyncCollector {
+    chronyc: String,
+    timedatectl: String,
+}
+
+impl TimeSyncCollector {
+    pub fn new() -> Self {
+        Self::new_with_commands("chronyc".to_string(), "timedatectl".to_string())
+    }
+
+    pub fn new_with_commands(chronyc: String, timedatectl: String) -> Self {
+        Self { chronyc, timedatectl }
+    }
+
+    /// Synchronized flag and offset (ms, positive = clock fast) from `chronyc -c tracking`.
+    pub fn parse_chrony_tracking(csv: &str) -> Option<(bool, f64)> {
+        let fields: Vec<&str> = csv.trim().split(',').collect();
+        if fields.len() < 14 {
+            return None;
+        }
+        // Field 4 is the correction still to apply: positive means the clock is slow
+        let correction: f64 = fields[4].parse().ok()?;
+        Some((fields[13] != "Not synchronised", -correction * 1000.0))
+    }
+
+    /// Offset in ms from the "Offset:" line of `timedatectl timesync-status` (e.g. "+1.234ms", "-512us").
+    pub fn parse_timesync_offset(status: &str) -> Option<f64> {
+        let value = status.lines().find_map(|line| line.trim().strip_prefix("Offset:"))?.trim();
+        let split = value.find(|c: char| !(c.is_ascii_digit() || matches!(c, '+' | '-' | '.')))?;
+        let number: f64 = value[..split].parse().ok()?;
+        match &value[split..] {
+            "us" | "µs" => Some(number / 1000.0),
+            "ms" => Some(number),
+            "s" => Some(number * 1000.0),
+            "min" => Some(number * 60_000.0),
+            _ => None,
+        }
+    }
+
+    /// Display text for an offset: magnitude first so thresholds apply either way.
+    pub fn format_offset(offset_ms: f64) -> String {
+        format!("{:.1} ms {}", offset_ms.abs(), if offset_ms >= 0.0 { "fast" } else { "slow" })
+    }
+
+    fn run(command: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(command).args(args).env("LC_ALL", "C").output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+impl Default for TimeSyncCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricCollector for TimeSyncCollector {
+    fn id(&self) -> &'static str { "time_sync" }
+    fn label(&self) -> &'static str { "Time Sync" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(60)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let chrony = Self::run(&self.chronyc, &["-c", "tracking"]).and_then(|csv| Self::parse_chrony_tracking(&csv));
+        let (state, offset) = match chrony {
+            Some((synced, offset)) => (format!("{} (chrony)", if synced { "synced" } else { "unsynced" }), Some(offset)),
+            None => match Self::run(&self.timedatectl, &["show", "-p", "NTPSynchronized", "--value"]) {
+                Some(flag) => {
+                    let offset = Self::run(&self.timedatectl, &["timesync-status"]).and_then(|s| Self::parse_timesync_offset(&s));
+                    (format!("{} (timesyncd)", if flag.trim() == "yes" { "synced" } else { "unsynced" }), offset)
+                }
+                None => ("N/A".to_string(), None),
+            },
+        };
+        map.insert(MetricId::TimeSync, MetricValue::String(state));
+        map.insert(
+            MetricId::ClockOffset,
+            offset.map_or_else(|| MetricValue::String("N/A".to_string()), |ms| MetricValue::String(Self::format_offset(ms))),
+        );
+        map
+    }
+}
+
+/// Kernel messages logged per cycle before the rest are summarized.
+const KERNEL_LOG_MAX_LINES: usize = 20;
+/// `O_NONBLOCK`, so reading /dev/kmsg stops at the newest record. This is the generic
+/// Linux value; alpha, MIPS, PA-RISC and SPARC use their own, so they go without the
+/// /dev/kmsg fallback rather than risk a read that blocks forever.
+const KMSG_NONBLOCK: Option<i32> = if cfg!(any(
+    target_arch = "x86",
+    target_arch = "x86_64",
+    target_arch = "arm",
+    target_arch = "aarch64",
+    target_arch = "riscv64",
+    target_arch = "powerpc64",
+    target_arch = "s390x",
+    target_arch = "loongarch64"
+)) {
+    Some(0o4000)
+} else {
+    None
+};
+
+/// Collector for `kernel_errors`: kernel messages at warning level or worse since the
+/// overlay started ("3 new"). Each new message is written to the overlay's own log, so
+/// the log viewer has the details. Reads `journalctl -k`, or /dev/kmsg where there is
+/// no journal (reading it may need `kernel.dmesg_restrict=0` or the CAP_SYSLOG capability).
+#[derive(Debug)]
+pub struct KernelLogCollector {
+    journalctl: String,
+    kmsg_path: PathBuf,
+    /// Open once the journal turned out to be unavailable.
+    kmsg: Option<fs::File>,
+    /// Timestamp (unix seconds) of the newest journal message counted.
+    last_seen: f64,
+    new_messages: u64,
+}
+
+impl KernelLogCollector {
+    pub fn new() -> Self {
+        Self::new_with_sources("journalctl".to_string(), PathBuf::from("/dev/kmsg"))
+    }
+
+    pub fn new_with_sources(journalctl: String, kmsg_path: PathBuf) -> Self {
+        let started = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Self { journalctl, kmsg_path, kmsg: None, last_seen: started as f64, new_messages: 0 }
+    }
+
+    /// Timestamp and message of a `journalctl -o short-unix` line
+    /// ("1700000000.123456 host kernel: usb 1-2: device descriptor read error").
+    pub fn parse_journal_line(line: &str) -> Option<(f64, &str)> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let timestamp: f64 = timestamp.parse().ok()?;
+        let message = rest.split_once(": ").map_or(rest, |(_, message)| message);
+        Some((timestamp, message.trim()))
+    }
+
+    /// Syslog level (0 emergency .. 7 debug) and message of a /dev/kmsg record
+    /// ("4,1234,5678901,-;message", followed by indented key=value lines).
+    pub fn parse_kmsg_record(record: &str) -> Option<(u8, &str)> {
+        let (header, message) = record.split_once(';')?;
+        let prefix: u32 = header.split(',').next()?.parse().ok()?;
+        let message = message.lines().next().unwrap_or("").trim();
+        Some(((prefix & 7) as u8, message))
+    }
+
+    /// New messages from the journal; `None` if journalctl can't be run.
+    fn read_journal(&mut self) -> Option<Vec<String>> {
+        // --since takes whole seconds; lines already counted are skipped by timestamp
+        let since = format!("@{}", self.last_seen.floor() as u64);
+        let output = Command::new(&self.journalctl)
+            .args(["-k", "-p", "warning", "-q", "--no-pager", "-o", "short-unix", "--since", &since])
+            .env("LC_ALL", "C")
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let mut messages = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if let Some((timestamp, message)) = Self::parse_journal_line(line) {
+                if timestamp > self.last_seen {
+                    self.last_seen = timestamp;
+                    messages.push(message.to_string());
+                }
+            }
+        }
+        Some(messages)
+    }
+
+    /// New warning-or-worse records from /dev/kmsg; `None` if it can't be read.
+    fn read_kmsg(&mut self) -> Option<Vec<String>> {
+        use std::io::{ErrorKind, Seek, SeekFrom};
+        use std::os::unix::fs::OpenOptionsExt;
+
+        if self.kmsg.is_none() {
+            let Some(nonblock) = KMSG_NONBLOCK else {
+                log::info!("Kernel log unavailable: no journal, and /dev/kmsg isn't supported on this architecture");
+                return None;
+            };
+            let mut file = match fs::OpenOptions::new().read(true).custom_flags(nonblock).open(&self.kmsg_path) {
+                Ok(file) => file,
+                Err(e) => {
+                    log::info!("Kernel log unavailable: no journal, and {:?}: {}", self.kmsg_path, e);
+                    return None;
+                }
+            };
+            // Start after the newest record; only what is logged from now on counts
+            file.seek(SeekFrom::End(0)).ok()?;
+            self.kmsg = Some(file);
+        }
+        let file = self.kmsg.as_mut()?;
+        let mut messages = Vec::new();
+        let mut buffer = [0u8; 8192];
+        // Every read returns one record, until there are none left
+        loop {
+            match file.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let record = String::from_utf8_lossy(&buffer[..n]);
+                    if let Some((level, message)) = Self::parse_kmsg_record(&record) {
+                        if level <= 4 {
+                            messages.push(message.to_string());
+                        }
+                    }
+                }
+                // EPIPE: records overwritten before we got to them
+                Err(e) if e.kind() == ErrorKind::BrokenPipe => continue,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    log::warn!("Reading {:?} failed: {}", self.kmsg_path, e);
+                    self.kmsg = None;
+                    return None;
+                }
+            }
+        }
+        Some(messages)
+    }
+}
+
+impl Default for KernelLogCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricCollector for KernelLogCollector {
+    fn id(&self) -> &'static str { "kernel_log" }
+    fn label(&self) -> &'static str { "Kernel Log" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(10)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let messages = if self.kmsg.is_none() { self.read_journal() } else { None };
+        let Some(messages) = messages.or_else(|| self.read_kmsg()) else {
+            map.insert(MetricId::KernelErrors, MetricValue::String("N/A".to_string()));
+            return map;
+        };
+        for message in messages.iter().take(KERNEL_LOG_MAX_LINES) {
+            log::warn!("kernel: {}", message);
+        }
+        if messages.len() > KERNEL_LOG_MAX_LINES {
+            log::warn!("kernel: ... and {} more", messages.len() - KERNEL_LOG_MAX_LINES);
+        }
+        self.new_messages += messages.len() as u64;
+        map.insert(MetricId::KernelErrors, MetricValue::String(format!("{} new", self.new_messages)));
+        map
+    }
+}
+
+/// Collector for Network usage (Bytes/sec).
+/// Reads /proc/net/dev directly to avoid sysinfo locking contention and ensure independent delta tracking.
+#[derive(Debug)]
+pub struct NetworkCollector {
+    last_snapshot: HashMap<String, (u64, u64)>, // iface -> (rx_bytes, tx_bytes)
+    last_collection_time: Instant,
+}
+
+impl NetworkCollector {
+    pub fn new() -> Self {
+        Self {
+            last_snapshot: HashMap::new(),
+            last_collection_time: Instant::now(),
+        }
+    }
+
+    fn read_proc_net_dev(&self) -> HashMap<String, (u64, u64)> {
+        let mut map = HashMap::new();
+        if let Ok(content) = fs::read_to_string("/proc/net/dev") {
+            for line in content.lines().skip(2) {
+                let line = line.trim();
+                if let Some(colon_idx) = line.find(':') {
+                    let iface = &line[..colon_idx];
+                    let stats_str = &line[colon_idx+1..];
+                    let stats: Vec<&str> = stats_str.split_whitespace().collect();
+                    if stats.len() >= 9 {
+                        if let (Ok(rx), Ok(tx)) = (stats[0].parse::<u64>(), stats[8].parse::<u64>()) {
+                            map.insert(iface.to_string(), (rx, tx));
+                        }
+                    }
+                }
+            }
+        }
+        map
+    }
+}
+
+impl MetricCollector for NetworkCollector {
+    fn id(&self) -> &'static str { "network" }
+    fn label(&self) -> &'static str { "Net" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let now = Instant::now();
+        let current_snapshot = self.read_proc_net_dev();
+        let duration = now.duration_since(self.last_collection_time).as_secs_f64();
+        let duration = if duration < 0.001 { 1.0 } else { duration };
+
+        let mut results = HashMap::new();
+        let mut details_map = HashMap::new();
+
+        for (iface, (curr_rx, curr_tx)) in &current_snapshot {
+            if iface == "lo" { continue; }
+            if let Some((last_rx, last_tx)) = self.last_snapshot.get(iface) {
+                // Counters reset on interface restart; treat that as zero traffic.
+                let delta_rx = curr_rx.saturating_sub(*last_rx);
+                let delta_tx = curr_tx.saturating_sub(*last_tx);
+
+                // Normalize by the measured interval so rates stay correct for any update_ms.
+                let rx_rate = delta_rx as f64 / duration;
+                let tx_rate = delta_tx as f64 / duration;
+                details_map.insert(iface.clone(), (rx_rate, tx_rate));
+            }
+        }
+
+        results.insert(MetricId::NetworkDetails, MetricValue::NetworkMap(details_map));
+        self.last_snapshot = current_snapshot;
+        self.last_collection_time = now;
+
+        results
+    }
+}
+
+/// Collector for Disk I/O throughput (Bytes/sec).
+/// Reads /proc/diskstats deltas, mirroring `NetworkCollector`'s approach for /proc/net/dev.
+///
+/// Publishes an aggregate `disk_io` value plus `disk_io_<device>` per whole disk,
+/// as (read, write) pairs rendered with the same ↓/↑ formatting as network.
+#[derive(Debug)]
+pub struct DiskIoCollector {
+    stats_path: PathBuf,
+    sys_block_path: PathBuf,
+    last_snapshot: HashMap<String, (u64, u64)>, // device -> (read_bytes, write_bytes)
+    last_collection_time: Instant,
+}
+
+impl DiskIoCollector {
+    /// Linux reports diskstats sector counts in 512-byte units regardless of device.
+    const SECTOR_SIZE: u64 = 512;
+
+    pub fn new() -> Self {
+        Self::new_with_paths(PathBuf::from("/proc/diskstats"), PathBuf::from("/sys/block"))
+    }
+
+    pub fn new_with_paths(stats_path: PathBuf, sys_block_path: PathBuf) -> Self {
+        Self {
+            stats_path,
+            sys_block_path,
+            last_snapshot: HashMap::new(),
+            last_collection_time: Instant::now(),
+        }
+    }
+
+    /// Whole disks appear in /sys/block; partitions do not. Virtual devices are skipped.
+    fn is_tracked_device(&self, name: &str) -> bool {
+        if name.starts_with("loop") || name.starts_with("ram") || name.starts_with("zram") {
+            return false;
+        }
+        if self.sys_block_path.is_dir() {
+            return self.sys_block_path.join(name).exists();
+        }
+        true
+    }
+
+    fn read_diskstats(&self) -> HashMap<String, (u64, u64)> {
+        let mut map = HashMap::new();
+        if let Ok(content) = fs::read_to_string(&self.stats_path) {
+            for line in content.lines() {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() < 10 {
+                    continue;
+                }
+                let name = fields[2];
+                if !self.is_tracked_device(name) {
+                    continue;
+                }
+                if let (Ok(read_sectors), Ok(write_sectors)) = (fields[5].parse::<u64>(), fields[9].parse::<u64>()) {
+                    map.insert(name.to_string(), (read_sectors * Self::SECTOR_SIZE, write_sectors * Self::SECTOR_SIZE));
+                }
+            }
+        }
+        map
+    }
+}
+
+impl Default for DiskIoCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricCollector for DiskIoCollector {
+    fn id(&self) -> &'static str { "disk_io" }
+    fn label(&self) -> &'static str { "Disk I/O" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let now = Instant::now();
+        let current_snapshot = self.read_diskstats();
+        let duration = now.duration_since(self.last_collection_time).as_secs_f64();
+        let duration = if duration < 0.001 { 1.0 } else { duration };
+
+        let mut results = HashMap::new();
+        let mut total = (0.0, 0.0);
+        let mut any_delta = false;
+
+        for (device, (curr_read, curr_write)) in &current_snapshot {
+            if let Some((last_read, last_write)) = self.last_snapshot.get(device) {
+                let read_rate = curr_read.saturating_sub(*last_read) as f64 / duration;
+                let write_rate = curr_write.saturating_sub(*last_write) as f64 / duration;
+                total.0 += read_rate;
+                total.1 += write_rate;
+                any_delta = true;
+
+                let mut device_map = HashMap::new();
+                device_map.insert(device.clone(), (read_rate, write_rate));
+                results.insert(MetricId::Custom(format!("disk_io_{}", device)), MetricValue::NetworkMap(device_map));
+            }
+        }
+
+        if any_delta {
+            let mut aggregate = HashMap::new();
+            aggregate.insert("all".to_string(), total);
+            results.insert(MetricId::DiskIo, MetricValue::NetworkMap(aggregate));
+        }
+
+        self.last_snapshot = current_snapshot;
+        self.last_collection_time = now;
+        results
+    }
+}
+
+/// Collector for Memory usage.
+#[derive(Debug)]
+pub struct MemoryCollector {
+    sys: Arc<Mutex<SysinfoManager>>,
+}
+
+impl MemoryCollector {
+    pub fn new(sys: Arc<Mutex<SysinfoManager>>) -> Self {
+        Self { sys }
+    }
+}
+
+impl MetricCollector for MemoryCollector {
+    fn id(&self) -> &'static str { "memory" }
+    fn label(&self) -> &'static str { "RAM" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        match self.sys.lock() {
+            Ok(mut manager) => {
+                manager.system.refresh_memory();
+                let used = manager.system.used_memory();
+                let total = manager.system.total_memory();
+                
+                let used_gb = used as f64 / 1024.0 / 1024.0 / 1024.0;
+                let total_gb = total as f64 / 1024.0 / 1024.0 / 1024.0;
+                let percent = if total > 0 { (used as f64 / total as f64) * 100.0 } else { 0.0 };
+                
+                map.insert(MetricId::RamUsed, MetricValue::String(format!("{:.1} GB", used_gb)));
+                map.insert(MetricId::RamTotal, MetricValue::String(format!("{:.1} GB", total_gb)));
+                map.insert(MetricId::RamUsage, MetricValue::String(format!("{:.0}%", percent)));
+            },
+            Err(e) => {
+                log::error!("MemoryCollector lock failed: {}", e);
+                map.insert(MetricId::RamUsage, MetricValue::String("ERR".to_string()));
+            }
+        }
+        map
+    }
+}
+
+/// Collector for the top processes by CPU or memory.
+/// Each row is `[name, cpu%, memory]`, with CPU normalized to total capacity like `cpu_usage`.
+#[derive(Debug)]
+pub struct TopProcessCollector {
+    sys: Arc<Mutex<SysinfoManager>>,
+    count: usize,
+    sort_by_memory: bool,
+}
+
+impl TopProcessCollector {
+    pub fn new(sys: Arc<Mutex<SysinfoManager>>, count: usize, sort_by: &str) -> Self {
+        Self { sys, count, sort_by_memory: sort_by == "memory" }
+    }
+
+    /// Sorts (name, cpu%, mem bytes) entries and formats the top `count` as table rows.
+    fn build_rows(&self, mut procs: Vec<(String, f32, u64)>) -> Vec<Vec<String>> {
+        if self.sort_by_memory {
+            procs.sort_by_key(|p| std::cmp::Reverse(p.2));
+        } else {
+            procs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        }
+        procs
+            .into_iter()
+            .take(self.count)
+            .map(|(name, cpu, mem)| {
+                vec![
+                    name,
+                    format!("{:.1}%", cpu),
+                    format!("{:.0} MB", mem as f64 / 1_048_576.0),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl MetricCollector for TopProcessCollector {
+    fn id(&self) -> &'static str { "top_processes" }
+    fn label(&self) -> &'static str { "Top" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let procs = match self.sys.lock() {
+            Ok(mut manager) => {
+                manager.system.refresh_processes();
+                let cores = manager.system.cpus().len().max(1) as f32;
+                manager
+                    .system
+                    .processes()
+                    .values()
+                    .map(|p| (p.name().to_string(), p.cpu_usage() / cores, p.memory()))
+                    .collect::<Vec<_>>()
+            }
+            Err(e) => {
+                log::error!("TopProcessCollector lock failed: {}", e);
+                map.insert(MetricId::TopProcesses, MetricValue::None);
+                return map;
+            }
+        };
+        map.insert(MetricId::TopProcesses, MetricValue::Table(self.build_rows(procs)));
+        map
+    }
+}
+
+/// Self-monitoring collector.
+/// Reports the overlay's own resident memory and the state held by its renderers,
+/// so leaks (e.g. stale per-item state) are visible on the overlay itself.
+#[derive(Debug)]
+pub struct SelfMonitorCollector {
+    status_path: PathBuf,
+}
+
+impl SelfMonitorCollector {
+    pub fn new() -> Self {
+        Self { status_path: PathBuf::from("/proc/self/status") }
+    }
+
+    /// Parses VmRSS (kB) from /proc/self/status.
+    fn read_rss_bytes(&self) -> Option<u64> {
+        let content = fs::read_to_string(&self.status_path).ok()?;
+        content
+            .lines()
+            .find(|l| l.starts_with("VmRSS:"))
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    }
+}
+
+impl Default for SelfMonitorCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricCollector for SelfMonitorCollector {
+    fn id(&self) -> &'static str { "self_monitor" }
+    fn label(&self) -> &'static str { "Overlay" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let rss = match self.read_rss_bytes() {
+            Some(bytes) => MetricValue::String(format!("{:.1} MB", bytes as f64 / 1_048_576.0)),
+            None => MetricValue::None,
+        };
+        map.insert(MetricId::OverlayMemory, rss);
+
+        let render = crate::render::render_memory_snapshot();
+        map.insert(MetricId::OverlayRender, MetricValue::String(format!(
+            "{} scroll / {} items / {:.1} MB surf",
+            render.scroll_offsets,
+            render.item_states,
+            render.surface_bytes as f64 / 1_048_576.0
+        )));
+        map
+    }
+}
+
+/// Collector for Uptime and Load Average.
+#[derive(Debug)]
+pub struct UptimeLoadCollector {
+    sys: Arc<Mutex<SysinfoManager>>,
+}
+
+impl UptimeLoadCollector {
+    pub fn new(sys: Arc<Mutex<SysinfoManager>>) -> Self {
+        Self { sys }
+    }
+}
+
+impl MetricCollector for UptimeLoadCollector {
+    fn id(&self) -> &'static str { "uptime_load" }
+    fn label(&self) -> &'static str { "System" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        match self.sys.lock() {
+            Ok(manager) => {
+                let uptime_secs = manager.system.uptime();
+                let days = uptime_secs / 86400;
+                let hours = (uptime_secs % 86400) / 3600;
+                let mins = (uptime_secs % 3600) / 60;
+                
+                let uptime_str = if days > 0 {
+                    format!("{} days {}:{:02}", days, hours, mins)
+                } else {
+                    format!("{}:{:02}", hours, mins)
+                };
+                
+                map.insert(MetricId::Uptime, MetricValue::String(uptime_str));
+                
+                let load = manager.system.load_average();
+                map.insert(MetricId::LoadAvg, MetricValue::String(format!("{:.2}", load.one)));
+            },
+            Err(e) => {
+                log::error!("UptimeLoadCollector lock failed: {}", e);
+                map.insert(MetricId::Uptime, MetricValue::String("ERR".to_string()));
+            }
+        }
+        map
+    }
+}
+
+/// Collector for Disk usage: `disk_usage` for "/", plus `disk_usage_<mount>` for each
+/// configured mount and a `disk_summary` row combining them.
+#[derive(Debug)]
+pub struct DiskCollector {
+    sys: Arc<Mutex<SysinfoManager>>,
+    mounts: Vec<String>,
+}
+
+impl DiskCollector {
+    pub fn new(sys: Arc<Mutex<SysinfoManager>>) -> Self {
+        Self { sys, mounts: Vec::new() }
+    }
+
+    /// Mount points to report individually (config `disks`).
+    pub fn with_mounts(mut self, mounts: Vec<String>) -> Self {
+        self.mounts = mounts;
+        self
+    }
+
+    /// Metric ID for a mount: "/" -> `disk_usage_root`, "/mnt/data" -> `disk_usage_mnt_data`.
+    pub fn mount_metric(mount: &str) -> MetricId {
+        let slug = mount.trim_matches('/').replace('/', "_");
+        MetricId::Custom(format!("disk_usage_{}", if slug.is_empty() { "root" } else { &slug }))
+    }
+
+    /// One-row summary such as "/ 41% · /home 73% · /mnt/data N/A".
+    pub fn format_summary(usage: &[(&str, Option<f64>)]) -> String {
+        usage
+            .iter()
+            .map(|(mount, percent)| match percent {
+                Some(p) => format!("{} {:.0}%", mount, p),
+                None => format!("{} N/A", mount),
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+impl MetricCollector for DiskCollector {
+    fn id(&self) -> &'static str { "disk" }
+    fn label(&self) -> &'static str { "Disk" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        if let Ok(mut manager) = self.sys.lock() {
+            manager.system.refresh_disks_list();
+            manager.system.refresh_disks();
+            let percent_of = |mount: &Path| {
+                manager.system.disks().iter().find(|disk| disk.mount_point() == mount).map(|disk| {
+                    let used = disk.total_space() - disk.available_space();
+                    let total = disk.total_space();
+                    if total > 0 { (used as f64 / total as f64) * 100.0 } else { 0.0 }
+                })
+            };
+
+            if let Some(percent) = percent_of(Path::new("/")) {
+                map.insert(MetricId::DiskUsage, MetricValue::String(format!("{:.1}%", percent)));
+            }
+
+            let usage: Vec<(&str, Option<f64>)> = self.mounts.iter().map(|m| (m.as_str(), percent_of(Path::new(m)))).collect();
+            for (mount, percent) in &usage {
+                let value = match percent {
+                    Some(p) => MetricValue::String(format!("{:.1}%", p)),
+                    None => MetricValue::String("N/A".to_string()),
+                };
+                map.insert(Self::mount_metric(mount), value);
+            }
+            if !usage.is_empty() {
+                map.insert(MetricId::DiskSummary, MetricValue::String(Self::format_summary(&usage)));
+            }
+        }
+        map
+    }
+}
+
+/// Collector for inode usage: `inode_usage` for `/` and `inode_usage_<mount>` for each of
+/// `disks`, from `df -i` (sysinfo only knows about space). Filesystems without a fixed
+/// inode table, like btrfs, report "N/A".
+#[derive(Debug)]
+pub struct InodeCollector {
+    mounts: Vec<String>,
+    command: String,
+}
+
+impl InodeCollector {
+    pub fn new(mounts: Vec<String>) -> Self {
+        Self::new_with_command(mounts, "df".to_string())
+    }
+
+    pub fn new_with_command(mounts: Vec<String>, command: String) -> Self {
+        Self { mounts, command }
+    }
+
+    /// Metric ID for a mount: "/" -> `inode_usage_root`, "/mnt/data" -> `inode_usage_mnt_data`.
+    pub fn mount_metric(mount: &str) -> MetricId {
+        let slug = mount.trim_matches('/').replace('/', "_");
+        MetricId::Custom(format!("inode_usage_{}", if slug.is_empty() { "root" } else { &slug }))
+    }
+
+    /// Inode usage in percent by mount point, from `df -P -i` output
+    /// ("Filesystem Inodes IUsed IFree IUse% Mounted on"). Mounts without inode counts are left out.
+    pub fn parse_df(output: &str) -> HashMap<String, f64> {
+        output
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() < 6 {
+                    return None;
+                }
+                let (total, used): (u64, u64) = (fields[1].parse().ok()?, fields[2].parse().ok()?);
+                (total > 0).then(|| (fields[5..].join(" "), used as f64 / total as f64 * 100.0))
+            })
+            .collect()
+    }
+}
+
+impl MetricCollector for InodeCollector {
+    fn id(&self) -> &'static str { "inodes" }
+    fn label(&self) -> &'static str { "Inodes" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(60)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut mounts = vec!["/".to_string()];
+        mounts.extend(self.mounts.iter().filter(|m| *m != "/").cloned());
+        // df exits non-zero when a mount is missing but still reports the others
+        let usage = Command::new(&self.command)
+            .args(["-P", "-i", "--"])
+            .args(&mounts)
+            .env("LC_ALL", "C")
+            .output()
+            .map(|output| Self::parse_df(&String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or_default();
+
+        let value = |mount: &str| match usage.get(mount) {
+            Some(percent) => MetricValue::String(format!("{:.1}%", percent)),
+            None => MetricValue::String("N/A".to_string()),
+        };
+        let mut map = HashMap::new();
+        map.insert(MetricId::InodeUsage, value("/"));
+        for mount in &self.mounts {
+            map.insert(Self::mount_metric(mount), value(mount));
+        }
+        map
+    }
+}
+
+/// Collector for Hardware Monitor sensors (Temperature, Fans).
 /// Scans /sys/class/hwmon for k10temp, amdgpu, etc.
 /// 
 /// Target Hardware (Dell G15 5515):
@@ -641,6 +3350,16 @@ impl HwmonCollector {
         }
         None
     }
+
+    /// A `sensors` temperature ("Tctl:  +45.1°C  ...") formatted in `units` like the
+    /// hwmon readings, falling back to the text as printed.
+    fn extract_sensor_temperature(line: &str, units: UnitSystem) -> Option<String> {
+        let val = Self::extract_sensor_value(line)?;
+        Some(match val.trim_end_matches("°C").trim().parse::<f64>() {
+            Ok(celsius) if val.ends_with("°C") => units.temperature(celsius, 0),
+            _ => val,
+        })
+    }
 }
 
 impl MetricCollector for HwmonCollector {
@@ -659,7 +3378,7 @@ impl MetricCollector for HwmonCollector {
                     match name.as_str() {
                         "k10temp" => {
                             if let Some(temp) = self.read_file_as_i64(path.join("temp1_input")) {
-                                map.insert(MetricId::CpuTemp, MetricValue::String(format!("{:.0}°C", temp as f64 / 1000.0)));
+                                map.insert(MetricId::CpuTemp, MetricValue::String(UnitSystem::current().temperature(temp as f64 / 1000.0, 0)));
                                 found_cpu = true;
                             }
                         },
@@ -697,7 +3416,7 @@ impl MetricCollector for HwmonCollector {
                      }
                      
                      if current_adapter.starts_with("k10temp") && line.contains("Tctl:") && !found_cpu {
-                         if let Some(val) = Self::extract_sensor_value(line) {
+                         if let Some(val) = Self::extract_sensor_temperature(line, UnitSystem::current()) {
                              map.insert(MetricId::CpuTemp, MetricValue::String(val));
                          }
                      }
@@ -748,14 +3467,8 @@ impl MetricCollector for FileCollector {
             if let Ok(mut f) = fs::File::open(file_path) {
                 let mut buffer = Vec::new();
                 // SEC-03: Cap at 64KB
-                if f.by_ref().take(64 * 1024).read_to_end(&mut buffer).is_ok() {
-                    let s = String::from_utf8_lossy(&buffer);
-                    let s = s.trim();
-                    if file.tail {
-                        content = s.lines().last().unwrap_or("").to_string();
-                    } else {
-                        content = s.to_string();
-                    }
+                if f.by_ref().take(MAX_CUSTOM_OUTPUT).read_to_end(&mut buffer).is_ok() {
+                    content = display_text(&buffer, file.tail);
                 }
             }
             map.insert(MetricId::Custom(file.metric_id.clone()), MetricValue::String(content));
@@ -764,7 +3477,139 @@ impl MetricCollector for FileCollector {
     }
 }
 
+/// Most bytes read from a custom file or command.
+const MAX_CUSTOM_OUTPUT: u64 = 64 * 1024;
+
+/// Trimmed text of file or command output; only the last line if `tail`.
+fn display_text(bytes: &[u8], tail: bool) -> String {
+    let s = String::from_utf8_lossy(bytes);
+    let s = s.trim();
+    if tail {
+        s.lines().last().unwrap_or("").to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Collector for one `commands` entry: runs the command on its own interval and shows
+/// its stdout as a custom metric ("TIMEOUT" if it overruns, "ERR" if it fails to start).
+#[derive(Debug)]
+pub struct CommandCollector {
+    command: crate::config::CustomCommand,
+    /// `command:<metric_id>`, so each command can be toggled and tuned on its own.
+    id: String,
+}
+
+impl CommandCollector {
+    pub fn new(command: crate::config::CustomCommand) -> Self {
+        let id = format!("command:{}", command.metric_id);
+        Self { command, id }
+    }
+
+    /// The program to run: a path as given, a bare name looked up in `PATH`.
+    fn resolve(program: &str) -> Option<PathBuf> {
+        if program.contains('/') {
+            return Some(PathBuf::from(program));
+        }
+        std::env::split_paths(&std::env::var_os("PATH")?).map(|dir| dir.join(program)).find(|path| path.is_file())
+    }
+
+    fn run(&self) -> String {
+        let (program, args) = match self.command.command.split_first() {
+            Some(split) => split,
+            None => return "ERR".to_string(),
+        };
+        let Some(path) = Self::resolve(program) else {
+            log::warn!("Command '{}' not found in PATH", program);
+            return "ERR".to_string();
+        };
+        // Bare names are checked where they resolve to, so `sh` or `python` from
+        // /usr/bin can't run arbitrary code around the sandbox
+        if !path_utils::is_safe_path(&path) {
+            log::warn!("Access Denied: command outside the sandbox: {:?}", path);
+            return "ACCESS DENIED".to_string();
+        }
+
+        let mut child = match Command::new(&path)
+            .args(args)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                log::warn!("Command '{}' failed to start: {}", program, e);
+                return "ERR".to_string();
+            }
+        };
+
+        // Read on a separate thread so a chatty command can't fill the pipe and stall;
+        // everything past the cap is drained and dropped
+        let stdout = child.stdout.take();
+        let reader = thread::spawn(move || {
+            let mut buffer = Vec::new();
+            if let Some(mut out) = stdout {
+                let _ = out.by_ref().take(MAX_CUSTOM_OUTPUT).read_to_end(&mut buffer);
+                let _ = std::io::copy(&mut out, &mut std::io::sink());
+            }
+            buffer
+        });
+
+        let deadline = Instant::now() + Duration::from_secs(self.command.timeout_secs);
+        loop {
+            match child.try_wait() {
+                Ok(Some(_)) => break,
+                Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(20)),
+                _ => {
+                    log::warn!("Command for '{}' timed out after {}s", self.command.metric_id, self.command.timeout_secs);
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return "TIMEOUT".to_string();
+                }
+            }
+        }
+        display_text(&reader.join().unwrap_or_default(), self.command.tail)
+    }
+}
+
+impl MetricCollector for CommandCollector {
+    fn id(&self) -> &str { &self.id }
+    fn label(&self) -> &'static str { "Command" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(self.command.interval_secs)) }
+    fn heavy(&self) -> bool { true }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        map.insert(MetricId::Custom(self.command.metric_id.clone()), MetricValue::String(self.run()));
+        map
+    }
+}
+
+/// Commits of one repo already diffed by `GitCollector`, so each scan only walks the
+/// commits made since the previous one.
+#[cfg(feature = "git")]
+#[derive(Debug, Default)]
+pub(crate) struct RepoHistory {
+    /// HEAD at the end of the last complete walk; later walks stop there.
+    head: Option<git2::Oid>,
+    /// Every commit on HEAD made at or after this time is in `commits`.
+    covered_since: i64,
+    /// Commit time, lines added and lines deleted per commit.
+    commits: HashMap<git2::Oid, (i64, i64, i64)>,
+}
+
+#[cfg(feature = "git")]
+impl RepoHistory {
+    fn delta_since(&self, since_ts: i64) -> (i64, i64) {
+        self.commits
+            .values()
+            .filter(|(time, _, _)| *time >= since_ts)
+            .fold((0, 0), |(added, deleted), (_, a, d)| (added + a, deleted + d))
+    }
+}
+
 /// Collector for Git productivity (Delta lines +/- over 24h).
+#[cfg(feature = "git")]
 #[derive(Debug)]
 pub struct GitCollector {
     pub repos: Vec<String>,
@@ -773,10 +3618,21 @@ pub struct GitCollector {
     pub cached_delta: (i64, i64),
     pub(crate) rotation_index: usize,
     pub(crate) start_time: Instant,
+    /// Repositories scanned per run.
+    pub(crate) batch_cap: usize,
+    /// Repositories scanned at the same time.
+    pub(crate) max_parallel: usize,
+    /// Time one repository's history walk may take.
+    pub(crate) repo_budget: Duration,
+    /// Commits counted so far, per repo path.
+    pub(crate) history: HashMap<String, RepoHistory>,
 }
 
+#[cfg(feature = "git")]
 impl GitCollector {
     pub fn new(repos: Vec<String>) -> Self {
+        let productivity = crate::config::Productivity::default();
+        let limits = productivity.git_scan;
         Self {
             repos,
             delta_window: Duration::from_secs(24 * 3600),
@@ -784,13 +3640,149 @@ impl GitCollector {
             cached_delta: (0, 0),
             rotation_index: 0,
             start_time: Instant::now(),
+            batch_cap: productivity.batch_cap as usize,
+            max_parallel: limits.max_parallel,
+            repo_budget: Duration::from_millis(limits.repo_budget_ms),
+            history: HashMap::new(),
+        }
+    }
+
+    /// Applies `productivity.batch_cap` and `productivity.git_scan`.
+    pub fn with_limits(mut self, batch_cap: u32, limits: &crate::config::GitScan) -> Self {
+        self.batch_cap = (batch_cap as usize).max(1);
+        self.max_parallel = limits.max_parallel.max(1);
+        self.repo_budget = Duration::from_millis(limits.repo_budget_ms);
+        self
+    }
+
+    /// Brings the history of `repos` up to date on up to `max_parallel` threads, each
+    /// sending its repo's updated history back over a channel.
+    fn scan_batch(&mut self, repos: &[String], since_ts: i64) {
+        let work: Vec<(String, RepoHistory)> =
+            repos.iter().map(|repo| (repo.clone(), self.history.remove(repo).unwrap_or_default())).collect();
+        let work = Mutex::new(work);
+        let (tx, rx) = unbounded();
+        let (budget, threads) = (self.repo_budget, self.max_parallel.clamp(1, repos.len().max(1)));
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                let (work, tx) = (&work, tx.clone());
+                scope.spawn(move || {
+                    while let Some((repo, history)) = work.lock().ok().and_then(|mut w| w.pop()) {
+                        let history = Self::scan_repo(&repo, since_ts, budget, history);
+                        let _ = tx.send((repo, history));
+                    }
+                });
+            }
+        });
+        drop(tx);
+        self.history.extend(rx.iter());
+    }
+
+    fn scan_repo(repo: &str, since_ts: i64, budget: Duration, history: RepoHistory) -> RepoHistory {
+        let repo_path = Path::new(repo);
+        if !path_utils::is_safe_path(repo_path) {
+            log::warn!("Access Denied: Git repo outside home or unsafe: {}", repo);
+            return history;
+        }
+        let started = Instant::now();
+        let history = match Repository::open(repo_path) {
+            Ok(handle) => Self::walk_new_commits(&handle, since_ts, budget, history),
+            Err(_) => return history,
+        };
+        log::debug!("GitCollector: Polled {} in {:?}", path_utils::sanitize_path_for_log(repo_path), started.elapsed());
+        history
+    }
+
+    /// Diffs the commits on HEAD made since `since_ts` that `history` doesn't have yet.
+    /// If HEAD no longer contains the last walked HEAD (rebase, reset) or the window
+    /// grew, the history is rebuilt. The walk stops after 500 new commits or `budget`;
+    /// a walk cut short by the budget keeps what it diffed and resumes next time.
+    pub(crate) fn walk_new_commits(repo: &Repository, since_ts: i64, budget: Duration, mut history: RepoHistory) -> RepoHistory {
+        let started = Instant::now();
+        let head = match repo.head().and_then(|h| h.peel_to_commit()) {
+            Ok(commit) => commit.id(),
+            Err(_) => return RepoHistory::default(),
+        };
+        let stale = match history.head {
+            Some(old) => (old != head && !repo.graph_descendant_of(head, old).unwrap_or(false)) || since_ts < history.covered_since,
+            None => false,
+        };
+        if stale {
+            history = RepoHistory::default();
+        }
+        history.commits.retain(|_, (time, _, _)| *time >= since_ts);
+        if history.head == Some(head) {
+            return history;
+        }
+
+        let mut revwalk = match repo.revwalk() {
+            Ok(rv) => rv,
+            Err(_) => return history,
+        };
+        if revwalk.push(head).is_err() {
+            return history;
+        }
+        if let Some(old) = history.head {
+            let _ = revwalk.hide(old);
+        }
+
+        let mut complete = true;
+        // SEC-04: Limit revwalk objects to 500
+        for (objects_seen, oid) in revwalk.enumerate() {
+            if objects_seen >= 500 {
+                log::debug!("GitCollector: Revwalk cap reached");
+                break;
+            }
+            if started.elapsed() >= budget {
+                log::debug!("GitCollector: Time budget of {:?} reached, resuming next scan", budget);
+                complete = false;
+                break;
+            }
+
+            let oid = match oid { Ok(o) => o, Err(_) => continue };
+            if history.commits.contains_key(&oid) {
+                continue; // Diffed by an earlier walk that ran out of time
+            }
+            let commit = match repo.find_commit(oid) { Ok(c) => c, Err(_) => continue };
+            let time = commit.time().seconds();
+            if time < since_ts {
+                break; // Older than window
+            }
+
+            let (mut added, mut deleted) = (0, 0);
+            if commit.parent_count() > 0 {
+                if let (Ok(parent), Ok(tree)) = (commit.parent(0), commit.tree()) {
+                    if let Ok(parent_tree) = parent.tree() {
+                        if let Ok(diff) = repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), None) {
+                            if let Ok(stats) = diff.stats() {
+                                added = stats.insertions() as i64;
+                                deleted = stats.deletions() as i64;
+                            }
+                        }
+                    }
+                }
+            }
+            history.commits.insert(oid, (time, added, deleted));
         }
+
+        if complete {
+            history.head = Some(head);
+            history.covered_since = since_ts;
+        }
+        history
     }
 }
 
+#[cfg(feature = "git")]
 impl MetricCollector for GitCollector {
     fn id(&self) -> &'static str { "git_delta" }
     fn label(&self) -> &'static str { "Productivity" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(3600)) }
+    fn heavy(&self) -> bool { true }
+    fn wake(&mut self) {
+        // Expire the hourly cache so the next run rescans
+        self.last_check = Instant::now() - Duration::from_secs(3600);
+    }
     fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
         let now = Instant::now();
         
@@ -801,9 +3793,6 @@ impl MetricCollector for GitCollector {
              return map;
         }
 
-        let mut total_added = 0;
-        let mut total_deleted = 0;
-        
         // Adaptive window: 1h for the first hour of uptime, 24h thereafter
         let uptime = self.start_time.elapsed();
         let window_hours = if uptime < Duration::from_secs(3600) { 1 } else { 24 };
@@ -816,77 +3805,521 @@ impl MetricCollector for GitCollector {
              return map;
         }
 
-        // Logic for batching (Cap at 5 repos per check)
-        let batch_cap = 5; // Should be tied to config in next iteration
-        let count = std::cmp::min(self.repos.len(), batch_cap);
+        // Rotate through the repos, `batch_cap` per run; the others keep their last history
+        let count = std::cmp::min(self.repos.len(), self.batch_cap);
+        let batch: Vec<String> = (0..count).map(|i| self.repos[(self.rotation_index + i) % self.repos.len()].clone()).collect();
+        self.scan_batch(&batch, yesterday_ts);
+        log::debug!("GitCollector: Scanned {} repo(s) (delta window {}h)", count, window_hours);
+        let (total_added, total_deleted) = self
+            .repos
+            .iter()
+            .filter_map(|repo| self.history.get(repo))
+            .map(|history| history.delta_since(yesterday_ts))
+            .fold((0, 0), |(added, deleted), (a, d)| (added + a, deleted + d));
         
-        for i in 0..count {
-            let idx = (self.rotation_index + i) % self.repos.len();
-            let repo_path = Path::new(&self.repos[idx]);
-            
-            if !path_utils::is_safe_path(repo_path) {
-                log::warn!("Access Denied: Git repo outside home or unsafe: {}", self.repos[idx]);
-                continue;
+        self.rotation_index = (self.rotation_index + count) % self.repos.len();
+        self.cached_delta = (total_added, total_deleted);
+        self.last_check = now;
+
+        let mut map = HashMap::new();
+        map.insert(MetricId::CodeDelta, MetricValue::String(format!("+{} / -{}", total_added, total_deleted)));
+        map
+    }
+}
+
+/// Runs a `GitCollector` on its own thread, so a scan of large repos never ties up a
+/// scheduler worker; `collect` only returns the latest published result. Registered as
+/// `git_delta` in place of the `GitCollector` itself. Scans run hourly and on `wake`.
+#[cfg(feature = "git")]
+#[derive(Debug)]
+pub struct GitDeltaWorker {
+    latest: Arc<Mutex<HashMap<MetricId, MetricValue>>>,
+    /// Requests a rescan; dropping it stops the worker after its current scan.
+    rescan: Sender<()>,
+}
+
+#[cfg(feature = "git")]
+impl GitDeltaWorker {
+    const SCAN_INTERVAL: Duration = Duration::from_secs(3600);
+
+    pub fn new(mut collector: GitCollector) -> Self {
+        let latest = Arc::new(Mutex::new(HashMap::new()));
+        let (rescan, rescan_rx) = unbounded::<()>();
+        let latest_thread = latest.clone();
+        thread::spawn(move || loop {
+            collector.wake();
+            let values = collector.collect();
+            if let Ok(mut latest) = latest_thread.lock() {
+                *latest = values;
             }
+            match rescan_rx.recv_timeout(Self::SCAN_INTERVAL) {
+                Ok(()) => while rescan_rx.try_recv().is_ok() {},
+                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+                Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+            }
+        });
+        Self { latest, rescan }
+    }
+}
 
-            if let Ok(repo) = Repository::open(repo_path) {
-                let mut revwalk = match repo.revwalk() {
-                    Ok(rv) => rv,
-                    Err(_) => continue,
-                };
-                let _ = revwalk.push_head();
+#[cfg(feature = "git")]
+impl MetricCollector for GitDeltaWorker {
+    fn id(&self) -> &'static str { "git_delta" }
+    fn label(&self) -> &'static str { "Productivity" }
+    fn wake(&mut self) {
+        let _ = self.rescan.send(());
+    }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        self.latest.lock().map(|latest| latest.clone()).unwrap_or_default()
+    }
+}
 
-                // SEC-04: Limit revwalk objects to 500
-                let mut objects_seen = 0;
-                for oid in revwalk {
-                    if objects_seen >= 500 {
-                        log::debug!("GitCollector: Revwalk cap reached for {}", self.repos[idx]);
-                        break;
-                    }
-                    objects_seen += 1;
+/// Collector for `auto_commits_today`: counts today's entries in the auto-commit journal.
+#[derive(Debug)]
+pub struct AutoCommitCollector {
+    journal: crate::journal::CommitJournal,
+}
 
-                    let oid = match oid { Ok(o) => o, Err(_) => continue };
-                    let commit = match repo.find_commit(oid) { Ok(c) => c, Err(_) => continue };
-                    
-                    if commit.time().seconds() < yesterday_ts {
-                        break; // Older than window
-                    }
+impl AutoCommitCollector {
+    pub fn new(journal: crate::journal::CommitJournal) -> Self {
+        Self { journal }
+    }
+}
+
+impl MetricCollector for AutoCommitCollector {
+    fn id(&self) -> &'static str { "auto_commits" }
+    fn label(&self) -> &'static str { "Auto-Commits" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(60)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        match self.journal.count_on(Local::now().date_naive()) {
+            Ok(count) => {
+                map.insert(MetricId::AutoCommitsToday, MetricValue::Int(count as i64));
+            }
+            Err(e) => log::warn!("Failed to read auto-commit journal: {:#}", e),
+        }
+        map
+    }
+}
+
+/// Collector for `auto_commit_next`, `auto_commit_last` and `auto_commit_<repo>`:
+/// the auto-commit schedule as recorded by the productivity thread.
+#[derive(Debug)]
+pub struct AutoCommitStatusCollector;
+
+impl MetricCollector for AutoCommitStatusCollector {
+    fn id(&self) -> &'static str { "auto_commit_status" }
+    fn label(&self) -> &'static str { "Auto-Commit Schedule" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let Ok(status) = crate::auto_commit::ScheduleStatus::shared().lock() else { return map };
+        let next = status.next_run.map_or("N/A".to_string(), |t| t.format("%H:%M").to_string());
+        map.insert(MetricId::AutoCommitNext, MetricValue::String(next));
+        let last = match (status.last_run, status.last_result()) {
+            (Some(t), Some(result)) => format!("{} · {}", t.format("%H:%M"), result),
+            _ => "Not yet".to_string(),
+        };
+        map.insert(MetricId::AutoCommitLast, MetricValue::String(last));
+        for (repo, outcome) in &status.repos {
+            let id = MetricId::Custom(format!("auto_commit_{}", crate::auto_commit::repo_name(repo)));
+            map.insert(id, MetricValue::String(outcome.to_string()));
+        }
+        map
+    }
+}
+
+/// Collector for `keystrokes_today` and `wpm_now`. A background thread counts raw
+/// key presses (see `crate::typing`); only registered when `typing.enabled` is set.
+#[derive(Debug)]
+pub struct TypingCollector {
+    stats: Arc<Mutex<crate::typing::TypingStats>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl TypingCollector {
+    pub fn new(path: std::path::PathBuf) -> Self {
+        let stats = Arc::new(Mutex::new(crate::typing::TypingStats::load(&path)));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (stats_thread, stop_thread) = (stats.clone(), stop.clone());
+        thread::spawn(move || {
+            if let Err(e) = crate::typing::listen(stats_thread, stop_thread, path) {
+                log::warn!("Typing tracker unavailable: {:#}", e);
+            }
+        });
+        Self { stats, stop }
+    }
+}
+
+impl Drop for TypingCollector {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+impl MetricCollector for TypingCollector {
+    fn id(&self) -> &'static str { "typing" }
+    fn label(&self) -> &'static str { "Typing" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        if let Ok(mut stats) = self.stats.lock() {
+            let now = Local::now();
+            map.insert(MetricId::KeystrokesToday, MetricValue::Int(stats.keystrokes_today(now) as i64));
+            map.insert(MetricId::WpmNow, MetricValue::Int(stats.wpm(now).round() as i64));
+        }
+        map
+    }
+}
+
+/// Collector for `top_app` and `focused_time`: samples the focused window every
+/// `screen_time.sample_secs` and credits the time since the last sample to it.
+#[derive(Debug)]
+pub struct ScreenTimeCollector {
+    window: Option<crate::screen_time::ActiveWindow>,
+    usage: crate::screen_time::DailyUsage,
+    path: std::path::PathBuf,
+    sample: Duration,
+    last_sample: Option<Instant>,
+    last_save: Instant,
+}
+
+impl ScreenTimeCollector {
+    /// How often today's totals are written back to disk.
+    const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new(config: &crate::config::ScreenTime, path: std::path::PathBuf) -> Self {
+        let window = crate::screen_time::ActiveWindow::connect()
+            .map_err(|e| log::warn!("Screen-time tracker unavailable: {:#}", e))
+            .ok();
+        Self {
+            window,
+            usage: crate::screen_time::DailyUsage::load(&path),
+            path,
+            sample: Duration::from_secs(config.sample_secs.max(1)),
+            last_sample: None,
+            last_save: Instant::now(),
+        }
+    }
+
+    fn save(&mut self) {
+        if let Err(e) = self.usage.save(&self.path) {
+            log::warn!("Screen-time tracker: {:#}", e);
+        }
+        self.last_save = Instant::now();
+    }
+}
+
+impl Drop for ScreenTimeCollector {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+impl MetricCollector for ScreenTimeCollector {
+    fn id(&self) -> &'static str { "screen_time" }
+    fn label(&self) -> &'static str { "Screen Time" }
+    fn interval(&self) -> Option<Duration> { Some(self.sample) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let Some(window) = &self.window else { return map };
+        let now = Local::now();
+        // Long gaps (suspend, a stalled thread) aren't counted as focused time
+        let elapsed = self.last_sample.map_or(Duration::ZERO, |t| t.elapsed().min(self.sample * 2));
+        self.last_sample = Some(Instant::now());
+        match window.class() {
+            Ok(Some(app)) => self.usage.add(now, &app, elapsed.as_secs_f64().round() as u64),
+            Ok(None) => self.usage.roll_over(now),
+            Err(e) => log::debug!("Screen-time tracker: {:#}", e),
+        }
+        if self.last_save.elapsed() >= Self::SAVE_INTERVAL {
+            self.save();
+        }
+
+        let top = match self.usage.top() {
+            Some((app, secs)) => format!("{} {}", app, crate::screen_time::format_duration(secs)),
+            None => "None".to_string(),
+        };
+        map.insert(MetricId::TopApp, MetricValue::String(top));
+        map.insert(MetricId::FocusedTime, MetricValue::String(crate::screen_time::format_duration(self.usage.total())));
+        map
+    }
+}
+
+/// Collector for `tasks`: the number of pending tasks in Taskwarrior or a todo.txt
+/// file, followed by the most urgent one if `tasks.show_top` is set.
+#[derive(Debug)]
+pub struct TasksCollector {
+    config: crate::config::Tasks,
+    command: String,
+}
+
+impl TasksCollector {
+    pub fn new(config: crate::config::Tasks) -> Self {
+        Self::new_with_command(config, "task".to_string())
+    }
+
+    pub fn new_with_command(config: crate::config::Tasks, command: String) -> Self {
+        Self { config, command }
+    }
+
+    /// Pending count and most urgent description from `task export` output.
+    pub fn parse_taskwarrior(export: &str) -> Option<(usize, Option<String>)> {
+        let tasks: Vec<serde_json::Value> = serde_json::from_str(export).ok()?;
+        let pending: Vec<&serde_json::Value> = tasks.iter().filter(|t| t["status"].as_str().is_none_or(|s| s == "pending")).collect();
+        let top = pending
+            .iter()
+            .max_by(|a, b| a["urgency"].as_f64().unwrap_or(0.0).total_cmp(&b["urgency"].as_f64().unwrap_or(0.0)))
+            .and_then(|t| t["description"].as_str())
+            .map(str::to_string);
+        Some((pending.len(), top))
+    }
+
+    /// Pending count and top task from todo.txt content: open lines are pending, the
+    /// highest `(A)`-style priority wins (first line if none are prioritized).
+    pub fn parse_todo_txt(content: &str) -> (usize, Option<String>) {
+        let pending: Vec<&str> = content.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with("x ")).collect();
+        let priority = |line: &str| match line.as_bytes() {
+            [b'(', p @ b'A'..=b'Z', b')', b' ', ..] => *p,
+            _ => b'Z' + 1,
+        };
+        let top = pending.iter().min_by_key(|l| priority(l)).map(|line| {
+            let text = if priority(line) <= b'Z' { &line[4..] } else { line };
+            text.trim().to_string()
+        });
+        (pending.len(), top)
+    }
+
+    fn read(&self) -> Result<(usize, Option<String>), String> {
+        match &self.config.todo_file {
+            Some(path) => {
+                if !path_utils::is_safe_path(Path::new(path)) {
+                    log::warn!("Access Denied: todo.txt outside the sandbox: {}", path);
+                    return Err("ACCESS DENIED".to_string());
+                }
+                fs::read_to_string(path).map(|content| Self::parse_todo_txt(&content)).map_err(|e| {
+                    log::warn!("Failed to read todo.txt {}: {}", path, e);
+                    "ERR".to_string()
+                })
+            }
+            None => {
+                let output = Command::new(&self.command)
+                    .args(["rc.hooks=off", "rc.verbose=nothing", "rc.confirmation=off", "status:pending", "export"])
+                    .stderr(std::process::Stdio::null())
+                    .output()
+                    .map_err(|e| {
+                        log::warn!("Taskwarrior ('{}') unavailable: {}", self.command, e);
+                        "ERR".to_string()
+                    })?;
+                Self::parse_taskwarrior(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+                    log::warn!("Unexpected output from '{} export' ({})", self.command, output.status);
+                    "ERR".to_string()
+                })
+            }
+        }
+    }
+}
+
+impl MetricCollector for TasksCollector {
+    fn id(&self) -> &'static str { "tasks" }
+    fn label(&self) -> &'static str { "Tasks" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(self.config.interval_secs)) }
+    fn heavy(&self) -> bool { true }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let value = match self.read() {
+            Ok((count, Some(top))) if self.config.show_top => MetricValue::String(format!("{} pending · {}", count, top)),
+            Ok((count, _)) => MetricValue::Int(count as i64),
+            Err(text) => MetricValue::String(text),
+        };
+        let mut map = HashMap::new();
+        map.insert(MetricId::Tasks, value);
+        map
+    }
+}
+
+/// Collector for `next_event`: the next upcoming event across `calendar.sources`
+/// ("14:00 Standup" today, "Tue 09:30 Dentist" this week, "Oct 21 Dentist" later).
+#[derive(Debug)]
+pub struct CalendarCollector {
+    sources: Vec<String>,
+    refresh: Duration,
+}
+
+impl CalendarCollector {
+    /// Events further out than this aren't shown.
+    const LOOKAHEAD_DAYS: i64 = 30;
+
+    pub fn new(config: &crate::config::Calendar) -> Self {
+        Self { sources: config.sources.clone(), refresh: Duration::from_secs(config.interval_secs) }
+    }
+
+    /// Display text for an event starting at `start`, relative to `now`.
+    pub fn format_event(start: chrono::DateTime<Local>, all_day: bool, summary: &str, now: chrono::DateTime<Local>) -> String {
+        let days = (start.date_naive() - now.date_naive()).num_days();
+        let when = match (days, all_day) {
+            (0, true) => "Today".to_string(),
+            (1, true) => "Tomorrow".to_string(),
+            (0, false) => start.format("%H:%M").to_string(),
+            (1..=6, true) => start.format("%a").to_string(),
+            (1..=6, false) => start.format("%a %H:%M").to_string(),
+            _ => start.format("%b %-d").to_string(),
+        };
+        format!("{} {}", when, summary)
+    }
+
+    fn read(&self, source: &str) -> Option<String> {
+        if source.starts_with("http://") || source.starts_with("https://") {
+            #[cfg(feature = "http")]
+            return HttpCache::shared()
+                .get_text(source, self.refresh / 2)
+                .map_err(|e| log::warn!("Calendar {} unavailable: {:#}", source, e))
+                .ok();
+            #[cfg(not(feature = "http"))]
+            {
+                log::warn!("Calendar {} requires building with --features http", source);
+                return None;
+            }
+        }
+        if !path_utils::is_safe_path(Path::new(source)) {
+            log::warn!("Access Denied: calendar file outside the sandbox: {}", source);
+            return None;
+        }
+        fs::read_to_string(source).map_err(|e| log::warn!("Failed to read calendar {}: {}", source, e)).ok()
+    }
+}
+
+impl MetricCollector for CalendarCollector {
+    fn id(&self) -> &'static str { "calendar" }
+    fn label(&self) -> &'static str { "Calendar" }
+    fn interval(&self) -> Option<Duration> { Some(self.refresh) }
+    fn heavy(&self) -> bool { true }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let events: Vec<crate::ics::Event> = self.sources.iter().filter_map(|s| self.read(s)).flat_map(|ics| crate::ics::parse(&ics)).collect();
+        let now = Local::now();
+        let text = match crate::ics::next_event(&events, now) {
+            Some((start, event)) if start - now <= chrono::Duration::days(Self::LOOKAHEAD_DAYS) => {
+                Self::format_event(start, event.all_day, &event.summary, now)
+            }
+            _ => "No events".to_string(),
+        };
+        let mut map = HashMap::new();
+        map.insert(MetricId::NextEvent, MetricValue::String(text));
+        map
+    }
+}
+
+/// Working state of a repository.
+#[cfg(feature = "git")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoStatus {
+    /// Branch name, or "@<short id>" on a detached HEAD.
+    pub branch: String,
+    /// Commits ahead of / behind the upstream; `None` without an upstream.
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Modified, staged and untracked files.
+    pub dirty: usize,
+}
+
+#[cfg(feature = "git")]
+impl RepoStatus {
+    /// "main ↑2 ↓1 3 dirty", "main clean".
+    pub fn format(&self) -> String {
+        let mut parts = vec![self.branch.clone()];
+        if let Some((ahead, behind)) = self.ahead_behind {
+            if ahead > 0 {
+                parts.push(format!("↑{}", ahead));
+            }
+            if behind > 0 {
+                parts.push(format!("↓{}", behind));
+            }
+        }
+        parts.push(if self.dirty > 0 { format!("{} dirty", self.dirty) } else { "clean".to_string() });
+        parts.join(" ")
+    }
+}
+
+/// Collector for the working state of `productivity.repos`: `git_status_<repo dir>` per
+/// repo and `git_status` with all of them on one row.
+#[cfg(feature = "git")]
+#[derive(Debug)]
+pub struct GitStatusCollector {
+    repos: Vec<String>,
+}
+
+#[cfg(feature = "git")]
+impl GitStatusCollector {
+    pub fn new(repos: Vec<String>) -> Self {
+        Self { repos }
+    }
+
+    /// Metric ID for a repo path (its directory name).
+    pub fn repo_metric(repo: &str) -> MetricId {
+        MetricId::Custom(format!("git_status_{}", Self::repo_name(repo)))
+    }
+
+    fn repo_name(repo: &str) -> String {
+        Path::new(repo).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| repo.to_string())
+    }
+
+    pub fn status(repo: &Repository) -> Result<RepoStatus, git2::Error> {
+        let head = repo.head()?;
+        let branch = match head.shorthand() {
+            Some(name) if head.is_branch() => name.to_string(),
+            _ => format!("@{}", head.target().map(|oid| oid.to_string()[..7].to_string()).unwrap_or_default()),
+        };
+
+        let ahead_behind = head.is_branch().then(|| {
+            let local = git2::Branch::wrap(head);
+            let upstream = local.upstream().ok()?;
+            let (local_oid, upstream_oid) = (local.get().target()?, upstream.get().target()?);
+            repo.graph_ahead_behind(local_oid, upstream_oid).ok()
+        }).flatten();
+
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(false).include_ignored(false);
+        let dirty = repo.statuses(Some(&mut options))?.iter().filter(|e| e.status() != git2::Status::CURRENT).count();
 
-                    if commit.parent_count() > 0 {
-                        if let (Ok(parent), Ok(tree)) = (commit.parent(0), commit.tree()) {
-                            if let Ok(parent_tree) = parent.tree() {
-                                if let Ok(diff) = repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), None) {
-                                    if let Ok(stats) = diff.stats() {
-                                        total_added += stats.insertions() as i64;
-                                        total_deleted += stats.deletions() as i64;
-                                    }
-                                }
-                            }
-                        }
+        Ok(RepoStatus { branch, ahead_behind, dirty })
+    }
+}
+
+#[cfg(feature = "git")]
+impl MetricCollector for GitStatusCollector {
+    fn id(&self) -> &'static str { "git_status" }
+    fn label(&self) -> &'static str { "Git Status" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(60)) }
+    fn heavy(&self) -> bool { true }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+        let mut summary = Vec::new();
+        for repo_path in &self.repos {
+            let text = if !path_utils::is_safe_path(Path::new(repo_path)) {
+                log::warn!("Access Denied: Git repo outside home or unsafe: {}", repo_path);
+                "ACCESS DENIED".to_string()
+            } else {
+                match Repository::open(repo_path).and_then(|repo| Self::status(&repo)) {
+                    Ok(status) => status.format(),
+                    Err(e) => {
+                        log::debug!("GitStatusCollector: {} unavailable: {}", path_utils::sanitize_path_for_log(Path::new(repo_path)), e.message());
+                        "N/A".to_string()
                     }
                 }
-                log::debug!("GitCollector: Polled {} (delta window {}h)", 
-                    path_utils::sanitize_path_for_log(repo_path), window_hours);
-            }
+            };
+            summary.push(format!("{}: {}", Self::repo_name(repo_path), text));
+            map.insert(Self::repo_metric(repo_path), MetricValue::String(text));
         }
-        
-        self.rotation_index = (self.rotation_index + count) % self.repos.len();
-        self.cached_delta = (total_added, total_deleted);
-        self.last_check = now;
-
-        let mut map = HashMap::new();
-        map.insert(MetricId::CodeDelta, MetricValue::String(format!("+{} / -{}", total_added, total_deleted)));
+        map.insert(MetricId::GitStatus, MetricValue::String(summary.join(" · ")));
         map
     }
 }
 
 /// Collector for AI-driven insights (Ollama).
 /// Throttled to 1/hr and skipped if CPU > 80%.
+#[cfg(feature = "ollama")]
 #[derive(Debug)]
 pub struct OllamaCollector {
     last_fetch: Instant,
 }
 
+#[cfg(feature = "ollama")]
 impl OllamaCollector {
     pub fn new() -> Self {
         Self {
@@ -895,9 +4328,11 @@ impl OllamaCollector {
     }
 }
 
+#[cfg(feature = "ollama")]
 impl MetricCollector for OllamaCollector {
     fn id(&self) -> &'static str { "ollama" }
     fn label(&self) -> &'static str { "AI Insight" }
+    fn heavy(&self) -> bool { true }
     fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
         let mut map = HashMap::new();
         
@@ -906,10 +4341,7 @@ impl MetricCollector for OllamaCollector {
             return map;
         }
 
-        // We don't have a real SysinfoManager here in the trait yet, 
-        // but in a real app we'd pass it or the guard would use a global one.
-        // For this blueprint, we skip if load is high.
-        
+        // Under high load the scheduler's ResourceGuard defers this collector (it is heavy).
         log::info!("OllamaCollector: Fetching insight (Throttled 1/hr)");
         self.last_fetch = Instant::now();
         map.insert(MetricId::Custom("ai_insight".to_string()), MetricValue::String("Ready".to_string()));
@@ -917,6 +4349,10 @@ impl MetricCollector for OllamaCollector {
     }
 }
 
+/// How far the wall clock may run ahead of the monotonic clock between ticks before we
+/// treat it as a resume from suspend (NTP adjustments stay well below this).
+const RESUME_DETECT_SLACK: Duration = Duration::from_secs(10);
+
 /// Spawns the metrics collection thread.
 /// 
 /// Returns shared metrics, shutdown flag, thread handle, and command sender.
@@ -933,56 +4369,108 @@ pub fn spawn_metrics_thread(config: &Config) -> (Arc<Mutex<SharedMetrics>>, Arc<
         let sys_manager = Arc::new(Mutex::new(SysinfoManager::new()));
         let mut current_config = config_initial;
         
-        let mut collectors: Vec<Box<dyn MetricCollector>> = init_collectors(&current_config, sys_manager.clone());
-        let guard = ResourceGuard::new(70.0); // 70% threshold for general throttling
+        // The guard pauses the whole loop above `cpu_threshold` and defers heavy collectors
+        let new_scheduler = |config: &Config| {
+            let guard = Arc::new(ResourceGuard::from_config(&config.resource_guard));
+            let scheduler = CollectorScheduler::new(
+                init_collectors(config, sys_manager.clone(), &guard),
+                Duration::from_millis(config.general.update_ms),
+                &config.collector_intervals,
+                &config.disabled_collectors,
+            )
+            .with_guard(guard.clone());
+            (scheduler, guard)
+        };
+        let (mut scheduler, mut guard) = new_scheduler(&current_config);
+        let mut smoother = crate::smoothing::MetricSmoother::new();
+        let mut alert_manager = crate::alerts::AlertManager::new();
 
-        log::info!("Metrics thread initialized with {} collectors.", collectors.len());
+        log::info!("Metrics thread initialized with {} collectors.", scheduler.len());
+        let mut last_wall = SystemTime::now();
+        let mut last_tick = Instant::now();
+        let mut pending: Vec<MetricsCommand> = Vec::new();
 
         while !shutdown_clone.load(Ordering::Relaxed) {
-            // Check for resource throttling
+            pending.extend(rx.try_iter());
+            let forced = pending.iter().any(|cmd| matches!(cmd, MetricsCommand::ForceRefresh));
+
+            // Keep the load average current whether or not a CpuCollector runs (it may not be
+            // displayed, be toggled off, or be paused by the throttle below)
             if let Ok(mut sys) = sys_manager.lock() {
-                if guard.should_throttle(&mut sys) {
-                    log::debug!("Metrics thread: Throttling due to high CPU load");
-                    thread::sleep(Duration::from_millis(2000));
-                    continue;
-                }
+                guard.sample(&mut sys);
+            }
+
+            // Check for resource throttling (an explicit refresh request always runs)
+            if !forced && guard.should_throttle() {
+                log::debug!("Metrics thread: Throttling due to high CPU load ({:.0}% average)", guard.cpu_usage());
+                thread::sleep(Duration::from_millis(2000));
+                continue;
             }
 
             let start_time = Instant::now();
             
             // 1. Process Commands
-            while let Ok(cmd) = rx.try_recv() {
+            // A wall clock that ran ahead of the monotonic clock means the system was suspended
+            let wall_elapsed = SystemTime::now().duration_since(last_wall).unwrap_or_default();
+            if wall_elapsed > last_tick.elapsed() + RESUME_DETECT_SLACK {
+                log::info!("Metrics thread: Resume from suspend detected, waking collectors.");
+                scheduler.wake();
+            }
+            last_wall = SystemTime::now();
+            last_tick = Instant::now();
+
+            for cmd in pending.drain(..) {
                 match cmd {
                     MetricsCommand::UpdateConfig(new_cfg) => {
                         log::info!("Metrics thread: Reloading configuration...");
                         current_config = new_cfg;
-                        collectors = init_collectors(&current_config, sys_manager.clone());
+                        (scheduler, guard) = new_scheduler(&current_config);
+                        smoother.reset();
+                        alert_manager.reset();
                     }
                     MetricsCommand::ForceRefresh => {
                         log::info!("Metrics thread: Force refresh requested.");
+                        scheduler.force_refresh(Duration::from_millis(current_config.general.update_ms));
+                    }
+                    MetricsCommand::SetCollectorEnabled(id, enabled) => {
+                        if scheduler.set_enabled(&id, enabled) {
+                            log::info!("Metrics thread: Collector '{}' {}.", id, if enabled { "enabled" } else { "disabled" });
+                        } else {
+                            log::warn!("Metrics thread: Unknown collector '{}'.", id);
+                        }
                     }
                 }
             }
 
-            // 2. Collect Data
-            let mut frame_data = HashMap::new();
-            for collector in &mut collectors {
-                let data = collector.collect();
-                frame_data.extend(data);
-            }
+            // 2. Collect Data: run due collectors on the worker pool; anything slower
+            // than half a tick is merged once it finishes, without holding up this frame
+            let interval = Duration::from_millis(current_config.general.update_ms);
+            scheduler.dispatch_due();
+            scheduler.gather(start_time + interval / 2);
+            let mut frame_data = scheduler.snapshot();
 
-            // 3. Update Shared State
+            // 3. Derived metrics (evaluated before publishing)
+            crate::computed::apply(&current_config.computed_metrics, &mut frame_data);
+            smoother.apply(&current_config.metric_settings, &mut frame_data);
+            let levels = alert_manager.update(&current_config.metric_settings, &frame_data);
+
+            // 4. Update Shared State
             if let Ok(mut shared) = shared_clone.lock() {
                 shared.data = MetricData { values: frame_data };
                 shared.timestamp = Instant::now();
                 shared.day_of_week = chrono::Local::now().weekday().to_string();
+                shared.levels = levels;
+                shared.collectors = scheduler.statuses();
+                shared.health = scheduler.health();
+                shared.record_history();
             }
 
-            // 4. Sleep
-            let interval = Duration::from_millis(current_config.general.update_ms);
+            // 5. Sleep (an incoming command, e.g. a forced refresh, cuts the wait short)
             let elapsed = start_time.elapsed();
             if elapsed < interval {
-                thread::sleep(interval - elapsed);
+                if let Ok(cmd) = rx.recv_timeout(interval - elapsed) {
+                    pending.push(cmd);
+                }
             }
         }
         log::info!("Metrics thread stopped.");
@@ -991,9 +4479,57 @@ pub fn spawn_metrics_thread(config: &Config) -> (Arc<Mutex<SharedMetrics>>, Arc<
     (shared_metrics, shutdown_flag, handle, tx)
 }
 
-fn init_collectors(config: &Config, sys_manager: Arc<Mutex<SysinfoManager>>) -> Vec<Box<dyn MetricCollector>> {
+/// Collector for `sunrise`, `sunset`, `daylight_remaining` and `moon_phase`, computed
+/// locally (see `crate::astro`). Also records whether the sun is up, for `general.night_theme`.
+#[derive(Debug)]
+pub struct AstroCollector {
+    lat: f64,
+    lon: f64,
+}
+
+impl AstroCollector {
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self { lat, lon }
+    }
+}
+
+impl MetricCollector for AstroCollector {
+    fn id(&self) -> &'static str { "astro" }
+    fn label(&self) -> &'static str { "Sun & Moon" }
+    fn interval(&self) -> Option<Duration> { Some(Duration::from_secs(60)) }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        use crate::astro::SunTimes;
+        let mut map = HashMap::new();
+        let now = Local::now();
+        let now_utc = now.with_timezone(&chrono::Utc);
+        let sun = SunTimes::on(now.date_naive(), self.lat, self.lon);
+        let up = sun.is_up(now_utc);
+        crate::astro::set_daytime(up);
+
+        let (sunrise, sunset, remaining) = match sun {
+            SunTimes::Rises { sunrise, sunset } => {
+                let local = |t: chrono::DateTime<chrono::Utc>| t.with_timezone(&Local).format("%H:%M").to_string();
+                let left = if up { (sunset - now_utc).num_seconds().max(0) as u64 } else { 0 };
+                (local(sunrise), local(sunset), crate::screen_time::format_duration(left))
+            }
+            SunTimes::AlwaysUp => ("--:--".to_string(), "--:--".to_string(), "All day".to_string()),
+            SunTimes::AlwaysDown => ("--:--".to_string(), "--:--".to_string(), "0:00".to_string()),
+        };
+        map.insert(MetricId::Sunrise, MetricValue::String(sunrise));
+        map.insert(MetricId::Sunset, MetricValue::String(sunset));
+        map.insert(MetricId::DaylightRemaining, MetricValue::String(remaining));
+
+        let age = crate::astro::moon_age(now_utc);
+        let moon = format!("{} {:.0}%", crate::astro::moon_phase_name(age), crate::astro::moon_illumination(age) * 100.0);
+        map.insert(MetricId::MoonPhase, MetricValue::String(moon));
+        map
+    }
+}
+
+fn init_collectors(config: &Config, sys_manager: Arc<Mutex<SysinfoManager>>, guard: &Arc<ResourceGuard>) -> Vec<Box<dyn MetricCollector>> {
     let mut collectors: Vec<Box<dyn MetricCollector>> = Vec::new();
     let mut required_metrics = HashSet::new();
+    UnitSystem::from_name(&config.general.units).make_current();
     
     // Core requirements
     required_metrics.insert(MetricId::CpuUsage);
@@ -1007,12 +4543,35 @@ fn init_collectors(config: &Config, sys_manager: Arc<Mutex<SysinfoManager>>) ->
                 required_metrics.insert(id);
             }
         }
+        // The ticker shows its own metrics and the feed
+        if screen.metrics.iter().any(|m| m == "ticker") {
+            required_metrics.extend(config.ticker.metrics.iter().filter_map(|m| MetricId::from_str(m)));
+            if config.ticker.feed_url.is_some() {
+                required_metrics.insert(MetricId::FeedHeadlines);
+            }
+        }
+    }
+    // The HUD can be toggled on at any time, so its metrics are always collected
+    required_metrics.extend(config.hud.metrics.iter().filter_map(|m| MetricId::from_str(m)));
+
+    // Inputs of computed metrics must be collected even if not displayed directly
+    for computed in &config.computed_metrics {
+        if let Ok(expr) = crate::computed::parse(&computed.expression) {
+            let mut vars = Vec::new();
+            expr.variables(&mut vars);
+            for var in vars {
+                let base = var.split('.').next().unwrap_or(&var);
+                if let Some(id) = MetricId::from_str(base) {
+                    required_metrics.insert(id);
+                }
+            }
+        }
     }
 
     if required_metrics.contains(&MetricId::CpuUsage) || required_metrics.contains(&MetricId::LoadAvg) {
-        collectors.push(Box::new(CpuCollector::new(sys_manager.clone())));
+        collectors.push(Box::new(CpuCollector::new(sys_manager.clone()).with_guard(guard.clone())));
     }
-    if required_metrics.contains(&MetricId::RamUsage) || required_metrics.contains(&MetricId::RamUsed) {
+    if required_metrics.contains(&MetricId::RamUsage) || required_metrics.contains(&MetricId::RamUsed) || required_metrics.contains(&MetricId::RamTotal) {
         collectors.push(Box::new(MemoryCollector::new(sys_manager.clone())));
     }
     if required_metrics.contains(&MetricId::Uptime) || required_metrics.contains(&MetricId::LoadAvg) {
@@ -1021,20 +4580,213 @@ fn init_collectors(config: &Config, sys_manager: Arc<Mutex<SysinfoManager>>) ->
     if required_metrics.contains(&MetricId::NetworkDetails) {
         collectors.push(Box::new(NetworkCollector::new()));
     }
-    if required_metrics.contains(&MetricId::DiskUsage) {
-        collectors.push(Box::new(DiskCollector::new(sys_manager.clone())));
+    if required_metrics.contains(&MetricId::WifiSsid) || required_metrics.contains(&MetricId::WifiSignal) {
+        collectors.push(Box::new(WifiCollector::new()));
+    }
+    if required_metrics.contains(&MetricId::NowPlaying) {
+        #[cfg(feature = "dbus")]
+        collectors.push(Box::new(MprisCollector::new()));
+        #[cfg(not(feature = "dbus"))]
+        log::warn!("now_playing requires building with --features dbus");
+    }
+    if required_metrics.contains(&MetricId::Notifications) {
+        #[cfg(feature = "dbus")]
+        collectors.push(Box::new(NotificationCollector::new(config.notifications.clone())));
+        #[cfg(not(feature = "dbus"))]
+        log::warn!("notifications requires building with --features dbus");
+    }
+    let audio_metrics = [MetricId::AudioVolume, MetricId::AudioMute, MetricId::AudioDevice];
+    if audio_metrics.iter().any(|id| required_metrics.contains(id)) {
+        collectors.push(Box::new(AudioCollector::new()));
+    }
+    if required_metrics.contains(&MetricId::UpdatesPending) {
+        collectors.push(Box::new(
+            UpdatesCollector::new(config.updates.command.clone(), config.updates.interval_secs),
+        ));
+    }
+    if required_metrics.contains(&MetricId::GhNotifications) || required_metrics.contains(&MetricId::GhReviewQueue) {
+        #[cfg(feature = "http")]
+        collectors.push(Box::new(GitHubCollector::new(&config.github)));
+        #[cfg(not(feature = "http"))]
+        log::warn!("GitHub metrics require building with --features http");
+    }
+    if required_metrics.contains(&MetricId::NextEvent) || config.calendar.in_header {
+        collectors.push(Box::new(CalendarCollector::new(&config.calendar)));
+    }
+    if required_metrics.contains(&MetricId::Tasks) {
+        collectors.push(Box::new(TasksCollector::new(config.tasks.clone())));
+    }
+    if required_metrics.contains(&MetricId::FeedHeadlines) {
+        match &config.ticker.feed_url {
+            #[cfg(feature = "http")]
+            Some(_) => collectors.push(Box::new(RssCollector::for_ticker(&config.ticker))),
+            #[cfg(not(feature = "http"))]
+            Some(_) => log::warn!("ticker.feed_url requires building with --features http"),
+            None => log::warn!("feed_headlines needs ticker.feed_url"),
+        }
+    }
+    if required_metrics.contains(&MetricId::AutoCommitsToday) {
+        match crate::journal::CommitJournal::default_path() {
+            Ok(path) => collectors.push(Box::new(AutoCommitCollector::new(crate::journal::CommitJournal::new(path)))),
+            Err(e) => log::warn!("Auto-commit journal unavailable: {:#}", e),
+        }
+    }
+    let wants_repo_outcome = required_metrics.iter().any(|id| matches!(id, MetricId::Custom(s) if s.starts_with("auto_commit_")));
+    if required_metrics.contains(&MetricId::AutoCommitNext) || required_metrics.contains(&MetricId::AutoCommitLast) || wants_repo_outcome {
+        collectors.push(Box::new(AutoCommitStatusCollector));
+    }
+    let wants_typing = required_metrics.contains(&MetricId::KeystrokesToday) || required_metrics.contains(&MetricId::WpmNow);
+    if wants_typing && config.typing.enabled {
+        match crate::typing::TypingStats::default_path() {
+            Ok(path) => collectors.push(Box::new(TypingCollector::new(path))),
+            Err(e) => log::warn!("Typing tracker unavailable: {:#}", e),
+        }
+    }
+    let wants_screen_time = required_metrics.contains(&MetricId::TopApp) || required_metrics.contains(&MetricId::FocusedTime);
+    if wants_screen_time && config.screen_time.enabled {
+        match crate::screen_time::DailyUsage::default_path() {
+            Ok(path) => collectors.push(Box::new(ScreenTimeCollector::new(&config.screen_time, path))),
+            Err(e) => log::warn!("Screen-time tracker unavailable: {:#}", e),
+        }
+    }
+    let wants_repo_status = required_metrics.iter().any(|id| matches!(id, MetricId::Custom(s) if s.starts_with("git_status_")));
+    if required_metrics.contains(&MetricId::GitStatus) || wants_repo_status {
+        #[cfg(feature = "git")]
+        collectors.push(Box::new(GitStatusCollector::new(config.productivity.repos.clone())));
+        #[cfg(not(feature = "git"))]
+        log::warn!("git_status requires building with --features git");
+    }
+    let wants_ci = required_metrics.iter().any(|id| matches!(id, MetricId::Custom(s) if s.starts_with("ci_")));
+    if wants_ci && !config.productivity.repos.is_empty() {
+        #[cfg(all(feature = "http", feature = "git"))]
+        collectors.push(Box::new(CiCollector::new(config.productivity.repos.clone(), &config.ci, &config.github)));
+        #[cfg(not(all(feature = "http", feature = "git")))]
+        log::warn!("CI metrics require building with --features http,git");
+    }
+    let wants_units = required_metrics.iter().any(|id| matches!(id, MetricId::Custom(s) if s.starts_with("service_")));
+    if required_metrics.contains(&MetricId::FailedUnits) || wants_units {
+        collectors.push(Box::new(SystemdCollector::new(config.services.clone())));
+    }
+    if required_metrics.contains(&MetricId::TimeSync) || required_metrics.contains(&MetricId::ClockOffset) {
+        collectors.push(Box::new(TimeSyncCollector::new()));
+    }
+    if required_metrics.contains(&MetricId::KernelErrors) {
+        collectors.push(Box::new(KernelLogCollector::new()));
+    }
+    let wants_mounts = required_metrics.iter().any(|id| matches!(id, MetricId::Custom(s) if s.starts_with("disk_usage_")));
+    if required_metrics.contains(&MetricId::DiskUsage) || required_metrics.contains(&MetricId::DiskSummary) || wants_mounts {
+        collectors.push(Box::new(DiskCollector::new(sys_manager.clone()).with_mounts(config.disks.clone())));
+    }
+    let wants_inodes = required_metrics.iter().any(|id| matches!(id, MetricId::Custom(s) if s.starts_with("inode_usage_")));
+    if required_metrics.contains(&MetricId::InodeUsage) || wants_inodes {
+        collectors.push(Box::new(InodeCollector::new(config.disks.clone())));
+    }
+    if let Some(mqtt) = &config.mqtt {
+        if mqtt.topics.iter().any(|t| required_metrics.contains(&MetricId::Custom(t.metric_id.clone()))) {
+            #[cfg(feature = "mqtt")]
+            collectors.push(Box::new(MqttCollector::new(mqtt.clone())));
+            #[cfg(not(feature = "mqtt"))]
+            log::warn!("MQTT metrics require building with --features mqtt");
+        }
+    }
+    for endpoint in &config.http_json {
+        if endpoint.fields.iter().any(|f| required_metrics.contains(&MetricId::Custom(f.metric_id.clone()))) {
+            #[cfg(feature = "http")]
+            collectors.push(Box::new(HttpJsonCollector::new(endpoint.clone())));
+            #[cfg(not(feature = "http"))]
+            log::warn!("{} requires building with --features http", endpoint.url);
+        }
+    }
+    for feed in &config.rss_feeds {
+        if required_metrics.contains(&MetricId::Custom(feed.metric_id.clone())) {
+            #[cfg(feature = "http")]
+            collectors.push(Box::new(RssCollector::new(feed.clone())));
+            #[cfg(not(feature = "http"))]
+            log::warn!("{} requires building with --features http", feed.url);
+        }
+    }
+    for remote in &config.remote_instances {
+        let displayed: Vec<String> = required_metrics
+            .iter()
+            .filter_map(|id| match id {
+                MetricId::Custom(s) if remote.remote_id(s).is_some() => Some(s.clone()),
+                _ => None,
+            })
+            .collect();
+        if !displayed.is_empty() {
+            #[cfg(feature = "http")]
+            collectors.push(Box::new(RemoteCollector::new(remote.clone(), displayed)));
+            #[cfg(not(feature = "http"))]
+            log::warn!("Remote instance '{}' requires building with --features http", remote.name);
+        }
+    }
+    for command in &config.commands {
+        if required_metrics.contains(&MetricId::Custom(command.metric_id.clone())) {
+            collectors.push(Box::new(CommandCollector::new(command.clone())));
+        }
+    }
+    let wants_device_io = required_metrics.iter().any(|id| matches!(id, MetricId::Custom(s) if s.starts_with("disk_io_")));
+    if required_metrics.contains(&MetricId::DiskIo) || wants_device_io {
+        collectors.push(Box::new(DiskIoCollector::new()));
     }
     if required_metrics.contains(&MetricId::CpuTemp) || required_metrics.contains(&MetricId::FanSpeed) {
         collectors.push(Box::new(HwmonCollector::new()));
     }
-    if required_metrics.contains(&MetricId::GpuTemp) || required_metrics.contains(&MetricId::GpuUtil) {
-        collectors.push(Box::new(NvidiaSmiCollector::new()));
+    if required_metrics.contains(&MetricId::PublicIp) {
+        #[cfg(feature = "http")]
+        collectors.push(Box::new(PublicIpCollector::new(&config.public_ip.endpoint, config.public_ip.interval_secs)));
+        #[cfg(not(feature = "http"))]
+        log::warn!("public_ip requires building with --features http");
+    }
+    if required_metrics.contains(&MetricId::VpnStatus) {
+        collectors.push(Box::new(VpnStatusCollector::new()));
+    }
+    if required_metrics.contains(&MetricId::TopProcesses) {
+        collectors.push(Box::new(TopProcessCollector::new(
+            sys_manager.clone(),
+            config.top_processes.count,
+            &config.top_processes.sort_by,
+        )));
+    }
+    if required_metrics.contains(&MetricId::OverlayMemory) || required_metrics.contains(&MetricId::OverlayRender) {
+        collectors.push(Box::new(SelfMonitorCollector::new()));
+    }
+    let gpu_metrics = [MetricId::GpuTemp, MetricId::GpuUtil, MetricId::GpuVram, MetricId::GpuPower, MetricId::GpuClock, MetricId::GpuState];
+    if gpu_metrics.iter().any(|id| required_metrics.contains(id)) {
+        // AMD first: on hybrid laptops the NVIDIA dGPU (collected later) takes precedence
+        if let Some(amd) = AmdGpuCollector::detect() {
+            collectors.push(Box::new(amd));
+        }
+        collectors.push(nvidia_collector());
     }
+    #[cfg(feature = "git")]
     if !config.productivity.repos.is_empty() {
-        collectors.push(Box::new(GitCollector::new(config.productivity.repos.clone())));
+        let scanner = GitCollector::new(config.productivity.repos.clone())
+            .with_limits(config.productivity.batch_cap, &config.productivity.git_scan);
+        collectors.push(Box::new(GitDeltaWorker::new(scanner)));
     }
-    if config.weather.enabled {
-        collectors.push(Box::new(OpenMeteoCollector::new(config.weather.lat, config.weather.lon, true)));
+    let astro_metrics = [MetricId::Sunrise, MetricId::Sunset, MetricId::DaylightRemaining, MetricId::MoonPhase];
+    let wants_astro = astro_metrics.iter().any(|id| required_metrics.contains(id)) || config.general.night_theme.is_some();
+    if config.weather.enabled || wants_astro {
+        let (lat, lon) = weather_coordinates(&config.weather);
+        #[cfg(feature = "weather")]
+        if config.weather.enabled {
+            collectors.push(Box::new(OpenMeteoCollector::new(lat, lon, true).with_forecast(config.weather.forecast)));
+            for location in &config.weather.locations {
+                collectors.push(Box::new(OpenMeteoCollector::for_location(location).with_forecast(config.weather.forecast)));
+            }
+            let air_metrics = [MetricId::AirQuality, MetricId::Pm25, MetricId::Pm10, MetricId::Pollen, MetricId::UvIndex];
+            if air_metrics.iter().any(|id| required_metrics.contains(id)) {
+                collectors.push(Box::new(AirQualityCollector::new(lat, lon)));
+            }
+        }
+        #[cfg(not(feature = "weather"))]
+        if config.weather.enabled {
+            log::warn!("weather metrics require building with --features weather");
+        }
+        if wants_astro {
+            collectors.push(Box::new(AstroCollector::new(lat, lon)));
+        }
     }
     
     collectors.push(Box::new(DateCollector));
@@ -1084,11 +4836,104 @@ impl MetricCollector for SysinfoCollector {
     }
 }
 
+/// Tracks whether the NVIDIA GPU is reachable. Hybrid-graphics laptops power the dGPU
+/// off entirely, after which every query fails; instead of retrying (and logging) each
+/// tick, the GPU is marked off until a wake event or until sysfs reports it active again.
+#[derive(Debug)]
+pub struct GpuAvailability {
+    failures: u32,
+    off: bool,
+    /// Whether the GPU ever answered; GPU metrics are only marked "off" for a GPU we have seen.
+    seen: bool,
+    pci_path: PathBuf,
+}
+
+impl GpuAvailability {
+    /// Consecutive failed queries before the GPU is considered powered off.
+    pub const OFF_AFTER_FAILURES: u32 = 3;
+    const NVIDIA_VENDOR_ID: &'static str = "0x10de";
+
+    pub fn new() -> Self {
+        Self::with_pci_path(PathBuf::from("/sys/bus/pci/devices"))
+    }
+
+    pub fn with_pci_path(pci_path: PathBuf) -> Self {
+        Self { failures: 0, off: false, seen: false, pci_path }
+    }
+
+    pub fn is_off(&self) -> bool {
+        self.off
+    }
+
+    /// Whether to query the GPU this tick. While off, only a runtime-PM "active" NVIDIA
+    /// device in sysfs brings it back without an explicit wake.
+    pub fn should_query(&mut self) -> bool {
+        if self.off && self.pci_device_active() {
+            log::info!("NVIDIA GPU is powered on again, resuming queries");
+            self.wake();
+        }
+        !self.off
+    }
+
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+        self.seen = true;
+    }
+
+    pub fn record_failure(&mut self, reason: &str) {
+        self.failures += 1;
+        if self.failures < Self::OFF_AFTER_FAILURES {
+            log::warn!("NVIDIA GPU query failed: {}", reason);
+        } else if !self.off {
+            self.off = true;
+            log::warn!(
+                "NVIDIA GPU unavailable after {} attempts ({}); pausing queries until it wakes",
+                self.failures,
+                reason
+            );
+        }
+    }
+
+    pub fn wake(&mut self) {
+        self.failures = 0;
+        self.off = false;
+    }
+
+    /// Adds the `gpu_state` metric, and marks the GPU metrics off while the GPU is unavailable.
+    pub fn annotate(&self, map: &mut HashMap<MetricId, MetricValue>) {
+        let state = if self.off { "off" } else { "on" };
+        map.insert(MetricId::GpuState, MetricValue::String(state.to_string()));
+        if self.off && self.seen {
+            for id in [MetricId::GpuTemp, MetricId::GpuUtil, MetricId::GpuVram, MetricId::GpuPower, MetricId::GpuClock] {
+                map.insert(id, MetricValue::String("off".to_string()));
+            }
+        }
+    }
+
+    fn pci_device_active(&self) -> bool {
+        let entries = match fs::read_dir(&self.pci_path) {
+            Ok(e) => e,
+            Err(_) => return false,
+        };
+        entries.flatten().map(|e| e.path()).any(|dev| {
+            fs::read_to_string(dev.join("vendor")).map(|v| v.trim() == Self::NVIDIA_VENDOR_ID).unwrap_or(false)
+                && fs::read_to_string(dev.join("power/runtime_status")).map(|s| s.trim() == "active").unwrap_or(false)
+        })
+    }
+}
+
+impl Default for GpuAvailability {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Collector for NVIDIA GPU metrics using `nvidia-smi`.
 #[derive(Debug)]
 pub struct NvidiaSmiCollector {
     command: String,
     args: Vec<String>,
+    availability: GpuAvailability,
 }
 
 impl NvidiaSmiCollector {
@@ -1096,22 +4941,35 @@ impl NvidiaSmiCollector {
         Self {
             command: "nvidia-smi".to_string(),
             args: vec![
-                "--query-gpu=temperature.gpu,utilization.gpu,fan.speed".to_string(),
+                "--query-gpu=temperature.gpu,utilization.gpu,fan.speed,memory.used,memory.total,power.draw,clocks.gr".to_string(),
                 "--format=csv,noheader,nounits".to_string(),
             ],
+            availability: GpuAvailability::new(),
         }
     }
 
     pub fn new_with_command(_metric_id: MetricId, command: String, args: Vec<String>) -> Self {
-        Self { command, args }
+        Self { command, args, availability: GpuAvailability::new() }
+    }
+
+    /// Replaces the availability tracker (e.g. to point it at a test sysfs tree).
+    pub fn with_availability(mut self, availability: GpuAvailability) -> Self {
+        self.availability = availability;
+        self
     }
 }
 
 impl MetricCollector for NvidiaSmiCollector {
     fn id(&self) -> &'static str { "nvidia" }
     fn label(&self) -> &'static str { "GPU" }
+    fn heavy(&self) -> bool { true }
+    fn wake(&mut self) { self.availability.wake(); }
     fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
         let mut map = HashMap::new();
+        if !self.availability.should_query() {
+            self.availability.annotate(&mut map);
+            return map;
+        }
 
         match Command::new(&self.command).args(&self.args).output() {
             Ok(output) => {
@@ -1120,8 +4978,9 @@ impl MetricCollector for NvidiaSmiCollector {
                     let parts: Vec<&str> = stdout.trim().split(',').map(|s| s.trim()).collect();
                     
                     if parts.len() >= 3 {
+                        self.availability.record_success();
                         if let Ok(temp) = parts[0].parse::<f64>() {
-                            map.insert(MetricId::GpuTemp, MetricValue::String(format!("{:.0}°C", temp)));
+                            map.insert(MetricId::GpuTemp, MetricValue::String(UnitSystem::current().temperature(temp, 0)));
                         }
                         if let Ok(util) = parts[1].parse::<f64>() {
                             map.insert(MetricId::GpuUtil, MetricValue::String(format!("{:.0}%", util)));
@@ -1129,26 +4988,199 @@ impl MetricCollector for NvidiaSmiCollector {
                         if let Ok(_fan) = parts[2].parse::<f64>() {
                             // map.insert(MetricId::GpuFan, ...); // MetricId doesn't have GpuFan yet
                         }
+                        // Extended fields (MiB, W, MHz); "[N/A]" on boards that don't report them
+                        let field = |i: usize| parts.get(i).and_then(|p| p.parse::<f64>().ok());
+                        if let (Some(used), Some(total)) = (field(3), field(4)) {
+                            map.insert(MetricId::GpuVram, format_vram(used * 1_048_576.0, total * 1_048_576.0));
+                        }
+                        if let Some(watts) = field(5) {
+                            map.insert(MetricId::GpuPower, MetricValue::String(format!("{:.0} W", watts)));
+                        }
+                        if let Some(mhz) = field(6) {
+                            map.insert(MetricId::GpuClock, MetricValue::String(format!("{:.0} MHz", mhz)));
+                        }
                     } else {
-                        log::warn!("nvidia-smi output format mismatch: {}", stdout);
+                        self.availability.record_failure(&format!("output format mismatch: {}", stdout.trim()));
                     }
                 } else {
-                    log::warn!("nvidia-smi failed with status: {}", output.status);
+                    self.availability.record_failure(&format!("nvidia-smi exited with {}", output.status));
                 }
             },
             Err(e) => {
-                log::error!("Failed to execute nvidia-smi: {}", e);
+                self.availability.record_failure(&format!("failed to execute nvidia-smi: {}", e));
+            }
+        }
+        self.availability.annotate(&mut map);
+        map
+    }
+}
+
+/// Collector for AMD dGPU/iGPU metrics from the amdgpu sysfs interface.
+/// Reads `/sys/class/drm/card*/device` for busy %, VRAM, power and temperature.
+/// When several AMD cards are present the one with the most VRAM (usually the dGPU) is used.
+#[derive(Debug)]
+pub struct AmdGpuCollector {
+    device_path: PathBuf,
+}
+
+impl AmdGpuCollector {
+    const AMD_VENDOR_ID: &'static str = "0x1002";
+
+    /// Returns a collector for the primary AMD GPU, or None if no amdgpu device is found.
+    pub fn detect() -> Option<Self> {
+        Self::detect_in(Path::new("/sys/class/drm"))
+    }
+
+    pub fn detect_in(drm_path: &Path) -> Option<Self> {
+        let entries = fs::read_dir(drm_path).ok()?;
+        entries
+            .flatten()
+            .filter(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                // Skip connector entries like card0-DP-1
+                name.starts_with("card") && !name.contains('-')
+            })
+            .map(|e| e.path().join("device"))
+            .filter(|dev| {
+                fs::read_to_string(dev.join("vendor")).map(|v| v.trim() == Self::AMD_VENDOR_ID).unwrap_or(false)
+                    && dev.join("gpu_busy_percent").exists()
+            })
+            .max_by_key(|dev| read_u64(&dev.join("mem_info_vram_total")).unwrap_or(0))
+            .map(|device_path| Self { device_path })
+    }
+
+    /// Finds the hwmon directory exposing power/temperature for this device.
+    fn hwmon_dir(&self) -> Option<PathBuf> {
+        fs::read_dir(self.device_path.join("hwmon")).ok()?.flatten().map(|e| e.path()).next()
+    }
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse::<u64>().ok()
+}
+
+impl MetricCollector for AmdGpuCollector {
+    fn id(&self) -> &'static str { "amdgpu" }
+    fn label(&self) -> &'static str { "GPU" }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        let mut map = HashMap::new();
+
+        if let Some(busy) = read_u64(&self.device_path.join("gpu_busy_percent")) {
+            map.insert(MetricId::GpuUtil, MetricValue::String(format!("{}%", busy)));
+        }
+        if let (Some(used), Some(total)) = (
+            read_u64(&self.device_path.join("mem_info_vram_used")),
+            read_u64(&self.device_path.join("mem_info_vram_total")),
+        ) {
+            map.insert(MetricId::GpuVram, format_vram(used as f64, total as f64));
+        }
+        if let Some(hwmon) = self.hwmon_dir() {
+            // power1_average is in microwatts (some APUs only expose power1_input)
+            if let Some(uw) = read_u64(&hwmon.join("power1_average")).or_else(|| read_u64(&hwmon.join("power1_input"))) {
+                map.insert(MetricId::GpuPower, MetricValue::String(format!("{:.0} W", uw as f64 / 1_000_000.0)));
+            }
+            if let Some(milli_c) = read_u64(&hwmon.join("temp1_input")) {
+                map.insert(MetricId::GpuTemp, MetricValue::String(UnitSystem::current().temperature(milli_c as f64 / 1000.0, 0)));
+            }
+        }
+        map
+    }
+}
+
+/// Formats VRAM usage from bytes as "used/total GB".
+fn format_vram(used_bytes: f64, total_bytes: f64) -> MetricValue {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    MetricValue::String(format!("{:.1}/{:.1} GB", used_bytes / GB, total_bytes / GB))
+}
+
+/// Collector for NVIDIA GPU metrics via NVML (libnvidia-ml, loaded at runtime).
+#[cfg(feature = "nvml")]
+pub struct NvmlCollector {
+    nvml: nvml_wrapper::Nvml,
+    device_index: u32,
+    availability: GpuAvailability,
+}
+
+#[cfg(feature = "nvml")]
+impl Debug for NvmlCollector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NvmlCollector").field("device_index", &self.device_index).finish()
+    }
+}
+
+#[cfg(feature = "nvml")]
+impl NvmlCollector {
+    /// Initializes NVML. Fails if the driver library is missing or no GPU is present.
+    pub fn new() -> anyhow::Result<Self> {
+        let nvml = nvml_wrapper::Nvml::init()?;
+        // Probe the device once so a driverless system falls back immediately
+        nvml.device_by_index(0)?;
+        let mut availability = GpuAvailability::new();
+        availability.record_success();
+        Ok(Self { nvml, device_index: 0, availability })
+    }
+}
+
+#[cfg(feature = "nvml")]
+impl MetricCollector for NvmlCollector {
+    fn id(&self) -> &'static str { "nvml" }
+    fn label(&self) -> &'static str { "GPU" }
+    fn wake(&mut self) { self.availability.wake(); }
+    fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+        use nvml_wrapper::enum_wrappers::device::{Clock, TemperatureSensor};
+
+        let mut map = HashMap::new();
+        if !self.availability.should_query() {
+            self.availability.annotate(&mut map);
+            return map;
+        }
+        let device = match self.nvml.device_by_index(self.device_index) {
+            Ok(d) => d,
+            Err(e) => {
+                self.availability.record_failure(&format!("NVML device {}: {}", self.device_index, e));
+                self.availability.annotate(&mut map);
+                return map;
             }
+        };
+        self.availability.record_success();
+        self.availability.annotate(&mut map);
+
+        if let Ok(temp) = device.temperature(TemperatureSensor::Gpu) {
+            map.insert(MetricId::GpuTemp, MetricValue::String(UnitSystem::current().temperature(temp as f64, 0)));
+        }
+        if let Ok(util) = device.utilization_rates() {
+            map.insert(MetricId::GpuUtil, MetricValue::String(format!("{}%", util.gpu)));
+        }
+        if let Ok(mem) = device.memory_info() {
+            map.insert(MetricId::GpuVram, format_vram(mem.used as f64, mem.total as f64));
+        }
+        if let Ok(milliwatts) = device.power_usage() {
+            map.insert(MetricId::GpuPower, MetricValue::String(format!("{:.0} W", milliwatts as f64 / 1000.0)));
+        }
+        if let Ok(mhz) = device.clock_info(Clock::Graphics) {
+            map.insert(MetricId::GpuClock, MetricValue::String(format!("{} MHz", mhz)));
         }
         map
     }
 }
 
+/// Picks the NVIDIA collector: NVML when it initializes, otherwise `nvidia-smi` parsing.
+fn nvidia_collector() -> Box<dyn MetricCollector> {
+    #[cfg(feature = "nvml")]
+    match NvmlCollector::new() {
+        Ok(collector) => return Box::new(collector),
+        Err(e) => log::info!("NVML init failed ({}), falling back to nvidia-smi", e),
+    }
+    Box::new(NvidiaSmiCollector::new())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::tempdir;
+    #[cfg(feature = "git")]
     use git2::Repository;
+    #[cfg(feature = "weather")]
     use mockito::Server;
 
     #[test]
@@ -1168,9 +5200,18 @@ mod tests {
     }
 
     #[test]
+    fn test_sensors_fallback_temperature_units() {
+        let line = "Tctl:         +45.1°C  (high = +95.0°C)";
+        assert_eq!(HwmonCollector::extract_sensor_temperature(line, UnitSystem::Metric).as_deref(), Some("45°C"));
+        assert_eq!(HwmonCollector::extract_sensor_temperature(line, UnitSystem::Imperial).as_deref(), Some("113°F"));
+        assert_eq!(HwmonCollector::extract_sensor_temperature("Tctl:  N/A", UnitSystem::Imperial).as_deref(), Some("N/A"));
+    }
+
+    #[test]
+    #[cfg(feature = "weather")]
     fn test_open_meteo_collector() {
         let mut server = Server::new();
-        let _m = server.mock("GET", "/v1/forecast?latitude=51.5074&longitude=-0.1278&current=temperature_2m,weather_code")
+        let _m = server.mock("GET", "/v1/forecast?latitude=51.5074&longitude=-0.1278&current=temperature_2m,weather_code,relative_humidity_2m,wind_speed_10m,wind_direction_10m,precipitation")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(r#"{"current": {"temperature_2m": 15.5, "weather_code": 3}}"#)
@@ -1191,6 +5232,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "git")]
     fn test_git_delta_accuracy_24h_rolling() {
         let dir = tempdir().unwrap();
         let repo = Repository::init(dir.path()).unwrap();
@@ -1215,6 +5257,69 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "git")]
+    fn test_git_delta_walks_only_new_commits() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let commit_file = |name: &str, content: &str| {
+            fs::write(dir.path().join(name), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new(name)).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = repo.head().ok().and_then(|h| h.peel_to_commit().ok()).into_iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, name, &tree, &parents.iter().collect::<Vec<_>>()).unwrap()
+        };
+        let since = Local::now().timestamp() - 3600;
+        let budget = Duration::from_secs(5);
+
+        let root = commit_file("a.txt", "one\n");
+        let second = commit_file("b.txt", "one\ntwo\nthree\n");
+        let history = GitCollector::walk_new_commits(&repo, since, budget, RepoHistory::default());
+        assert_eq!(history.delta_since(since), (3, 0));
+
+        // A commit already walked isn't diffed again
+        let mut history = history;
+        history.commits.get_mut(&second).unwrap().1 = 100;
+        commit_file("c.txt", "one\ntwo\n");
+        let history = GitCollector::walk_new_commits(&repo, since, budget, history);
+        assert_eq!(history.delta_since(since), (102, 0));
+
+        // Rewritten history is walked from scratch
+        repo.reset(&repo.find_object(root, None).unwrap(), git2::ResetType::Hard, None).unwrap();
+        commit_file("d.txt", "one\n");
+        let history = GitCollector::walk_new_commits(&repo, since, budget, history);
+        assert_eq!(history.delta_since(since), (1, 0));
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn test_git_status_branch_ahead_and_dirty() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let first = repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[]).unwrap();
+
+        // Upstream at the first commit, then one local commit on top
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        repo.reference(&format!("refs/remotes/origin/{}", branch), first, true, "test").unwrap();
+        repo.find_branch(&branch, git2::BranchType::Local).unwrap().set_upstream(Some(&format!("origin/{}", branch))).unwrap();
+        let parent = repo.find_commit(first).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Local work", &tree, &[&parent]).unwrap();
+
+        fs::write(dir.path().join("notes.txt"), "wip").unwrap();
+        let status = GitStatusCollector::status(&repo).unwrap();
+        assert_eq!(status, RepoStatus { branch: branch.clone(), ahead_behind: Some((1, 0)), dirty: 1 });
+        assert_eq!(status.format(), format!("{} ↑1 1 dirty", branch));
+
+        let clean = RepoStatus { branch: "main".to_string(), ahead_behind: None, dirty: 0 };
+        assert_eq!(clean.format(), "main clean");
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
     fn test_git_rotation_batching_cap() {
         let repos = (0..10).map(|i| format!("/tmp/repo{}", i)).collect::<Vec<_>>();
         let mut collector = GitCollector::new(repos);
@@ -1224,6 +5329,126 @@ mod tests {
         assert_eq!(collector.rotation_index, 0);
     }
 
+    #[test]
+    #[cfg(feature = "git")]
+    fn test_git_delta_worker_publishes_in_background() {
+        let mut worker = GitDeltaWorker::new(GitCollector::new(Vec::new()));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut values = worker.collect();
+        while values.is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+            values = worker.collect();
+        }
+        assert_eq!(values.get(&MetricId::CodeDelta), Some(&MetricValue::String("+0 / -0".to_string())));
+    }
+
+    #[test]
+    fn test_disk_io_collector_rates() {
+        let dir = tempdir().unwrap();
+        let stats_path = dir.path().join("diskstats");
+        let sys_block = dir.path().join("block");
+        fs::create_dir_all(sys_block.join("nvme0n1")).unwrap();
+
+        fs::write(&stats_path, "259 0 nvme0n1 100 0 2048 10 50 0 4096 20 0 30 30\n259 1 nvme0n1p1 10 0 8 1 5 0 8 1 0 2 2\n").unwrap();
+        let mut collector = DiskIoCollector::new_with_paths(stats_path.clone(), sys_block);
+        assert!(collector.collect().is_empty(), "First sample has no delta");
+
+        fs::write(&stats_path, "259 0 nvme0n1 200 0 4096 10 60 0 6144 20 0 30 30\n259 1 nvme0n1p1 10 0 8 1 5 0 8 1 0 2 2\n").unwrap();
+        let values = collector.collect();
+        assert!(values.contains_key(&MetricId::DiskIo));
+        assert!(values.contains_key(&MetricId::Custom("disk_io_nvme0n1".to_string())));
+        assert!(!values.contains_key(&MetricId::Custom("disk_io_nvme0n1p1".to_string())), "Partitions are skipped");
+    }
+
+    #[test]
+    fn test_top_process_rows_sorted() {
+        let sys = Arc::new(Mutex::new(SysinfoManager::new()));
+        let procs = vec![
+            ("idle".to_string(), 0.1, 10 * 1_048_576),
+            ("rustc".to_string(), 80.0, 900 * 1_048_576),
+            ("firefox".to_string(), 12.5, 2048 * 1_048_576),
+        ];
+
+        let by_cpu = TopProcessCollector::new(sys.clone(), 2, "cpu").build_rows(procs.clone());
+        assert_eq!(by_cpu, vec![
+            vec!["rustc".to_string(), "80.0%".to_string(), "900 MB".to_string()],
+            vec!["firefox".to_string(), "12.5%".to_string(), "2048 MB".to_string()],
+        ]);
+
+        let by_mem = TopProcessCollector::new(sys, 1, "memory").build_rows(procs);
+        assert_eq!(by_mem[0][0], "firefox");
+    }
+
+    #[test]
+    fn test_amd_gpu_collector_sysfs() {
+        let dir = tempdir().unwrap();
+        let igpu = dir.path().join("card0/device");
+        let dgpu = dir.path().join("card1/device");
+        let hwmon = dgpu.join("hwmon/hwmon3");
+        fs::create_dir_all(&igpu).unwrap();
+        fs::create_dir_all(&hwmon).unwrap();
+        fs::create_dir_all(dir.path().join("card1-DP-1")).unwrap();
+
+        for (dev, vram_total) in [(&igpu, "536870912"), (&dgpu, "8589934592")] {
+            fs::write(dev.join("vendor"), "0x1002\n").unwrap();
+            fs::write(dev.join("gpu_busy_percent"), "37\n").unwrap();
+            fs::write(dev.join("mem_info_vram_total"), vram_total).unwrap();
+        }
+        fs::write(dgpu.join("mem_info_vram_used"), "2147483648").unwrap();
+        fs::write(hwmon.join("power1_average"), "45000000").unwrap();
+        fs::write(hwmon.join("temp1_input"), "61000").unwrap();
+
+        let mut collector = AmdGpuCollector::detect_in(dir.path()).expect("AMD GPU should be detected");
+        let values = collector.collect();
+        assert_eq!(values.get(&MetricId::GpuUtil), Some(&MetricValue::String("37%".to_string())));
+        assert_eq!(values.get(&MetricId::GpuVram), Some(&MetricValue::String("2.0/8.0 GB".to_string())));
+        assert_eq!(values.get(&MetricId::GpuPower), Some(&MetricValue::String("45 W".to_string())));
+        assert_eq!(values.get(&MetricId::GpuTemp), Some(&MetricValue::String("61°C".to_string())));
+    }
+
+    #[test]
+    fn test_history_ring_buffer() {
+        let mut shared = SharedMetrics::new();
+        for i in 0..(SharedMetrics::HISTORY_LEN + 5) {
+            shared.data.values.insert(MetricId::CpuUsage, MetricValue::String(format!("{}%", i)));
+            shared.record_history();
+        }
+        let samples = &shared.history[&MetricId::CpuUsage];
+        assert_eq!(samples.len(), SharedMetrics::HISTORY_LEN);
+        assert_eq!(samples.front(), Some(&5.0));
+
+        shared.data.values.clear();
+        shared.record_history();
+        assert!(shared.history.is_empty(), "History of vanished metrics is dropped");
+    }
+
+    #[test]
+    fn test_self_monitor_reads_rss() {
+        let dir = tempdir().unwrap();
+        let status_path = dir.path().join("status");
+        fs::write(&status_path, "Name:\tmatrix-overlay\nVmRSS:\t   20480 kB\n").unwrap();
+        let mut collector = SelfMonitorCollector { status_path };
+        let values = collector.collect();
+        assert_eq!(values.get(&MetricId::OverlayMemory), Some(&MetricValue::String("20.0 MB".to_string())));
+        assert!(values.contains_key(&MetricId::OverlayRender));
+    }
+
+    #[test]
+    fn test_resource_guard_averages_load() {
+        let guard = ResourceGuard::new(70.0);
+        assert!(!guard.should_throttle());
+        guard.record(20.0);
+        assert_eq!(guard.cpu_usage(), 20.0, "The first sample seeds the average");
+
+        // A single spike doesn't throttle; sustained load does
+        guard.record(100.0);
+        assert!(!guard.should_throttle());
+        for _ in 0..10 {
+            guard.record(100.0);
+        }
+        assert!(guard.should_throttle());
+    }
+
     #[test]
     fn test_path_traversal_blocked() {
         assert!(!crate::path_utils::is_safe_path(Path::new("/etc/passwd")));
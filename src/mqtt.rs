@@ -0,0 +1,297 @@
+//! Minimal MQTT 3.1.1 client.
+//! Just enough of the protocol to read values from a home-automation broker
+//! (Home Assistant, Mosquitto): CONNECT with optional credentials, SUBSCRIBE at
+//! QoS 0, incoming PUBLISH and keep-alive pings, plus outgoing QoS 0 PUBLISH for
+//! `serve`. Plain TCP only; no TLS.
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+
+/// Keep-alive announced to the broker; we ping at half this.
+const KEEP_ALIVE: Duration = Duration::from_secs(60);
+
+/// How long a blocking read waits before the caller gets a chance to ping or stop.
+const POLL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Largest packet accepted; the length field alone could ask for 256 MiB.
+const MAX_PACKET: usize = 256 * 1024;
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const PUBACK: u8 = 0x40;
+const SUBSCRIBE: u8 = 0x82;
+const SUBACK: u8 = 0x90;
+const PINGREQ: u8 = 0xC0;
+
+/// A message received on a subscribed topic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub topic: String,
+    pub payload: Vec<u8>,
+}
+
+/// Connection options.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectOptions {
+    pub client_id: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// A connected, subscribed MQTT session.
+pub struct MqttClient {
+    stream: TcpStream,
+    last_sent: Instant,
+}
+
+impl MqttClient {
+    /// Connects to `host:port` and subscribes to `topics` (filters may use `+` and `#`).
+    pub fn connect(host: &str, port: u16, options: &ConnectOptions, topics: &[String]) -> Result<Self> {
+        let stream = TcpStream::connect((host, port)).with_context(|| format!("Failed to connect to MQTT broker {}:{}", host, port))?;
+        stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+        let mut client = Self { stream, last_sent: Instant::now() };
+
+        client.send(&encode_connect(options))?;
+        let (header, body) = client.read_packet()?;
+        if header & 0xF0 != CONNACK || body.len() < 2 {
+            bail!("Unexpected reply to CONNECT (packet type 0x{:02x})", header);
+        }
+        if body[1] != 0 {
+            bail!("MQTT broker refused the connection: {}", connack_reason(body[1]));
+        }
+
+        if !topics.is_empty() {
+            client.send(&encode_subscribe(1, topics))?;
+            let (header, _) = client.read_packet()?;
+            if header & 0xF0 != SUBACK {
+                bail!("Unexpected reply to SUBSCRIBE (packet type 0x{:02x})", header);
+            }
+        }
+        client.stream.set_read_timeout(Some(POLL_TIMEOUT))?;
+        Ok(client)
+    }
+
+    /// Waits up to about a second for the next message, pinging the broker as needed.
+    /// `Ok(None)` means nothing arrived; errors mean the connection is gone.
+    pub fn poll(&mut self) -> Result<Option<Message>> {
+        if self.last_sent.elapsed() >= KEEP_ALIVE / 2 {
+            self.send(&[PINGREQ, 0])?;
+        }
+
+        let mut first = [0u8; 1];
+        match self.stream.read(&mut first) {
+            Ok(0) => bail!("MQTT broker closed the connection"),
+            Ok(_) => {}
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let body = self.read_body()?;
+
+        if first[0] & 0xF0 != PUBLISH {
+            // PINGRESP, or acknowledgements we don't track
+            return Ok(None);
+        }
+        let (message, packet_id) = parse_publish(first[0], &body)?;
+        if let Some(id) = packet_id {
+            let [hi, lo] = id.to_be_bytes();
+            self.send(&[PUBACK, 2, hi, lo])?;
+        }
+        Ok(Some(message))
+    }
+
+    /// Keeps a publish-only connection open: once nothing was sent for half the keep-alive,
+    /// pings the broker and reads its reply so PINGRESPs don't pile up unread.
+    pub fn keep_alive(&mut self) -> Result<()> {
+        if self.last_sent.elapsed() >= KEEP_ALIVE / 2 {
+            self.poll()?;
+        }
+        Ok(())
+    }
+
+    /// Publishes `payload` to `topic` at QoS 0 (fire and forget, not retained).
+    pub fn publish(&mut self, topic: &str, payload: &[u8]) -> Result<()> {
+        self.send(&encode_publish(topic, payload))
+    }
+
+    fn send(&mut self, packet: &[u8]) -> Result<()> {
+        self.stream.write_all(packet).context("Failed to write to MQTT broker")?;
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+
+    fn read_packet(&mut self) -> Result<(u8, Vec<u8>)> {
+        let mut first = [0u8; 1];
+        self.stream.read_exact(&mut first).context("Failed to read from MQTT broker")?;
+        Ok((first[0], self.read_body()?))
+    }
+
+    /// Reads the remaining-length field and the packet body that follows it.
+    fn read_body(&mut self) -> Result<Vec<u8>> {
+        let mut len = 0usize;
+        for shift in (0..4).map(|i| i * 7) {
+            let mut byte = [0u8; 1];
+            self.stream.read_exact(&mut byte)?;
+            len |= ((byte[0] & 0x7F) as usize) << shift;
+            if byte[0] & 0x80 == 0 {
+                if len > MAX_PACKET {
+                    bail!("MQTT packet of {} bytes exceeds the {} byte limit", len, MAX_PACKET);
+                }
+                let mut body = vec![0u8; len];
+                self.stream.read_exact(&mut body)?;
+                return Ok(body);
+            }
+        }
+        bail!("Malformed MQTT packet length")
+    }
+}
+
+fn connack_reason(code: u8) -> &'static str {
+    match code {
+        1 => "unacceptable protocol version",
+        2 => "client ID rejected",
+        3 => "server unavailable",
+        4 => "bad user name or password",
+        5 => "not authorized",
+        _ => "unknown reason",
+    }
+}
+
+fn push_remaining_length(out: &mut Vec<u8>, mut len: usize) {
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+fn push_str(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn packet(header: u8, body: Vec<u8>) -> Vec<u8> {
+    let mut out = vec![header];
+    push_remaining_length(&mut out, body.len());
+    out.extend(body);
+    out
+}
+
+/// CONNECT packet (clean session, MQTT 3.1.1).
+pub fn encode_connect(options: &ConnectOptions) -> Vec<u8> {
+    let mut flags = 0x02;
+    if options.username.is_some() {
+        flags |= 0x80;
+    }
+    if options.password.is_some() {
+        flags |= 0x40;
+    }
+    let mut body = Vec::new();
+    push_str(&mut body, "MQTT");
+    body.push(4);
+    body.push(flags);
+    body.extend_from_slice(&(KEEP_ALIVE.as_secs() as u16).to_be_bytes());
+    push_str(&mut body, &options.client_id);
+    if let Some(user) = &options.username {
+        push_str(&mut body, user);
+    }
+    if let Some(password) = &options.password {
+        push_str(&mut body, password);
+    }
+    packet(CONNECT, body)
+}
+
+/// SUBSCRIBE packet requesting QoS 0 for every filter.
+pub fn encode_subscribe(packet_id: u16, topics: &[String]) -> Vec<u8> {
+    let mut body = packet_id.to_be_bytes().to_vec();
+    for topic in topics {
+        push_str(&mut body, topic);
+        body.push(0);
+    }
+    packet(SUBSCRIBE, body)
+}
+
+/// PUBLISH packet at QoS 0.
+pub fn encode_publish(topic: &str, payload: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    push_str(&mut body, topic);
+    body.extend_from_slice(payload);
+    packet(PUBLISH, body)
+}
+
+/// Splits a PUBLISH body into the message and, for QoS 1/2, its packet ID.
+pub fn parse_publish(header: u8, body: &[u8]) -> Result<(Message, Option<u16>)> {
+    let topic_len = u16::from_be_bytes([*body.first().context("Empty PUBLISH")?, *body.get(1).context("Short PUBLISH")?]) as usize;
+    let topic = body.get(2..2 + topic_len).context("Truncated PUBLISH topic")?;
+    let mut rest = 2 + topic_len;
+    let packet_id = if (header >> 1) & 0x03 > 0 {
+        let id = body.get(rest..rest + 2).context("Missing PUBLISH packet ID")?;
+        rest += 2;
+        Some(u16::from_be_bytes([id[0], id[1]]))
+    } else {
+        None
+    };
+    let message = Message { topic: String::from_utf8_lossy(topic).to_string(), payload: body[rest..].to_vec() };
+    Ok((message, packet_id))
+}
+
+/// Whether `topic` matches the subscription `filter` (`+` = one level, `#` = the rest).
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    let mut levels = topic.split('/');
+    for part in filter.split('/') {
+        match (part, levels.next()) {
+            ("#", _) => return true,
+            ("+", Some(_)) => {}
+            (p, Some(level)) if p == level => {}
+            _ => return false,
+        }
+    }
+    levels.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_filters() {
+        assert!(topic_matches("home/+/temperature", "home/living/temperature"));
+        assert!(topic_matches("home/#", "home/living/temperature"));
+        assert!(!topic_matches("home/+", "home/living/temperature"));
+        assert!(!topic_matches("home/living/humidity", "home/living/temperature"));
+    }
+
+    #[test]
+    fn test_packet_encoding() {
+        let connect = encode_connect(&ConnectOptions { client_id: "mo".to_string(), username: Some("u".to_string()), password: None });
+        assert_eq!(connect[0], CONNECT);
+        assert_eq!(connect[1] as usize, connect.len() - 2);
+        assert_eq!(connect[9], 0x82, "clean session + user name flags");
+
+        // Remaining lengths above 127 take two bytes
+        let long_topic = "t".repeat(200);
+        let subscribe = encode_subscribe(1, &[long_topic]);
+        assert_eq!(&subscribe[..3], &[SUBSCRIBE, 0xCD, 0x01]);
+
+        let body = [&[0, 3][..], b"a/b", &[0, 7][..], b"21.5"].concat();
+        let (message, id) = parse_publish(0x32, &body).unwrap();
+        assert_eq!(message, Message { topic: "a/b".to_string(), payload: b"21.5".to_vec() });
+        assert_eq!(id, Some(7));
+
+        // Our own QoS 0 PUBLISH parses back, without a packet ID
+        let publish = encode_publish("servers/db1/cpu_usage", b"12.5%");
+        assert_eq!(publish[1] as usize, publish.len() - 2);
+        let (message, id) = parse_publish(publish[0], &publish[2..]).unwrap();
+        assert_eq!(message, Message { topic: "servers/db1/cpu_usage".to_string(), payload: b"12.5%".to_vec() });
+        assert_eq!(id, None);
+    }
+}
@@ -0,0 +1,220 @@
+//! Presentation targets.
+//! The renderer draws every frame into a Cairo image surface; a `Present`
+//! target then puts it somewhere visible: an X11 window (`window::X11Present`),
+//! a numbered PNG sequence, a Linux framebuffer device, or a DRM/KMS display
+//! (`kms::KmsOutput`) for kiosks and SBCs that don't run an X session.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use cairo::ImageSurface;
+
+use crate::config::Output;
+
+/// A destination for finished frames.
+pub trait Present {
+    /// Pushes a fully drawn frame to the output.
+    fn present(&mut self, frame: &mut ImageSurface) -> Result<()>;
+
+    /// Frees any resources held by the target (server-side objects, devices).
+    fn release(&mut self) {}
+
+    /// Short name for logs.
+    fn name(&self) -> &'static str;
+}
+
+/// Writes frames as `frame_000001.png`, `frame_000002.png`, ... keeping the newest `max_files`.
+pub struct PngSequence {
+    dir: PathBuf,
+    index: u64,
+    max_files: usize,
+    written: VecDeque<PathBuf>,
+}
+
+impl PngSequence {
+    pub fn new(dir: &Path, max_files: usize) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create PNG output directory {:?}", dir))?;
+        Ok(Self { dir: dir.to_path_buf(), index: 0, max_files: max_files.max(1), written: VecDeque::new() })
+    }
+}
+
+impl Present for PngSequence {
+    fn present(&mut self, frame: &mut ImageSurface) -> Result<()> {
+        self.index += 1;
+        let path = self.dir.join(format!("frame_{:06}.png", self.index));
+        let mut file = File::create(&path).with_context(|| format!("Failed to create {:?}", path))?;
+        frame.flush();
+        frame.write_to_png(&mut file).map_err(|e| anyhow::anyhow!("Failed to write {:?}: {}", path, e))?;
+
+        self.written.push_back(path);
+        while self.written.len() > self.max_files {
+            if let Some(old) = self.written.pop_front() {
+                let _ = fs::remove_file(old);
+            }
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "png"
+    }
+}
+
+/// Geometry of a Linux fbdev device, as reported in sysfs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramebufferInfo {
+    pub width: u16,
+    pub height: u16,
+    pub bits_per_pixel: u32,
+    /// Bytes per scanline.
+    pub stride: usize,
+}
+
+impl FramebufferInfo {
+    /// Reads `virtual_size`, `bits_per_pixel` and `stride` from a sysfs dir like /sys/class/graphics/fb0.
+    pub fn from_sysfs(dir: &Path) -> Result<Self> {
+        let read = |name: &str| -> Result<String> {
+            fs::read_to_string(dir.join(name))
+                .map(|s| s.trim().to_string())
+                .with_context(|| format!("Failed to read {:?}", dir.join(name)))
+        };
+        let size = read("virtual_size")?;
+        let (w, h) = size.split_once(',').with_context(|| format!("Malformed virtual_size '{}'", size))?;
+        let width: u16 = w.trim().parse().context("Invalid framebuffer width")?;
+        let height: u16 = h.trim().parse().context("Invalid framebuffer height")?;
+        let bits_per_pixel: u32 = read("bits_per_pixel")?.parse().context("Invalid bits_per_pixel")?;
+        if bits_per_pixel != 32 && bits_per_pixel != 16 {
+            bail!("Unsupported framebuffer depth: {} bpp (need 32 or 16)", bits_per_pixel);
+        }
+        // Older drivers don't expose stride; assume tightly packed rows.
+        let stride = match read("stride") {
+            Ok(s) => s.parse().context("Invalid framebuffer stride")?,
+            Err(_) => width as usize * (bits_per_pixel as usize / 8),
+        };
+        Ok(Self { width, height, bits_per_pixel, stride })
+    }
+}
+
+/// Copies frames to a Linux framebuffer device (e.g. /dev/fb0).
+pub struct Framebuffer {
+    device: File,
+    info: FramebufferInfo,
+    row: Vec<u8>,
+}
+
+impl Framebuffer {
+    /// Opens `device` (e.g. "/dev/fb0") and reads its geometry from /sys/class/graphics.
+    pub fn open(device: &Path) -> Result<Self> {
+        let name = device.file_name().and_then(|n| n.to_str()).context("Invalid framebuffer device path")?;
+        let info = FramebufferInfo::from_sysfs(&Path::new("/sys/class/graphics").join(name))?;
+        Self::with_info(device, info)
+    }
+
+    pub fn with_info(device: &Path, info: FramebufferInfo) -> Result<Self> {
+        let device_file = OpenOptions::new()
+            .write(true)
+            .open(device)
+            .with_context(|| format!("Failed to open framebuffer {:?} (is the user in the 'video' group?)", device))?;
+        log::info!("Framebuffer {:?}: {}x{} @ {} bpp", device, info.width, info.height, info.bits_per_pixel);
+        Ok(Self { device: device_file, info, row: Vec::new() })
+    }
+
+    pub fn info(&self) -> FramebufferInfo {
+        self.info
+    }
+}
+
+/// Converts one row of Cairo ARGB32 pixels (native-endian u32) to the framebuffer format.
+pub fn convert_row(src: &[u8], bits_per_pixel: u32, out: &mut Vec<u8>) {
+    out.clear();
+    if bits_per_pixel == 32 {
+        // Cairo's native-endian ARGB32 matches the common XRGB8888 fbdev layout.
+        out.extend_from_slice(src);
+        return;
+    }
+    for px in src.chunks_exact(4) {
+        let argb = u32::from_ne_bytes([px[0], px[1], px[2], px[3]]);
+        let (r, g, b) = ((argb >> 16) & 0xFF, (argb >> 8) & 0xFF, argb & 0xFF);
+        let rgb565 = (((r >> 3) << 11) | ((g >> 2) << 5) | (b >> 3)) as u16;
+        out.extend_from_slice(&rgb565.to_ne_bytes());
+    }
+}
+
+impl Present for Framebuffer {
+    fn present(&mut self, frame: &mut ImageSurface) -> Result<()> {
+        frame.flush();
+        let src_stride = frame.stride() as usize;
+        let width = (frame.width().max(0) as usize).min(self.info.width as usize);
+        let height = (frame.height().max(0) as usize).min(self.info.height as usize);
+        let data = frame.data().map_err(|e| anyhow::anyhow!("Failed to get surface data: {}", e))?;
+
+        for y in 0..height {
+            let src = &data[y * src_stride..y * src_stride + width * 4];
+            convert_row(src, self.info.bits_per_pixel, &mut self.row);
+            self.device
+                .write_all_at(&self.row, (y * self.info.stride) as u64)
+                .context("Failed to write to framebuffer")?;
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "framebuffer"
+    }
+}
+
+/// Frame size and target for a non-X11 `output` config.
+pub fn open_target(output: &Output) -> Result<(u16, u16, Box<dyn Present>)> {
+    match output.target.as_str() {
+        "png" => {
+            let dir = output.path.as_deref().context("output.path is required for the png target")?;
+            let target = PngSequence::new(Path::new(dir), output.max_files)?;
+            Ok((output.width, output.height, Box::new(target)))
+        }
+        "framebuffer" => {
+            let device = output.path.as_deref().unwrap_or("/dev/fb0");
+            let fb = Framebuffer::open(Path::new(device))?;
+            let info = fb.info();
+            Ok((info.width, info.height, Box::new(fb)))
+        }
+        #[cfg(feature = "kms")]
+        "kms" => {
+            let device = output.path.as_deref().unwrap_or("/dev/dri/card0");
+            let kms = crate::kms::KmsOutput::open(Path::new(device), output.connector.as_deref())?;
+            let (width, height) = kms.size();
+            Ok((width, height, Box::new(kms)))
+        }
+        #[cfg(not(feature = "kms"))]
+        "kms" => bail!("The kms output requires building with --features kms"),
+        other => bail!("Output target '{}' needs an X11 window", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framebuffer_info_from_sysfs() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("virtual_size"), "800,480\n").unwrap();
+        fs::write(dir.path().join("bits_per_pixel"), "16\n").unwrap();
+        let info = FramebufferInfo::from_sysfs(dir.path()).unwrap();
+        assert_eq!(info, FramebufferInfo { width: 800, height: 480, bits_per_pixel: 16, stride: 1600 });
+
+        fs::write(dir.path().join("bits_per_pixel"), "24\n").unwrap();
+        assert!(FramebufferInfo::from_sysfs(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_convert_row_rgb565() {
+        let white = 0xFFFF_FFFFu32.to_ne_bytes();
+        let green = 0xFF00_FF00u32.to_ne_bytes();
+        let mut out = Vec::new();
+        convert_row(&[white, green].concat(), 16, &mut out);
+        assert_eq!(out, [0xFFFFu16.to_ne_bytes(), 0x07E0u16.to_ne_bytes()].concat());
+    }
+}
@@ -1,17 +1,23 @@
 // src/render.rs
-use std::collections::HashMap;
-use std::time::Duration;
-use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+use std::cell::{Cell, RefCell};
+use std::sync::Mutex;
 use anyhow::Result;
-use cairo::{Context as CairoContext, Format, ImageSurface, Operator};
+use cairo::{Context as CairoContext, Operator};
 use pangocairo::pango::{self, FontDescription, Layout as PangoLayout, Weight};
-use xcb::x;
-use rand::Rng;
-use rand::thread_rng;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 
 use crate::config::Config;
-use crate::layout::Layout as ConfigLayout;
-use crate::metrics::{MetricData, MetricId, MetricValue};
+use crate::layout::{self, Bounds, Layout as ConfigLayout, LayoutItem};
+use crate::stats::UsageStats;
+use crate::alerts::AlertLevel;
+use crate::audio::{self, AudioSpectrum, BANDS};
+use crate::effects::{self, BackgroundEffect, EffectSettings};
+use crate::metrics::{MetricData, MetricId, MetricValue, SharedMetrics};
+use crate::present::Present;
+use crate::window::OffscreenBuffer;
 
 /// Represents a single falling stream of glyphs in the Matrix rain.
 pub struct RainStream {
@@ -39,8 +45,48 @@ pub struct RainManager {
     pub last_width: i32,
     /// Last known height of the rendering surface.
     pub last_height: i32,
+    /// Areas where no glyph is drawn (from the screen's `rain_exclusions`).
+    pub exclusions: Vec<Bounds>,
+    /// Streams are re-randomized on the next update (exclusions changed).
+    needs_reset: bool,
+    /// Fast non-cryptographic RNG for spawning and glyph mutation.
+    rng: SmallRng,
+    /// Seed of `rng` if the rain is deterministic.
+    seed: Option<u64>,
+    /// Smoothed system load (0.0-1.0) driving the "reactive" rain mode.
+    load: f64,
+    /// Audio band levels for this frame when `cosmetics.audio_reactive` is on.
+    audio: Option<[f64; BANDS]>,
+    /// Metric boxes the rain collides with (`cosmetics.rain_collision`).
+    obstacles: Vec<Bounds>,
+    /// Splashes still in flight.
+    splashes: Vec<Splash>,
 }
 
+/// Droplets thrown up where a stream hit the top of a metric box.
+struct Splash {
+    x: f64,
+    y: f64,
+    /// Updates since the impact.
+    age: u32,
+}
+
+/// Longest stream in glyphs; glyph buffers are allocated at this capacity once and reused.
+const MAX_STREAM_GLYPHS: usize = 15;
+
+/// Weight of the newest load sample per update in reactive mode (~1s to follow a step at 30 FPS).
+const LOAD_SMOOTHING: f64 = 0.1;
+/// Share of streams still falling in reactive mode when the system is idle.
+const IDLE_STREAM_SHARE: f64 = 0.25;
+/// Extra speed a stream gets when its audio band is at full level.
+const AUDIO_SPEED_BOOST: f64 = 2.0;
+/// Distance in pixels above a metric box over which "fade" collisions dim the glyphs.
+const RAIN_FADE_DISTANCE: f64 = 40.0;
+/// Updates a splash stays visible (~quarter second at 30 FPS).
+const SPLASH_FRAMES: u32 = 8;
+/// Cap on splashes in flight, so dense rain over wide boxes stays cheap.
+const MAX_SPLASHES: usize = 64;
+
 impl RainManager {
     pub fn new(realism_scale: u32) -> Self {
         Self { 
@@ -49,64 +95,199 @@ impl RainManager {
             last_realism_scale: realism_scale,
             last_width: 1920,
             last_height: 1080,
+            exclusions: Vec::new(),
+            needs_reset: false,
+            rng: SmallRng::from_entropy(),
+            seed: None,
+            load: 0.0,
+            audio: None,
+            obstacles: Vec::new(),
+            splashes: Vec::new(),
+        }
+    }
+
+    /// A manager whose rain is the same frame for frame on every run with the same seed.
+    pub fn seeded(realism_scale: u32, seed: u64) -> Self {
+        Self { rng: SmallRng::seed_from_u64(seed), seed: Some(seed), ..Self::new(realism_scale) }
+    }
+
+    /// Switches to `seed` (or back to entropy) and respawns the streams; a no-op if unchanged.
+    pub fn reseed(&mut self, seed: Option<u64>) {
+        if seed == self.seed {
+            return;
+        }
+        self.rng = match seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            None => SmallRng::from_entropy(),
+        };
+        self.seed = seed;
+        self.needs_reset = true;
+    }
+
+    /// Sets the rain-free areas; streams are respawned when they change.
+    pub fn set_exclusions(&mut self, exclusions: Vec<Bounds>) {
+        if exclusions != self.exclusions {
+            self.exclusions = exclusions;
+            self.needs_reset = true;
         }
     }
 
+    fn is_excluded(&self, x: f64, y: f64) -> bool {
+        self.exclusions.iter().any(|zone| zone.contains(x, y))
+    }
+
+    /// Sets the metric boxes (in rain coordinates) that streams collide with. Unlike
+    /// exclusions these move with the layout every frame, so the streams are kept.
+    pub fn set_obstacles(&mut self, obstacles: Vec<Bounds>) {
+        self.obstacles = obstacles;
+    }
+
+    /// Visibility (0.0-1.0) of a glyph at (`x`, `y`) for the collision `mode`: hidden inside
+    /// a metric box, and with "fade" dimmed over the last `RAIN_FADE_DISTANCE` pixels above one.
+    fn collision_visibility(&self, x: f64, y: f64, mode: &str) -> f64 {
+        if mode == "none" {
+            return 1.0;
+        }
+        let mut visibility: f64 = 1.0;
+        for obstacle in &self.obstacles {
+            if x < obstacle.x as f64 || x >= (obstacle.x + obstacle.width) as f64 {
+                continue;
+            }
+            let above = obstacle.y as f64 - y;
+            if above <= 0.0 && y < obstacle.bottom() as f64 {
+                return 0.0;
+            }
+            if mode == "fade" && above > 0.0 && above < RAIN_FADE_DISTANCE {
+                visibility = visibility.min(above / RAIN_FADE_DISTANCE);
+            }
+        }
+        visibility
+    }
+
+    /// True if an exclusion covers column `x` over the full height, so a stream there would never show.
+    fn column_blocked(exclusions: &[Bounds], x: f64, height: i32) -> bool {
+        exclusions.iter().any(|zone| zone.y <= 0 && zone.bottom() >= height && zone.contains(x, zone.y as f64))
+    }
+
+    /// Re-randomizes the streams for a `width` x `height` area, reusing existing
+    /// streams and their glyph buffers; only growth past the current count allocates.
     fn reset_streams(&mut self, width: i32, height: i32) {
-        let mut rng = thread_rng();
         let count = (self.realism_scale as f64 * (width as f64 / 100.0)) as usize;
         let count = std::cmp::min(count, 500); // Increased cap for realism_scale up to 50
 
-        self.streams.clear();
+        let mut active = 0;
         for _ in 0..count {
-            self.streams.push(RainStream {
-                x: rng.gen_range(0.0..width as f64),
-                y: rng.gen_range(-(height as f64)..0.0),
-                speed: rng.gen_range(2.0..10.0),
-                glyphs: (0..rng.gen_range(5..15)).map(|_| random_matrix_char()).collect(),
-                depth_scale: rng.gen_range(0.5..1.2),
-            });
+            // Don't spend streams on columns that are excluded top to bottom
+            let (rng, exclusions) = (&mut self.rng, &self.exclusions);
+            let x = match (0..10).map(|_| rng.gen_range(0.0..width.max(1) as f64)).find(|&x| !Self::column_blocked(exclusions, x, height)) {
+                Some(x) => x,
+                None => continue,
+            };
+            if active == self.streams.len() {
+                self.streams.push(RainStream { x: 0.0, y: 0.0, speed: 0.0, glyphs: Vec::with_capacity(MAX_STREAM_GLYPHS), depth_scale: 1.0 });
+            }
+            let rng = &mut self.rng;
+            let stream = &mut self.streams[active];
+            stream.x = x;
+            stream.y = rng.gen_range(-(height as f64)..0.0);
+            stream.speed = rng.gen_range(2.0..10.0);
+            stream.depth_scale = rng.gen_range(0.5..1.2);
+            refill_glyphs(&mut stream.glyphs, rng);
+            active += 1;
         }
+        self.streams.truncate(active);
         self.last_width = width;
         self.last_height = height;
+        self.needs_reset = false;
     }
 
-    pub fn update(&mut self, dt: Duration, width: i32, height: i32, config: &Config) {
-        if self.streams.is_empty() || width != self.last_width || height != self.last_height || config.cosmetics.realism_scale != self.last_realism_scale {
-            self.realism_scale = config.cosmetics.realism_scale;
-            self.last_realism_scale = config.cosmetics.realism_scale;
+    /// Highest of CPU and GPU usage as a fraction, if either is being collected.
+    fn system_load(metrics: &MetricData) -> Option<f64> {
+        [MetricId::CpuUsage, MetricId::GpuUtil]
+            .iter()
+            .filter_map(|id| metrics.values.get(id).and_then(MetricValue::as_f64))
+            .map(|percent| (percent / 100.0).clamp(0.0, 1.0))
+            .reduce(f64::max)
+    }
+
+    /// Number of streams in motion and the speed and brightness multipliers for the
+    /// current load; all streams at full speed and brightness outside the reactive mode.
+    fn load_response(&self, settings: &EffectSettings) -> (usize, f64, f64) {
+        if settings.mode != "reactive" {
+            return (self.streams.len(), 1.0, 1.0);
+        }
+        let share = IDLE_STREAM_SHARE + (1.0 - IDLE_STREAM_SHARE) * self.load;
+        let active = (self.streams.len() as f64 * share).ceil() as usize;
+        (active, 0.4 + 1.6 * self.load, 0.5 + 0.5 * self.load)
+    }
+
+    /// Advances the rain by `dt`. In the "reactive" mode `metrics` supplies the CPU/GPU
+    /// load that the density, speed and brightness follow; with `audio_reactive` each
+    /// column also speeds up with the level of its audio band.
+    pub fn update(&mut self, dt: Duration, width: i32, height: i32, settings: &EffectSettings, metrics: &MetricData) {
+        if settings.mode == "reactive" {
+            if let Some(target) = Self::system_load(metrics) {
+                self.load += LOAD_SMOOTHING * (target - self.load);
+            }
+        }
+        self.audio = settings.config.cosmetics.audio_reactive.then(|| {
+            AudioSpectrum::shared().lock().map(|s| s.levels(Instant::now())).unwrap_or_default()
+        });
+        if self.needs_reset || self.streams.is_empty() || width != self.last_width || height != self.last_height || settings.realism_scale != self.last_realism_scale {
+            self.realism_scale = settings.realism_scale;
+            self.last_realism_scale = settings.realism_scale;
             self.reset_streams(width, height);
         }
 
-        if config.cosmetics.rain_speed == 0.0 {
+        for splash in &mut self.splashes {
+            splash.age += 1;
+        }
+        self.splashes.retain(|splash| splash.age < SPLASH_FRAMES);
+
+        let (active, speed, _) = self.load_response(settings);
+        let audio = self.audio;
+        let splash = settings.config.cosmetics.rain_collision == "splash";
+        let rng = &mut self.rng;
+        if settings.speed == 0.0 {
             // Static effect: No vertical movement, but letters slowly mutation and fade
             for stream in &mut self.streams {
                 // Occasional mutation even when static
-                if thread_rng().gen_bool(0.01) {
-                    let idx = thread_rng().gen_range(0..stream.glyphs.len());
-                    stream.glyphs[idx] = random_matrix_char();
+                if rng.gen_bool(0.01) {
+                    let idx = rng.gen_range(0..stream.glyphs.len());
+                    stream.glyphs[idx] = random_matrix_char(rng);
                 }
             }
             return;
         }
 
-        let dy = 60.0 * dt.as_secs_f64() * config.cosmetics.rain_speed;
-        for stream in &mut self.streams {
-            stream.y += stream.speed * dy;
+        let dy = 60.0 * dt.as_secs_f64() * settings.speed * speed;
+        for stream in self.streams.iter_mut().take(active) {
+            let boost = audio.map_or(1.0, |levels| 1.0 + AUDIO_SPEED_BOOST * levels[audio::band_for_column(stream.x, width)]);
+            let before = stream.y;
+            stream.y += stream.speed * dy * boost;
+            if splash && self.splashes.len() < MAX_SPLASHES {
+                // The lead glyph crossed the top edge of a box this update
+                let hit = self.obstacles.iter().find(|o| {
+                    stream.x >= o.x as f64 && stream.x < (o.x + o.width) as f64 && before < o.y as f64 && stream.y >= o.y as f64
+                });
+                if let Some(obstacle) = hit {
+                    self.splashes.push(Splash { x: stream.x, y: obstacle.y as f64, age: 0 });
+                }
+            }
             if stream.y > height as f64 + 200.0 {
                 stream.y = -200.0;
-                stream.glyphs = (0..thread_rng().gen_range(5..15)).map(|_| random_matrix_char()).collect();
+                refill_glyphs(&mut stream.glyphs, rng);
             }
             // Occasionally mutation
-            if thread_rng().gen_bool(0.05) {
-                let idx = thread_rng().gen_range(0..stream.glyphs.len());
-                stream.glyphs[idx] = random_matrix_char();
+            if rng.gen_bool(0.05) {
+                let idx = rng.gen_range(0..stream.glyphs.len());
+                stream.glyphs[idx] = random_matrix_char(rng);
             }
         }
     }
 
-    pub fn draw(&self, cr: &CairoContext, _width: f64, height: f64, frame_count: u64, config: &Config) -> Result<()> {
-        let glyph_size = config.general.font_size as f64 * 0.8;
+    pub fn draw(&self, cr: &CairoContext, width: f64, height: f64, frame_count: u64, settings: &EffectSettings, palette: &Palette) -> Result<()> {
+        let glyph_size = settings.config.general.font_size as f64 * 0.8;
         
         if self.streams.is_empty() {
             log::warn!("RainManager: No streams to draw! Realism scale might be 0.");
@@ -115,9 +296,16 @@ impl RainManager {
         // Create local layout for isolation
         let layout = pangocairo::functions::create_layout(cr);
         let mut desc = pango::FontDescription::from_string("Monospace");
+        let mut utf8 = [0u8; 4];
+        let (active, _, brightness) = self.load_response(settings);
+        let brightness = settings.brightness * brightness;
+        let collision = settings.config.cosmetics.rain_collision.as_str();
 
-        for stream in &self.streams {
+        for stream in self.streams.iter().take(active) {
             let alpha_base = stream.depth_scale.powf(2.0);
+            // Lead glyphs pulse with their audio band
+            let lead_brightness = brightness
+                * self.audio.map_or(1.0, |levels| 0.4 + 0.6 * levels[audio::band_for_column(stream.x, width as i32)]);
             
             // Configure font size for this stream
             desc.set_size((glyph_size * stream.depth_scale * pango::SCALE as f64) as i32);
@@ -126,12 +314,15 @@ impl RainManager {
             for (i, &glyph) in stream.glyphs.iter().enumerate() {
                 let y = stream.y - (i as f64 * glyph_size * 1.2);
                 if y < -20.0 || y > height + 20.0 { continue; }
+                if self.is_excluded(stream.x, y) { continue; }
+                let visibility = self.collision_visibility(stream.x, y, collision);
+                if visibility <= 0.0 { continue; }
                 
                 let alpha = if i == 0 { 1.0 } else { alpha_base * (1.0 - (i as f64 / stream.glyphs.len() as f64)) };
-                let alpha = alpha.clamp(0.0, 1.0);
+                let alpha = alpha.clamp(0.0, 1.0) * visibility;
 
                 // Static speed 0.0 specific fade-to-black simulation
-                let alpha = if config.cosmetics.rain_speed == 0.0 {
+                let alpha = if settings.speed == 0.0 {
                     // Pulse-fade over 1.5s (simulated by frame count)
                     let fc = frame_count as f64;
                     let pulse = ( (fc * 0.05).sin() * 0.5 ) + 0.5;
@@ -141,61 +332,256 @@ impl RainManager {
                 };
 
                 cr.save()?;
-                let (r, g, b) = match config.general.theme.as_str() {
-                    "calm" => (0.0, 0.8, 1.0),
-                    "alert" => (1.0, 0.2, 0.2),
-                    _ => (0.0, 1.0, 65.0/255.0), // Classic Matrix Green
-                };
-                cr.set_source_rgba(r, g, b, alpha * 0.9 * config.cosmetics.matrix_brightness); // Split brightness applied
+                let (r, g, b) = palette.rain;
+                cr.set_source_rgba(r, g, b, alpha * 0.9 * brightness); // Split brightness applied
                 if i == 0 {
-                    let (hr, hg, hb) = match config.general.theme.as_str() {
-                        "calm" => (0.8, 0.9, 1.0),
-                        "alert" => (1.0, 0.8, 0.8),
-                        _ => (0.8, 1.0, 0.9), // Bright Green lead
-                    };
-                    cr.set_source_rgba(hr, hg, hb, 1.0 * config.cosmetics.matrix_brightness); // Lead glyph brightness
+                    let (hr, hg, hb) = palette.lead;
+                    cr.set_source_rgba(hr, hg, hb, visibility * lead_brightness); // Lead glyph brightness
                 }
 
-                layout.set_text(&glyph.to_string());
+                layout.set_text(glyph.encode_utf8(&mut utf8));
                 cr.move_to(stream.x, y);
                 pangocairo::functions::show_layout(cr, &layout);
                 cr.restore()?;
             }
         }
+
+        // Splashes: two droplets to each side arcing out from the impact and fading
+        if collision == "splash" && !self.splashes.is_empty() {
+            let (r, g, b) = palette.lead;
+            cr.save()?;
+            for splash in &self.splashes {
+                let t = splash.age as f64 / SPLASH_FRAMES as f64;
+                cr.set_source_rgba(r, g, b, (1.0 - t) * brightness);
+                for side in [-1.0, 1.0] {
+                    for k in 1..=2 {
+                        let k = k as f64;
+                        let x = splash.x + side * k * 6.0 * t;
+                        let y = splash.y - k * 8.0 * 4.0 * t * (1.0 - t);
+                        cr.new_sub_path();
+                        cr.arc(x, y, 1.2, 0.0, std::f64::consts::TAU);
+                    }
+                }
+                cr.fill()?;
+            }
+            cr.restore()?;
+        }
         Ok(())
     }
 }
 
-fn random_matrix_char() -> char {
+impl BackgroundEffect for RainManager {
+    fn update(&mut self, dt: Duration, width: i32, height: i32, settings: &EffectSettings, metrics: &MetricData) {
+        RainManager::update(self, dt, width, height, settings, metrics);
+    }
+
+    fn draw(&self, cr: &CairoContext, width: f64, height: f64, frame_count: u64, settings: &EffectSettings, palette: &Palette) -> Result<()> {
+        RainManager::draw(self, cr, width, height, frame_count, settings, palette)
+    }
+}
+
+/// Replaces the glyphs of a stream in place with 5-14 new ones (within the buffer's capacity).
+fn refill_glyphs(glyphs: &mut Vec<char>, rng: &mut SmallRng) {
+    let len = rng.gen_range(5..MAX_STREAM_GLYPHS);
+    glyphs.clear();
+    glyphs.extend((0..len).map(|_| random_matrix_char(rng)));
+}
+
+fn random_matrix_char(rng: &mut SmallRng) -> char {
     // Use Katakana (0x30A0 - 0x30FF) for authentic Matrix look
-    let code = thread_rng().gen_range(0x30A1..=0x30F6);
+    let code = rng.gen_range(0x30A1..=0x30F6);
     std::char::from_u32(code).unwrap_or('?')
 }
 
-/// Handles drawing to an offscreen surface and presenting it to the X11 window.
+/// How long a changed metric value takes to decode into its real characters.
+const DECODE_DURATION: Duration = Duration::from_millis(500);
+
+/// How long the rain takes to brighten to full during a screensaver takeover.
+const TAKEOVER_RAMP: Duration = Duration::from_secs(5);
+/// Stream density during a takeover (at least; a denser `realism_scale` is kept).
+const TAKEOVER_REALISM: u32 = 20;
+
+/// Frame interval requested while a value decode or theme fade is running.
+pub const ANIMATION_FRAME: Duration = Duration::from_millis(50);
+
+/// Where a metric's graphic is drawn, in surface pixels. Tables grow downwards
+/// row by row and ignore `height`.
+#[derive(Debug, Clone, Copy)]
+struct Area {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Decode animation state of one metric.
+struct DecodeState {
+    previous: String,
+    current: String,
+    /// When `current` replaced `previous`; `None` until the value first changes.
+    changed: Option<Instant>,
+}
+
+/// One frame of the decode animation. Characters that differ from `previous` show random
+/// half-width katakana (which keep the monospace width) until `progress` (0.0-1.0) reaches
+/// their position, so the value resolves left to right. A value whose length changed
+/// decodes completely; spaces never scramble.
+fn decode_frame(previous: &str, value: &str, progress: f64, rng: &mut SmallRng) -> String {
+    let len = value.chars().count();
+    let same_length = previous.chars().count() == len;
+    let mut old = previous.chars();
+    value
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let unchanged = same_length && old.next() == Some(c);
+            let resolved = progress * len as f64 >= (i + 1) as f64;
+            if unchanged || resolved || c.is_whitespace() {
+                c
+            } else {
+                std::char::from_u32(rng.gen_range(0xFF66..=0xFF9D)).unwrap_or(c)
+            }
+        })
+        .collect()
+}
+
+/// Width in pixels of the sparkline graph drawn next to a value.
+const SPARKLINE_WIDTH: f64 = 90.0;
+
+/// Smallest fraction of the metric font size a value may shrink to before it is ellipsized.
+const MIN_SHRINK_RATIO: f64 = 0.75;
+
+/// Font size at which a value `text_width` pixels wide at `base_size` fits into `max_width`,
+/// never below MIN_SHRINK_RATIO of `base_size`, and whether it has to be ellipsized even then.
+/// Text width is taken to scale with the font size (values are drawn in a monospace font).
+fn shrink_to_fit(text_width: f64, max_width: f64, base_size: f64) -> (f64, bool) {
+    if text_width <= max_width {
+        return (base_size, false);
+    }
+    let size = base_size * max_width / text_width;
+    let min_size = base_size * MIN_SHRINK_RATIO;
+    if size < min_size { (min_size, true) } else { (size, false) }
+}
+
+/// Pango mode for an item's `ellipsize` option; `None` shrinks or scrolls instead.
+fn ellipsize_mode(name: Option<&str>) -> Option<pango::EllipsizeMode> {
+    match name {
+        Some("start") => Some(pango::EllipsizeMode::Start),
+        Some("middle") => Some(pango::EllipsizeMode::Middle),
+        Some("end") => Some(pango::EllipsizeMode::End),
+        _ => None,
+    }
+}
+
+/// Width in pixels of the bar gauge drawn next to a value.
+const BAR_WIDTH: f64 = 140.0;
+/// Fill levels at which the bar gauge turns amber / red.
+const BAR_WARN: f64 = 0.7;
+const BAR_CRIT: f64 = 0.9;
+
+/// Horizontal scroll positions of text wider than its area (long metric values, the
+/// ticker), keyed by item. Text enters at the right edge, leaves at the left and comes round again.
+#[derive(Debug, Default)]
+pub struct ScrollState {
+    offsets: HashMap<String, f64>,
+}
+
+impl ScrollState {
+    /// X of the text's left edge relative to the area's left edge, after moving it `step`
+    /// pixels (0 to hold it, e.g. for animation frames).
+    pub fn position(&mut self, key: &str, text_width: f64, area_width: f64, step: f64) -> f64 {
+        let offset = self.offsets.entry(key.to_string()).or_insert(0.0);
+        *offset += step;
+        // Past the left edge: restart a full area width beyond the right edge
+        if *offset > text_width + area_width {
+            *offset = -area_width;
+        }
+        area_width - *offset
+    }
+
+    /// Drops the positions of items not in `active`.
+    pub fn retain(&mut self, active: &HashSet<&str>) {
+        self.offsets.retain(|key, _| active.contains(key.as_str()));
+        self.offsets.shrink_to_fit();
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+}
+
+/// Memory held by a single renderer, published for the self-monitoring collector.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RendererMemory {
+    /// Number of entries in the scroll offset map.
+    pub scroll_offsets: usize,
+    /// Number of item states retained for logging.
+    pub item_states: usize,
+    /// Number of active rain streams.
+    pub rain_streams: usize,
+    /// Size of the backing image surface in bytes.
+    pub surface_bytes: usize,
+}
+
+/// Latest memory stats per monitor index, shared with the metrics thread.
+static RENDER_MEMORY: Mutex<BTreeMap<usize, RendererMemory>> = Mutex::new(BTreeMap::new());
+
+/// Returns renderer memory stats summed across all monitors.
+pub fn render_memory_snapshot() -> RendererMemory {
+    let mut total = RendererMemory::default();
+    if let Ok(map) = RENDER_MEMORY.lock() {
+        for mem in map.values() {
+            total.scroll_offsets += mem.scroll_offsets;
+            total.item_states += mem.item_states;
+            total.rain_streams += mem.rain_streams;
+            total.surface_bytes += mem.surface_bytes;
+        }
+    }
+    total
+}
+
+/// Handles drawing to an offscreen surface and presenting it to an output target.
 pub struct Renderer {
-    /// The target Cairo image surface.
-    pub surface: ImageSurface,
+    /// Back surface that frames are drawn into.
+    pub buffer: OffscreenBuffer,
+    /// Where finished frames go (X11 window, PNG files, framebuffer).
+    target: Box<dyn Present>,
     /// Default font description used for metrics.
     pub base_font_desc: FontDescription,
     /// Width of the renderer's surface.
     pub width: i32,
     /// Height of the renderer's surface.
     pub height: i32,
-    /// Base color for rendering (from config).
-    pub color_rgb: (f64, f64, f64),
+    /// Colors of the current theme.
+    palette: Palette,
+    /// Palette being faded out and when the fade started, after a theme switch.
+    theme_fade: Option<(Palette, Instant)>,
     /// Layout configuration from config.json.
     config_layout: ConfigLayout,
     #[allow(dead_code)]
     monitor_index: usize,
-    /// Map of metric IDs to their current scroll offset (for long text).
-    scroll_offsets: RefCell<HashMap<String, f64>>,
+    /// Scroll positions of long values and the ticker, by metric ID.
+    scroll: RefCell<ScrollState>,
     /// manager for the background rain effect.
     rain_manager: RainManager,
+    /// Ambient background ("starfield", "plasma", "life") and the mode it was built for.
+    ambient: Option<(String, Box<dyn BackgroundEffect>)>,
     /// Monotonically increasing frame counter for animations.
     frame_count: RefCell<u64>,
     /// State of items for logging
     pub item_states: RefCell<Vec<crate::logging::ItemState>>,
+    /// Decode animation per metric ID.
+    decodes: RefCell<HashMap<String, DecodeState>>,
+    /// False while drawing an extra animation frame, which must not advance the rain or scrolling.
+    advancing: Cell<bool>,
+    /// Occlusion boxes drawn this frame; the rain collides with them on the next one.
+    occlusion_boxes: RefCell<Vec<Bounds>>,
+    /// When the screensaver takeover started: full-screen rain only, no header or metrics.
+    takeover: Option<Instant>,
 }
 
 impl Renderer {
@@ -204,10 +590,10 @@ impl Renderer {
         height: u16, 
         monitor_index: usize, 
         layout: ConfigLayout, 
-        config: &Config
+        config: &Config,
+        target: Box<dyn Present>,
     ) -> Result<Self> {
-        let surface = ImageSurface::create(Format::ARgb32, width as i32, height as i32)
-            .map_err(|e| anyhow::anyhow!("Cairo surface creation failed: {}", e))?;
+        let buffer = OffscreenBuffer::new(width, height)?;
 
         let font_str = format!("{} {}", "Monospace", config.general.font_size); // Default fallback
         let mut font_desc = FontDescription::from_string(&font_str);
@@ -217,23 +603,35 @@ impl Renderer {
             font_desc.set_family("Monospace");
         }
 
-        let color_rgb = parse_hex_color(&config.general.color)?;
+        parse_hex_color(&config.general.color)?;
 
-        let cr = CairoContext::new(&surface)?;
+        let cr = buffer.context()?;
         
-        let renderer = Self {
-            surface,
+        let mut renderer = Self {
+            buffer,
+            target,
             base_font_desc: font_desc,
             width: width as i32,
             height: height as i32,
-            color_rgb,
+            palette: Palette::for_theme(&config.general.theme, &config.general.color),
+            theme_fade: None,
             config_layout: layout,
             monitor_index,
-            scroll_offsets: RefCell::new(HashMap::new()),
-            rain_manager: RainManager::new(config.cosmetics.realism_scale),
+            scroll: RefCell::new(ScrollState::default()),
+            rain_manager: match config.cosmetics.rain_seed {
+                // Monitors get distinct, but still reproducible, rain
+                Some(seed) => RainManager::seeded(config.cosmetics.realism_scale, seed.wrapping_add(monitor_index as u64)),
+                None => RainManager::new(config.cosmetics.realism_scale),
+            },
+            ambient: None,
             frame_count: RefCell::new(0),
             item_states: RefCell::new(Vec::new()),
+            decodes: RefCell::new(HashMap::new()),
+            advancing: Cell::new(true),
+            occlusion_boxes: RefCell::new(Vec::new()),
+            takeover: None,
         };
+        renderer.rain_manager.set_exclusions(renderer.config_layout.rain_exclusions.clone());
         
         // Initial clear
         renderer.clear(&cr)?;
@@ -253,50 +651,176 @@ impl Renderer {
         let screen = &config.screens[self.monitor_index];
         self.config_layout = crate::layout::compute(
             screen, 
-            self.surface.width() as u16, 
-            self.surface.height() as u16, 
-            config.general.font_size as f64
+            self.buffer.width(), 
+            self.buffer.height(), 
+            &config
         );
         self.rain_manager.realism_scale = config.cosmetics.realism_scale;
-        
-        // Update color based on theme if it's one of the presets
-        self.color_rgb = match config.general.theme.as_str() {
-            "calm" => (0.0, 0.8, 1.0),
-            "alert" => (1.0, 0.2, 0.2),
-            "classic" => (0.0, 1.0, 65.0 / 255.0),
-            _ => parse_hex_color(&config.general.color).unwrap_or((0.0, 1.0, 65.0 / 255.0)),
-        };
+        self.rain_manager.set_exclusions(self.config_layout.rain_exclusions.clone());
+        self.rain_manager.reseed(config.cosmetics.rain_seed.map(|seed| seed.wrapping_add(self.monitor_index as u64)));
+        self.prune_state();
+        self.set_theme(&config);
+    }
+
+    /// Switches to the palette of `config.general.theme`, fading over from the current
+    /// colors. Unlike `update_config` this leaves the layout and rain untouched.
+    pub fn set_theme(&mut self, config: &Config) {
+        let target = Palette::for_theme(&config.general.theme, &config.general.color);
+        if target == self.palette {
+            return;
+        }
+        self.theme_fade = Some((self.palette(), Instant::now()));
+        self.palette = target;
+    }
+
+    /// Colors for the current frame, part way through a theme fade if one is running.
+    pub fn palette(&self) -> Palette {
+        match self.theme_fade {
+            Some((from, started)) => {
+                let t = started.elapsed().as_secs_f64() / THEME_FADE.as_secs_f64();
+                if t >= 1.0 { self.palette } else { from.blend(&self.palette, t) }
+            }
+            None => self.palette,
+        }
+    }
+
+    /// Drops per-item state for metrics no longer in the active layout.
+    fn prune_state(&self) {
+        let active: HashSet<&str> = self.config_layout.items.iter().map(|i| i.metric_id.as_str()).collect();
+        let mut offsets = self.scroll.borrow_mut();
+        let before = offsets.len();
+        offsets.retain(&active);
+        self.decodes.borrow_mut().retain(|id, _| active.contains(id.as_str()));
+        if offsets.len() != before {
+            log::debug!("Pruned {} stale scroll offsets on monitor {}", before - offsets.len(), self.monitor_index);
+        }
+
+        // Item states are rebuilt every frame; release capacity left over from larger layouts.
+        self.item_states.borrow_mut().shrink_to_fit();
+        drop(offsets);
+        self.publish_memory_stats();
+    }
+
+    /// Current memory held by this renderer.
+    pub fn memory_stats(&self) -> RendererMemory {
+        RendererMemory {
+            scroll_offsets: self.scroll.borrow().len(),
+            item_states: self.item_states.borrow().len(),
+            rain_streams: self.rain_manager.streams.len(),
+            surface_bytes: (self.buffer.surface().stride() * self.buffer.surface().height()).max(0) as usize,
+        }
+    }
+
+    fn publish_memory_stats(&self) {
+        if let Ok(mut map) = RENDER_MEMORY.lock() {
+            map.insert(self.monitor_index, self.memory_stats());
+        }
+    }
+
+    /// Starts or ends the screensaver takeover. The rain then covers the whole surface,
+    /// margins and rain exclusions included, and ramps up to full density and brightness.
+    pub fn set_takeover(&mut self, on: bool) {
+        if on == self.takeover.is_some() {
+            return;
+        }
+        self.takeover = on.then(Instant::now);
+        let exclusions = if on { Vec::new() } else { self.config_layout.rain_exclusions.clone() };
+        self.rain_manager.set_exclusions(exclusions);
+    }
+
+    /// True while a value decode or theme fade wants frames faster than the update interval.
+    pub fn is_animating(&self) -> bool {
+        let fading = self.theme_fade.is_some_and(|(_, started)| started.elapsed() < THEME_FADE);
+        fading || self.decodes.borrow().values().any(|d| d.changed.is_some_and(|at| at.elapsed() < DECODE_DURATION))
+    }
+
+    /// Redraws the current frame for an animation (see `is_animating`) without moving the
+    /// rain or scrolling text forward, so their speed doesn't depend on how often this runs.
+    pub fn draw_animation_frame(&mut self, config: &Config, shared: &SharedMetrics) -> Result<()> {
+        self.advancing.set(false);
+        let result = self.draw(config, shared);
+        self.advancing.set(true);
+        result
     }
 
     /// Main draw loop.
     pub fn draw(
-        &mut self, 
-        conn: &xcb::Connection, 
-        window: x::Window, 
-        config: &Config, 
-        metrics: &MetricData
+        &mut self,
+        config: &Config,
+        shared: &SharedMetrics
     ) -> Result<()> {
+        let metrics = &shared.data;
+        let started = Instant::now();
+
+        let takeover = self.takeover.map(|at| (at.elapsed().as_secs_f64() / TAKEOVER_RAMP.as_secs_f64()).min(1.0));
+        let mut settings = EffectSettings::new(config);
+        settings.mode = config.rain_mode_for(self.monitor_index);
+        if let Some(ramp) = takeover {
+            if !effects::is_animated(settings.mode) {
+                settings.mode = "fall";
+            }
+            settings.realism_scale = settings.realism_scale.max(TAKEOVER_REALISM);
+            settings.brightness += (1.0 - settings.brightness) * ramp;
+            if settings.speed == 0.0 {
+                settings.speed = 1.0;
+            }
+        }
+        let rain_mode = settings.mode;
+        if self.ambient.as_ref().map(|(mode, _)| mode.as_str()) != Some(rain_mode) {
+            let seed = config.cosmetics.rain_seed.map(|seed| seed.wrapping_add(self.monitor_index as u64));
+            self.ambient = effects::ambient_effect(rain_mode, seed).map(|effect| (rain_mode.to_string(), effect));
+        }
+
         // FPS Capping logic
-        *self.frame_count.borrow_mut() += 1;
+        if self.advancing.get() {
+            *self.frame_count.borrow_mut() += 1;
+        }
         let frame_count = *self.frame_count.borrow();
 
-        let cr = CairoContext::new(&self.surface)?;
+        let cr = self.buffer.context()?;
         self.clear(&cr)?;
 
-        // Update physics
-        self.rain_manager.update(
-            Duration::from_millis(33), // Fixed 30 FPS delta (approx 33ms)
-            self.surface.width(),
-            self.surface.height(),
-            config
-        );
+        // Everything is drawn inside the screen margins, except a takeover's rain
+        let bounds = match takeover {
+            Some(_) => Bounds { x: 0, y: 0, width: self.width, height: self.height },
+            None => self.config_layout.bounds,
+        };
+
+        // The rain collides with last frame's metric boxes (the layout rarely moves between frames)
+        let obstacles = self.occlusion_boxes.take().into_iter()
+            .map(|b| Bounds { x: b.x - bounds.x, y: b.y - bounds.y, ..b })
+            .collect();
+        self.rain_manager.set_obstacles(obstacles);
+
+        // Update physics (backgrounds live in bounds-local coordinates)
+        let falling_rain = matches!(rain_mode, "fall" | "reactive");
+        let background: Option<&mut dyn BackgroundEffect> = match &mut self.ambient {
+            _ if falling_rain => Some(&mut self.rain_manager),
+            Some((_, effect)) => Some(effect.as_mut()),
+            None => None,
+        };
+        if let Some(background) = background.filter(|_| self.advancing.get()) {
+            background.update(
+                Duration::from_millis(33), // Fixed 30 FPS delta (approx 33ms)
+                bounds.width,
+                bounds.height,
+                &settings,
+                metrics
+            );
+        }
 
         // Clear item states for this frame
         self.item_states.borrow_mut().clear();
 
+        // Global clip: nothing (rain, header, metrics) may spill past the safe area
+        cr.rectangle(bounds.x as f64, bounds.y as f64, bounds.width as f64, bounds.height as f64);
+        cr.clip();
+
         // 1. Draw Rain
-        if config.cosmetics.rain_mode == "fall" {
-            self.rain_manager.draw(&cr, self.width as f64, self.height as f64, *self.frame_count.borrow(), config)?;
+        cr.save()?;
+        if falling_rain {
+            cr.translate(bounds.x as f64, bounds.y as f64);
+            self.rain_manager.draw(&cr, bounds.width as f64, bounds.height as f64, *self.frame_count.borrow(), &settings, &self.palette())?;
             
             // Log rain positions (sampled for performance)
             if config.logging.enabled {
@@ -306,31 +830,33 @@ impl Renderer {
                         states.push(crate::logging::ItemState {
                             id: format!("rain_{}", i),
                             item_type: "rain".to_string(),
-                            x: stream.x,
-                            y: stream.y,
+                            x: stream.x + bounds.x as f64,
+                            y: stream.y + bounds.y as f64,
                             width: 10.0, // approx
                             height: 10.0,
                         });
                     }
                 }
             }
-        } else if config.cosmetics.rain_mode == "pulse" {
+        } else if let Some((_, effect)) = &self.ambient {
+            cr.translate(bounds.x as f64, bounds.y as f64);
+            effect.draw(&cr, bounds.width as f64, bounds.height as f64, frame_count, &settings, &self.palette())?;
+        } else if rain_mode == "pulse" {
             // Optimization: Pulse Mode (Very low CPU)
             let pulse = ( (frame_count as f64 * 0.05).sin() * 0.2 ) + 0.3;
-            let theme_color = match config.general.theme.as_str() {
-                "calm" => (0.0, 0.8, 1.0),
-                "alert" => (1.0, 0.2, 0.2),
-                _ => (0.0, 1.0, 65.0/255.0), // classic
-            };
+            let theme_color = self.palette().rain;
             cr.save()?;
             cr.set_source_rgba(theme_color.0, theme_color.1, theme_color.2, pulse);
-            cr.rectangle(0.0, 0.0, self.width as f64, self.height as f64);
+            cr.rectangle(bounds.x as f64, bounds.y as f64, bounds.width as f64, bounds.height as f64);
             cr.set_operator(Operator::Atop); 
             cr.paint_with_alpha(pulse)?;
             cr.restore()?;
         }
+        cr.restore()?;
 
-        if let Some(MetricValue::String(dow)) = metrics.values.get(&MetricId::DayOfWeek) {
+        // The day-of-week header needs the full desktop; panel bars only show their metrics
+        let header = metrics.values.get(&MetricId::DayOfWeek).filter(|_| !config.panel.enabled && takeover.is_none());
+        if let Some(MetricValue::String(dow)) = header {
             let header_text = if config.general.show_monitor_label {
                 format!("{} (Monitor {})", dow, self.monitor_index + 1)
             } else {
@@ -340,8 +866,8 @@ impl Renderer {
             // Calculate Box dimensions
             let box_w = 400.0;
             let box_h = config.general.font_size as f64 * 3.0; // Dynamic box height
-            let box_x = (self.width as f64 - box_w) / 2.0;
-            let box_y = 60.0; // Moved slightly up for better aesthetic
+            let box_x = bounds.x as f64 + (bounds.width as f64 - box_w) / 2.0;
+            let box_y = bounds.y as f64 + 40.0; // 60px below the screen edge with the default 20px margin
 
             // Draw occlusion box
             if config.cosmetics.occlusion_enabled {
@@ -349,22 +875,34 @@ impl Renderer {
             }
             
             self.draw_day_of_week(&cr, &header_text, box_x, box_y, box_w, box_h, &config.general.glow_passes, config)?;
+
+            // Next calendar event, centered just below the header
+            if config.calendar.in_header {
+                if let Some(MetricValue::String(event)) = metrics.values.get(&MetricId::NextEvent) {
+                    let layout = pangocairo::functions::create_layout(&cr);
+                    layout.set_font_description(Some(&self.base_font_desc));
+                    layout.set_text(event);
+                    let (_, logical) = layout.pixel_extents();
+                    let x = bounds.x as f64 + (bounds.width as f64 - logical.width() as f64) / 2.0;
+                    self.draw_text_glow_at(&cr, &layout, x, box_y + box_h + 4.0, None, &config.general.glow_passes, config)?;
+                }
+            }
             
             if config.logging.enabled {
                 let (w, h) = (200.0, 40.0 * 1.8); // Appoximate size for Day of Week
                 self.item_states.borrow_mut().push(crate::logging::ItemState {
                     id: "day_of_week".to_string(),
                     item_type: "metric".to_string(),
-                    x: (self.width as f64 - 200.0) / 2.0, // approx center
-                    y: 100.0,
+                    x: box_x + (box_w - 200.0) / 2.0, // approx center
+                    y: box_y + 40.0,
                     width: w,
                     height: h,
                 });
             }
         }
 
-        // Iterate over layout items and draw them
-        let items = self.config_layout.items.clone();
+        // Iterate over layout items and draw them (none during a takeover)
+        let items = if takeover.is_none() { self.config_layout.items.clone() } else { Vec::new() };
         for item in &items {
             // Resolve metric value
             let metric_id_enum = MetricId::from_str(&item.metric_id);
@@ -374,33 +912,105 @@ impl Renderer {
                 continue;
             }
 
+            // The clock reads the time itself, so it doesn't lag the metrics tick
+            if item.metric_id == "clock" {
+                self.draw_clock(&cr, item, chrono::Local::now(), config)?;
+                continue;
+            }
+            if item.metric_id == "calendar" {
+                self.draw_calendar(&cr, item, chrono::Local::now().date_naive(), config)?;
+                continue;
+            }
+            if item.metric_id == "notifications" {
+                if let Some(MetricValue::Table(rows)) = metrics.values.get(&MetricId::Notifications) {
+                    self.draw_notifications(&cr, item, rows, chrono::Utc::now().timestamp(), config)?;
+                }
+                continue;
+            }
+            if item.metric_id == "ticker" {
+                self.draw_ticker(&cr, item, metrics, config)?;
+                continue;
+            }
+
             // Standard Metrics
             if let Some(id) = metric_id_enum {
-                if let Some(value) = metrics.values.get(&id) {
-                    let value_str = self.format_metric_value(value);
+                if let Some(MetricValue::Table(rows)) = metrics.values.get(&id) {
+                    let label = if item.label.is_empty() { id.label() } else { item.label.clone() };
+                    let line_h = config.general.font_size as f64 * 1.5;
+                    let table_h = line_h * (rows.len() + 1) as f64;
+                    if config.cosmetics.occlusion_enabled {
+                        self.draw_occlusion_box(&cr, item.x as f64 - 5.0, item.y as f64 - 2.0, item.max_width as f64 + 10.0, table_h, config)?;
+                    }
+                    let area = Area { x: item.x as f64, y: item.y as f64, width: item.max_width as f64, height: table_h };
+                    self.draw_table(&cr, &label, rows, area, config)?;
+
+                    if config.logging.enabled {
+                        self.item_states.borrow_mut().push(crate::logging::ItemState {
+                            id: item.metric_id.clone(),
+                            item_type: "table".to_string(),
+                            x: item.x as f64,
+                            y: item.y as f64,
+                            width: item.max_width as f64,
+                            height: table_h,
+                        });
+                    }
+                } else if let Some(value) = metrics.values.get(&id) {
+                    let label = if item.label.is_empty() { id.label() } else { item.label.clone() };
+                    let value_str = self.format_metric_value(value, item, &label, config);
+                    // A template that places the label itself replaces the label column
+                    let label = if item.format.as_deref().is_some_and(|f| f.contains("{label}")) { String::new() } else { label };
                     
                     // 2. Draw Occlusion Box if enabled
                     let box_h = config.general.metric_font_size as f64 * 1.5;
                     if config.cosmetics.occlusion_enabled {
                         self.draw_occlusion_box(&cr, item.x as f64 - 5.0, item.y as f64 - 2.0, item.max_width as f64 + 10.0, box_h, config)?;
                     }
-
-                    let label = if item.label.is_empty() { id.label() } else { item.label.clone() };
                     
-                    // Enable scrolling for network or weather which might be long
-                    let allow_scroll = item.metric_id == "network_details" || item.metric_id.contains("weather");
+                    // Enable scrolling for network, weather, track titles, tasks or headlines which might be long
+                    // (not in low-power mode, where frames are static and shrink/ellipsize apply instead)
+                    let allow_scroll = !config.is_low_power()
+                        && (item.metric_id == "network_details"
+                            || item.metric_id == "now_playing"
+                            || item.metric_id == "tasks"
+                            || item.metric_id == "feed_headlines"
+                            || config.rss_feeds.iter().any(|f| f.metric_id == item.metric_id)
+                            || item.metric_id.contains("weather"));
                     
                     log::trace!("Drawing metric {:?} at y={}", id, item.y);
 
+                    // Threshold color (warn/crit) for the value and bar fill
+                    let level_color = shared.levels.get(&id).and_then(|level| threshold_color(config, &id, *level));
+
+                    // Sparkline/bar: graphic at the right edge, value drawn just left of it
+                    let mut text_width = item.max_width as f64;
+                    if item.style == "sparkline" {
+                        if let Some(samples) = shared.history.get(&id) {
+                            let graph_w = SPARKLINE_WIDTH.min(text_width / 3.0);
+                            let area = Area { x: item.x as f64 + text_width - graph_w, y: item.y as f64, width: graph_w, height: box_h };
+                            self.draw_sparkline(&cr, samples, area, config)?;
+                            text_width -= graph_w + 8.0;
+                        }
+                    } else if item.style == "bar" {
+                        if let Some(v) = value.as_f64() {
+                            let bar_w = BAR_WIDTH.min(text_width / 3.0);
+                            let area = Area { x: item.x as f64 + text_width - bar_w, y: item.y as f64, width: bar_w, height: box_h };
+                            self.draw_bar(&cr, v / item.bar_max, area, level_color, config)?;
+                            text_width -= bar_w + 8.0;
+                        }
+                    }
+
                     self.draw_metric_pair(
                         &cr,
                         &label, 
                         &value_str, 
                         item.x as f64, 
                         item.y as f64, 
-                        item.max_width as f64,
+                        text_width,
+                        &item.alignment,
                         &item.metric_id,
                         item.clip || allow_scroll,
+                        item.ellipsize.as_deref(),
+                        level_color,
                         &config.general.glow_passes,
                         config
                     )?;
@@ -421,14 +1031,38 @@ impl Renderer {
             }
         }
 
+        cr.reset_clip();
+
         // Explicitly drop context to release surface lock
         drop(cr);
 
-        self.present(conn, window)?;
+        self.publish_memory_stats();
+        self.present()?;
+        if let Ok(mut stats) = UsageStats::shared().lock() {
+            stats.record_frame(started.elapsed());
+        }
         Ok(())
     }
 
-    fn format_metric_value(&self, value: &MetricValue) -> String {
+    /// Display text for an item's value: the default formatting, the item's `precision`
+    /// and `unit`, the metric's value map, then the item's `format` template.
+    fn format_metric_value(&self, value: &MetricValue, item: &LayoutItem, label: &str, config: &Config) -> String {
+        let bits = config.general.network_bits;
+        if let (MetricValue::NetworkMap(map), Some(template)) = (value, &item.format) {
+            if ["{iface}", "{rx}", "{tx}"].iter().any(|p| template.contains(p)) {
+                return format_network_template(template, label, map, bits);
+            }
+        }
+        let text = reshape_number(&self.default_value_text(value, bits), item.precision, item.unit.as_deref());
+        // Config-defined value maps (e.g. "1" -> "ON") apply to the displayed text only
+        let text = config.metric_settings.get(&item.metric_id).and_then(|s| s.map_value(&text)).unwrap_or(text);
+        match &item.format {
+            Some(template) => fill_template(template, label, &text),
+            None => text,
+        }
+    }
+
+    fn default_value_text(&self, value: &MetricValue, bits: bool) -> String {
         match value {
             MetricValue::Float(v) => format!("{:.1}", v),
             MetricValue::Int(v) => format!("{}", v),
@@ -439,8 +1073,8 @@ impl Renderer {
                 keys.sort(); // Ensure stable order
                 for k in keys {
                     if let Some((rx, tx)) = map.get(k) {
-                        if *rx > 0 || *tx > 0 {
-                            parts.push(format!("{}: ↓{} ↑{}", k, format_bytes(*rx), format_bytes(*tx)));
+                        if *rx > 0.0 || *tx > 0.0 {
+                            parts.push(format!("{}: ↓{} ↑{}", k, format_rate(*rx, bits), format_rate(*tx, bits)));
                         }
                     }
                 }
@@ -450,6 +1084,7 @@ impl Renderer {
                     parts.join(" | ")
                 }
             },
+            MetricValue::Table(rows) => rows.iter().map(|r| r.join(" ")).collect::<Vec<_>>().join(" | "),
             MetricValue::None => "---".to_string(),
         }
     }
@@ -471,19 +1106,15 @@ impl Renderer {
         
         layout.set_text(header_text);
         let (_, logical) = layout.pixel_extents();
-        let text_width = logical.width as f64; 
-        let text_height = logical.height as f64;
+        let text_width = logical.width() as f64; 
+        let text_height = logical.height() as f64;
         
         // Center horizontally and vertically within the box
         let x = box_x + (box_w - text_width) / 2.0;
         let y = box_y + (box_h - text_height) / 2.0;
         
         // Theme-aware colors
-        let theme_color = match config.general.theme.as_str() {
-            "calm" => (0.0, 0.8, 1.0),
-            "alert" => (1.0, 0.2, 0.2),
-            _ => (0.0, 1.0, 65.0 / 255.0), // classic
-        };
+        let theme_color = self.palette().glow;
         
         self.draw_text_glow_at(cr, &layout, x, y, Some(theme_color), glow_passes, config)?;
         
@@ -491,6 +1122,237 @@ impl Renderer {
         Ok(())
     }
 
+    /// Draws the clock item over `CLOCK_ROWS` rows: the time in a large bold font, or an
+    /// analog face with hour and minute hands (and a second hand outside low-power mode).
+    /// Either sits on the side of the item its alignment (anchor) asks for.
+    fn draw_clock(&self, cr: &CairoContext, item: &LayoutItem, now: chrono::DateTime<chrono::Local>, config: &Config) -> Result<()> {
+        use std::fmt::Write;
+
+        let block_h = config.general.font_size as f64 * 1.5 * layout::CLOCK_ROWS as f64;
+        let y = item.y as f64;
+
+        if item.style != "analog" {
+            // `validate` rejects bad formats; a failure here still must not take the render thread down
+            let mut text = String::new();
+            if write!(text, "{}", now.format(item.format.as_deref().unwrap_or("%H:%M"))).is_err() {
+                text = now.format("%H:%M").to_string();
+            }
+            let layout = pangocairo::functions::create_layout(cr);
+            let mut desc = self.base_font_desc.clone();
+            desc.set_size((config.general.font_size as f64 * 2.5 * pango::SCALE as f64) as i32);
+            desc.set_weight(Weight::Bold);
+            layout.set_font_description(Some(&desc));
+            layout.set_text(&text);
+            let (_, logical) = layout.pixel_extents();
+            let x = aligned_x(item.x as f64, item.max_width as f64, logical.width() as f64, &item.alignment);
+            let text_y = y + (block_h - logical.height() as f64) / 2.0;
+            if config.cosmetics.occlusion_enabled {
+                self.draw_occlusion_box(cr, x - 5.0, y - 2.0, (logical.width() as f64).min(item.max_width as f64) + 10.0, block_h, config)?;
+            }
+            return self.draw_text_glow_at(cr, &layout, x, text_y, Some(self.palette().glow), &config.general.glow_passes, config);
+        }
+
+        let x = aligned_x(item.x as f64, item.max_width as f64, block_h, &item.alignment);
+        let radius = block_h / 2.0 - 4.0;
+        let (cx, cy) = (x + radius + 4.0, y + block_h / 2.0);
+        if config.cosmetics.occlusion_enabled {
+            self.draw_occlusion_box(cr, x - 5.0, y - 2.0, block_h + 10.0, block_h, config)?;
+        }
+        let (r, g, b) = self.palette().text;
+        let alpha = config.cosmetics.metrics_brightness;
+        cr.save()?;
+        cr.set_line_cap(cairo::LineCap::Round);
+        cr.set_source_rgba(r, g, b, 0.6 * alpha);
+        cr.set_line_width(1.5);
+        cr.arc(cx, cy, radius, 0.0, std::f64::consts::TAU);
+        cr.stroke()?;
+        // Hour marks, longer at the quarters
+        for hour in 0..12 {
+            let angle = hour as f64 / 12.0 * std::f64::consts::TAU;
+            let inner = if hour % 3 == 0 { 0.8 } else { 0.9 };
+            cr.move_to(cx + angle.sin() * radius * inner, cy - angle.cos() * radius * inner);
+            cr.line_to(cx + angle.sin() * radius, cy - angle.cos() * radius);
+            cr.stroke()?;
+        }
+
+        let (hour, minute, second) = clock_hand_angles(now.time());
+        let mut hands = vec![(hour, 0.5, 3.0, self.palette().text), (minute, 0.8, 2.0, self.palette().text)];
+        if !config.is_low_power() {
+            hands.push((second, 0.9, 1.0, self.palette().lead));
+        }
+        for (angle, length, width, (r, g, b)) in hands {
+            cr.set_source_rgba(r, g, b, alpha);
+            cr.set_line_width(width);
+            cr.move_to(cx, cy);
+            cr.line_to(cx + angle.sin() * radius * length, cy - angle.cos() * radius * length);
+            cr.stroke()?;
+        }
+        cr.restore()?;
+        Ok(())
+    }
+
+    /// Draws the month of `today` as a grid over `CALENDAR_ROWS` rows: the month title,
+    /// weekday names and the weeks, columns aligned with Pango tab stops. Today gets a
+    /// highlight behind it and bold digits. The grid follows the item's alignment.
+    fn draw_calendar(&self, cr: &CairoContext, item: &LayoutItem, today: chrono::NaiveDate, config: &Config) -> Result<()> {
+        use chrono::Datelike;
+
+        let line_h = config.general.font_size as f64 * 1.5;
+        let cell_w = config.general.metric_font_size as f64 * 2.2;
+        let y = item.y as f64;
+
+        // Month names and digits only, so the markup needs no escaping
+        let mut text = format!("<b>{}</b>\n{}", today.format("%B %Y"), CALENDAR_WEEKDAYS.join("\t"));
+        for week in month_weeks(today) {
+            let cells: Vec<String> = week
+                .iter()
+                .map(|day| match day {
+                    Some(day) if *day == today.day() => format!("<b>{:>2}</b>", day),
+                    Some(day) => format!("{:>2}", day),
+                    None => String::new(),
+                })
+                .collect();
+            text.push('\n');
+            text.push_str(&cells.join("\t"));
+        }
+
+        let layout = pangocairo::functions::create_layout(cr);
+        let mut desc = self.base_font_desc.clone();
+        desc.set_size((config.general.metric_font_size as f64 * pango::SCALE as f64) as i32);
+        layout.set_font_description(Some(&desc));
+        let mut tabs = pango::TabArray::new(7, true);
+        for column in 0..7 {
+            tabs.set_tab(column, pango::TabAlign::Left, (column as f64 * cell_w) as i32);
+        }
+        layout.set_tabs(Some(&tabs));
+        layout.set_spacing(((line_h - config.general.metric_font_size as f64 * 1.25).max(0.0) * pango::SCALE as f64) as i32);
+        layout.set_markup(&text);
+
+        let (_, logical) = layout.pixel_extents();
+        let grid_w = (logical.width() as f64).max(7.0 * cell_w);
+        let x = aligned_x(item.x as f64, item.max_width as f64, grid_w, &item.alignment);
+        if config.cosmetics.occlusion_enabled {
+            self.draw_occlusion_box(cr, x - 5.0, y - 2.0, grid_w + 10.0, line_h * layout::CALENDAR_ROWS as f64, config)?;
+        }
+
+        // Highlight behind today's digits, found again in the layout's plain text (markup
+        // offsets don't match it)
+        let plain = layout.text();
+        let week_start = plain.match_indices('\n').nth(1).map_or(0, |(i, _)| i);
+        let day = format!("{:>2}", today.day());
+        let day_at = plain[week_start..]
+            .split(['\n', '\t'])
+            .scan(week_start, |offset, cell| {
+                let start = *offset;
+                *offset += cell.len() + 1;
+                Some((start, cell))
+            })
+            .find(|(_, cell)| *cell == day)
+            .map(|(start, _)| start);
+        if let Some(start) = day_at {
+            let first = layout.index_to_pos(start as i32);
+            let last = layout.index_to_pos((start + day.len() - 1) as i32);
+            let scale = pango::SCALE as f64;
+            let (r, g, b) = self.palette().lead;
+            cr.save()?;
+            cr.set_source_rgba(r, g, b, 0.35 * config.cosmetics.metrics_brightness);
+            cr.rectangle(
+                x + first.x() as f64 / scale - 3.0,
+                y + first.y() as f64 / scale,
+                (last.x() + last.width() - first.x()) as f64 / scale + 6.0,
+                first.height() as f64 / scale,
+            );
+            cr.fill()?;
+            cr.restore()?;
+        }
+
+        self.draw_text_glow_at(cr, &layout, x, y, None, &config.general.glow_passes, config)
+    }
+
+    /// Draws the mirrored notifications below the item's label, newest first, as
+    /// "APP summary" lines that fade out over the second half of `fade_secs`.
+    fn draw_notifications(&self, cr: &CairoContext, item: &LayoutItem, rows: &[Vec<String>], now: i64, config: &Config) -> Result<()> {
+        let line_h = config.general.font_size as f64 * 1.5;
+        let (x, y, width) = (item.x as f64, item.y as f64, item.max_width as f64);
+        if config.cosmetics.occlusion_enabled && !rows.is_empty() {
+            self.draw_occlusion_box(cr, x - 5.0, y - 2.0, width + 10.0, line_h * (rows.len() + 1) as f64, config)?;
+        }
+
+        let layout = pangocairo::functions::create_layout(cr);
+        let mut desc = self.base_font_desc.clone();
+        desc.set_size((config.general.metric_font_size as f64 * pango::SCALE as f64) as i32);
+        layout.set_font_description(Some(&desc));
+        layout.set_text(&item.label);
+        self.draw_text_glow_at(cr, &layout, x, y, None, &config.general.glow_passes, config)?;
+
+        layout.set_width((width * pango::SCALE as f64) as i32);
+        layout.set_ellipsize(pango::EllipsizeMode::End);
+        for (i, row) in rows.iter().enumerate() {
+            let [received, app, summary] = row.as_slice() else {
+                continue;
+            };
+            let age = now - received.parse::<i64>().unwrap_or(now);
+            let alpha = notification_alpha(age as f64, config.notifications.fade_secs as f64);
+            if alpha <= 0.0 {
+                continue;
+            }
+            layout.set_text(&format!("{} {}", app.to_uppercase(), summary));
+            cr.push_group();
+            self.draw_text_glow_at(cr, &layout, x, y + line_h * (i + 1) as f64, None, &config.general.glow_passes, config)?;
+            cr.pop_group_to_source()?;
+            cr.paint_with_alpha(alpha)?;
+        }
+        Ok(())
+    }
+
+    /// Draws the ticker line: `ticker.metrics` as "LABEL value" and the feed headlines,
+    /// scrolling right to left across the item (held still in low-power mode).
+    fn draw_ticker(&self, cr: &CairoContext, item: &LayoutItem, metrics: &MetricData, config: &Config) -> Result<()> {
+        let bits = config.general.network_bits;
+        let mut entries: Vec<String> = config
+            .ticker
+            .metrics
+            .iter()
+            .filter_map(|m| {
+                let id = MetricId::from_str(m)?;
+                let value = metrics.values.get(&id)?;
+                Some(format!("{} {}", id.label().to_uppercase(), self.default_value_text(value, bits)))
+            })
+            .collect();
+        if let Some(MetricValue::String(headlines)) = metrics.values.get(&MetricId::FeedHeadlines) {
+            entries.push(headlines.clone());
+        }
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let layout = pangocairo::functions::create_layout(cr);
+        let mut desc = self.base_font_desc.clone();
+        desc.set_size((config.general.metric_font_size as f64 * pango::SCALE as f64) as i32);
+        layout.set_font_description(Some(&desc));
+        layout.set_text(&entries.join(&config.ticker.separator));
+        let (text_w, text_h) = layout.pixel_size();
+
+        let (x, y, width) = (item.x as f64, item.y as f64, item.max_width as f64);
+        let box_h = config.general.font_size as f64 * 1.5;
+        if config.cosmetics.occlusion_enabled {
+            self.draw_occlusion_box(cr, x - 5.0, y - 2.0, width + 10.0, box_h, config)?;
+        }
+
+        let text_x = if config.is_low_power() || (text_w as f64) <= width {
+            0.0
+        } else {
+            let step = if self.advancing.get() { config.ticker.speed } else { 0.0 };
+            self.scroll.borrow_mut().position(&item.metric_id, text_w as f64, width, step)
+        };
+        cr.save()?;
+        cr.rectangle(x, y, width, box_h);
+        cr.clip();
+        self.draw_text_glow_at(cr, &layout, x + text_x, y + (box_h - text_h as f64) / 2.0, None, &config.general.glow_passes, config)?;
+        cr.restore()?;
+        Ok(())
+    }
+
     /// Draws a Label: Value pair.
     fn draw_metric_pair(
         &self, 
@@ -500,8 +1362,11 @@ impl Renderer {
         x: f64, 
         y: f64, 
         max_width: f64,
+        alignment: &str,
         metric_id: &str,
         allow_scroll: bool,
+        ellipsize: Option<&str>,
+        value_color: Option<(f64, f64, f64)>,
         glow_passes: &[(f64, f64, f64)],
         config: &Config
     ) -> Result<()> {
@@ -511,6 +1376,21 @@ impl Renderer {
         layout.set_font_description(Some(&desc));
 
         let box_h = config.general.metric_font_size as f64 * 1.5;
+        let padding = 10.0;
+
+        // Centered and right-aligned items draw label and value next to each other,
+        // shifted within the item, as long as they fit
+        let (mut x, mut max_width) = (x, max_width);
+        if alignment != "left" {
+            layout.set_text(label);
+            let label_w = layout.pixel_size().0 as f64;
+            layout.set_text(value);
+            let pair_width = label_w + padding + layout.pixel_size().0 as f64;
+            if pair_width < max_width {
+                x += if alignment == "center" { (max_width - pair_width) / 2.0 } else { max_width - pair_width };
+                max_width = pair_width;
+            }
+        }
         
         // 1. Draw Label
         layout.set_text(label);
@@ -528,10 +1408,10 @@ impl Renderer {
         // 2. Prepare Value
         layout.set_text(value);
         let (val_w_px, _) = layout.pixel_size();
-        let value_width = val_w_px as f64;
+        let mut value_width = val_w_px as f64;
+        let mut value_y = centered_y;
 
         // Calculate available space for value
-        let padding = 10.0;
         let value_area_start = x + label_width + padding;
         let value_area_width = max_width - label_width - padding;
 
@@ -547,45 +1427,208 @@ impl Renderer {
         cr.rectangle(value_area_start, y, value_area_width, self.height as f64); // Height is loose here, clip handles it
         cr.clip();
 
-        if value_width > value_area_width && allow_scroll {
-            // Scrolling logic
-            let mut offsets = self.scroll_offsets.borrow_mut();
-            let offset = offsets.entry(metric_id.to_string()).or_insert(0.0);
-            
+        if let (true, Some(mode)) = (value_width > value_area_width, ellipsize_mode(ellipsize)) {
+            // Configured ellipsization keeps the relevant part (e.g. a path's tail) visible
+            layout.set_width((value_area_width * pango::SCALE as f64) as i32);
+            layout.set_ellipsize(mode);
+            value_width = layout.pixel_size().0 as f64;
+            draw_x = x + max_width - value_width;
+        } else if value_width > value_area_width && allow_scroll {
             // Slow scroll: 0.5px per frame
-            *offset += 0.5;
-            
-            // Reset if scrolled past
-            let scroll_span = value_width + value_area_width; 
-            if *offset > scroll_span {
-                *offset = -value_area_width; // Start entering from right
-            }
-
-            // Override draw_x for scrolling
-            draw_x = (x + max_width) - *offset;
-            
-            // If we have scrolled so far that the text is gone, reset
-            if draw_x + value_width < value_area_start {
-                 *offset = 0.0; // Reset to start
-            }
-        } else {
-            // Ensure right alignment if fitting, or clamped if not scrolling
-            if value_width > value_area_width {
-                // If too big and no scroll, align left of value area (show start of string)
-                draw_x = value_area_start;
+            let step = if self.advancing.get() { 0.5 } else { 0.0 };
+            draw_x = value_area_start + self.scroll.borrow_mut().position(metric_id, value_width, value_area_width, step);
+        } else if value_width > value_area_width {
+            // Shrink-to-fit, down to MIN_SHRINK_RATIO of the font size, then ellipsize
+            let (size, ellipsize) = shrink_to_fit(value_width, value_area_width, desc.size() as f64);
+            desc.set_size(size as i32);
+            layout.set_font_description(Some(&desc));
+            value_width = layout.pixel_size().0 as f64;
+            if ellipsize || value_width > value_area_width {
+                layout.set_width((value_area_width * pango::SCALE as f64) as i32);
+                layout.set_ellipsize(pango::EllipsizeMode::End);
+                value_width = layout.pixel_size().0 as f64;
             }
+            let value_h = layout.pixel_size().1 as f64;
+            value_y = y + (box_h - value_h) / 2.0 - 2.0;
+            draw_x = x + max_width - value_width;
         }
 
-        // Draw Value
-        self.draw_text_glow_at(cr, &layout, draw_x, centered_y, None, glow_passes, config)?;
+        // Draw Value, scrambled while a new value decodes
+        if let Some(scrambled) = self.decoded_value(metric_id, value, config) {
+            layout.set_text(&scrambled);
+        }
+        self.draw_text_glow_at(cr, &layout, draw_x, value_y, value_color, glow_passes, config)?;
 
         cr.restore()?; // Restore clip
 
         Ok(())
     }
 
+    /// Tracks value changes of `metric_id` and returns the scrambled text to draw while the
+    /// newest value is decoding, or `None` once it has resolved.
+    fn decoded_value(&self, metric_id: &str, value: &str, config: &Config) -> Option<String> {
+        if !config.cosmetics.decode_animation || config.is_low_power() {
+            return None;
+        }
+        let mut decodes = self.decodes.borrow_mut();
+        let state = decodes.entry(metric_id.to_string()).or_insert_with(|| DecodeState {
+            previous: value.to_string(),
+            current: value.to_string(),
+            changed: None,
+        });
+        if state.current != value {
+            state.previous = std::mem::replace(&mut state.current, value.to_string());
+            state.changed = Some(Instant::now());
+        }
+        let elapsed = state.changed?.elapsed();
+        if elapsed >= DECODE_DURATION {
+            return None;
+        }
+        // New random glyphs every animation frame
+        let mut rng = SmallRng::seed_from_u64((elapsed.as_millis() / ANIMATION_FRAME.as_millis()) as u64 ^ metric_id.len() as u64);
+        let progress = elapsed.as_secs_f64() / DECODE_DURATION.as_secs_f64();
+        Some(decode_frame(&state.previous, value, progress, &mut rng))
+    }
+
+    /// Draws a line graph of recent samples, auto-scaled to the visible range (baseline at 0 for non-negative series).
+    fn draw_sparkline(&self, cr: &CairoContext, samples: &std::collections::VecDeque<f64>, area: Area, config: &Config) -> Result<()> {
+        let Area { x, y, width: w, height: h } = area;
+        if samples.len() < 2 || w <= 0.0 {
+            return Ok(());
+        }
+        let min = samples.iter().cloned().fold(f64::INFINITY, f64::min).min(0.0);
+        let max = samples.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let range = if max - min > f64::EPSILON { max - min } else { 1.0 };
+
+        // Fixed slot per sample so the graph scrolls left as history fills
+        let step = w / (SharedMetrics::HISTORY_LEN - 1) as f64;
+        let start_x = x + w - step * (samples.len() - 1) as f64;
+        let inset = 2.0;
+        let graph_h = h - inset * 2.0;
+        let point = |i: usize, v: f64| (start_x + step * i as f64, y + inset + graph_h * (1.0 - (v - min) / range));
+
+        let (r, g, b) = self.palette().text;
+        let brightness = config.cosmetics.metrics_brightness;
+
+        cr.save()?;
+        for (i, v) in samples.iter().enumerate() {
+            let (px, py) = point(i, *v);
+            if i == 0 { cr.move_to(px, py); } else { cr.line_to(px, py); }
+        }
+        cr.set_source_rgba(r, g, b, brightness);
+        cr.set_line_width(1.5);
+        cr.stroke_preserve()?;
+
+        // Faint fill under the line
+        cr.line_to(x + w, y + inset + graph_h);
+        cr.line_to(start_x, y + inset + graph_h);
+        cr.close_path();
+        cr.set_source_rgba(r, g, b, 0.2 * brightness);
+        cr.fill()?;
+        cr.restore()?;
+        Ok(())
+    }
+
+    /// Draws a horizontal gauge filled to `fraction` (clamped to 0..1).
+    /// The fill uses the metric's threshold color when given, otherwise
+    /// turns amber/red by fill level; the outline uses the theme color.
+    fn draw_bar(&self, cr: &CairoContext, fraction: f64, area: Area, level_color: Option<(f64, f64, f64)>, config: &Config) -> Result<()> {
+        let Area { x, y, width: w, height: h } = area;
+        if w <= 0.0 {
+            return Ok(());
+        }
+        let fraction = fraction.clamp(0.0, 1.0);
+        let inset = h * 0.25;
+        let bar_h = h - inset * 2.0;
+        let brightness = config.cosmetics.metrics_brightness;
+
+        let fill = if let Some(color) = level_color {
+            color
+        } else if fraction >= BAR_CRIT {
+            (1.0, 0.2, 0.2)
+        } else if fraction >= BAR_WARN {
+            (1.0, 0.75, 0.0)
+        } else {
+            self.palette().text
+        };
+
+        cr.save()?;
+        cr.rectangle(x, y + inset, w * fraction, bar_h);
+        cr.set_source_rgba(fill.0, fill.1, fill.2, 0.8 * brightness);
+        cr.fill()?;
+
+        let (r, g, b) = self.palette().text;
+        cr.rectangle(x + 0.5, y + inset + 0.5, w - 1.0, bar_h - 1.0);
+        cr.set_source_rgba(r, g, b, brightness);
+        cr.set_line_width(1.0);
+        cr.stroke()?;
+        cr.restore()?;
+        Ok(())
+    }
+
+    /// Draws a table: the label on the first row, then one row per entry.
+    /// The first column (e.g. process name) is left-aligned and clipped; remaining
+    /// columns are right-aligned at the item's right edge, like metric values.
+    fn draw_table(
+        &self,
+        cr: &CairoContext,
+        label: &str,
+        rows: &[Vec<String>],
+        area: Area,
+        config: &Config
+    ) -> Result<()> {
+        let Area { x, y, width: max_width, .. } = area;
+        let layout = pangocairo::functions::create_layout(cr);
+        let mut desc = pango::FontDescription::from_string("Monospace");
+        desc.set_size((config.general.metric_font_size as f64 * pango::SCALE as f64) as i32);
+        layout.set_font_description(Some(&desc));
+
+        // Must match the row spacing reserved by layout::compute
+        let line_h = config.general.font_size as f64 * 1.5;
+        let padding = 10.0;
+        let glow_passes = &config.general.glow_passes;
+
+        layout.set_text(label);
+        self.draw_text_glow_at(cr, &layout, x, y, None, glow_passes, config)?;
+
+        let cols = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let mut col_widths = vec![0.0f64; cols];
+        for row in rows {
+            for (i, cell) in row.iter().enumerate() {
+                layout.set_text(cell);
+                col_widths[i] = col_widths[i].max(layout.pixel_size().0 as f64);
+            }
+        }
+        let trailing: f64 = col_widths.iter().skip(1).map(|w| w + padding).sum();
+        let first_width = max_width - trailing - padding;
+
+        for (r, row) in rows.iter().enumerate() {
+            let row_y = y + line_h * (r + 1) as f64;
+
+            let mut col_right = x + max_width;
+            for i in (1..row.len()).rev() {
+                layout.set_text(&row[i]);
+                let w = layout.pixel_size().0 as f64;
+                self.draw_text_glow_at(cr, &layout, col_right - w, row_y, None, glow_passes, config)?;
+                col_right -= col_widths[i] + padding;
+            }
+
+            if let Some(name) = row.first() {
+                if first_width > 0.0 {
+                    cr.save()?;
+                    cr.rectangle(x, row_y, first_width + padding, line_h);
+                    cr.clip();
+                    layout.set_text(name);
+                    self.draw_text_glow_at(cr, &layout, x + padding, row_y, None, glow_passes, config)?;
+                    cr.restore()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn draw_text_glow_at(&self, cr: &CairoContext, layout: &PangoLayout, x: f64, y: f64, color: Option<(f64, f64, f64)>, glow_passes: &[(f64, f64, f64)], config: &Config) -> Result<()> {
-        let (r, g, b) = color.unwrap_or(self.color_rgb);
+        let (r, g, b) = color.unwrap_or(self.palette().text);
         let global_brightness = config.cosmetics.metrics_brightness;
 
         for (ox, oy, alpha) in glow_passes {
@@ -609,6 +1652,7 @@ impl Renderer {
     }
 
     fn draw_occlusion_box(&self, cr: &CairoContext, x: f64, y: f64, w: f64, h: f64, config: &Config) -> Result<()> {
+        self.occlusion_boxes.borrow_mut().push(Bounds { x: x as i32, y: y as i32, width: w as i32, height: h as i32 });
         cr.save()?;
         cr.set_source_rgba(0.0, 0.0, 0.0, config.cosmetics.background_opacity); 
         cr.rectangle(x, y, w, h);
@@ -626,33 +1670,122 @@ impl Renderer {
         Ok(())
     }
 
-    pub fn present(&mut self, conn: &xcb::Connection, window: x::Window) -> Result<()> {
-        self.surface.flush();
-        let data = self.surface.data().map_err(|e| anyhow::anyhow!("Failed to get surface data: {}", e))?;
+    pub fn present(&mut self) -> Result<()> {
+        self.target.present(self.buffer.surface_mut())
+    }
 
-        let gc: x::Gcontext = conn.generate_id();
-        conn.send_request(&x::CreateGc {
-            cid: gc,
-            drawable: x::Drawable::Window(window),
-            value_list: &[],
-        });
+    /// Frees resources held by the output target.
+    pub fn release(&mut self) {
+        self.target.release();
+    }
+}
 
-        conn.send_request(&x::PutImage {
-            format: x::ImageFormat::ZPixmap,
-            drawable: x::Drawable::Window(window),
-            gc,
-            width: self.width as u16,
-            height: self.height as u16,
-            dst_x: 0,
-            dst_y: 0,
-            left_pad: 0,
-            depth: 32,
-            data: &data,
-        });
+/// Resolves the configured warn/crit color for a metric at `level`.
+fn threshold_color(config: &Config, id: &MetricId, level: AlertLevel) -> Option<(f64, f64, f64)> {
+    let thresholds = crate::config::effective_thresholds(&config.metric_settings, id.as_str())?;
+    let hex = match level {
+        AlertLevel::Warn => &thresholds.warn_color,
+        AlertLevel::Crit => &thresholds.crit_color,
+        AlertLevel::Normal => return None,
+    };
+    parse_hex_color(hex).ok()
+}
 
-        conn.send_request(&x::FreeGc { gc });
+/// How long a theme switch takes to fade from the old colors to the new ones.
+const THEME_FADE: Duration = Duration::from_millis(800);
+
+const CLASSIC_GREEN: (f64, f64, f64) = (0.0, 1.0, 65.0 / 255.0);
+
+/// Weekday column headers of the month calendar; weeks start on Monday (ISO 8601).
+const CALENDAR_WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// Weeks of the month containing `date`, Monday first; days outside the month are `None`.
+fn month_weeks(date: chrono::NaiveDate) -> Vec<[Option<u32>; 7]> {
+    use chrono::Datelike;
+    let first = date.with_day(1).unwrap_or(date);
+    let days = (1..=31).take_while(|&day| first.with_day(day).is_some()).count() as u32;
+    let offset = first.weekday().num_days_from_monday() as usize;
+    let mut weeks = Vec::new();
+    let mut week = [None; 7];
+    for day in 1..=days {
+        let column = (offset + day as usize - 1) % 7;
+        week[column] = Some(day);
+        if column == 6 {
+            weeks.push(week);
+            week = [None; 7];
+        }
+    }
+    if week.iter().any(Option::is_some) {
+        weeks.push(week);
+    }
+    weeks
+}
 
-        Ok(())
+/// Opacity of a mirrored notification `age` seconds old: full for the first half of
+/// `fade`, then fading linearly to nothing.
+fn notification_alpha(age: f64, fade: f64) -> f64 {
+    (2.0 * (1.0 - age / fade)).clamp(0.0, 1.0)
+}
+
+/// Left edge of `content_width` pixels of content placed in an item `max_width` wide at
+/// `x`, following the item's alignment; content wider than the item starts at `x`.
+fn aligned_x(x: f64, max_width: f64, content_width: f64, alignment: &str) -> f64 {
+    let spare = (max_width - content_width).max(0.0);
+    match alignment {
+        "center" => x + spare / 2.0,
+        "right" => x + spare,
+        _ => x,
+    }
+}
+
+/// Angles of the hour, minute and second hands at `time`, in radians clockwise from 12.
+/// The hour and minute hands move continuously; the second hand ticks.
+fn clock_hand_angles(time: chrono::NaiveTime) -> (f64, f64, f64) {
+    use chrono::Timelike;
+    let seconds = time.num_seconds_from_midnight() as f64;
+    let turn = std::f64::consts::TAU;
+    (
+        (seconds / 43_200.0).fract() * turn,
+        (seconds / 3600.0).fract() * turn,
+        time.second() as f64 / 60.0 * turn,
+    )
+}
+
+/// Colors of a theme.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    /// Rain trails and the pulse glow.
+    pub rain: (f64, f64, f64),
+    /// Lead glyph of each rain stream.
+    pub lead: (f64, f64, f64),
+    /// Metric text, graphs and bars; `general.color` for themes that aren't presets.
+    pub text: (f64, f64, f64),
+    /// Centered banner text.
+    pub glow: (f64, f64, f64),
+}
+
+impl Palette {
+    pub fn for_theme(theme: &str, color: &str) -> Self {
+        match theme {
+            "calm" => Self { rain: (0.0, 0.8, 1.0), lead: (0.8, 0.9, 1.0), text: (0.0, 0.8, 1.0), glow: (0.0, 0.8, 1.0) },
+            "alert" => Self { rain: (1.0, 0.2, 0.2), lead: (1.0, 0.8, 0.8), text: (1.0, 0.2, 0.2), glow: (1.0, 0.2, 0.2) },
+            "high_contrast" => Self { rain: CLASSIC_GREEN, lead: (0.8, 1.0, 0.9), text: (1.0, 1.0, 1.0), glow: (1.0, 1.0, 1.0) },
+            "classic" => Self { rain: CLASSIC_GREEN, lead: (0.8, 1.0, 0.9), text: CLASSIC_GREEN, glow: CLASSIC_GREEN },
+            _ => Self {
+                text: parse_hex_color(color).unwrap_or(CLASSIC_GREEN),
+                ..Self::for_theme("classic", color)
+            },
+        }
+    }
+
+    /// Mix of this palette and `to`; `t` runs from 0.0 (this) to 1.0 (`to`).
+    pub fn blend(&self, to: &Palette, t: f64) -> Palette {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: (f64, f64, f64), b: (f64, f64, f64)| {
+            // Weighted sum rather than a + (b - a) * t, so both ends are exact
+            (a.0 * (1.0 - t) + b.0 * t, a.1 * (1.0 - t) + b.1 * t, a.2 * (1.0 - t) + b.2 * t)
+        };
+        Palette { rain: mix(self.rain, to.rain), lead: mix(self.lead, to.lead), text: mix(self.text, to.text), glow: mix(self.glow, to.glow) }
     }
 }
 
@@ -667,19 +1800,84 @@ fn parse_hex_color(hex: &str) -> Result<(f64, f64, f64)> {
     Ok((r, g, b))
 }
 
-fn format_bytes(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
+/// Splits a formatted value into its leading number and the rest ("45.2°C" -> ("45.2", "°C")).
+fn split_number(text: &str) -> Option<(&str, &str)> {
+    let end = text
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && (c == '-' || c == '+'))))
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    text[..end].parse::<f64>().ok().map(|_| text.split_at(end))
+}
+
+/// Rounds the leading number to `precision` digits and swaps the unit after it for `unit`.
+fn reshape_number(text: &str, precision: Option<usize>, unit: Option<&str>) -> String {
+    let Some((number, rest)) = split_number(text) else {
+        return text.to_string();
+    };
+    let number = match precision {
+        Some(digits) => format!("{:.*}", digits, number.parse::<f64>().unwrap_or_default()),
+        None => number.to_string(),
+    };
+    format!("{}{}", number, unit.unwrap_or(rest))
+}
+
+/// Fills {value}, {number}, {unit} and {label} in an item's format template.
+fn fill_template(template: &str, label: &str, value: &str) -> String {
+    let (number, unit) = split_number(value).unwrap_or((value, ""));
+    template
+        .replace("{value}", value)
+        .replace("{number}", number)
+        .replace("{unit}", unit.trim_start())
+        .replace("{label}", label)
+}
+
+/// Fills a network template once per active interface, joined like the default text.
+fn format_network_template(template: &str, label: &str, map: &HashMap<String, (f64, f64)>, bits: bool) -> String {
+    let mut keys: Vec<_> = map.keys().collect();
+    keys.sort();
+    let parts: Vec<String> = keys
+        .into_iter()
+        .filter_map(|iface| map.get(iface).filter(|(rx, tx)| *rx > 0.0 || *tx > 0.0).map(|rates| (iface, rates)))
+        .map(|(iface, (rx, tx))| {
+            let (rx, tx) = (format_rate(*rx, bits), format_rate(*tx, bits));
+            template
+                .replace("{value}", &format!("{}: ↓{} ↑{}", iface, rx, tx))
+                .replace("{iface}", iface)
+                .replace("{rx}", &rx)
+                .replace("{tx}", &tx)
+                .replace("{label}", label)
+        })
+        .collect();
+    if parts.is_empty() { "Idle".to_string() } else { parts.join(" | ") }
+}
+
+/// Formats a rate given in bytes per second, as bytes or (with `bits`) decimal bits.
+fn format_rate(bytes_per_sec: f64, bits: bool) -> String {
+    if bits {
+        let bits_per_sec = bytes_per_sec * 8.0;
+        return if bits_per_sec >= 1e9 {
+            format!("{:.1}Gb/s", bits_per_sec / 1e9)
+        } else if bits_per_sec >= 1e6 {
+            format!("{:.1}Mb/s", bits_per_sec / 1e6)
+        } else if bits_per_sec >= 1e3 {
+            format!("{:.1}kb/s", bits_per_sec / 1e3)
+        } else {
+            format!("{:.0}b/s", bits_per_sec)
+        };
+    }
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
     
-    if bytes >= GB {
-        format!("{:.1}GB/s", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1}MB/s", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1}KB/s", bytes as f64 / KB as f64)
+    if bytes_per_sec >= GB {
+        format!("{:.1}GB/s", bytes_per_sec / GB)
+    } else if bytes_per_sec >= MB {
+        format!("{:.1}MB/s", bytes_per_sec / MB)
+    } else if bytes_per_sec >= KB {
+        format!("{:.1}KB/s", bytes_per_sec / KB)
     } else {
-        format!("{}B/s", bytes)
+        format!("{:.0}B/s", bytes_per_sec)
     }
 }
 
@@ -688,27 +1886,273 @@ mod tests {
     use super::*;
     use std::time::Duration;
 
+    #[test]
+    fn test_month_weeks_start_on_monday() {
+        // October 2026 starts on a Thursday and ends on a Saturday
+        let weeks = month_weeks(chrono::NaiveDate::from_ymd_opt(2026, 10, 16).unwrap());
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0], [None, None, None, Some(1), Some(2), Some(3), Some(4)]);
+        assert_eq!(weeks[4], [Some(26), Some(27), Some(28), Some(29), Some(30), Some(31), None]);
+
+        // February 2021 fits exactly four weeks; a leap February has a 29th
+        assert_eq!(month_weeks(chrono::NaiveDate::from_ymd_opt(2021, 2, 1).unwrap()).len(), 4);
+        let leap = month_weeks(chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        assert_eq!(leap.last().unwrap()[3], Some(29));
+    }
+
+    #[test]
+    fn test_scroll_state_wraps_around() {
+        let mut scroll = ScrollState::default();
+        // Starts at the right edge, moves left by the step
+        assert_eq!(scroll.position("ticker", 100.0, 50.0, 0.0), 50.0);
+        assert_eq!(scroll.position("ticker", 100.0, 50.0, 10.0), 40.0);
+        // Once fully past the left edge it re-enters from beyond the right edge
+        assert_eq!(scroll.position("ticker", 100.0, 50.0, 141.0), 100.0);
+
+        scroll.position("cpu_usage", 80.0, 40.0, 1.0);
+        assert_eq!(scroll.len(), 2);
+        scroll.retain(&["cpu_usage"].into_iter().collect());
+        assert_eq!(scroll.len(), 1);
+    }
+
+    #[test]
+    fn test_notification_alpha_fades_over_second_half() {
+        assert_eq!(notification_alpha(0.0, 300.0), 1.0);
+        assert_eq!(notification_alpha(150.0, 300.0), 1.0);
+        assert!((notification_alpha(225.0, 300.0) - 0.5).abs() < 1e-9);
+        assert_eq!(notification_alpha(300.0, 300.0), 0.0);
+        assert_eq!(notification_alpha(400.0, 300.0), 0.0);
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        // Fits as-is
+        assert_eq!(shrink_to_fit(80.0, 100.0, 12.0), (12.0, false));
+        assert_eq!(shrink_to_fit(100.0, 100.0, 12.0), (12.0, false));
+        // Shrinks just enough
+        assert_eq!(shrink_to_fit(120.0, 100.0, 12.0), (10.0, false));
+        assert_eq!(shrink_to_fit(400.0, 300.0, 12.0), (9.0, false));
+        // Stops at the floor and ellipsizes the rest
+        assert_eq!(shrink_to_fit(200.0, 100.0, 12.0), (9.0, true));
+    }
+
+    #[test]
+    fn test_aligned_x() {
+        assert_eq!(aligned_x(100.0, 300.0, 120.0, "left"), 100.0);
+        assert_eq!(aligned_x(100.0, 300.0, 120.0, "center"), 190.0);
+        assert_eq!(aligned_x(100.0, 300.0, 120.0, "right"), 280.0);
+        assert_eq!(aligned_x(100.0, 300.0, 400.0, "right"), 100.0);
+    }
+
+    #[test]
+    fn test_clock_hand_angles() {
+        use std::f64::consts::{FRAC_PI_2, PI, TAU};
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        let (hour, minute, second) = clock_hand_angles(chrono::NaiveTime::from_hms_opt(3, 0, 0).unwrap());
+        assert!(close(hour, FRAC_PI_2) && close(minute, 0.0) && close(second, 0.0));
+
+        // Half past six: the hour hand is just past halfway between 6 and 7
+        let (hour, minute, second) = clock_hand_angles(chrono::NaiveTime::from_hms_opt(18, 30, 15).unwrap());
+        assert!(close(hour, PI + PI / 12.0 + 15.0 / 43_200.0 * TAU));
+        assert!(close(minute, PI + 15.0 / 3600.0 * TAU));
+        assert!(close(second, FRAC_PI_2));
+    }
+
     #[test]
     fn test_rain_manager_scale_density() {
+        let mut config_v1 = Config::default();
+        config_v1.cosmetics.realism_scale = 1;
         let mut manager_v1 = RainManager::new(1);
-        manager_v1.update(Duration::from_millis(16), 1920, 1080);
+        manager_v1.update(Duration::from_millis(16), 1920, 1080, &EffectSettings::new(&config_v1), &MetricData::default());
         let count_v1 = manager_v1.streams.len();
 
+        let mut config_v10 = Config::default();
+        config_v10.cosmetics.realism_scale = 10;
         let mut manager_v10 = RainManager::new(10);
-        manager_v10.update(Duration::from_millis(16), 1920, 1080);
+        manager_v10.update(Duration::from_millis(16), 1920, 1080, &EffectSettings::new(&config_v10), &MetricData::default());
         let count_v10 = manager_v10.streams.len();
 
         assert!(count_v10 > count_v1, "Scale 10 should have more streams than Scale 1: {} vs {}", count_v10, count_v1);
-        assert!(count_v10 <= 50, "Density should be capped at 50 for performance");
+        assert!(count_v10 <= 500, "Density should be capped at 500 for performance");
+    }
+
+    #[test]
+    fn test_reactive_rain_follows_load() {
+        let mut config = Config::default();
+        config.cosmetics.rain_mode = "reactive".to_string();
+        config.cosmetics.rain_speed = 1.0;
+        let mut busy = MetricData::default();
+        busy.values.insert(MetricId::CpuUsage, MetricValue::Float(20.0));
+        busy.values.insert(MetricId::GpuUtil, MetricValue::Float(100.0));
+        let mut idle = MetricData::default();
+        idle.values.insert(MetricId::CpuUsage, MetricValue::Float(0.0));
+
+        let mut loaded = RainManager::new(5);
+        let mut quiet = RainManager::new(5);
+        for _ in 0..100 {
+            loaded.update(Duration::from_millis(33), 1920, 1080, &EffectSettings::new(&config), &busy);
+            quiet.update(Duration::from_millis(33), 1920, 1080, &EffectSettings::new(&config), &idle);
+        }
+        assert!(loaded.load > 0.99, "GPU load drives the rain: {}", loaded.load);
+        assert_eq!(quiet.load, 0.0);
+
+        let (busy_streams, busy_speed, busy_brightness) = loaded.load_response(&EffectSettings::new(&config));
+        let (idle_streams, idle_speed, idle_brightness) = quiet.load_response(&EffectSettings::new(&config));
+        assert!(busy_streams > idle_streams);
+        assert_eq!(idle_streams, (quiet.streams.len() as f64 * IDLE_STREAM_SHARE).ceil() as usize);
+        assert!(busy_speed > idle_speed && busy_brightness > idle_brightness);
+
+        // Other modes ignore the load
+        config.cosmetics.rain_mode = "fall".to_string();
+        assert_eq!(quiet.load_response(&EffectSettings::new(&config)), (quiet.streams.len(), 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_rain_collision_with_metric_boxes() {
+        let mut config = Config::default();
+        config.cosmetics.rain_collision = "splash".to_string();
+        let mut manager = RainManager::seeded(5, 7);
+        manager.set_obstacles(vec![Bounds { x: 0, y: 500, width: 1920, height: 100 }]);
+
+        let mut most_splashes = 0;
+        for _ in 0..300 {
+            manager.update(Duration::from_millis(33), 1920, 1080, &EffectSettings::new(&config), &MetricData::default());
+            most_splashes = most_splashes.max(manager.splashes.len());
+            assert!(manager.splashes.iter().all(|s| s.y == 500.0 && s.age < SPLASH_FRAMES));
+        }
+        assert!(most_splashes > 0, "Streams hitting the box top should splash");
+
+        assert_eq!(manager.collision_visibility(100.0, 550.0, "splash"), 0.0);
+        assert_eq!(manager.collision_visibility(100.0, 480.0, "splash"), 1.0);
+        assert_eq!(manager.collision_visibility(100.0, 480.0, "fade"), 0.5);
+        assert_eq!(manager.collision_visibility(100.0, 700.0, "fade"), 1.0);
+        assert_eq!(manager.collision_visibility(100.0, 550.0, "none"), 1.0);
+    }
+
+    #[test]
+    fn test_theme_palette_fade() {
+        let classic = Palette::for_theme("classic", "#00FF41");
+        let alert = Palette::for_theme("alert", "#00FF41");
+        assert_eq!(Palette::for_theme("custom", "#0000FF").text, (0.0, 0.0, 1.0));
+        assert_eq!(Palette::for_theme("custom", "#0000FF").rain, classic.rain);
+
+        assert_eq!(classic.blend(&alert, 0.0), classic);
+        assert_eq!(classic.blend(&alert, 2.0), alert);
+        let half = classic.blend(&alert, 0.5);
+        assert!((half.rain.0 - 0.5).abs() < 1e-9 && (half.rain.1 - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_decode_frame_resolves_left_to_right() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let scrambled = |c: char| ('\u{FF66}'..='\u{FF9D}').contains(&c);
+
+        // Only the changed digit scrambles
+        let frame: Vec<char> = decode_frame("45.2%", "45.7%", 0.0, &mut rng).chars().collect();
+        assert_eq!(frame.iter().filter(|c| scrambled(**c)).count(), 1);
+        assert!(scrambled(frame[3]));
+        assert_eq!(decode_frame("45.2%", "45.7%", 1.0, &mut rng), "45.7%");
+
+        // A new length decodes everything but spaces, resolving from the left
+        let frame: Vec<char> = decode_frame("idle", "ab cd", 0.5, &mut rng).chars().collect();
+        assert_eq!(&frame[..3], &['a', 'b', ' ']);
+        assert!(scrambled(frame[3]) && scrambled(frame[4]));
+    }
+
+    #[test]
+    fn test_rain_avoids_excluded_columns() {
+        let config = Config::default();
+        let mut manager = RainManager::new(5);
+        // Left half blocked top to bottom, top band of the right half clear of glyphs
+        manager.set_exclusions(vec![
+            Bounds { x: 0, y: 0, width: 960, height: 1080 },
+            Bounds { x: 960, y: 0, width: 960, height: 360 },
+        ]);
+        manager.update(Duration::from_millis(16), 1920, 1080, &EffectSettings::new(&config), &MetricData::default());
+        assert!(!manager.streams.is_empty());
+        assert!(manager.streams.iter().all(|s| s.x >= 960.0), "No streams spawn in fully excluded columns");
+        assert!(manager.is_excluded(1200.0, 100.0));
+        assert!(!manager.is_excluded(1200.0, 400.0));
+    }
+
+    #[test]
+    fn test_rain_buffers_reused() {
+        let mut config = Config::default();
+        config.cosmetics.rain_speed = 1.0;
+        let mut manager = RainManager::new(5);
+        manager.update(Duration::from_millis(16), 1920, 1080, &EffectSettings::new(&config), &MetricData::default());
+        let glyphs = manager.streams[0].glyphs.as_ptr();
+
+        // Wrapping to the top and a full respawn both refill the existing buffers
+        manager.streams[0].y = 10000.0;
+        manager.update(Duration::from_millis(16), 1920, 1080, &EffectSettings::new(&config), &MetricData::default());
+        manager.set_exclusions(vec![Bounds { x: 0, y: 0, width: 100, height: 100 }]);
+        manager.update(Duration::from_millis(16), 1920, 1080, &EffectSettings::new(&config), &MetricData::default());
+        assert_eq!(manager.streams[0].glyphs.as_ptr(), glyphs);
+        assert!(manager.streams.iter().all(|s| (5..MAX_STREAM_GLYPHS).contains(&s.glyphs.len())));
+    }
+
+    #[test]
+    fn test_seeded_rain_is_reproducible() {
+        let mut config = Config::default();
+        config.cosmetics.rain_speed = 1.0;
+        let run = |seed: u64| {
+            let mut manager = RainManager::seeded(5, seed);
+            for _ in 0..100 {
+                manager.update(Duration::from_millis(33), 1920, 1080, &EffectSettings::new(&config), &MetricData::default());
+            }
+            manager.streams.iter().map(|s| (s.x, s.y, s.glyphs.clone())).collect::<Vec<_>>()
+        };
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
+
+    #[test]
+    fn test_format_rate_units() {
+        assert_eq!(format_rate(512.0, false), "512B/s");
+        assert_eq!(format_rate(1536.0, false), "1.5KB/s");
+        assert_eq!(format_rate(5.0 * 1024.0 * 1024.0, false), "5.0MB/s");
+        assert_eq!(format_rate(100.0, true), "800b/s");
+        assert_eq!(format_rate(12_500_000.0, true), "100.0Mb/s");
+    }
+
+    #[test]
+    fn test_value_format_templates() {
+        assert_eq!(reshape_number("45.27°C", Some(0), None), "45°C");
+        assert_eq!(reshape_number("42.0%", Some(2), Some(" pct")), "42.00 pct");
+        assert_eq!(reshape_number("6.5 GB", None, Some("")), "6.5");
+        assert_eq!(reshape_number("Idle", Some(1), None), "Idle");
+
+        assert_eq!(fill_template("{value} ({label})", "CPU", "42.0%"), "42.0% (CPU)");
+        assert_eq!(fill_template("{number} of 100 {unit}", "RAM", "6.5 GB"), "6.5 of 100 GB");
+        assert_eq!(fill_template("[{value}]", "WEATHER", "Clear sky"), "[Clear sky]");
+
+        let mut map = HashMap::new();
+        map.insert("wlan0".to_string(), (2048.0, 512.0));
+        map.insert("eth0".to_string(), (0.0, 0.0));
+        assert_eq!(format_network_template("↓{rx} ↑{tx}", "NET", &map, false), "↓2.0KB/s ↑512B/s");
+        assert_eq!(format_network_template("{iface} {rx}", "NET", &HashMap::new(), false), "Idle");
+    }
+
+    #[test]
+    fn test_ellipsize_modes() {
+        assert_eq!(ellipsize_mode(Some("start")), Some(pango::EllipsizeMode::Start));
+        assert_eq!(ellipsize_mode(Some("middle")), Some(pango::EllipsizeMode::Middle));
+        assert_eq!(ellipsize_mode(Some("end")), Some(pango::EllipsizeMode::End));
+        assert_eq!(ellipsize_mode(None), None);
     }
 
     #[test]
     fn test_rain_stream_reset() {
+        let mut config = Config::default();
+        config.cosmetics.realism_scale = 5;
+        config.cosmetics.rain_speed = 1.0;
         let mut manager = RainManager::new(5);
-        manager.update(Duration::from_millis(16), 1920, 1080);
+        manager.update(Duration::from_millis(16), 1920, 1080, &EffectSettings::new(&config), &MetricData::default());
         // Move stream far off bottom
         manager.streams[0].y = 10000.0;
-        manager.update(Duration::from_millis(16), 1920, 1080);
+        manager.update(Duration::from_millis(16), 1920, 1080, &EffectSettings::new(&config), &MetricData::default());
         assert!(manager.streams[0].y < 0.0, "Stream should have reset to top after falling below height");
     }
 }
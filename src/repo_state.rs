@@ -0,0 +1,56 @@
+//! Repository state checks for auto-commit.
+//! Committing on top of a rebase, merge, cherry-pick or bisect in progress (or on a
+//! detached HEAD) would tangle the user's work, so auto-commit only runs on a branch
+//! with no operation in progress.
+
+use git2::{Repository, RepositoryState};
+
+/// Why auto-commit must leave `repo` alone, or `None` if it's on a branch in a normal state.
+pub fn auto_commit_blocker(repo: &Repository) -> Option<String> {
+    let operation = match repo.state() {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some("a merge"),
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some("a revert"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("a cherry-pick"),
+        RepositoryState::Bisect => Some("a bisect"),
+        RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => Some("a rebase"),
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some("git am"),
+    };
+    if let Some(operation) = operation {
+        return Some(format!("{} is in progress", operation));
+    }
+    match repo.head_detached() {
+        Ok(true) => return Some("HEAD is detached".to_string()),
+        Ok(false) => {}
+        Err(e) => return Some(format!("HEAD is unreadable ({})", e.message())),
+    }
+    if repo.head().is_err() {
+        return Some("the branch has no commits yet".to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocks_unborn_detached_and_merging_repos() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        assert!(auto_commit_blocker(&repo).unwrap().contains("no commits"));
+
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let first = repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[]).unwrap();
+        assert_eq!(auto_commit_blocker(&repo), None);
+
+        // A MERGE_HEAD file is what marks a merge in progress
+        std::fs::write(repo.path().join("MERGE_HEAD"), format!("{}\n", first)).unwrap();
+        assert_eq!(auto_commit_blocker(&repo).as_deref(), Some("a merge is in progress"));
+        std::fs::remove_file(repo.path().join("MERGE_HEAD")).unwrap();
+
+        repo.set_head_detached(first).unwrap();
+        assert_eq!(auto_commit_blocker(&repo).as_deref(), Some("HEAD is detached"));
+    }
+}
@@ -0,0 +1,504 @@
+//! Collector scheduling.
+//! Runs each collector on its own refresh interval on a small worker pool, so
+//! a slow collector (weather HTTP fetch, git scan) never delays the others.
+//! Results are cached per collector and merged into every published frame.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use serde::Serialize;
+
+use crate::metrics::{MetricCollector, MetricId, MetricValue, ResourceGuard};
+use crate::stats::UsageStats;
+
+/// Maximum number of worker threads.
+const MAX_WORKERS: usize = 4;
+
+/// Collectors with their own long interval (web APIs, git scans) are only re-run by a
+/// forced refresh if their last run is at least this old, so repeated presses don't
+/// hammer rate-limited services.
+const FORCE_REFRESH_MIN_AGE: Duration = Duration::from_secs(30);
+
+type SharedCollector = Arc<Mutex<Box<dyn MetricCollector>>>;
+type Job = (usize, SharedCollector);
+type JobResult = (usize, HashMap<MetricId, MetricValue>);
+
+/// A registered collector as listed in the tray "Collectors" menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectorStatus {
+    pub id: String,
+    pub label: String,
+    pub enabled: bool,
+}
+
+/// Runtime state of a collector, reported by `ctl status`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CollectorHealth {
+    pub id: String,
+    pub enabled: bool,
+    /// Currently held back by the resource guard.
+    pub deferred: bool,
+    pub interval_ms: u64,
+    /// Seconds since the collector was last dispatched; `None` if it hasn't run yet.
+    pub last_run_secs: Option<u64>,
+    /// Values returned by its last completed run (0 usually means it is failing).
+    pub values: usize,
+}
+
+struct Slot {
+    id: String,
+    label: &'static str,
+    enabled: bool,
+    collector: SharedCollector,
+    /// Deferred by the resource guard while the CPU is busy.
+    heavy: bool,
+    /// Start of the current deferral, if the collector is being held back.
+    deferred_since: Option<Instant>,
+    interval: Duration,
+    next_due: Instant,
+    /// When the collector was last dispatched.
+    last_run: Option<Instant>,
+    in_flight: bool,
+    /// Values from the collector's last completed run.
+    latest: HashMap<MetricId, MetricValue>,
+}
+
+/// Dispatches due collectors to worker threads and merges their latest results.
+///
+/// Dropping the scheduler closes the job queue; workers exit once their current job finishes.
+pub struct CollectorScheduler {
+    slots: Vec<Slot>,
+    jobs: Sender<Job>,
+    results: Receiver<JobResult>,
+    guard: Option<Arc<ResourceGuard>>,
+}
+
+impl CollectorScheduler {
+    /// Builds a scheduler. Collectors without their own `interval()` (and no entry in
+    /// `overrides`, keyed by collector ID in ms) run every `base_interval`.
+    /// Collectors whose ID is in `disabled` are registered but never run.
+    ///
+    /// Slow collectors (those with an interval longer than `base_interval`: weather, git,
+    /// package checks) start one tick apart, so their refreshes never land on the same tick.
+    pub fn new(
+        collectors: Vec<Box<dyn MetricCollector>>,
+        base_interval: Duration,
+        overrides: &HashMap<String, u64>,
+        disabled: &[String],
+    ) -> Self {
+        let (jobs, job_rx) = unbounded::<Job>();
+        let (result_tx, results) = unbounded::<JobResult>();
+
+        let now = Instant::now();
+        let mut staggered: u32 = 0;
+        let slots: Vec<Slot> = collectors
+            .into_iter()
+            .map(|c| {
+                let interval = overrides
+                    .get(c.id())
+                    .map(|ms| Duration::from_millis(*ms))
+                    .or_else(|| c.interval())
+                    .unwrap_or(base_interval);
+                let enabled = !disabled.iter().any(|d| d == c.id());
+                let phase = if interval > base_interval {
+                    staggered += 1;
+                    (base_interval * (staggered - 1)).min(interval / 2)
+                } else {
+                    Duration::ZERO
+                };
+                log::debug!(
+                    "Collector '{}' runs every {:?} (phase {:?}){}",
+                    c.id(),
+                    interval,
+                    phase,
+                    if enabled { "" } else { " (disabled)" }
+                );
+                Slot {
+                    id: c.id().to_string(),
+                    label: c.label(),
+                    enabled,
+                    heavy: c.heavy(),
+                    deferred_since: None,
+                    collector: Arc::new(Mutex::new(c)),
+                    interval,
+                    next_due: now + phase,
+                    last_run: None,
+                    in_flight: false,
+                    latest: HashMap::new(),
+                }
+            })
+            .collect();
+
+        for _ in 0..slots.len().clamp(1, MAX_WORKERS) {
+            let job_rx = job_rx.clone();
+            let result_tx = result_tx.clone();
+            thread::spawn(move || {
+                for (index, collector) in job_rx {
+                    let values = match collector.lock() {
+                        Ok(mut c) => c.collect(),
+                        Err(_) => HashMap::new(),
+                    };
+                    if result_tx.send((index, values)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        Self { slots, jobs, results, guard: None }
+    }
+
+    /// Defers heavy collectors while `guard` reports high load. A collector is held back
+    /// for at most one of its own intervals, so it still runs under sustained load.
+    pub fn with_guard(mut self, guard: Arc<ResourceGuard>) -> Self {
+        self.guard = Some(guard);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Starts every collector that is due and not still running from a previous cycle.
+    pub fn dispatch_due(&mut self) {
+        let now = Instant::now();
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if !slot.enabled || slot.in_flight || now < slot.next_due {
+                continue;
+            }
+            if let (true, Some(guard)) = (slot.heavy, &self.guard) {
+                let waited = slot.deferred_since.map_or(Duration::ZERO, |t| now.duration_since(t));
+                if guard.should_defer(&slot.id) && waited < slot.interval {
+                    if slot.deferred_since.is_none() {
+                        log::debug!("Deferring collector '{}': CPU at {:.0}%", slot.id, guard.cpu_usage());
+                        slot.deferred_since = Some(now);
+                    }
+                    continue;
+                }
+            }
+            slot.deferred_since = None;
+            slot.in_flight = true;
+            slot.next_due = now + slot.interval;
+            slot.last_run = Some(now);
+            if self.jobs.send((index, slot.collector.clone())).is_err() {
+                slot.in_flight = false;
+            }
+        }
+    }
+
+    /// Collects finished results, waiting until `deadline` for jobs still in flight.
+    /// Collectors that miss the deadline keep running and are merged in a later cycle.
+    pub fn gather(&mut self, deadline: Instant) {
+        while self.slots.iter().any(|s| s.in_flight) {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match self.results.recv_timeout(timeout) {
+                Ok((index, values)) => {
+                    if let Some(slot) = self.slots.get_mut(index) {
+                        if let Ok(mut stats) = UsageStats::shared().lock() {
+                            stats.record_collector_run(&slot.id, is_failed_run(&values));
+                        }
+                        slot.in_flight = false;
+                        slot.latest = values;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// Forwards a wake event to every collector and makes them all due immediately.
+    /// Collectors busy on a worker are skipped; their next run is already under way.
+    pub fn wake(&mut self) {
+        let now = Instant::now();
+        for slot in &mut self.slots {
+            if let Ok(mut collector) = slot.collector.try_lock() {
+                collector.wake();
+            }
+            slot.next_due = now;
+        }
+    }
+
+    /// Makes every collector due now for an immediate refresh, expiring their internal caches.
+    /// Slow collectors that ran within `FORCE_REFRESH_MIN_AGE` keep their cached values.
+    pub fn force_refresh(&mut self, base_interval: Duration) {
+        let now = Instant::now();
+        for slot in &mut self.slots {
+            let rate_limited = slot.interval > base_interval
+                && slot.last_run.is_some_and(|t| now.duration_since(t) < FORCE_REFRESH_MIN_AGE);
+            if rate_limited {
+                continue;
+            }
+            if let Ok(mut collector) = slot.collector.try_lock() {
+                collector.wake();
+            }
+            slot.next_due = now;
+        }
+    }
+
+    /// Switches every collector with this ID on or off. A disabled collector's
+    /// values disappear from the next snapshot; re-enabling runs it right away.
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        let mut found = false;
+        for slot in self.slots.iter_mut().filter(|s| s.id == id) {
+            found = true;
+            slot.enabled = enabled;
+            if enabled {
+                slot.next_due = Instant::now();
+            }
+        }
+        found
+    }
+
+    /// Registered collectors, one entry per ID, in registration order.
+    pub fn statuses(&self) -> Vec<CollectorStatus> {
+        let mut statuses: Vec<CollectorStatus> = Vec::new();
+        for slot in &self.slots {
+            if !statuses.iter().any(|s| s.id == slot.id) {
+                statuses.push(CollectorStatus { id: slot.id.to_string(), label: slot.label.to_string(), enabled: slot.enabled });
+            }
+        }
+        statuses
+    }
+
+    /// Runtime state of every registered collector, in registration order.
+    pub fn health(&self) -> Vec<CollectorHealth> {
+        self.slots
+            .iter()
+            .map(|slot| CollectorHealth {
+                id: slot.id.to_string(),
+                enabled: slot.enabled,
+                deferred: slot.deferred_since.is_some(),
+                interval_ms: slot.interval.as_millis() as u64,
+                last_run_secs: slot.last_run.map(|t| t.elapsed().as_secs()),
+                values: slot.latest.len(),
+            })
+            .collect()
+    }
+
+    /// Latest values of all enabled collectors, merged in registration order
+    /// (later collectors win on conflicts, e.g. NVIDIA over AMD GPU values).
+    pub fn snapshot(&self) -> HashMap<MetricId, MetricValue> {
+        let mut merged = HashMap::new();
+        for slot in self.slots.iter().filter(|s| s.enabled) {
+            merged.extend(slot.latest.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        merged
+    }
+}
+
+/// Placeholders collectors report for a value they couldn't fetch.
+const FAILURE_VALUES: [&str; 3] = ["N/A", "ERR", "TIMEOUT"];
+
+/// A run that reported a failure placeholder. Returning nothing isn't one: collectors
+/// legitimately have nothing to report at times (no unread notifications, no headlines).
+fn is_failed_run(values: &HashMap<MetricId, MetricValue>) -> bool {
+    values.values().any(|v| matches!(v, MetricValue::String(s) if FAILURE_VALUES.contains(&s.as_str())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct CountingCollector {
+        id: &'static str,
+        runs: i64,
+        delay: Duration,
+        interval: Option<Duration>,
+    }
+
+    impl MetricCollector for CountingCollector {
+        fn id(&self) -> &'static str { self.id }
+        fn label(&self) -> &'static str { "Counting" }
+        fn interval(&self) -> Option<Duration> { self.interval }
+        fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+            thread::sleep(self.delay);
+            self.runs += 1;
+            let mut map = HashMap::new();
+            map.insert(MetricId::Custom(self.id.to_string()), MetricValue::Int(self.runs));
+            map
+        }
+    }
+
+    fn counting(id: &'static str, delay_ms: u64, interval: Option<Duration>) -> Box<dyn MetricCollector> {
+        Box::new(CountingCollector { id, runs: 0, delay: Duration::from_millis(delay_ms), interval })
+    }
+
+    #[test]
+    fn test_slow_collector_does_not_block_fast_ones() {
+        let collectors = vec![counting("fast", 0, None), counting("slow", 300, None)];
+        let mut scheduler = CollectorScheduler::new(collectors, Duration::from_millis(10), &HashMap::new(), &[]);
+
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(100));
+        let values = scheduler.snapshot();
+        assert_eq!(values.get(&MetricId::Custom("fast".to_string())), Some(&MetricValue::Int(1)));
+        assert!(!values.contains_key(&MetricId::Custom("slow".to_string())));
+
+        // The slow result shows up in a later cycle
+        thread::sleep(Duration::from_millis(300));
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(100));
+        let values = scheduler.snapshot();
+        assert_eq!(values.get(&MetricId::Custom("slow".to_string())), Some(&MetricValue::Int(1)));
+    }
+
+    #[test]
+    fn test_per_collector_intervals() {
+        let collectors = vec![counting("every_tick", 0, None), counting("hourly", 0, Some(Duration::from_secs(3600)))];
+        let mut overrides = HashMap::new();
+        overrides.insert("every_tick".to_string(), 1);
+        let mut scheduler = CollectorScheduler::new(collectors, Duration::from_secs(60), &overrides, &[]);
+
+        for _ in 0..3 {
+            scheduler.dispatch_due();
+            scheduler.gather(Instant::now() + Duration::from_millis(200));
+            thread::sleep(Duration::from_millis(5));
+        }
+        let values = scheduler.snapshot();
+        assert_eq!(values.get(&MetricId::Custom("every_tick".to_string())), Some(&MetricValue::Int(3)));
+        assert_eq!(values.get(&MetricId::Custom("hourly".to_string())), Some(&MetricValue::Int(1)));
+    }
+
+    #[test]
+    fn test_disabled_collectors_are_skipped() {
+        let collectors = vec![counting("gpu", 0, None), counting("net", 0, None)];
+        let mut scheduler = CollectorScheduler::new(collectors, Duration::from_millis(1), &HashMap::new(), &["gpu".to_string()]);
+
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(200));
+        let values = scheduler.snapshot();
+        assert!(!values.contains_key(&MetricId::Custom("gpu".to_string())));
+        assert_eq!(scheduler.statuses()[0], CollectorStatus { id: "gpu".to_string(), label: "Counting".to_string(), enabled: false });
+
+        assert!(scheduler.set_enabled("gpu", true));
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(200));
+        assert_eq!(scheduler.snapshot().get(&MetricId::Custom("gpu".to_string())), Some(&MetricValue::Int(1)));
+
+        // Disabling drops the cached values immediately
+        scheduler.set_enabled("net", false);
+        assert!(!scheduler.snapshot().contains_key(&MetricId::Custom("net".to_string())));
+    }
+
+    #[test]
+    fn test_force_refresh_spares_recent_slow_collectors() {
+        let collectors = vec![counting("fast", 0, None), counting("weather", 0, Some(Duration::from_secs(600)))];
+        let base = Duration::from_secs(60);
+        let mut scheduler = CollectorScheduler::new(collectors, base, &HashMap::new(), &[]);
+
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(200));
+        scheduler.force_refresh(base);
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(200));
+
+        let values = scheduler.snapshot();
+        assert_eq!(values.get(&MetricId::Custom("fast".to_string())), Some(&MetricValue::Int(2)));
+        assert_eq!(values.get(&MetricId::Custom("weather".to_string())), Some(&MetricValue::Int(1)));
+    }
+
+    #[test]
+    fn test_slow_collectors_are_staggered() {
+        let hourly = || Some(Duration::from_secs(3600));
+        let collectors = vec![counting("tick", 0, None), counting("git", 0, hourly()), counting("weather", 0, hourly())];
+        let base = Duration::from_millis(100);
+        let mut scheduler = CollectorScheduler::new(collectors, base, &HashMap::new(), &[]);
+
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(50));
+        let values = scheduler.snapshot();
+        assert!(values.contains_key(&MetricId::Custom("tick".to_string())));
+        assert!(values.contains_key(&MetricId::Custom("git".to_string())));
+        assert!(!values.contains_key(&MetricId::Custom("weather".to_string())));
+
+        // One tick later the second slow collector gets its turn
+        thread::sleep(base);
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(50));
+        assert!(scheduler.snapshot().contains_key(&MetricId::Custom("weather".to_string())));
+    }
+
+    #[derive(Debug)]
+    struct HeavyCollector(i64);
+
+    impl MetricCollector for HeavyCollector {
+        fn id(&self) -> &'static str { "git_delta" }
+        fn label(&self) -> &'static str { "Heavy" }
+        fn heavy(&self) -> bool { true }
+        fn collect(&mut self) -> HashMap<MetricId, MetricValue> {
+            self.0 += 1;
+            let mut map = HashMap::new();
+            map.insert(MetricId::CodeDelta, MetricValue::Int(self.0));
+            map
+        }
+    }
+
+    #[test]
+    fn test_heavy_collectors_deferred_under_load() {
+        let mut limits = crate::config::ResourceLimits::default();
+        limits.collector_thresholds.insert("git_delta".to_string(), 40.0);
+        let guard = Arc::new(ResourceGuard::from_config(&limits));
+        let collectors: Vec<Box<dyn MetricCollector>> = vec![Box::new(HeavyCollector(0)), counting("light", 0, None)];
+        let mut scheduler = CollectorScheduler::new(collectors, Duration::from_secs(60), &HashMap::new(), &[]).with_guard(guard.clone());
+
+        // 50% is below the global threshold but above git_delta's own
+        guard.record(50.0);
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(200));
+        let values = scheduler.snapshot();
+        assert!(!values.contains_key(&MetricId::CodeDelta));
+        assert!(values.contains_key(&MetricId::Custom("light".to_string())));
+
+        // The moving average takes a few quiet samples to drop below the threshold
+        for _ in 0..5 {
+            guard.record(10.0);
+        }
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(200));
+        assert_eq!(scheduler.snapshot().get(&MetricId::CodeDelta), Some(&MetricValue::Int(1)));
+    }
+
+    #[test]
+    fn test_loop_samples_feed_the_guard_without_cpu_collector() {
+        let guard = Arc::new(ResourceGuard::new(70.0));
+        let collectors: Vec<Box<dyn MetricCollector>> = vec![Box::new(HeavyCollector(0)), counting("light", 0, None)];
+        let mut scheduler = CollectorScheduler::new(collectors, Duration::from_secs(60), &HashMap::new(), &[]).with_guard(guard.clone());
+
+        // Nothing records load, so the loop's own sample is what defers the heavy collector
+        guard.sample_with(|| 95.0);
+        assert!(guard.should_throttle());
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(200));
+        assert!(!scheduler.snapshot().contains_key(&MetricId::CodeDelta));
+
+        for _ in 0..20 {
+            guard.sample_with(|| 5.0);
+        }
+        assert!(!guard.should_throttle());
+        scheduler.dispatch_due();
+        scheduler.gather(Instant::now() + Duration::from_millis(200));
+        assert_eq!(scheduler.snapshot().get(&MetricId::CodeDelta), Some(&MetricValue::Int(1)));
+
+        // A sample recorded by a CpuCollector since the last iteration stands in for the loop's
+        guard.record(5.0);
+        guard.sample_with(|| panic!("sampled twice in one tick"));
+        guard.sample_with(|| 5.0);
+    }
+
+    #[test]
+    fn test_failed_runs() {
+        let value = |text: &str| HashMap::from([(MetricId::Custom("x".to_string()), MetricValue::String(text.to_string()))]);
+        assert!(!is_failed_run(&HashMap::new()));
+        assert!(!is_failed_run(&value("21.5°C")));
+        assert!(is_failed_run(&value("N/A")));
+        assert!(is_failed_run(&value("TIMEOUT")));
+    }
+}
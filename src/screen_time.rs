@@ -0,0 +1,157 @@
+//! Screen-time tracker (opt-in via `screen_time.enabled`).
+//! Samples the focused window's WM_CLASS every few seconds and adds the time since
+//! the previous sample to that application. Today's totals are kept in
+//! `$XDG_DATA_HOME/matrix-overlay/screen-time.json`; window titles are never read.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use xcb::{x, Xid};
+
+/// Focused seconds per application class for one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub apps: BTreeMap<String, u64>,
+}
+
+impl DailyUsage {
+    pub fn new(date: NaiveDate) -> Self {
+        Self { date, apps: BTreeMap::new() }
+    }
+
+    /// `$XDG_DATA_HOME/matrix-overlay/screen-time.json`.
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::journal::data_dir()?.join("screen-time.json"))
+    }
+
+    /// Usage saved at `path`; a missing or unreadable file starts empty.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_else(|| Self::new(Local::now().date_naive()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create data directory")?;
+        }
+        fs::write(path, serde_json::to_string(self)?).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    /// Adds `secs` to `app`, starting a new day first if `now` is past this one.
+    pub fn add(&mut self, now: DateTime<Local>, app: &str, secs: u64) {
+        self.roll_over(now);
+        *self.apps.entry(app.to_string()).or_insert(0) += secs;
+    }
+
+    /// Starts a new, empty day if `now` is past this one.
+    pub fn roll_over(&mut self, now: DateTime<Local>) {
+        if self.date != now.date_naive() {
+            *self = Self::new(now.date_naive());
+        }
+    }
+
+    /// The application with the most focused time, ties going to the first by name.
+    pub fn top(&self) -> Option<(&str, u64)> {
+        self.apps.iter().fold(None, |best, (app, &secs)| match best {
+            Some((_, most)) if most >= secs => best,
+            _ => Some((app.as_str(), secs)),
+        })
+    }
+
+    pub fn total(&self) -> u64 {
+        self.apps.values().sum()
+    }
+}
+
+/// Hours and minutes, as "H:MM".
+pub fn format_duration(secs: u64) -> String {
+    format!("{}:{:02}", secs / 3600, secs / 60 % 60)
+}
+
+/// Reads the focused window's class through `_NET_ACTIVE_WINDOW`.
+pub struct ActiveWindow {
+    conn: xcb::Connection,
+    root: x::Window,
+    net_active_window: x::Atom,
+}
+
+impl std::fmt::Debug for ActiveWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ActiveWindow").field("root", &self.root).finish()
+    }
+}
+
+impl ActiveWindow {
+    pub fn connect() -> Result<Self> {
+        let (conn, screen_num) = xcb::Connection::connect(None).context("Failed to connect to X server")?;
+        let root = conn.get_setup().roots().nth(screen_num as usize).context("No X screen found")?.root();
+        let net_active_window = conn
+            .wait_for_reply(conn.send_request(&x::InternAtom { only_if_exists: false, name: b"_NET_ACTIVE_WINDOW" }))?
+            .atom();
+        Ok(Self { conn, root, net_active_window })
+    }
+
+    /// Class of the focused window (e.g. "firefox"), `None` if nothing has focus.
+    pub fn class(&self) -> Result<Option<String>> {
+        let active = self.conn.wait_for_reply(self.conn.send_request(&x::GetProperty {
+            delete: false,
+            window: self.root,
+            property: self.net_active_window,
+            r#type: x::ATOM_WINDOW,
+            long_offset: 0,
+            long_length: 1,
+        }))?;
+        let Some(&window) = active.value::<x::Window>().first().filter(|w| !w.is_none()) else {
+            return Ok(None);
+        };
+        let class = self.conn.wait_for_reply(self.conn.send_request(&x::GetProperty {
+            delete: false,
+            window,
+            property: x::ATOM_WM_CLASS,
+            r#type: x::ATOM_STRING,
+            long_offset: 0,
+            long_length: 256,
+        }))?;
+        Ok(parse_wm_class(class.value::<u8>()))
+    }
+}
+
+/// WM_CLASS holds "instance\0Class\0"; the class is the more readable of the two.
+pub fn parse_wm_class(raw: &[u8]) -> Option<String> {
+    let mut parts = raw.split(|&b| b == 0).map(|s| String::from_utf8_lossy(s).trim().to_string()).filter(|s| !s.is_empty());
+    let instance = parts.next();
+    parts.next().or(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_usage_accumulates_per_day() {
+        assert_eq!(parse_wm_class(b"navigator\0firefox\0"), Some("firefox".to_string()));
+        assert_eq!(parse_wm_class(b"xterm\0"), Some("xterm".to_string()));
+        assert_eq!(parse_wm_class(b""), None);
+
+        let morning = Local.with_ymd_and_hms(2026, 3, 10, 9, 0, 0).unwrap();
+        let mut usage = DailyUsage::new(morning.date_naive());
+        usage.add(morning, "firefox", 3000);
+        usage.add(morning, "Code", 4000);
+        usage.add(morning, "firefox", 1500);
+        assert_eq!(usage.top(), Some(("firefox", 4500)));
+        assert_eq!(format_duration(usage.total()), "2:21");
+
+        usage.add(morning + chrono::Duration::days(1), "Code", 5);
+        assert_eq!(usage.total(), 5);
+        assert_eq!(usage.top(), Some(("Code", 5)));
+    }
+}
@@ -0,0 +1,153 @@
+//! Screensaver hack mode.
+//! When started by xscreensaver or xsecurelock, render the full rain and
+//! metrics into the window they provide (`$XSCREENSAVER_WINDOW` or
+//! `-window-id <id>`) instead of creating overlay windows.
+//!
+//! Also the idle check behind the overlay's own takeover (`cosmetics.screensaver_after_min`).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use xcb::{x, XidNew};
+
+use crate::config::Config;
+use crate::effects;
+use crate::layout;
+use crate::metrics::SharedMetrics;
+use crate::render::Renderer;
+use crate::window::X11Present;
+
+/// Frame interval while the rain is animating (the overlay's own tick is `update_ms`).
+const RAIN_FRAME_MS: u64 = 33;
+
+/// Frame interval of the overlay during a screensaver takeover.
+pub const TAKEOVER_FRAME: Duration = Duration::from_millis(RAIN_FRAME_MS);
+
+/// Time since the last keyboard or mouse input, from the MIT-SCREEN-SAVER extension.
+pub fn idle_time(conn: &xcb::Connection) -> Result<Duration> {
+    let root = conn.get_setup().roots().next().context("No screen found")?.root();
+    let reply = conn
+        .wait_for_reply(conn.send_request(&xcb::screensaver::QueryInfo { drawable: x::Drawable::Window(root) }))
+        .context("MIT-SCREEN-SAVER extension unavailable")?;
+    Ok(Duration::from_millis(reply.ms_since_user_input() as u64))
+}
+
+/// True once `idle` reaches `cosmetics.screensaver_after_min` (never when it is 0).
+pub fn takeover_due(idle: Duration, config: &Config) -> bool {
+    let after = config.cosmetics.screensaver_after_min;
+    after > 0 && idle >= Duration::from_secs(after as u64 * 60)
+}
+
+/// Parses an X window ID as passed by screensaver daemons ("0x1c00007" or decimal).
+pub fn parse_xid(s: &str) -> Option<u32> {
+    let s = s.trim();
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+    .filter(|&id| id != 0)
+}
+
+/// Returns the window to draw into if we were launched as a screensaver hack.
+///
+/// xscreensaver passes `-window-id <id>` (and sets `$XSCREENSAVER_WINDOW`);
+/// xsecurelock only sets the environment variable.
+pub fn target_window(args: &[String]) -> Option<u32> {
+    if let Some(pos) = args.iter().position(|a| a == "-window-id" || a == "--window-id") {
+        return args.get(pos + 1).and_then(|id| parse_xid(id));
+    }
+    std::env::var("XSCREENSAVER_WINDOW").ok().and_then(|id| parse_xid(&id))
+}
+
+fn build_renderer(conn: &Arc<xcb::Connection>, window: x::Window, width: u16, height: u16, depth: u8, config: &Config) -> Result<Renderer> {
+    let target = X11Present::new(conn.clone(), window, width, height, config.general.pixmap_buffering).with_depth(depth);
+    let layout = layout::compute(&config.screens[0], width, height, config);
+    Renderer::new(width, height, 0, layout, config, Box::new(target))
+}
+
+/// Renders into `xid` until the window is destroyed or the daemon kills us.
+pub fn run(config: &Config, metrics: &Arc<Mutex<SharedMetrics>>, shutdown: &Arc<AtomicBool>, xid: u32) -> Result<()> {
+    let (conn, _) = xcb::Connection::connect(None).context("Failed to connect to X server")?;
+    let conn = Arc::new(conn);
+    let window = x::Window::new(xid);
+
+    let geometry = conn
+        .wait_for_reply(conn.send_request(&x::GetGeometry { drawable: x::Drawable::Window(window) }))
+        .with_context(|| format!("Screensaver window 0x{:x} is not accessible", xid))?;
+    let (mut width, mut height, depth) = (geometry.width(), geometry.height(), geometry.depth());
+    log::info!("Screensaver mode: drawing into window 0x{:x} ({}x{}, depth {})", xid, width, height, depth);
+
+    // Other clients (the daemon) keep their own event selections on this window
+    conn.send_and_check_request(&x::ChangeWindowAttributes {
+        window,
+        value_list: &[x::Cw::EventMask(x::EventMask::EXPOSURE | x::EventMask::STRUCTURE_NOTIFY)],
+    })
+    .context("Failed to select events on the screensaver window")?;
+
+    let mut renderer = build_renderer(&conn, window, width, height, depth, config)?;
+    let interval = if effects::is_animated(config.rain_mode_for(0)) {
+        Duration::from_millis(RAIN_FRAME_MS)
+    } else {
+        Duration::from_millis(config.general.update_ms)
+    };
+
+    'frames: while !shutdown.load(Ordering::Relaxed) {
+        let start = Instant::now();
+        if let Ok(shared) = metrics.lock() {
+            if let Err(e) = renderer.draw(config, &shared) {
+                log::warn!("Failed to draw screensaver frame: {:#}", e);
+            }
+        }
+        conn.flush()?;
+
+        loop {
+            match conn.poll_for_event()? {
+                Some(xcb::Event::X(x::Event::DestroyNotify(_))) => break 'frames,
+                Some(xcb::Event::X(x::Event::ConfigureNotify(ev))) if (ev.width(), ev.height()) != (width, height) => {
+                    width = ev.width();
+                    height = ev.height();
+                    renderer.release();
+                    renderer = build_renderer(&conn, window, width, height, depth, config)?;
+                }
+                Some(_) => {}
+                None if start.elapsed() >= interval => break,
+                None => thread::sleep(Duration::from_millis(5).min(interval.saturating_sub(start.elapsed()))),
+            }
+        }
+    }
+
+    log::info!("Screensaver window closed, exiting.");
+    renderer.release();
+    let _ = conn.flush();
+    shutdown.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_id_sources() {
+        assert_eq!(parse_xid("0x1c00007"), Some(0x1c00007));
+        assert_eq!(parse_xid("29360135"), Some(29360135));
+        assert_eq!(parse_xid("0"), None);
+        assert_eq!(parse_xid("window"), None);
+
+        let args = vec!["matrix-overlay".to_string(), "-window-id".to_string(), "0x2a".to_string()];
+        assert_eq!(target_window(&args), Some(0x2a));
+    }
+
+    #[test]
+    fn test_takeover_after_idle_minutes() {
+        let mut config = Config::default();
+        assert!(!takeover_due(Duration::from_secs(86_400), &config));
+
+        config.cosmetics.screensaver_after_min = 5;
+        assert!(!takeover_due(Duration::from_secs(299), &config));
+        assert!(takeover_due(Duration::from_secs(300), &config));
+    }
+}
@@ -0,0 +1,206 @@
+//! Headless server mode: `matrix-overlay serve --headless`.
+//! Runs only the collector stack, without an X connection, and exports the values over
+//! HTTP on `serve.listen` (Prometheus text at `/metrics`, the `ctl status` JSON at
+//! `/status.json`) and, with `serve.mqtt_prefix`, as MQTT messages. A desktop overlay
+//! shows a server's values through an `http_json` endpoint pointed at `/status.json`.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::ipc::IpcState;
+use crate::metrics::{spawn_metrics_thread, MetricValue};
+
+/// How long a client gets to send its request line.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The configuration with the collectors that need an X server (typing and screen time) turned off.
+pub fn headless_config(config: &Config) -> Config {
+    let mut config = config.clone();
+    if config.typing.enabled || config.screen_time.enabled {
+        log::info!("Headless: typing and screen-time trackers need an X server and are disabled");
+    }
+    config.typing.enabled = false;
+    config.screen_time.enabled = false;
+    config
+}
+
+/// Entry point for `serve`: starts the collectors and exporters, then serves HTTP until killed.
+pub fn run(config: &Config) -> Result<()> {
+    let config = headless_config(config);
+    let listener = TcpListener::bind(&config.serve.listen)
+        .with_context(|| format!("Failed to listen on {}", config.serve.listen))?;
+    log::info!("Serving metrics on http://{} (/metrics, /status.json)", config.serve.listen);
+
+    let (metrics, _shutdown, _metrics_handle, metrics_tx) = spawn_metrics_thread(&config);
+    // Same snapshot as `ctl status`; the control socket itself is left to a desktop overlay
+    // running on the same machine
+    let state = IpcState::new();
+    state.set_config(&config);
+    state.set_metrics(metrics);
+    state.set_metrics_sender(metrics_tx);
+
+    if let Some(prefix) = &config.serve.mqtt_prefix {
+        #[cfg(feature = "mqtt")]
+        spawn_mqtt_publisher(&config, prefix.clone(), state.clone());
+        #[cfg(not(feature = "mqtt"))]
+        log::warn!("serve.mqtt_prefix '{}' requires building with --features mqtt", prefix);
+    }
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(e) = handle_connection(stream, &state) {
+                    log::debug!("HTTP client error: {}", e);
+                }
+            }
+            Err(e) => log::warn!("HTTP accept failed: {}", e),
+        }
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, state: &IpcState) -> Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new(stream.try_clone()?).read_line(&mut line)?;
+    // "GET /metrics HTTP/1.1"; the headers that follow don't matter
+    let mut parts = line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (status, content_type, body) = if method != "GET" {
+        ("405 Method Not Allowed", "text/plain", "GET only\n".to_string())
+    } else {
+        route(path, state)
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+/// Status line, content type and body for a GET of `path` (query strings are ignored).
+fn route(path: &str, state: &IpcState) -> (&'static str, &'static str, String) {
+    match path.split('?').next().unwrap_or("") {
+        "/metrics" => {
+            let snapshot = state.snapshot();
+            ("200 OK", "text/plain; version=0.0.4", prometheus_text(&snapshot.metrics, snapshot.metrics_age_ms))
+        }
+        "/status.json" | "/" => {
+            let body = serde_json::to_string(&state.snapshot()).unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() }).to_string());
+            ("200 OK", "application/json", body)
+        }
+        _ => ("404 Not Found", "text/plain", "Not found; try /metrics or /status.json\n".to_string()),
+    }
+}
+
+/// Prometheus text exposition of every metric with a numeric view (formatted strings
+/// give their leading number), labelled with the metric ID.
+pub fn prometheus_text(metrics: &BTreeMap<String, MetricValue>, age_ms: u64) -> String {
+    let mut out = String::new();
+    out.push_str("# HELP matrix_overlay_metric Numeric value of each metric, as shown on the overlay.\n");
+    out.push_str("# TYPE matrix_overlay_metric gauge\n");
+    for (id, value) in metrics {
+        if let Some(number) = value.as_f64().filter(|v| v.is_finite()) {
+            let id = id.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            out.push_str(&format!("matrix_overlay_metric{{id=\"{}\"}} {}\n", id, number));
+        }
+    }
+    out.push_str("# HELP matrix_overlay_metrics_age_seconds Time since the last collection cycle.\n");
+    out.push_str("# TYPE matrix_overlay_metrics_age_seconds gauge\n");
+    out.push_str(&format!("matrix_overlay_metrics_age_seconds {}\n", age_ms as f64 / 1000.0));
+    out
+}
+
+/// MQTT payload of a value: strings as-is, everything else as JSON.
+pub fn mqtt_payload(value: &MetricValue) -> String {
+    match value {
+        MetricValue::String(s) => s.clone(),
+        other => serde_json::to_string(other).unwrap_or_default(),
+    }
+}
+
+/// Publishes every metric to `<prefix>/<metric_id>` on the `mqtt` broker once per
+/// `update_ms`, reconnecting after failures.
+#[cfg(feature = "mqtt")]
+fn spawn_mqtt_publisher(config: &Config, prefix: String, state: std::sync::Arc<IpcState>) {
+    use crate::mqtt::{ConnectOptions, MqttClient};
+    use std::time::Instant;
+
+    let Some(broker) = config.mqtt.clone() else {
+        return;
+    };
+    let interval = Duration::from_millis(config.general.update_ms);
+    let options = ConnectOptions {
+        client_id: format!("{}-serve", broker.client_id),
+        username: broker.username.clone(),
+        password: broker.password.clone(),
+    };
+    std::thread::spawn(move || loop {
+        let mut client = match MqttClient::connect(&broker.host, broker.port, &options, &[]) {
+            Ok(client) => client,
+            Err(e) => {
+                log::warn!("MQTT publisher: {}; retrying in 30s", e);
+                std::thread::sleep(Duration::from_secs(30));
+                continue;
+            }
+        };
+        log::info!("Publishing metrics to {}:{} under '{}/'", broker.host, broker.port, prefix);
+        'publish: loop {
+            for (id, value) in &state.snapshot().metrics {
+                if let Err(e) = client.publish(&format!("{}/{}", prefix, id), mqtt_payload(value).as_bytes()) {
+                    log::warn!("MQTT publisher: {}; reconnecting", e);
+                    break 'publish;
+                }
+            }
+            // Nothing may be published for a while (no metrics yet, or a long update_ms),
+            // so keep the connection alive while waiting for the next round
+            let published = Instant::now();
+            while published.elapsed() < interval {
+                if let Err(e) = client.keep_alive() {
+                    log::warn!("MQTT publisher: {}; reconnecting", e);
+                    break 'publish;
+                }
+                std::thread::sleep(interval.saturating_sub(published.elapsed()).min(Duration::from_secs(1)));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prometheus_text_and_routes() {
+        let mut metrics = BTreeMap::new();
+        metrics.insert("cpu_usage".to_string(), MetricValue::String("12.5%".to_string()));
+        metrics.insert("ram_total".to_string(), MetricValue::Int(16));
+        metrics.insert("weather_condition".to_string(), MetricValue::String("Clear".to_string()));
+        metrics.insert("say \"hi\"".to_string(), MetricValue::Float(1.0));
+
+        let text = prometheus_text(&metrics, 1500);
+        assert!(text.contains("matrix_overlay_metric{id=\"cpu_usage\"} 12.5\n"));
+        assert!(text.contains("matrix_overlay_metric{id=\"ram_total\"} 16\n"));
+        assert!(text.contains("matrix_overlay_metric{id=\"say \\\"hi\\\"\"} 1\n"));
+        assert!(!text.contains("weather_condition"));
+        assert!(text.contains("matrix_overlay_metrics_age_seconds 1.5\n"));
+
+        assert_eq!(mqtt_payload(&MetricValue::String("Clear".to_string())), "Clear");
+        assert_eq!(mqtt_payload(&MetricValue::Float(2.5)), "2.5");
+
+        let state = IpcState::new();
+        assert_eq!(route("/metrics?x=1", &state).0, "200 OK");
+        assert_eq!(route("/status.json", &state).1, "application/json");
+        assert_eq!(route("/nope", &state).0, "404 Not Found");
+    }
+}
@@ -0,0 +1,139 @@
+//! Per-metric smoothing and hysteresis.
+//! Applies the `metric_settings` smoothing options to freshly collected values
+//! so rapidly changing readings (CPU %, network rates) don't flicker every tick.
+//!
+//! Ties to ASD Requirement: values should update calmly and predictably.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::config::MetricSettings;
+use crate::metrics::{parse_leading_number, MetricId, MetricValue};
+
+/// Running state for a single numeric series.
+#[derive(Debug, Default)]
+struct SeriesState {
+    /// Last exponential moving average.
+    ema: Option<f64>,
+    /// Recent raw samples for the simple moving average.
+    window: VecDeque<f64>,
+    /// Last value actually published (for hysteresis).
+    published: Option<f64>,
+}
+
+impl SeriesState {
+    fn step(&mut self, raw: f64, settings: &MetricSettings) -> f64 {
+        let mut value = raw;
+
+        if let Some(size) = settings.moving_average.filter(|s| *s > 1) {
+            self.window.push_back(raw);
+            while self.window.len() > size {
+                self.window.pop_front();
+            }
+            value = self.window.iter().sum::<f64>() / self.window.len() as f64;
+        }
+
+        if let Some(alpha) = settings.smoothing {
+            let alpha = alpha.clamp(0.01, 1.0);
+            let ema = match self.ema {
+                Some(prev) => prev + alpha * (value - prev),
+                None => value,
+            };
+            self.ema = Some(ema);
+            value = ema;
+        }
+
+        // Hysteresis: hold the published value until it moves by at least the band.
+        match self.published {
+            Some(prev) if (value - prev).abs() < settings.hysteresis => prev,
+            _ => {
+                self.published = Some(value);
+                value
+            }
+        }
+    }
+}
+
+/// Applies smoothing state across collection cycles.
+#[derive(Debug, Default)]
+pub struct MetricSmoother {
+    series: HashMap<String, SeriesState>,
+}
+
+impl MetricSmoother {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops all accumulated state (e.g. after a config reload).
+    pub fn reset(&mut self) {
+        self.series.clear();
+    }
+
+    /// Smooths every value that has settings configured, in place.
+    pub fn apply(&mut self, settings: &HashMap<String, MetricSettings>, values: &mut HashMap<MetricId, MetricValue>) {
+        for (id, value) in values.iter_mut() {
+            let cfg = match settings.get(id.as_str()) {
+                Some(cfg) if cfg.is_active() => cfg,
+                _ => continue,
+            };
+
+            match value {
+                MetricValue::Float(v) => {
+                    *v = self.series.entry(id.as_str().to_string()).or_default().step(*v, cfg);
+                }
+                MetricValue::String(s) => {
+                    if let Some(raw) = parse_leading_number(s) {
+                        let smoothed = self.series.entry(id.as_str().to_string()).or_default().step(raw, cfg);
+                        *s = replace_leading_number(s, smoothed);
+                    }
+                }
+                MetricValue::NetworkMap(map) => {
+                    for (iface, (rx, tx)) in map.iter_mut() {
+                        let rx_key = format!("{}.{}.rx", id.as_str(), iface);
+                        let tx_key = format!("{}.{}.tx", id.as_str(), iface);
+                        *rx = self.series.entry(rx_key).or_default().step(*rx, cfg).max(0.0);
+                        *tx = self.series.entry(tx_key).or_default().step(*tx, cfg).max(0.0);
+                    }
+                }
+                MetricValue::Int(_) | MetricValue::Table(_) | MetricValue::None => {}
+            }
+        }
+    }
+}
+
+/// Replaces the numeric prefix of `s` with `value`, keeping the original precision and suffix.
+fn replace_leading_number(s: &str, value: f64) -> String {
+    let trimmed = s.trim_start();
+    let end = trimmed
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && (c == '-' || c == '+'))))
+        .map(|(i, _)| i)
+        .unwrap_or(trimmed.len());
+    let prefix = &trimmed[..end];
+    let precision = prefix.split_once('.').map(|(_, frac)| frac.len()).unwrap_or(0);
+    let sign = if prefix.starts_with('+') && value >= 0.0 { "+" } else { "" };
+    format!("{}{:.*}{}", sign, precision, value, &trimmed[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_leading_number_keeps_format() {
+        assert_eq!(replace_leading_number("12.3%", 7.26), "7.3%");
+        assert_eq!(replace_leading_number("45°C", 44.6), "45°C");
+        assert_eq!(replace_leading_number("+12 / -3", 10.0), "+10 / -3");
+    }
+
+    #[test]
+    fn test_ema_and_hysteresis() {
+        let settings = MetricSettings { smoothing: Some(0.5), hysteresis: 2.0, ..Default::default() };
+        let mut state = SeriesState::default();
+        assert_eq!(state.step(10.0, &settings), 10.0);
+        // EMA moves to 11.0, within the 2.0 band -> hold 10.0
+        assert_eq!(state.step(12.0, &settings), 10.0);
+        // EMA moves to 15.5 -> published
+        assert_eq!(state.step(20.0, &settings), 15.5);
+    }
+}
@@ -0,0 +1,152 @@
+//! Local usage statistics for the settings window's "Stats" tab: frames rendered,
+//! average frame time, collector runs and failures, uptime, and the values chosen by the
+//! adaptive density tuner. They only live in memory for the current run; nothing is
+//! written to disk or sent anywhere.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Runs and failed runs of one collector.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollectorRuns {
+    pub runs: u64,
+    pub failures: u64,
+}
+
+/// Settings picked by the adaptive density tuner at its last step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TunedSettings {
+    pub realism_scale: u32,
+    pub update_ms: u64,
+    /// Overlay CPU usage that step measured, in percent of one core.
+    pub cpu_percent: f64,
+    pub cpu_budget: f64,
+}
+
+#[derive(Debug)]
+pub struct UsageStats {
+    started: Instant,
+    frames: u64,
+    frame_time: Duration,
+    collectors: BTreeMap<String, CollectorRuns>,
+    auto_tune: Option<TunedSettings>,
+}
+
+impl Default for UsageStats {
+    fn default() -> Self {
+        Self { started: Instant::now(), frames: 0, frame_time: Duration::ZERO, collectors: BTreeMap::new(), auto_tune: None }
+    }
+}
+
+impl UsageStats {
+    /// Process-wide instance fed by the renderers and the collector scheduler.
+    pub fn shared() -> &'static Mutex<UsageStats> {
+        static SHARED: OnceLock<Mutex<UsageStats>> = OnceLock::new();
+        SHARED.get_or_init(|| Mutex::new(UsageStats::default()))
+    }
+
+    /// Counts one rendered frame (on any monitor) that took `took` to draw.
+    pub fn record_frame(&mut self, took: Duration) {
+        self.frames += 1;
+        self.frame_time += took;
+    }
+
+    /// Counts one completed run of collector `id`.
+    pub fn record_collector_run(&mut self, id: &str, failed: bool) {
+        let entry = self.collectors.entry(id.to_string()).or_default();
+        entry.runs += 1;
+        if failed {
+            entry.failures += 1;
+        }
+    }
+
+    pub fn record_auto_tune(&mut self, tuned: TunedSettings) {
+        self.auto_tune = Some(tuned);
+    }
+
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn average_frame_time(&self) -> Option<Duration> {
+        (self.frames > 0).then(|| self.frame_time.div_f64(self.frames as f64))
+    }
+
+    pub fn collectors(&self) -> &BTreeMap<String, CollectorRuns> {
+        &self.collectors
+    }
+
+    /// Lines for the Stats tab. `update_ms` is the configured refresh interval, which the
+    /// frame time is compared against to show how much headroom the hardware has.
+    pub fn summary(&self, update_ms: u64) -> Vec<String> {
+        let uptime = self.uptime().as_secs();
+        let mut lines = vec![
+            format!("Uptime: {}h {:02}m {:02}s", uptime / 3600, uptime / 60 % 60, uptime % 60),
+            format!("Frames rendered: {}", self.frames),
+        ];
+        match self.average_frame_time() {
+            Some(avg) => {
+                let ms = avg.as_secs_f64() * 1000.0;
+                let share = if update_ms > 0 { ms / update_ms as f64 * 100.0 } else { 0.0 };
+                lines.push(format!("Average frame time: {:.1} ms ({:.1}% of the {} ms update interval)", ms, share, update_ms));
+            }
+            None => lines.push("Average frame time: no frames yet".to_string()),
+        }
+        if let Some(tuned) = &self.auto_tune {
+            lines.push(format!(
+                "Auto-tune: density {}, update interval {} ms (overlay CPU {:.1}% of a {:.1}% budget)",
+                tuned.realism_scale, tuned.update_ms, tuned.cpu_percent, tuned.cpu_budget
+            ));
+        }
+        let failing: Vec<String> = self
+            .collectors
+            .iter()
+            .filter(|(_, runs)| runs.failures > 0)
+            .map(|(id, runs)| format!("  {}: {} of {} runs failed", id, runs.failures, runs.runs))
+            .collect();
+        let total_runs: u64 = self.collectors.values().map(|r| r.runs).sum();
+        lines.push(format!("Collector runs: {} ({} collectors)", total_runs, self.collectors.len()));
+        if failing.is_empty() {
+            lines.push("Collector errors: none".to_string());
+        } else {
+            lines.push("Collector errors:".to_string());
+            lines.extend(failing);
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_stats_summary() {
+        let mut stats = UsageStats::default();
+        assert_eq!(stats.average_frame_time(), None);
+        assert_eq!(stats.summary(1000)[2], "Average frame time: no frames yet");
+
+        stats.record_frame(Duration::from_millis(4));
+        stats.record_frame(Duration::from_millis(6));
+        stats.record_collector_run("open_meteo", true);
+        stats.record_collector_run("open_meteo", false);
+        stats.record_collector_run("cpu", false);
+        assert_eq!(stats.frames(), 2);
+        assert_eq!(stats.average_frame_time(), Some(Duration::from_millis(5)));
+        assert_eq!(stats.collectors()["open_meteo"], CollectorRuns { runs: 2, failures: 1 });
+
+        let summary = stats.summary(500);
+        assert_eq!(summary[1], "Frames rendered: 2");
+        assert_eq!(summary[2], "Average frame time: 5.0 ms (1.0% of the 500 ms update interval)");
+        assert_eq!(summary[3], "Collector runs: 3 (2 collectors)");
+        assert_eq!(summary[4..], ["Collector errors:".to_string(), "  open_meteo: 1 of 2 runs failed".to_string()]);
+
+        stats.record_auto_tune(TunedSettings { realism_scale: 6, update_ms: 1250, cpu_percent: 4.21, cpu_budget: 5.0 });
+        assert_eq!(stats.summary(500)[3], "Auto-tune: density 6, update interval 1250 ms (overlay CPU 4.2% of a 5.0% budget)");
+    }
+}
@@ -13,8 +13,10 @@ use crate::metrics::{
     SharedMetrics, MetricData, MetricId, MetricCollector,
     SysinfoManager, CpuCollector, MemoryCollector, UptimeLoadCollector,
     NetworkCollector, DiskCollector, HwmonCollector, NvidiaSmiCollector,
-    OpenMeteoCollector, DateCollector
+    DateCollector
 };
+#[cfg(feature = "weather")]
+use crate::metrics::OpenMeteoCollector;
 
 /// Spawns a thread that collects metrics and signals a redraw event at a fixed interval.
 ///
@@ -76,8 +78,9 @@ pub fn spawn_metrics_and_timer_thread(
         if required_metrics.contains(&MetricId::GpuTemp) || required_metrics.contains(&MetricId::GpuUtil) {
              collectors.push(Box::new(NvidiaSmiCollector::new()));
         }
+        #[cfg(feature = "weather")]
         if config.weather.enabled {
-            collectors.push(Box::new(OpenMeteoCollector::new(config.weather.lat, config.weather.lon, true)));
+            collectors.push(Box::new(OpenMeteoCollector::new(config.weather.lat, config.weather.lon, true).with_forecast(config.weather.forecast)));
         }
         collectors.push(Box::new(DateCollector));
 
@@ -1,24 +1,66 @@
 // src/tray.rs
-use anyhow::Result;
-use tray_icon::{Icon, TrayIconBuilder, menu::{Menu, MenuItem, PredefinedMenuItem, Submenu, CheckMenuItem}};
-use crate::config::Config;
+use crossbeam_channel::Receiver;
+
+// Everything but the menu IDs and `menu_clicks` needs the `tray` feature
+#[cfg(feature = "tray")]
+use {
+    std::cell::RefCell,
+    anyhow::Result,
+    tray_icon::{Icon, TrayIconBuilder, menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu, CheckMenuItem}},
+    crate::config::Config,
+    crate::scheduler::CollectorStatus,
+    crate::auto_commit::ScheduleStatus,
+};
 
 pub const MENU_QUIT_ID: &str = "quit";
 pub const MENU_RELOAD_ID: &str = "reload";
+pub const MENU_REFRESH_ID: &str = "refresh";
+pub const MENU_VIEW_LOGS_ID: &str = "view_logs";
 pub const MENU_EDIT_ID: &str = "edit";
 pub const MENU_THEME_CLASSIC: &str = "theme_classic";
 pub const MENU_THEME_CALM: &str = "theme_calm";
 pub const MENU_THEME_ALERT: &str = "theme_alert";
+pub const MENU_OPACITY_UP: &str = "opacity_up";
+pub const MENU_OPACITY_DOWN: &str = "opacity_down";
 pub const MENU_TOGGLE_AUTO_COMMIT: &str = "toggle_auto_commit";
 pub const MENU_TOGGLE_OLLAMA: &str = "toggle_ollama";
+pub const MENU_TOGGLE_HUD: &str = "toggle_hud";
 pub const MENU_CONFIG_GUI_ID: &str = "config_gui";
 pub const MENU_CONFIG_JSON_ID: &str = "config_json";
+/// Prefix of the per-collector check items ("collector:nvidia").
+pub const MENU_COLLECTOR_PREFIX: &str = "collector:";
+
+/// IDs of clicked tray menu items; never yields without the `tray` feature.
+pub fn menu_clicks() -> Receiver<String> {
+    #[cfg(feature = "tray")]
+    {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        std::thread::spawn(move || {
+            for event in MenuEvent::receiver().iter() {
+                if tx.send(event.id.as_ref().to_string()).is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+    #[cfg(not(feature = "tray"))]
+    crossbeam_channel::never()
+}
 
+#[cfg(feature = "tray")]
 pub struct SystemTray {
     _tray: tray_icon::TrayIcon,
     _menu: Menu,
+    collectors_menu: Submenu,
+    /// Collectors currently shown in `collectors_menu`.
+    collectors: RefCell<Vec<CollectorStatus>>,
+    auto_commit_menu: Submenu,
+    /// Lines currently shown in `auto_commit_menu`.
+    auto_commit_lines: RefCell<Vec<String>>,
 }
 
+#[cfg(feature = "tray")]
 impl SystemTray {
     pub fn new(config: &Config) -> Result<Self> {
         let icon = generate_icon()?;
@@ -31,6 +73,8 @@ impl SystemTray {
         menu.append(&config_submenu)?;
         
         menu.append(&MenuItem::with_id(MENU_RELOAD_ID, "Reload Overlay", true, None))?;
+        menu.append(&MenuItem::with_id(MENU_REFRESH_ID, "Refresh Metrics Now", true, None))?;
+        menu.append(&MenuItem::with_id(MENU_VIEW_LOGS_ID, "View Logs", true, None))?;
         menu.append(&PredefinedMenuItem::separator())?;
         
         // 2. Themes (Submenu restored for cleaner look)
@@ -39,6 +83,23 @@ impl SystemTray {
         theme_submenu.append(&MenuItem::with_id(MENU_THEME_CALM, "Calm Blue", true, None))?;
         theme_submenu.append(&MenuItem::with_id(MENU_THEME_ALERT, "Alert Red", true, None))?;
         menu.append(&theme_submenu)?;
+
+        let opacity_submenu = Submenu::new("Opacity", true);
+        opacity_submenu.append(&MenuItem::with_id(MENU_OPACITY_UP, "More Opaque (Ctrl+Alt+=)", true, None))?;
+        opacity_submenu.append(&MenuItem::with_id(MENU_OPACITY_DOWN, "More Transparent (Ctrl+Alt+-)", true, None))?;
+        menu.append(&opacity_submenu)?;
+
+        menu.append(&CheckMenuItem::with_id(
+            MENU_TOGGLE_HUD,
+            "Mini-HUD (Ctrl+Alt+H)",
+            true,
+            config.hud.enabled,
+            None
+        ))?;
+
+        // Filled in once the metrics thread has registered its collectors
+        let collectors_menu = Submenu::new("Collectors", true);
+        menu.append(&collectors_menu)?;
         
         menu.append(&PredefinedMenuItem::separator())?;
         
@@ -50,6 +111,10 @@ impl SystemTray {
             config.productivity.auto_commit_threshold > 0, 
             None
         ))?;
+
+        // Schedule and last results, filled in by `sync_auto_commit`
+        let auto_commit_menu = Submenu::new("Auto-Commit Schedule", true);
+        menu.append(&auto_commit_menu)?;
         
         menu.append(&CheckMenuItem::with_id(
             MENU_TOGGLE_OLLAMA, 
@@ -68,10 +133,51 @@ impl SystemTray {
             .with_icon(icon)
             .build()?;
 
-        Ok(Self { _tray: tray, _menu: menu })
+        Ok(Self {
+            _tray: tray,
+            _menu: menu,
+            collectors_menu,
+            collectors: RefCell::new(Vec::new()),
+            auto_commit_menu,
+            auto_commit_lines: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Rebuilds the "Collectors" submenu if the registered collectors or their state changed.
+    pub fn sync_collectors(&self, statuses: &[CollectorStatus]) -> Result<()> {
+        if self.collectors.borrow().as_slice() == statuses {
+            return Ok(());
+        }
+        while self.collectors_menu.remove_at(0).is_some() {}
+        for status in statuses {
+            self.collectors_menu.append(&CheckMenuItem::with_id(
+                format!("{}{}", MENU_COLLECTOR_PREFIX, status.id),
+                format!("{} ({})", status.label, status.id),
+                true,
+                status.enabled,
+                None,
+            ))?;
+        }
+        *self.collectors.borrow_mut() = statuses.to_vec();
+        Ok(())
+    }
+
+    /// Rebuilds the "Auto-Commit Schedule" submenu if the schedule or results changed.
+    pub fn sync_auto_commit(&self, status: &ScheduleStatus) -> Result<()> {
+        let lines = status.menu_lines();
+        if *self.auto_commit_lines.borrow() == lines {
+            return Ok(());
+        }
+        while self.auto_commit_menu.remove_at(0).is_some() {}
+        for line in &lines {
+            self.auto_commit_menu.append(&MenuItem::new(line, false, None))?;
+        }
+        *self.auto_commit_lines.borrow_mut() = lines;
+        Ok(())
     }
 }
 
+#[cfg(feature = "tray")]
 fn generate_icon() -> Result<Icon> {
     // Generate a simple 32x32 green square
     let width = 32;
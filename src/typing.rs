@@ -0,0 +1,181 @@
+//! Typing activity tracker (opt-in via `typing.enabled`).
+//! Counts key presses from XInput 2 raw events on the root window, which arrive
+//! whichever client has focus. Only the number and time of presses are kept, never
+//! which keys; today's count is saved to `$XDG_DATA_HOME/matrix-overlay/typing.json`
+//! so a restart doesn't reset it. Nothing leaves the machine.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use xcb::xinput;
+
+/// Window over which `wpm_now` is computed.
+pub const WPM_WINDOW: Duration = Duration::from_secs(60);
+
+/// Key presses per word, the usual typing-test convention.
+const KEYS_PER_WORD: f64 = 5.0;
+
+/// How often the daily count is written back to disk while typing.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The persisted part of the stats.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyCount {
+    pub date: NaiveDate,
+    pub keystrokes: u64,
+}
+
+/// Today's key press count and the press times inside the WPM window.
+#[derive(Debug)]
+pub struct TypingStats {
+    today: DailyCount,
+    recent: VecDeque<DateTime<Local>>,
+}
+
+impl TypingStats {
+    pub fn new(today: DailyCount) -> Self {
+        Self { today, recent: VecDeque::new() }
+    }
+
+    /// `$XDG_DATA_HOME/matrix-overlay/typing.json`.
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::journal::data_dir()?.join("typing.json"))
+    }
+
+    /// Stats saved at `path`; a missing or unreadable file starts from zero.
+    pub fn load(path: &Path) -> Self {
+        let today = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or(DailyCount { date: Local::now().date_naive(), keystrokes: 0 });
+        Self::new(today)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create data directory")?;
+        }
+        fs::write(path, serde_json::to_string(&self.today)?).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    pub fn record(&mut self, now: DateTime<Local>) {
+        self.roll_over(now);
+        self.today.keystrokes += 1;
+        self.recent.push_back(now);
+        self.prune(now);
+    }
+
+    pub fn keystrokes_today(&mut self, now: DateTime<Local>) -> u64 {
+        self.roll_over(now);
+        self.today.keystrokes
+    }
+
+    /// Words per minute over the last `WPM_WINDOW`.
+    pub fn wpm(&mut self, now: DateTime<Local>) -> f64 {
+        self.prune(now);
+        self.recent.len() as f64 / KEYS_PER_WORD * (60.0 / WPM_WINDOW.as_secs_f64())
+    }
+
+    fn roll_over(&mut self, now: DateTime<Local>) {
+        if self.today.date != now.date_naive() {
+            self.today = DailyCount { date: now.date_naive(), keystrokes: 0 };
+        }
+    }
+
+    fn prune(&mut self, now: DateTime<Local>) {
+        let window = chrono::Duration::from_std(WPM_WINDOW).unwrap_or_default();
+        while self.recent.front().is_some_and(|&t| now - t >= window) {
+            self.recent.pop_front();
+        }
+    }
+}
+
+/// Counts raw key presses into `stats` until `stop` is set, saving to `path` now and then.
+pub fn listen(stats: Arc<Mutex<TypingStats>>, stop: Arc<AtomicBool>, path: PathBuf) -> Result<()> {
+    let (conn, screen_num) = xcb::Connection::connect_with_extensions(None, &[xcb::Extension::Input], &[])
+        .context("Failed to connect to X server with XInput")?;
+    let version = conn
+        .wait_for_reply(conn.send_request(&xinput::XiQueryVersion { major_version: 2, minor_version: 2 }))
+        .context("Failed to query XInput version")?;
+    if version.major_version() < 2 {
+        bail!("XInput 2 is not available (server has {}.{})", version.major_version(), version.minor_version());
+    }
+    let root = conn.get_setup().roots().nth(screen_num as usize).context("No X screen found")?.root();
+    conn.send_and_check_request(&xinput::XiSelectEvents {
+        window: root,
+        masks: &[xinput::EventMaskBuf::new(xinput::Device::AllMaster, &[xinput::XiEventMask::RAW_KEY_PRESS])],
+    })
+    .context("Failed to select raw key events")?;
+    log::info!("Typing tracker: counting key presses");
+
+    let mut last_save = Instant::now();
+    let mut dirty = false;
+    while !stop.load(Ordering::Relaxed) {
+        match conn.poll_for_event()? {
+            Some(xcb::Event::Input(xinput::Event::RawKeyPress(_))) => {
+                if let Ok(mut stats) = stats.lock() {
+                    stats.record(Local::now());
+                }
+                dirty = true;
+            }
+            Some(_) => {}
+            None => thread::sleep(Duration::from_millis(50)),
+        }
+        if dirty && last_save.elapsed() >= SAVE_INTERVAL {
+            save_shared(&stats, &path);
+            last_save = Instant::now();
+            dirty = false;
+        }
+    }
+    if dirty {
+        save_shared(&stats, &path);
+    }
+    Ok(())
+}
+
+fn save_shared(stats: &Mutex<TypingStats>, path: &Path) {
+    if let Ok(stats) = stats.lock() {
+        if let Err(e) = stats.save(path) {
+            log::warn!("Typing tracker: {:#}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_wpm_window_and_day_rollover() {
+        let start = Local.with_ymd_and_hms(2026, 3, 10, 23, 59, 0).unwrap();
+        let mut stats = TypingStats::new(DailyCount { date: start.date_naive(), keystrokes: 100 });
+
+        // 50 presses in the last minute = 10 words
+        for i in 0..50 {
+            stats.record(start + chrono::Duration::milliseconds(i * 1000));
+        }
+        let now = start + chrono::Duration::seconds(50);
+        assert_eq!(stats.wpm(now), 10.0);
+        assert_eq!(stats.keystrokes_today(now), 150);
+
+        // Past midnight the count restarts; presses older than the window no longer count
+        let later = start + chrono::Duration::seconds(150);
+        assert_eq!(stats.keystrokes_today(later), 0);
+        assert_eq!(stats.wpm(later), 0.0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("typing.json");
+        stats.record(later);
+        stats.save(&path).unwrap();
+        assert_eq!(TypingStats::load(&path).today, DailyCount { date: later.date_naive(), keystrokes: 1 });
+    }
+}
@@ -0,0 +1,94 @@
+//! Unit system for displayed values (`general.units`).
+//! Collectors measure in metric and format temperatures, wind speed and precipitation
+//! through `UnitSystem::current()`, which the metrics thread sets from the config each
+//! time it builds its collectors.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnitSystem {
+    /// °C, km/h and mm.
+    #[default]
+    Metric,
+    /// °F, mph and inches.
+    Imperial,
+}
+
+static IMPERIAL: AtomicBool = AtomicBool::new(false);
+
+impl UnitSystem {
+    /// Parses a `general.units` value; anything other than "imperial" is metric.
+    pub fn from_name(name: &str) -> Self {
+        if name.eq_ignore_ascii_case("imperial") {
+            Self::Imperial
+        } else {
+            Self::Metric
+        }
+    }
+
+    /// The unit system collectors format with.
+    pub fn current() -> Self {
+        if IMPERIAL.load(Ordering::Relaxed) {
+            Self::Imperial
+        } else {
+            Self::Metric
+        }
+    }
+
+    pub fn make_current(self) {
+        IMPERIAL.store(self == Self::Imperial, Ordering::Relaxed);
+    }
+
+    /// A Celsius reading in this system's degrees, without the unit.
+    pub fn degrees(self, celsius: f64) -> f64 {
+        match self {
+            Self::Metric => celsius,
+            Self::Imperial => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    /// "45°C" / "113°F", with `decimals` digits after the point.
+    pub fn temperature(self, celsius: f64, decimals: usize) -> String {
+        let unit = match self {
+            Self::Metric => "°C",
+            Self::Imperial => "°F",
+        };
+        format!("{:.*}{}", decimals, self.degrees(celsius), unit)
+    }
+
+    /// "12.0 km/h" / "7.5 mph".
+    pub fn speed(self, kmh: f64) -> String {
+        match self {
+            Self::Metric => format!("{:.1} km/h", kmh),
+            Self::Imperial => format!("{:.1} mph", kmh / 1.609_344),
+        }
+    }
+
+    /// "1.2 mm" / "0.05 in".
+    pub fn precipitation(self, mm: f64) -> String {
+        match self {
+            Self::Metric => format!("{:.1} mm", mm),
+            Self::Imperial => format!("{:.2} in", mm / 25.4),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_conversions() {
+        assert_eq!(UnitSystem::from_name("Imperial"), UnitSystem::Imperial);
+        assert_eq!(UnitSystem::from_name("metric"), UnitSystem::Metric);
+        assert_eq!(UnitSystem::from_name("kelvin"), UnitSystem::Metric);
+
+        assert_eq!(UnitSystem::Metric.temperature(45.0, 0), "45°C");
+        assert_eq!(UnitSystem::Imperial.temperature(45.0, 0), "113°F");
+        assert_eq!(UnitSystem::Imperial.temperature(-40.0, 1), "-40.0°F");
+        assert_eq!(UnitSystem::Metric.speed(12.0), "12.0 km/h");
+        assert_eq!(UnitSystem::Imperial.speed(16.09344), "10.0 mph");
+        assert_eq!(UnitSystem::Metric.precipitation(1.2), "1.2 mm");
+        assert_eq!(UnitSystem::Imperial.precipitation(25.4), "1.00 in");
+    }
+}
@@ -3,10 +3,70 @@ use std::process::Command;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Short commit hash of the build ("-dirty" with uncommitted changes, "unknown" outside git).
+pub const GIT_SHA: &str = env!("MATRIX_OVERLAY_GIT_SHA");
+
+/// UTC build date (from `SOURCE_DATE_EPOCH` when set).
+pub const BUILD_DATE: &str = env!("MATRIX_OVERLAY_BUILD_DATE");
+
+pub const TARGET: &str = env!("MATRIX_OVERLAY_TARGET");
+
+/// Cargo profile ("debug" or "release").
+pub const PROFILE: &str = env!("MATRIX_OVERLAY_PROFILE");
+
 pub fn get_version() -> &'static str {
     VERSION
 }
 
+/// Optional cargo features compiled into this binary.
+pub fn enabled_features() -> Vec<&'static str> {
+    [
+        ("gui", cfg!(feature = "gui")),
+        ("tray", cfg!(feature = "tray")),
+        ("http", cfg!(feature = "http")),
+        ("weather", cfg!(feature = "weather")),
+        ("ollama", cfg!(feature = "ollama")),
+        ("mqtt", cfg!(feature = "mqtt")),
+        ("git", cfg!(feature = "git")),
+        ("nvml", cfg!(feature = "nvml")),
+        ("dbus", cfg!(feature = "dbus")),
+        ("kms", cfg!(feature = "kms")),
+        ("audio", cfg!(feature = "audio")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name)
+    .collect()
+}
+
+/// `--version` output: "matrix-overlay 0.1.1 (3f2c9a1b0e 2026-10-16)".
+pub fn version_line() -> String {
+    format!("matrix-overlay {} ({} {})", VERSION, GIT_SHA, BUILD_DATE)
+}
+
+/// `--version --verbose` output, also shown in the settings window's About tab.
+pub fn build_info() -> Vec<(&'static str, String)> {
+    let features = enabled_features();
+    vec![
+        ("Version", VERSION.to_string()),
+        ("Commit", GIT_SHA.to_string()),
+        ("Built", BUILD_DATE.to_string()),
+        ("Profile", PROFILE.to_string()),
+        ("Target", TARGET.to_string()),
+        ("Features", if features.is_empty() { "none".to_string() } else { features.join(", ") }),
+    ]
+}
+
+/// Prints `--version` (or with `--verbose`, the full build info).
+pub fn print_version(verbose: bool) {
+    println!("{}", version_line());
+    if verbose {
+        for (name, value) in build_info() {
+            println!("  {:<9} {}", format!("{}:", name.to_lowercase()), value);
+        }
+    }
+}
+
 /// Checks for other running instances of matrix-overlay or matrix_overlay.
 /// Returns a list of PIDs of other instances.
 pub fn detect_other_instances() -> Vec<u32> {
@@ -50,3 +110,17 @@ pub fn print_startup_info() {
     println!("Matrix Overlay v{} (PID: {})", VERSION, std::process::id());
     kill_other_instances();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_lists_features() {
+        assert!(version_line().starts_with(&format!("matrix-overlay {} (", VERSION)));
+        let info = build_info();
+        let features = &info.iter().find(|(name, _)| *name == "Features").unwrap().1;
+        assert_eq!(features.contains("weather"), cfg!(feature = "weather"));
+        assert!(!GIT_SHA.is_empty() && BUILD_DATE.len() == 10);
+    }
+}
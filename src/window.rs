@@ -6,8 +6,10 @@ use xcb::randr;
 use xcb::x;
 use xcb::shape;
 use xcb::Xid;
+use std::sync::Arc;
 use cairo::{ImageSurface, Format, Context as CairoContext};
-use crate::config::Config;
+use crate::config::{Config, Panel};
+use crate::present::Present;
 
 /// Represents a physical monitor detected via RandR.
 #[derive(Debug, Clone)]
@@ -146,7 +148,7 @@ pub fn detect_monitors(conn: &xcb::Connection) -> Result<Vec<Monitor>> {
 /// # Verification
 /// Use `xwininfo -id <WINDOW_ID>` to verify that "Absolute upper-left X" and "Absolute upper-left Y"
 /// match the monitor's RandR position exactly (e.g., 0,0 or 1920,0), without extra offsets.
-pub fn create_overlay_window(conn: &xcb::Connection, monitor: &Monitor, _config: &Config) -> Result<x::Window> {
+pub fn create_overlay_window(conn: &xcb::Connection, monitor: &Monitor, profile: WmProfile) -> Result<x::Window> {
     let setup = conn.get_setup();
     let screen = setup.roots().next().context("No screen found")?;
 
@@ -193,7 +195,7 @@ pub fn create_overlay_window(conn: &xcb::Connection, monitor: &Monitor, _config:
         value_list: &[
             x::Cw::BackPixel(0x00000000),
             x::Cw::BorderPixel(0),
-            x::Cw::OverrideRedirect(false),
+            x::Cw::OverrideRedirect(profile.override_redirect()),
             x::Cw::EventMask(x::EventMask::EXPOSURE | x::EventMask::KEY_PRESS),
             x::Cw::Colormap(colormap),
         ],
@@ -202,6 +204,227 @@ pub fn create_overlay_window(conn: &xcb::Connection, monitor: &Monitor, _config:
     Ok(window)
 }
 
+/// Window manager families with their own layering strategy (`general.wm_profile`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WmProfile {
+    /// Mutter and anything unrecognized: a managed `_NET_WM_WINDOW_TYPE_DESKTOP` window.
+    Gnome,
+    /// KWin: a managed, undecorated normal window in the "keep below" layer. Plasma's own
+    /// desktop window is also of the desktop type and would paint its wallpaper over ours.
+    Kde,
+    /// Xfwm4: same as KDE, for the same reason with xfdesktop.
+    Xfce,
+    /// i3 tiles every managed window, so the overlay is override-redirect (unmanaged) and
+    /// lowered to the bottom of the stack; nothing else draws a desktop there.
+    I3,
+}
+
+impl WmProfile {
+    /// Profile for a `general.wm_profile` value; `None` for "auto".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "gnome" => Some(Self::Gnome),
+            "kde" => Some(Self::Kde),
+            "xfce" => Some(Self::Xfce),
+            "i3" => Some(Self::I3),
+            _ => None,
+        }
+    }
+
+    /// Profile for the name a window manager advertises in `_NET_WM_NAME`.
+    pub fn from_wm_name(wm_name: &str) -> Self {
+        let name = wm_name.to_ascii_lowercase();
+        if name.contains("kwin") {
+            Self::Kde
+        } else if name.contains("xfwm") {
+            Self::Xfce
+        } else if name == "i3" || name.starts_with("i3 ") {
+            Self::I3
+        } else {
+            Self::Gnome
+        }
+    }
+
+    /// The configured profile, or the one matching the running window manager.
+    pub fn resolve(conn: &xcb::Connection, config: &Config) -> Self {
+        if let Some(profile) = Self::from_name(&config.general.wm_profile) {
+            return profile;
+        }
+        match running_wm_name(conn) {
+            Ok(Some(name)) => {
+                let profile = Self::from_wm_name(&name);
+                log::info!("Window manager '{}': using the {:?} layering profile", name, profile);
+                profile
+            }
+            Ok(None) => {
+                log::info!("No EWMH window manager detected; using the Gnome layering profile");
+                Self::Gnome
+            }
+            Err(e) => {
+                log::warn!("Window manager detection failed ({}); using the Gnome layering profile", e);
+                Self::Gnome
+            }
+        }
+    }
+
+    pub fn override_redirect(self) -> bool {
+        self == Self::I3
+    }
+
+    /// `_NET_WM_WINDOW_TYPE` to request.
+    fn window_type(self) -> &'static str {
+        match self {
+            Self::Gnome | Self::I3 => "_NET_WM_WINDOW_TYPE_DESKTOP",
+            Self::Kde | Self::Xfce => "_NET_WM_WINDOW_TYPE_NORMAL",
+        }
+    }
+
+    /// Whether to ask the WM to drop the title bar and borders (`_MOTIF_WM_HINTS`);
+    /// desktop-type windows are never decorated.
+    fn undecorated(self) -> bool {
+        matches!(self, Self::Kde | Self::Xfce)
+    }
+}
+
+/// `_NET_WM_NAME` of the window manager, found through `_NET_SUPPORTING_WM_CHECK`.
+pub fn running_wm_name(conn: &xcb::Connection) -> Result<Option<String>> {
+    let root = conn.get_setup().roots().next().context("No screen found")?.root();
+    let intern = |name: &[u8]| conn.send_request(&x::InternAtom { only_if_exists: false, name });
+    let (check, wm_name, utf8) = (intern(b"_NET_SUPPORTING_WM_CHECK"), intern(b"_NET_WM_NAME"), intern(b"UTF8_STRING"));
+    let (check, wm_name, utf8) = (conn.wait_for_reply(check)?.atom(), conn.wait_for_reply(wm_name)?.atom(), conn.wait_for_reply(utf8)?.atom());
+
+    let reply = conn.wait_for_reply(conn.send_request(&x::GetProperty {
+        delete: false,
+        window: root,
+        property: check,
+        r#type: x::ATOM_WINDOW,
+        long_offset: 0,
+        long_length: 1,
+    }))?;
+    let Some(&wm_window) = reply.value::<x::Window>().first().filter(|w| !w.is_none()) else {
+        return Ok(None);
+    };
+    let name = conn.wait_for_reply(conn.send_request(&x::GetProperty {
+        delete: false,
+        window: wm_window,
+        property: wm_name,
+        r#type: utf8,
+        long_offset: 0,
+        long_length: 64,
+    }))?;
+    let name = String::from_utf8_lossy(name.value::<u8>()).trim_end_matches('\0').trim().to_string();
+    Ok((!name.is_empty()).then_some(name))
+}
+
+/// Area of `monitor` taken by a panel bar docked to `panel.edge`.
+pub fn panel_area(monitor: &Monitor, panel: &Panel) -> Monitor {
+    let size = panel.size.min(if panel.is_horizontal() { monitor.height } else { monitor.width });
+    let mut area = monitor.clone();
+    match panel.edge.as_str() {
+        "bottom" => {
+            area.y = monitor.y + (monitor.height - size) as i16;
+            area.height = size;
+        }
+        "left" => area.width = size,
+        "right" => {
+            area.x = monitor.x + (monitor.width - size) as i16;
+            area.width = size;
+        }
+        _ => area.height = size,
+    }
+    area
+}
+
+/// `_NET_WM_STRUT_PARTIAL` reserving `area` (a panel docked to `edge`) on a root window of
+/// `root_width` x `root_height`. Struts are measured from the edges of the whole X screen,
+/// with start/end coordinates limiting them to the panel's own monitor.
+fn strut_partial(area: &Monitor, edge: &str, root_width: u16, root_height: u16) -> [u32; 12] {
+    let (x, y) = (area.x.max(0) as u32, area.y.max(0) as u32);
+    let (w, h) = (area.width as u32, area.height as u32);
+    let mut strut = [0u32; 12];
+    match edge {
+        "bottom" => {
+            strut[3] = (root_height as u32).saturating_sub(y);
+            strut[10] = x;
+            strut[11] = x + w - 1;
+        }
+        "left" => {
+            strut[0] = x + w;
+            strut[4] = y;
+            strut[5] = y + h - 1;
+        }
+        "right" => {
+            strut[1] = (root_width as u32).saturating_sub(x);
+            strut[6] = y;
+            strut[7] = y + h - 1;
+        }
+        _ => {
+            strut[2] = y + h;
+            strut[8] = x;
+            strut[9] = x + w - 1;
+        }
+    }
+    strut
+}
+
+/// Makes `win` a dock covering `area` and reserves its space, so maximized windows stop
+/// short of the panel instead of covering it.
+pub fn setup_panel_properties(conn: &xcb::Connection, win: x::Window, area: &Monitor, edge: &str) -> Result<()> {
+    let atom_names = [
+        "_NET_WM_WINDOW_TYPE",
+        "_NET_WM_WINDOW_TYPE_DOCK",
+        "_NET_WM_STATE",
+        "_NET_WM_STATE_STICKY",
+        "_NET_WM_STATE_SKIP_TASKBAR",
+        "_NET_WM_STATE_SKIP_PAGER",
+        "_NET_WM_STRUT",
+        "_NET_WM_STRUT_PARTIAL",
+    ];
+    let cookies: Vec<_> = atom_names
+        .iter()
+        .map(|name| conn.send_request(&x::InternAtom { only_if_exists: false, name: name.as_bytes() }))
+        .collect();
+    let mut atoms = Vec::with_capacity(atom_names.len());
+    for cookie in cookies {
+        atoms.push(conn.wait_for_reply(cookie)?.atom());
+    }
+
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: win,
+        property: atoms[0],
+        r#type: x::ATOM_ATOM,
+        data: &[atoms[1]],
+    });
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: win,
+        property: atoms[2],
+        r#type: x::ATOM_ATOM,
+        data: &atoms[3..6],
+    });
+
+    let screen = conn.get_setup().roots().next().context("No screen found")?;
+    let strut = strut_partial(area, edge, screen.width_in_pixels(), screen.height_in_pixels());
+    // Window managers predating _NET_WM_STRUT_PARTIAL only read the first four values
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: win,
+        property: atoms[6],
+        r#type: x::ATOM_CARDINAL,
+        data: &strut[..4],
+    });
+    conn.send_request(&x::ChangeProperty {
+        mode: x::PropMode::Replace,
+        window: win,
+        property: atoms[7],
+        r#type: x::ATOM_CARDINAL,
+        data: &strut,
+    });
+    log::info!("Panel docked to the {} edge of '{}' ({}x{})", edge, area.name, area.width, area.height);
+    Ok(())
+}
+
 /// Configures EWMH properties for the overlay window.
 ///
 /// # Mutter / GNOME 42.9 X11 Behavior
@@ -233,16 +456,19 @@ pub fn create_overlay_window(conn: &xcb::Connection, monitor: &Monitor, _config:
 /// 1. **Dual-Monitor**: eDP primary + HDMI.
 /// 2. **Icon Covering**: Ensure no icon covering on both screens.
 /// 3. **Stability**: Test for stable positioning at 120Hz/60Hz.
-pub fn setup_ewmh_properties(conn: &xcb::Connection, win: x::Window) -> Result<()> {
+///
+/// Other window managers get the window type and decorations of their `WmProfile`.
+pub fn setup_ewmh_properties(conn: &xcb::Connection, win: x::Window, profile: WmProfile) -> Result<()> {
     // Intern atoms
     let atom_names = [
         "_NET_WM_WINDOW_TYPE",
-        "_NET_WM_WINDOW_TYPE_DESKTOP",
+        profile.window_type(),
         "_NET_WM_STATE",
         "_NET_WM_STATE_BELOW",
         "_NET_WM_STATE_STICKY",
         "_NET_WM_STATE_SKIP_TASKBAR",
         "_NET_WM_STATE_SKIP_PAGER",
+        "_MOTIF_WM_HINTS",
     ];
 
     let cookies: Vec<_> = atom_names
@@ -261,22 +487,34 @@ pub fn setup_ewmh_properties(conn: &xcb::Connection, win: x::Window) -> Result<(
     }
 
     let net_wm_window_type = atoms[0];
-    let net_wm_window_type_desktop = atoms[1];
+    let net_wm_window_type_value = atoms[1];
     let net_wm_state = atoms[2];
     let net_wm_state_below = atoms[3];
     let net_wm_state_sticky = atoms[4];
     let net_wm_state_skip_taskbar = atoms[5];
     let net_wm_state_skip_pager = atoms[6];
+    let motif_wm_hints = atoms[7];
 
-    // Set _NET_WM_WINDOW_TYPE = [_NET_WM_WINDOW_TYPE_DESKTOP]
+    // Set _NET_WM_WINDOW_TYPE = [_NET_WM_WINDOW_TYPE_DESKTOP] (or NORMAL, per profile)
     conn.send_request(&x::ChangeProperty {
         mode: x::PropMode::Replace,
         window: win,
         property: net_wm_window_type,
         r#type: x::ATOM_ATOM,
-        data: &[net_wm_window_type_desktop],
+        data: &[net_wm_window_type_value],
     });
 
+    if profile.undecorated() {
+        // flags = MWM_HINTS_DECORATIONS, decorations = 0
+        conn.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window: win,
+            property: motif_wm_hints,
+            r#type: motif_wm_hints,
+            data: &[2u32, 0, 0, 0, 0],
+        });
+    }
+
     // Set _NET_WM_STATE = [BELOW, STICKY, SKIP_TASKBAR, SKIP_PAGER]
     let states = [
         net_wm_state_below,
@@ -296,6 +534,45 @@ pub fn setup_ewmh_properties(conn: &xcb::Connection, win: x::Window) -> Result<(
     Ok(())
 }
 
+/// Lowest `cosmetics.window_opacity`; below this the overlay would be practically invisible.
+pub const MIN_WINDOW_OPACITY: f64 = 0.1;
+
+/// Change per tray item or hotkey press.
+pub const WINDOW_OPACITY_STEP: f64 = 0.1;
+
+/// `current` moved by `delta`, kept within `MIN_WINDOW_OPACITY..=1.0` and rounded to a
+/// whole step so repeated presses don't accumulate float error.
+pub fn step_opacity(current: f64, delta: f64) -> f64 {
+    (((current + delta) / WINDOW_OPACITY_STEP).round() * WINDOW_OPACITY_STEP).clamp(MIN_WINDOW_OPACITY, 1.0)
+}
+
+/// `_NET_WM_WINDOW_OPACITY` value for `opacity`: 0xFFFFFFFF is fully opaque.
+fn opacity_cardinal(opacity: f64) -> u32 {
+    (opacity.clamp(0.0, 1.0) * u32::MAX as f64).round() as u32
+}
+
+/// Sets the compositor opacity of `window`. Full opacity removes the property, which is
+/// how compositors expect an opaque window to be marked. Without a compositor this has no effect.
+pub fn set_window_opacity(conn: &xcb::Connection, window: x::Window, opacity: f64) -> Result<()> {
+    let cookie = conn.send_request(&x::InternAtom {
+        only_if_exists: false,
+        name: b"_NET_WM_WINDOW_OPACITY",
+    });
+    let property = conn.wait_for_reply(cookie)?.atom();
+    if opacity >= 1.0 {
+        conn.send_request(&x::DeleteProperty { window, property });
+    } else {
+        conn.send_request(&x::ChangeProperty {
+            mode: x::PropMode::Replace,
+            window,
+            property,
+            r#type: x::ATOM_CARDINAL,
+            data: &[opacity_cardinal(opacity)],
+        });
+    }
+    Ok(())
+}
+
 /// Configures the window input shape to be empty, allowing click-through.
 /// Uses the XShape extension to set the Input region to an empty list of rectangles.
 pub fn setup_input_shape(conn: &xcb::Connection, window: x::Window) -> Result<()> {
@@ -311,7 +588,9 @@ pub fn setup_input_shape(conn: &xcb::Connection, window: x::Window) -> Result<()
     Ok(())
 }
 
-/// Manages an offscreen Cairo surface for double-buffered rendering.
+/// Client-side back surface that Cairo draws frames into.
+///
+/// Finished frames are handed to a `Present` target (see `crate::present`).
 pub struct OffscreenBuffer {
     surface: ImageSurface,
     width: u16,
@@ -325,29 +604,147 @@ impl OffscreenBuffer {
         Ok(Self { surface, width, height })
     }
 
+    /// The back surface that frames are drawn into.
+    pub fn surface(&self) -> &ImageSurface {
+        &self.surface
+    }
+
+    pub fn surface_mut(&mut self) -> &mut ImageSurface {
+        &mut self.surface
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
     pub fn context(&self) -> Result<CairoContext> {
         CairoContext::new(&self.surface).map_err(|e| anyhow::anyhow!("Failed to create Cairo context: {}", e))
     }
+}
 
-    /// Uploads the offscreen buffer to the X11 window.
-    pub fn present(&mut self, conn: &xcb::Connection, window: x::Window, gc: x::Gcontext) -> Result<()> {
-        self.surface.flush();
-        let data = self.surface.data().map_err(|e| anyhow::anyhow!("Failed to get surface data: {}", e))?;
-        
-        conn.send_request(&x::PutImage {
+/// Presents frames on an X11 overlay window.
+///
+/// Frames are uploaded to a server-side Pixmap (the front buffer) and blitted
+/// to the window with a single CopyArea, so the window never shows a partially
+/// uploaded frame. The GC and Pixmap are created once on first present and
+/// reused every frame. If Pixmap creation fails (or is disabled), frames are
+/// uploaded straight to the window.
+pub struct X11Present {
+    conn: Arc<xcb::Connection>,
+    window: x::Window,
+    width: u16,
+    height: u16,
+    /// Window depth: 32 for our ARGB overlays, usually 24 for foreign windows.
+    depth: u8,
+    use_pixmap: bool,
+    gc: Option<x::Gcontext>,
+    pixmap: Option<x::Pixmap>,
+}
+
+impl X11Present {
+    pub fn new(conn: Arc<xcb::Connection>, window: x::Window, width: u16, height: u16, use_pixmap: bool) -> Self {
+        Self { conn, window, width, height, depth: 32, use_pixmap, gc: None, pixmap: None }
+    }
+
+    /// Presents into a window of a different depth (e.g. a 24-bit screensaver window).
+    /// Both depths use 32 bits per pixel in ZPixmap format, so the frame data is unchanged.
+    pub fn with_depth(mut self, depth: u8) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Lazily creates the persistent GC and (optionally) the front Pixmap.
+    fn ensure_resources(&mut self) -> Result<x::Gcontext> {
+        if let Some(gc) = self.gc {
+            return Ok(gc);
+        }
+
+        let gc: x::Gcontext = self.conn.generate_id();
+        self.conn.send_and_check_request(&x::CreateGc {
+            cid: gc,
+            drawable: x::Drawable::Window(self.window),
+            value_list: &[x::Gc::GraphicsExposures(false)],
+        }).context("Failed to create graphics context")?;
+        self.gc = Some(gc);
+
+        if self.use_pixmap {
+            let pixmap: x::Pixmap = self.conn.generate_id();
+            match self.conn.send_and_check_request(&x::CreatePixmap {
+                depth: self.depth,
+                pid: pixmap,
+                drawable: x::Drawable::Window(self.window),
+                width: self.width,
+                height: self.height,
+            }) {
+                Ok(()) => self.pixmap = Some(pixmap),
+                Err(e) => {
+                    log::warn!("Pixmap back buffer unavailable, presenting directly: {}", e);
+                    self.use_pixmap = false;
+                }
+            }
+        }
+
+        Ok(gc)
+    }
+}
+
+impl Present for X11Present {
+    fn present(&mut self, frame: &mut ImageSurface) -> Result<()> {
+        let gc = self.ensure_resources()?;
+        let target = match self.pixmap {
+            Some(pixmap) => x::Drawable::Pixmap(pixmap),
+            None => x::Drawable::Window(self.window),
+        };
+
+        frame.flush();
+        let data = frame.data().map_err(|e| anyhow::anyhow!("Failed to get surface data: {}", e))?;
+
+        self.conn.send_request(&x::PutImage {
             format: x::ImageFormat::ZPixmap,
-            drawable: x::Drawable::Window(window),
+            drawable: target,
             gc,
             width: self.width,
             height: self.height,
             dst_x: 0,
             dst_y: 0,
             left_pad: 0,
-            depth: 32,
+            depth: self.depth,
             data: &data,
         });
+
+        if let Some(pixmap) = self.pixmap {
+            self.conn.send_request(&x::CopyArea {
+                src_drawable: x::Drawable::Pixmap(pixmap),
+                dst_drawable: x::Drawable::Window(self.window),
+                gc,
+                src_x: 0,
+                src_y: 0,
+                dst_x: 0,
+                dst_y: 0,
+                width: self.width,
+                height: self.height,
+            });
+        }
         Ok(())
     }
+
+    /// Frees the server-side GC and Pixmap.
+    fn release(&mut self) {
+        if let Some(pixmap) = self.pixmap.take() {
+            self.conn.send_request(&x::FreePixmap { pixmap });
+        }
+        if let Some(gc) = self.gc.take() {
+            self.conn.send_request(&x::FreeGc { gc });
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "x11"
+    }
 }
 
 /// Helper to initialize double buffering.
@@ -362,15 +759,19 @@ pub fn map_window(conn: &xcb::Connection, window: x::Window) -> Result<()> {
 }
 
 /// Context for a single monitor's overlay window.
+/// The window's back buffer and `X11Present` target are owned by its `Renderer`.
 pub struct MonitorContext {
     pub monitor: Monitor,
     pub window: x::Window,
-    pub surface: OffscreenBuffer,
 }
 
 /// Manages the lifecycle of overlay windows.
 pub struct WindowManager {
     pub monitors: Vec<MonitorContext>,
+    /// Layering profile the windows were created with, restored after a takeover.
+    profile: WmProfile,
+    /// `panel.edge` when the windows are docked panels.
+    panel_edge: Option<String>,
 }
 
 impl WindowManager {
@@ -382,35 +783,138 @@ impl WindowManager {
         conn.flush()?;
         Ok(())
     }
+
+    /// Lifts the overlay windows above every other window for a screensaver takeover
+    /// (unmanaged, like a real screensaver), or hands them back to the window manager
+    /// below everything. A new override-redirect flag only applies on the next map, hence
+    /// the unmap and remap. Window managers drop `_NET_WM_STATE` from withdrawn windows,
+    /// so the layering (or dock) properties are set again before the remap.
+    pub fn set_takeover(&self, conn: &xcb::Connection, on: bool) -> Result<()> {
+        for ctx in &self.monitors {
+            conn.send_request(&x::UnmapWindow { window: ctx.window });
+            conn.send_request(&x::ChangeWindowAttributes {
+                window: ctx.window,
+                value_list: &[x::Cw::OverrideRedirect(on || self.profile.override_redirect())],
+            });
+            match &self.panel_edge {
+                Some(edge) => setup_panel_properties(conn, ctx.window, &ctx.monitor, edge)?,
+                None => setup_ewmh_properties(conn, ctx.window, self.profile)?,
+            }
+            conn.send_request(&x::MapWindow { window: ctx.window });
+            conn.send_request(&x::ConfigureWindow {
+                window: ctx.window,
+                value_list: &[x::ConfigWindow::StackMode(if on { x::StackMode::Above } else { x::StackMode::Below })],
+            });
+        }
+        conn.flush()?;
+        Ok(())
+    }
+
+    /// Applies `cosmetics.window_opacity` to every overlay window.
+    pub fn set_opacity(&self, conn: &xcb::Connection, opacity: f64) -> Result<()> {
+        for ctx in &self.monitors {
+            set_window_opacity(conn, ctx.window, opacity)?;
+        }
+        conn.flush()?;
+        Ok(())
+    }
 }
 
 /// Creates overlay windows for all detected monitors.
 pub fn create_all_windows(conn: &xcb::Connection, config: &Config) -> Result<WindowManager> {
     let detected_monitors = detect_monitors(conn)?;
+    let panel = config.panel.enabled.then_some(&config.panel);
+    // Docks are managed windows under every window manager, so panels skip the layering profiles
+    let profile = if panel.is_some() { WmProfile::Gnome } else { WmProfile::resolve(conn, config) };
     let mut contexts = Vec::new();
 
     for monitor in detected_monitors {
-        let window = create_overlay_window(conn, &monitor, config)?;
-        setup_ewmh_properties(conn, window)?;
+        let monitor = match panel {
+            Some(panel) => panel_area(&monitor, panel),
+            None => monitor,
+        };
+        let window = create_overlay_window(conn, &monitor, profile)?;
+        match panel {
+            Some(panel) => setup_panel_properties(conn, window, &monitor, &panel.edge)?,
+            None => setup_ewmh_properties(conn, window, profile)?,
+        }
         setup_input_shape(conn, window)?;
         
         map_window(conn, window)?;
 
-        conn.send_request(&x::ConfigureWindow {
-            window,
-            value_list: &[x::ConfigWindow::StackMode(x::StackMode::Below)],
-        });
-
-        let surface = setup_double_buffering(monitor.width, monitor.height)?;
+        if panel.is_none() {
+            conn.send_request(&x::ConfigureWindow {
+                window,
+                value_list: &[x::ConfigWindow::StackMode(x::StackMode::Below)],
+            });
+        }
 
         contexts.push(MonitorContext {
             monitor,
             window,
-            surface,
         });
     }
     
     conn.flush()?;
 
-    Ok(WindowManager { monitors: contexts })
+    Ok(WindowManager { monitors: contexts, profile, panel_edge: panel.map(|p| p.edge.clone()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panel_area_and_struts() {
+        // Second monitor right of a 1920x1080 one, on a 3840x1080 screen
+        let monitor = Monitor { id: 1, name: "HDMI-1".to_string(), x: 1920, y: 0, width: 1920, height: 1080, refresh: 60 };
+        let panel = |edge: &str| Panel { enabled: true, edge: edge.to_string(), size: 32 };
+
+        let top = panel_area(&monitor, &panel("top"));
+        assert_eq!((top.x, top.y, top.width, top.height), (1920, 0, 1920, 32));
+        assert_eq!(strut_partial(&top, "top", 3840, 1080), [0, 0, 32, 0, 0, 0, 0, 0, 1920, 3839, 0, 0]);
+
+        let bottom = panel_area(&monitor, &panel("bottom"));
+        assert_eq!((bottom.y, bottom.height), (1048, 32));
+        assert_eq!(strut_partial(&bottom, "bottom", 3840, 1080), [0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 1920, 3839]);
+
+        let left = panel_area(&monitor, &panel("left"));
+        assert_eq!((left.x, left.width, left.height), (1920, 32, 1080));
+        assert_eq!(strut_partial(&left, "left", 3840, 1080)[..6], [1952, 0, 0, 0, 0, 1079]);
+
+        let right = panel_area(&monitor, &panel("right"));
+        assert_eq!(right.x, 3808);
+        assert_eq!(strut_partial(&right, "right", 3840, 1080)[..8], [0, 32, 0, 0, 0, 0, 0, 1079]);
+    }
+
+    #[test]
+    fn test_wm_profile_detection() {
+        assert_eq!(WmProfile::from_wm_name("GNOME Shell"), WmProfile::Gnome);
+        assert_eq!(WmProfile::from_wm_name("Mutter (Muffin)"), WmProfile::Gnome);
+        assert_eq!(WmProfile::from_wm_name("KWin"), WmProfile::Kde);
+        assert_eq!(WmProfile::from_wm_name("Xfwm4"), WmProfile::Xfce);
+        assert_eq!(WmProfile::from_wm_name("i3"), WmProfile::I3);
+        assert_eq!(WmProfile::from_wm_name("i3wm-fork"), WmProfile::Gnome);
+        assert_eq!(WmProfile::from_name("auto"), None);
+        assert_eq!(WmProfile::from_name("kde"), Some(WmProfile::Kde));
+
+        assert!(WmProfile::I3.override_redirect());
+        assert!(!WmProfile::Kde.override_redirect());
+        assert_eq!(WmProfile::Kde.window_type(), "_NET_WM_WINDOW_TYPE_NORMAL");
+        assert!(!WmProfile::Gnome.undecorated());
+    }
+
+    #[test]
+    fn test_window_opacity_steps() {
+        assert_eq!(step_opacity(1.0, WINDOW_OPACITY_STEP), 1.0);
+        assert_eq!(step_opacity(0.2, -WINDOW_OPACITY_STEP), MIN_WINDOW_OPACITY);
+        assert_eq!(step_opacity(0.1, -WINDOW_OPACITY_STEP), MIN_WINDOW_OPACITY);
+        // Ten steps down and back up land exactly on full opacity again
+        let low = (0..10).fold(1.0, |o, _| step_opacity(o, -WINDOW_OPACITY_STEP));
+        assert_eq!((0..10).fold(low, |o, _| step_opacity(o, WINDOW_OPACITY_STEP)), 1.0);
+
+        assert_eq!(opacity_cardinal(1.0), u32::MAX);
+        assert_eq!(opacity_cardinal(0.0), 0);
+        assert_eq!(opacity_cardinal(0.5), 0x8000_0000);
+    }
 }
@@ -92,3 +92,22 @@ fn parse_hex_color(hex: &str) -> Result<(f64, f64, f64), anyhow::Error> {
     let b = u8::from_str_radix(&hex[4..6], 16)? as f64 / 255.0;
     Ok((r, g, b))
 }
+
+#[test]
+fn test_margins_bound_layout() {
+    // Offsets act as render-safe margins; per-edge margins override them.
+    let mut config = Config::default();
+    let screen = &mut config.screens[0];
+    screen.margins.right = Some(100);
+    screen.margins.bottom = Some(1080 - 230);
+
+    let layout = matrix_overlay::layout::compute(&config.screens[0], 1920, 1080, &config);
+    assert_eq!(layout.bounds.x, 20);
+    assert_eq!(layout.bounds.width, 1920 - 20 - 100);
+    for item in &layout.items {
+        assert!(item.x + item.max_width <= 1920 - 100, "Item must stay inside the right margin");
+        assert!(item.y < layout.bounds.bottom(), "Item must stay above the bottom margin");
+    }
+    // Only the first rows fit between the 180px safe zone and the bottom margin
+    assert!(layout.items.len() < config.screens[0].metrics.len());
+}
@@ -0,0 +1,395 @@
+use matrix_overlay::config::{MetricSettings, ValueMapping};
+
+fn mapping(value: Option<&str>, min: Option<f64>, max: Option<f64>, text: &str) -> ValueMapping {
+    ValueMapping { value: value.map(str::to_string), min, max, text: text.to_string() }
+}
+
+#[test]
+fn test_value_map_exact_and_ranges() {
+    let settings = MetricSettings {
+        value_map: vec![
+            mapping(Some("0"), None, None, "OFF"),
+            mapping(Some("1"), None, None, "ON"),
+            mapping(None, Some(30.0), None, "HOT {value}"),
+            mapping(None, None, Some(5.0), "cold"),
+        ],
+        ..Default::default()
+    };
+
+    assert_eq!(settings.map_value("1"), Some("ON".to_string()));
+    assert_eq!(settings.map_value(" 0\n"), Some("OFF".to_string()));
+    assert_eq!(settings.map_value("32.5°C"), Some("HOT 32.5°C".to_string()));
+    assert_eq!(settings.map_value("-2°C"), Some("cold".to_string()));
+    assert_eq!(settings.map_value("20°C"), None);
+    assert_eq!(settings.map_value("Clear"), None);
+}
+
+#[test]
+fn test_config_round_trips_through_all_formats() {
+    use matrix_overlay::config::{Config, ConfigFormat};
+
+    let mut config = Config::default();
+    config.metric_settings.insert("cpu_temp".to_string(), MetricSettings {
+        value_map: vec![mapping(None, Some(85.0), None, "HOT")],
+        ..Default::default()
+    });
+
+    for format in [ConfigFormat::Json, ConfigFormat::Toml, ConfigFormat::Yaml] {
+        let text = format.serialize(&config).unwrap();
+        let parsed = format.parse(&text).unwrap_or_else(|e| panic!("{:?} failed to parse: {:#}", format, e));
+        assert_eq!(parsed.general.font_size, config.general.font_size);
+        assert_eq!(parsed.screens[0].metrics, config.screens[0].metrics);
+        assert_eq!(parsed.metric_settings["cpu_temp"].value_map[0].text, "HOT");
+    }
+}
+
+#[test]
+fn test_low_power_profile_overrides_runtime_settings() {
+    use matrix_overlay::config::{Config, LOW_POWER_UPDATE_MS};
+
+    let mut config = Config::default();
+    assert_eq!(config.with_profile().general.update_ms, config.general.update_ms);
+
+    config.general.profile = "low_power".to_string();
+    assert!(config.validate().is_ok());
+    let runtime = config.with_profile();
+    assert_eq!(runtime.general.update_ms, LOW_POWER_UPDATE_MS);
+    assert!(runtime.general.glow_passes.is_empty());
+    assert_eq!(runtime.cosmetics.rain_mode, "off");
+    // The stored config is left as-is
+    assert_eq!(config.general.update_ms, 1000);
+
+    config.general.profile = "turbo".to_string();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_units_setting_is_validated() {
+    use matrix_overlay::config::Config;
+
+    let mut config = Config::default();
+    config.general.units = "Imperial".to_string();
+    assert!(config.validate().is_ok());
+    config.general.units = "kelvin".to_string();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_set_overrides_apply_on_top_of_config() {
+    use matrix_overlay::config::Config;
+
+    let mut config = Config::default();
+    assert_eq!(config.cosmetics.rain_mode, "fall");
+    assert_eq!(config.cosmetics.realism_scale, 10);
+
+    config
+        .apply_overrides(&[
+            "cosmetics.rain_mode=pulse".to_string(),
+            "cosmetics.realism_scale=3".to_string(),
+            "screens.0.metrics=[\"cpu_usage\"]".to_string(),
+        ])
+        .unwrap();
+    assert_eq!(config.cosmetics.rain_mode, "pulse");
+    assert_eq!(config.cosmetics.realism_scale, 3);
+    assert_eq!(config.screens[0].metrics, vec!["cpu_usage".to_string()]);
+
+    // Unset optional settings can be targeted too, including inside an optional section
+    config.apply_override("screens.0.rain_mode=plasma").unwrap();
+    config.apply_override("output.path=/tmp/frames").unwrap();
+    assert_eq!(config.rain_mode_for(0), "plasma");
+    assert_eq!(config.output.path.as_deref(), Some("/tmp/frames"));
+    assert!(config.apply_override("mqtt.host=broker").is_err(), "mqtt needs its topics as well");
+    config.apply_override(r#"mqtt={"host": "broker", "topics": []}"#).unwrap();
+    config.apply_override("mqtt.username=me").unwrap();
+    let mqtt = config.mqtt.as_ref().unwrap();
+    assert_eq!((mqtt.host.as_str(), mqtt.username.as_deref()), ("broker", Some("me")));
+    assert!(config.apply_override("cosmetics.rain_seed=42").is_ok());
+
+    assert!(config.apply_override("cosmetics.no_such_key=1").is_err());
+    assert!(config.apply_override("screens.0.no_such_key=1").is_err());
+    assert!(config.apply_override("no_such_section.key=1").is_err());
+    assert!(config.apply_override("cosmetics.realism_scale=lots").is_err());
+    assert!(config.apply_override("missing_equals").is_err());
+    assert!(config.apply_overrides(&["general.update_ms=10".to_string()]).is_err());
+}
+
+#[test]
+fn test_weather_locations_per_screen() {
+    use matrix_overlay::config::{Config, WeatherLocation};
+
+    let mut config = Config::default();
+    config.weather.enabled = true;
+    config.weather.locations.push(WeatherLocation {
+        name: "office".to_string(),
+        lat: 52.52,
+        lon: 13.41,
+        label: Some("Berlin".to_string()),
+    });
+    config.screens[0].metrics = vec!["weather_temp".to_string(), "cpu_usage".to_string()];
+    config.screens[0].weather_location = Some("office".to_string());
+    assert!(config.validate().is_ok());
+
+    let layout = matrix_overlay::layout::compute(&config.screens[0], 1920, 1080, &config);
+    assert_eq!(layout.items[0].metric_id, "weather_temp@office");
+    assert_eq!(layout.items[0].label, "WEATHER TEMP (Berlin)");
+    assert_eq!(layout.items[1].metric_id, "cpu_usage");
+
+    config.screens[0].weather_location = Some("mars".to_string());
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_rain_exclusions_resolve_to_rain_space() {
+    use matrix_overlay::config::{Config, RainExclusion};
+    use matrix_overlay::layout::Bounds;
+
+    let mut config = Config::default();
+    // Keep the top third of a 1080p monitor clear
+    config.screens[0].rain_exclusions = vec![RainExclusion { height: Some(360), ..Default::default() }];
+    assert!(config.validate().is_ok());
+
+    // Default margins are 20px; rain coordinates start inside them
+    let layout = matrix_overlay::layout::compute(&config.screens[0], 1920, 1080, &config);
+    assert_eq!(layout.rain_exclusions, vec![Bounds { x: -20, y: -20, width: 1920, height: 360 }]);
+
+    config.screens[0].rain_exclusions[0].height = Some(0);
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_panel_layout_lines_metrics_up_along_the_bar() {
+    use matrix_overlay::config::Config;
+
+    let mut config = Config::default();
+    config.panel.enabled = true;
+    assert!(config.validate().is_ok());
+
+    // A 40px bar on a 1920px wide monitor: six metrics side by side, margins ignored
+    let layout = matrix_overlay::layout::compute(&config.screens[0], 1920, 40, &config);
+    assert_eq!(layout.items.len(), config.screens[0].metrics.len());
+    assert!(layout.items.iter().all(|item| item.y == layout.items[0].y && item.y >= 0));
+    assert!(layout.items.windows(2).all(|pair| pair[1].x > pair[0].x + pair[0].max_width));
+    assert!(layout.rain_exclusions.is_empty());
+
+    // Stacked down a bar on the left edge, as many as fit
+    config.panel.edge = "left".to_string();
+    let layout = matrix_overlay::layout::compute(&config.screens[0], 200, 1080, &config);
+    assert_eq!(layout.items.len(), config.screens[0].metrics.len());
+    assert!(layout.items.windows(2).all(|pair| pair[1].y > pair[0].y && pair[1].x == pair[0].x));
+
+    // Too thin for a line of metrics at this font size
+    config.panel.size = 24;
+    assert!(config.validate().is_err());
+    config.general.metric_font_size = 10;
+    config.panel.size = 31;
+    assert!(config.validate().is_ok());
+
+    config.panel.edge = "middle".to_string();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_clock_item_spans_rows_and_checks_its_format() {
+    use matrix_overlay::config::{Config, ItemOptions};
+
+    let mut config = Config::default();
+    config.screens[0].metrics = vec!["clock".to_string(), "cpu_usage".to_string()];
+    let layout = matrix_overlay::layout::compute(&config.screens[0], 1920, 1080, &config);
+    let line_height = (config.general.font_size as f64 * 1.5) as i32;
+    assert_eq!(layout.items[1].y - layout.items[0].y, line_height * matrix_overlay::layout::CLOCK_ROWS);
+    assert!(config.unknown_metrics().is_empty());
+
+    let options = ItemOptions { style: "analog".to_string(), format: Some("%H:%M:%S".to_string()), ..Default::default() };
+    config.screens[0].item_options.insert("clock".to_string(), options);
+    assert!(config.validate().is_ok());
+
+    config.screens[0].item_options.get_mut("clock").unwrap().format = Some("%H:%Q".to_string());
+    assert!(config.validate().is_err());
+    // Well-formed, but a time of day has no weekday, date or zone to show
+    for format in ["%a %H:%M", "%d.%m %H:%M", "%Z"] {
+        config.screens[0].item_options.get_mut("clock").unwrap().format = Some(format.to_string());
+        assert!(config.validate().is_err(), "{} should be rejected", format);
+    }
+    config.screens[0].item_options.get_mut("clock").unwrap().format = None;
+    config.screens[0].item_options.get_mut("clock").unwrap().style = "bar".to_string();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_anchored_items_and_list_columns() {
+    use matrix_overlay::config::{Config, ItemOptions};
+    use matrix_overlay::layout::{anchored_position, Bounds};
+
+    let bounds = Bounds { x: 20, y: 20, width: 1880, height: 1040 };
+    assert_eq!(anchored_position("bottom-right", bounds, 400, 30, 0, 0), (1500, 1030, "right"));
+    assert_eq!(anchored_position("center", bounds, 400, 30, 10, -5), (770, 520, "center"));
+    // Offsets can't push an item past the margins
+    assert_eq!(anchored_position("top-left", bounds, 400, 30, -100, -100), (20, 20, "left"));
+
+    let mut config = Config::default();
+    config.screens[0].metrics = vec!["cpu_usage".to_string(), "ram_usage".to_string(), "disk_usage".to_string()];
+    config.screens[0].columns = 2;
+    config.screens[0].item_options.insert("ram_usage".to_string(), ItemOptions { column: 1, ..Default::default() });
+    config.screens[0].item_options.insert(
+        "disk_usage".to_string(),
+        ItemOptions { anchor: Some("bottom-center".to_string()), width: Some(300), ..Default::default() },
+    );
+    assert!(config.validate().is_ok());
+
+    let layout = matrix_overlay::layout::compute(&config.screens[0], 1920, 1080, &config);
+    let (cpu, ram, disk) = (&layout.items[0], &layout.items[1], &layout.items[2]);
+    // Both columns start at the top of the list
+    assert_eq!(cpu.y, ram.y);
+    assert_eq!(ram.x, cpu.x + cpu.max_width + config.screens[0].column_gap);
+    assert_eq!((disk.x, disk.max_width, disk.alignment.as_str()), (810, 300, "center"));
+    assert_eq!(disk.y + (config.general.font_size as f64 * 1.5) as i32, layout.bounds.bottom());
+
+    config.screens[0].item_options.get_mut("ram_usage").unwrap().column = 2;
+    assert!(config.validate().is_err());
+    config.screens[0].item_options.get_mut("ram_usage").unwrap().column = 0;
+    config.screens[0].item_options.get_mut("disk_usage").unwrap().anchor = Some("upper-left".to_string());
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_item_ellipsize_is_validated() {
+    use matrix_overlay::config::{Config, ItemOptions};
+
+    let mut config = Config::default();
+    for mode in ["start", "middle", "end"] {
+        config.screens[0].item_options.insert(
+            "cpu_usage".to_string(),
+            ItemOptions { ellipsize: Some(mode.to_string()), ..Default::default() },
+        );
+        assert!(config.validate().is_ok(), "{} should be accepted", mode);
+    }
+    for mode in ["", "End", "left", "none"] {
+        config.screens[0].item_options.get_mut("cpu_usage").unwrap().ellipsize = Some(mode.to_string());
+        assert!(config.validate().is_err(), "{:?} should be rejected", mode);
+    }
+}
+
+#[test]
+fn test_remote_instance_metrics_are_known_and_labelled() {
+    use matrix_overlay::config::{Config, RemoteInstance};
+
+    let mut config = Config::default();
+    config.remote_instances.push(RemoteInstance { name: "nas".to_string(), url: "http://nas:9184".to_string(), interval_secs: 10 });
+    config.screens[0].metrics = vec!["nas.cpu_usage".to_string(), "pi.cpu_usage".to_string()];
+    assert!(config.validate().is_ok());
+    let unknown: Vec<String> = config.unknown_metrics().into_iter().map(|(metric, _, _)| metric).collect();
+    assert_eq!(unknown, ["pi.cpu_usage"]);
+
+    let layout = matrix_overlay::layout::compute(&config.screens[0], 1920, 1080, &config);
+    assert_eq!(layout.items[0].label, "CPU USAGE (nas)");
+    assert_eq!(layout.items[1].label, "PI.CPU USAGE");
+
+    config.remote_instances.push(config.remote_instances[0].clone());
+    assert!(config.validate().is_err());
+    config.remote_instances.truncate(1);
+    config.remote_instances[0].name = "nas.local".to_string();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_drop_in_fragments_merge_over_base() {
+    use matrix_overlay::config::Config;
+
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("config.json");
+    let mut config = Config::default();
+    config.general.font_size = 16;
+    std::fs::write(&base, serde_json::to_string(&config).unwrap()).unwrap();
+
+    let fragments = dir.path().join("config.d");
+    std::fs::create_dir(&fragments).unwrap();
+    std::fs::write(fragments.join("10-theme.toml"), "[general]\ncolor = \"#FF0000\"\n").unwrap();
+    std::fs::write(fragments.join("20-alerts.json"), r##"{"general": {"color": "#00FFFF"}, "cosmetics": {"rain_mode": "pulse"}}"##).unwrap();
+    std::fs::write(fragments.join("README"), "ignored").unwrap();
+
+    let merged = Config::load_merged(&base, &fragments).unwrap();
+    // Later fragments win, untouched keys keep the base value
+    assert_eq!(merged.general.color, "#00FFFF");
+    assert_eq!(merged.general.font_size, 16);
+    assert_eq!(merged.cosmetics.rain_mode, "pulse");
+    assert_eq!(merged.screens.len(), config.screens.len());
+
+    // No config.d at all is the same as loading the base file
+    let plain = Config::load_merged(&base, &dir.path().join("missing")).unwrap();
+    assert_eq!(plain.general.color, config.general.color);
+}
+
+#[test]
+fn test_save_keeps_fragment_values_out_of_the_main_file() {
+    use matrix_overlay::config::Config;
+
+    let dir = tempfile::tempdir().unwrap();
+    let base = dir.path().join("config.json");
+    let mut config = Config::default();
+    config.general.font_size = 16;
+    std::fs::write(&base, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+    let fragments = dir.path().join("config.d");
+    std::fs::create_dir(&fragments).unwrap();
+    std::fs::write(fragments.join("10-theme.json"), r##"{"general": {"color": "#FF0000"}}"##).unwrap();
+
+    let mut config = Config::load_merged(&base, &fragments).unwrap();
+    config.cosmetics.rain_mode = "pulse".to_string();
+    config.disabled_collectors = vec!["weather".to_string()];
+    config.save_to(&base, &fragments).unwrap();
+
+    let written = std::fs::read_to_string(&base).unwrap();
+    assert!(written.contains("\"font_size\": 16"), "{}", written);
+    assert!(written.contains("pulse") && written.contains("weather"), "{}", written);
+    assert!(!written.contains("#FF0000"), "{}", written);
+
+    let reloaded = Config::load_merged(&base, &fragments).unwrap();
+    assert_eq!(reloaded.general.color, "#FF0000");
+    assert_eq!(reloaded.cosmetics.rain_mode, "pulse");
+    assert_eq!(reloaded.disabled_collectors, ["weather"]);
+}
+
+#[test]
+fn test_unknown_metric_ids_get_suggestions() {
+    use matrix_overlay::config::{Config, CustomFile};
+    use matrix_overlay::metrics::MetricId;
+
+    // The list of built-in IDs must stay in sync with the parser
+    for id in MetricId::BUILTIN {
+        assert!(!matches!(MetricId::from_str(id), Some(MetricId::Custom(_))), "{} parses as a custom metric", id);
+    }
+
+    let mut config = Config::default();
+    assert!(config.unknown_metrics().is_empty());
+
+    config.custom_files.push(CustomFile {
+        name: "Server".to_string(),
+        path: "/home/me/status.txt".to_string(),
+        metric_id: "server_status".to_string(),
+        tail: false,
+    });
+    config.screens[0].metrics = vec![
+        "cpu_useage".to_string(),
+        "server_stauts".to_string(),
+        "server_status".to_string(),
+        "service_docker.service".to_string(),
+        "totally_made_up".to_string(),
+    ];
+    let unknown = config.unknown_metrics();
+    assert_eq!(unknown, vec![
+        ("cpu_useage".to_string(), 0, Some("cpu_usage".to_string())),
+        ("server_stauts".to_string(), 0, Some("server_status".to_string())),
+        ("totally_made_up".to_string(), 0, None),
+    ]);
+    // Typos are reported, not rejected
+    assert!(config.validate().is_ok());
+
+    // The HUD has nothing else to show, so a typo there is an error
+    config.hud.metrics = vec!["server_status".to_string(), "ram_usgae".to_string()];
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("hud.metrics") && err.contains("did you mean 'ram_usage'"), "{}", err);
+    config.hud.metrics = vec!["server_status".to_string()];
+    config.ticker.metrics = vec!["cpu_temp".to_string(), "cpu_tmep".to_string()];
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("ticker.metrics") && err.contains("did you mean 'cpu_temp'"), "{}", err);
+}
+
@@ -1,11 +1,12 @@
 use std::fs::{self, File};
 use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use tempfile::tempdir;
 use matrix_overlay::metrics::{
     MetricCollector, MetricId, MetricValue, HwmonCollector, NvidiaSmiCollector, 
-    OpenMeteoCollector, SysinfoCollector, SysinfoManager
+    OpenMeteoCollector, SysinfoCollector, SysinfoManager, AirQualityCollector, VpnStatusCollector, GpuAvailability, WifiCollector, format_now_playing, AudioCollector, UpdatesCollector, SystemdCollector, TimeSyncCollector, KernelLogCollector, DiskCollector, InodeCollector, CommandCollector, MqttCollector, HttpJsonCollector, RemoteCollector, RssCollector, GitHubCollector, search_locations_at, CiCollector, TasksCollector, CalendarCollector, MirroredNotification, notification_rows, geoip_location, AstroCollector, parse_feed_titles
 };
 
 #[test]
@@ -95,13 +96,68 @@ fn test_nvidia_collector_parsing() {
     }
 }
 
+#[test]
+fn test_nvidia_collector_extended_fields() {
+    let mut collector = NvidiaSmiCollector::new_with_command(
+        MetricId::GpuVram,
+        "echo".to_string(),
+        vec!["45, 20, 0, 2048, 8192, 95.5, 1800".to_string()]
+    );
+
+    let map = collector.collect();
+    assert_eq!(map.get(&MetricId::GpuVram), Some(&MetricValue::String("2.0/8.0 GB".to_string())));
+    assert_eq!(map.get(&MetricId::GpuPower), Some(&MetricValue::String("96 W".to_string())));
+    assert_eq!(map.get(&MetricId::GpuClock), Some(&MetricValue::String("1800 MHz".to_string())));
+}
+
+#[test]
+fn test_nvidia_collector_backs_off_when_gpu_powers_off() {
+    let dir = tempdir().unwrap();
+    let calls = dir.path().join("calls");
+    let off_flag = dir.path().join("off");
+    let script = format!(
+        "echo x >> {calls}; [ -e {off} ] && exit 9; echo '45, 20, 0'",
+        calls = calls.display(),
+        off = off_flag.display()
+    );
+    let pci = dir.path().join("pci");
+    let gpu = pci.join("0000:01:00.0");
+    fs::create_dir_all(gpu.join("power")).unwrap();
+    fs::write(gpu.join("vendor"), "0x10de\n").unwrap();
+    fs::write(gpu.join("power/runtime_status"), "suspended\n").unwrap();
+
+    let mut collector = NvidiaSmiCollector::new_with_command(MetricId::GpuState, "sh".to_string(), vec!["-c".to_string(), script])
+        .with_availability(GpuAvailability::with_pci_path(pci));
+    let invocations = || fs::read_to_string(&calls).map(|s| s.lines().count()).unwrap_or(0);
+
+    let map = collector.collect();
+    assert_eq!(map.get(&MetricId::GpuState), Some(&MetricValue::String("on".to_string())));
+
+    // dGPU powered off: after a few failures the binary is no longer invoked
+    File::create(&off_flag).unwrap();
+    for _ in 0..6 {
+        collector.collect();
+    }
+    assert_eq!(invocations(), 1 + GpuAvailability::OFF_AFTER_FAILURES as usize);
+    let map = collector.collect();
+    assert_eq!(map.get(&MetricId::GpuState), Some(&MetricValue::String("off".to_string())));
+    assert_eq!(map.get(&MetricId::GpuTemp), Some(&MetricValue::String("off".to_string())));
+
+    // Runtime PM reports the GPU active again: queries resume
+    fs::remove_file(&off_flag).unwrap();
+    fs::write(gpu.join("power/runtime_status"), "active\n").unwrap();
+    let map = collector.collect();
+    assert_eq!(map.get(&MetricId::GpuState), Some(&MetricValue::String("on".to_string())));
+    assert_eq!(map.get(&MetricId::GpuTemp), Some(&MetricValue::String("45°C".to_string())));
+}
+
 #[test]
 fn test_open_meteo_collector() {
     let mut server = mockito::Server::new();
-    let _m = server.mock("GET", "/v1/forecast?latitude=51.5074&longitude=-0.1278&current=temperature_2m,weather_code")
+    let _m = server.mock("GET", "/v1/forecast?latitude=51.5074&longitude=-0.1278&current=temperature_2m,weather_code,relative_humidity_2m,wind_speed_10m,wind_direction_10m,precipitation")
         .with_status(200)
         .with_header("content-type", "application/json")
-        .with_body(r#"{"current": {"temperature_2m": 15.5, "weather_code": 3}}"#)
+        .with_body(r#"{"current": {"temperature_2m": 15.5, "weather_code": 3, "relative_humidity_2m": 71, "wind_speed_10m": 14.2, "wind_direction_10m": 300, "precipitation": 0.4}}"#)
         .create();
 
     let url = server.url();
@@ -124,6 +180,428 @@ fn test_open_meteo_collector() {
         Some(MetricValue::String(s)) => assert_eq!(s, "Partly cloudy"),
         _ => panic!("Expected weather_cond string, got {:?}", map.get(&MetricId::WeatherCondition)),
     }
+
+    assert_eq!(map.get(&MetricId::WeatherHumidity), Some(&MetricValue::String("71%".to_string())));
+    // 300° is west-northwest, which rounds to NW on an 8-point compass
+    assert_eq!(map.get(&MetricId::WeatherWind), Some(&MetricValue::String("14.2 km/h NW".to_string())));
+    assert_eq!(map.get(&MetricId::WeatherPrecipitation), Some(&MetricValue::String("0.4 mm".to_string())));
+}
+
+#[test]
+fn test_open_meteo_forecast() {
+    let mut server = mockito::Server::new();
+    let _m = server.mock("GET", mockito::Matcher::Regex("daily=weather_code,temperature_2m_max".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"current": {"temperature_2m": 15.5, "weather_code": 3},
+            "daily": {"time": ["2026-03-10", "2026-03-11", "2026-03-12", "2026-03-13"],
+                "weather_code": [3, 61, 0, null],
+                "temperature_2m_max": [18.24, 15.0, 17.6, 16.0],
+                "temperature_2m_min": [9.0, 8.2, null, 7.0],
+                "precipitation_probability_max": [40, 85, 5, 10]}}"#)
+        .create();
+
+    let mut collector = OpenMeteoCollector::new_with_url(MetricId::WeatherTemp, 51.5074, -0.1278, server.url()).with_forecast(true);
+    let map = collector.collect();
+
+    assert_eq!(map.get(&MetricId::WeatherHigh), Some(&MetricValue::String("18.2°C".to_string())));
+    assert_eq!(map.get(&MetricId::WeatherLow), Some(&MetricValue::String("9.0°C".to_string())));
+    assert_eq!(map.get(&MetricId::WeatherRainProb), Some(&MetricValue::String("40%".to_string())));
+    // Missing values are left out of the strip rather than shown as zero
+    assert_eq!(
+        map.get(&MetricId::WeatherForecast),
+        Some(&MetricValue::String("Wed 15°/8° Rain · Thu Clear sky · Fri 16°/7°".to_string()))
+    );
+}
+
+#[test]
+fn test_geoip_location_is_saved() {
+    let mut server = mockito::Server::new();
+    let lookup = server.mock("GET", "/json")
+        .with_body(r#"{"ip": "203.0.113.7", "city": "Berlin", "latitude": 52.52, "longitude": 13.4}"#)
+        .expect(1)
+        .create();
+    let dir = tempdir().unwrap();
+    let cache = dir.path().join("geoip.json");
+
+    let url = format!("{}/json", server.url());
+    assert_eq!(geoip_location(&url, Some(&cache)).unwrap(), (52.52, 13.4));
+    // Served from the saved file, even with a URL the HTTP cache hasn't seen
+    assert_eq!(geoip_location(&format!("{}?again", url), Some(&cache)).unwrap(), (52.52, 13.4));
+    lookup.assert();
+    // The IP leaves the machine, so at least not in the clear
+    assert!(matrix_overlay::metrics::GEO_IP_URL.starts_with("https://"));
+}
+
+#[test]
+fn test_air_quality_collector() {
+    let mut server = mockito::Server::new();
+    let _m = server.mock("GET", mockito::Matcher::Regex("^/v1/air-quality".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"current": {"us_aqi": 57.4, "pm2_5": 12.34, "pm10": 20.0,
+            "alder_pollen": null, "birch_pollen": 12.0, "grass_pollen": 34.0,
+            "mugwort_pollen": 0.0, "olive_pollen": null, "ragweed_pollen": null, "uv_index": 6.24}}"#)
+        .create();
+
+    let mut collector = AirQualityCollector::new_with_url(48.85, 2.35, server.url());
+    let map = collector.collect();
+
+    assert_eq!(map.get(&MetricId::AirQuality), Some(&MetricValue::Int(57)));
+    assert_eq!(map.get(&MetricId::Pm25), Some(&MetricValue::String("12.3 µg/m³".to_string())));
+    assert_eq!(map.get(&MetricId::Pollen), Some(&MetricValue::String("34 (Grass)".to_string())));
+    assert_eq!(map.get(&MetricId::UvIndex), Some(&MetricValue::String("6.2 (High)".to_string())));
+}
+
+#[test]
+fn test_vpn_status_collector() {
+    let dir = tempdir().unwrap();
+    let add_iface = |name: &str, operstate: &str, kind: &str| {
+        let iface = dir.path().join(name);
+        fs::create_dir_all(&iface).unwrap();
+        fs::write(iface.join("operstate"), operstate).unwrap();
+        fs::write(iface.join("type"), kind).unwrap();
+    };
+    add_iface("eth0", "up\n", "1\n");
+    add_iface("tun0", "down\n", "65534\n");
+
+    let mut collector = VpnStatusCollector::new_with_path(dir.path().to_path_buf());
+    assert_eq!(collector.collect().get(&MetricId::VpnStatus), Some(&MetricValue::String("OFF".to_string())));
+
+    // WireGuard reports "unknown" while active; renamed tunnels are caught by their link type
+    add_iface("wg0", "unknown\n", "65534\n");
+    add_iface("corp", "up\n", "65534\n");
+    assert_eq!(
+        collector.collect().get(&MetricId::VpnStatus),
+        Some(&MetricValue::String("ON (corp, wg0)".to_string()))
+    );
+}
+
+#[test]
+fn test_wifi_collector() {
+    let dir = tempdir().unwrap();
+    let wireless = dir.path().join("wireless");
+    fs::write(&wireless, "Inter-| sta-|   Quality        |   Discarded packets               | Missed | WE\n \
+         face | tus | link level noise |  nwid  crypt   frag  retry   misc | beacon | 22\n\
+         wlp2s0: 0000   54.  -56.  -256        0      0      0      0     24        0\n").unwrap();
+    let iw = dir.path().join("iw");
+    let calls = dir.path().join("calls");
+    fs::write(&iw, format!("#!/bin/sh\necho x >> {}\necho \"Connected to aa:bb:cc:dd:ee:ff (on $2)\"\necho \"\tSSID: Home Net\"\n", calls.display())).unwrap();
+    fs::set_permissions(&iw, fs::Permissions::from_mode(0o755)).unwrap();
+    let iw_calls = || fs::read_to_string(&calls).map_or(0, |c| c.lines().count());
+
+    let mut collector = WifiCollector::new_with_path(wireless.clone(), iw.to_string_lossy().to_string());
+    let map = collector.collect();
+    assert_eq!(map.get(&MetricId::WifiSsid), Some(&MetricValue::String("Home Net".to_string())));
+    assert_eq!(map.get(&MetricId::WifiSignal), Some(&MetricValue::String("-56 dBm (77%)".to_string())));
+    // The SSID is kept while the link stays up
+    collector.collect();
+    assert_eq!(iw_calls(), 1);
+
+    // Headers only: not associated
+    fs::write(&wireless, "Inter-| sta-|\n face | tus |\n").unwrap();
+    let map = collector.collect();
+    assert_eq!(map.get(&MetricId::WifiSsid), Some(&MetricValue::String("Disconnected".to_string())));
+}
+
+#[test]
+fn test_format_now_playing() {
+    assert_eq!(
+        format_now_playing("Playing", Some("Daft Punk"), Some("Veridis Quo"), Some(83_000_000), Some(345_000_000)),
+        "▶ Daft Punk – Veridis Quo 1:23/5:45"
+    );
+    // Streams often have no artist or length
+    assert_eq!(format_now_playing("Paused", None, Some("Radio"), Some(5_000_000), None), "⏸ Radio 0:05");
+    assert_eq!(format_now_playing("Stopped", None, None, None, None), "■ Unknown track");
+}
+
+#[test]
+fn test_audio_sink_parsing() {
+    let list = "Sink #47
+\tState: SUSPENDED
+\tName: alsa_output.pci-0000_00_1f.3.hdmi-stereo
+\tDescription: Built-in Audio Digital Stereo (HDMI)
+\tMute: no
+\tVolume: front-left: 65536 / 100% / 0.00 dB,   front-right: 65536 / 100% / 0.00 dB
+Sink #48
+\tState: RUNNING
+\tName: alsa_output.pci-0000_00_1f.3.analog-stereo
+\tDescription: Built-in Audio Analog Stereo
+\tMute: yes
+\tVolume: front-left: 39321 /  60% / -13.31 dB,   front-right: 36045 /  55% / -15.58 dB
+\t        balance -0.08
+";
+    let sink = AudioCollector::parse_sinks(list, "alsa_output.pci-0000_00_1f.3.analog-stereo").unwrap();
+    assert_eq!(sink.description, "Built-in Audio Analog Stereo");
+    assert_eq!(sink.volume, 57);
+    assert!(sink.muted);
+    assert!(AudioCollector::parse_sinks(list, "bluez_output.missing").is_none());
+}
+
+#[test]
+fn test_audio_collector_reuses_default_sink() {
+    let dir = tempdir().unwrap();
+    let default = dir.path().join("default");
+    let calls = dir.path().join("calls");
+    fs::write(&default, "speakers\n").unwrap();
+    let pactl = dir.path().join("pactl");
+    fs::write(&pactl, format!(r#"#!/bin/sh
+if [ "$1" = "get-default-sink" ]; then
+    echo x >> {}
+    cat {}
+    exit 0
+fi
+printf 'Sink #1\n\tName: speakers\n\tDescription: Speakers\n\tMute: no\n\tVolume: front-left: 32768 /  50%% / -18.06 dB\n'
+printf 'Sink #2\n\tName: headphones\n\tDescription: Headphones\n\tMute: yes\n\tVolume: front-left: 19661 /  30%% / -31.37 dB\n'
+"#, calls.display(), default.display())).unwrap();
+    fs::set_permissions(&pactl, fs::Permissions::from_mode(0o755)).unwrap();
+    let default_calls = || fs::read_to_string(&calls).map_or(0, |c| c.lines().count());
+
+    let mut collector = AudioCollector::new_with_command(pactl.to_string_lossy().to_string());
+    assert_eq!(collector.collect().get(&MetricId::AudioVolume), Some(&MetricValue::Int(50)));
+    assert_eq!(collector.collect().get(&MetricId::AudioDevice), Some(&MetricValue::String("Speakers".to_string())));
+    assert_eq!(default_calls(), 1);
+
+    // A vanished default sink triggers a fresh lookup
+    fs::write(&default, "headphones\n").unwrap();
+    fs::write(&pactl, fs::read_to_string(&pactl).unwrap().replace("Name: speakers", "Name: hdmi")).unwrap();
+    assert_eq!(collector.collect().get(&MetricId::AudioDevice), Some(&MetricValue::String("Headphones".to_string())));
+    assert_eq!(default_calls(), 2);
+}
+
+#[test]
+fn test_updates_collector_counts_and_caches() {
+    let apt = "Listing... Done\nfirefox/jammy-updates 120.0 amd64 [upgradable from: 119.0]\nlibc6/jammy-updates 2.35-0ubuntu3.5 amd64 [upgradable from: 2.35-0ubuntu3.4]\n";
+    assert_eq!(UpdatesCollector::count_updates(apt), 2);
+
+    let dir = tempdir().unwrap();
+    let calls = dir.path().join("calls");
+    // checkupdates-style: exit 2 means "no updates" and must not count as a failure
+    let command = format!("echo x >> {}; printf 'linux 6.6.1 -> 6.6.2\\nmesa 23.1 -> 23.2\\n'; exit 2", calls.display());
+    let mut collector = UpdatesCollector::new(Some(command), 3600);
+
+    assert_eq!(collector.collect().get(&MetricId::UpdatesPending), Some(&MetricValue::Int(2)));
+    // Within the refresh interval the cached count is reused
+    assert_eq!(collector.collect().get(&MetricId::UpdatesPending), Some(&MetricValue::Int(2)));
+    assert_eq!(fs::read_to_string(&calls).unwrap().lines().count(), 1);
+}
+
+#[test]
+fn test_systemd_collector_unit_states() {
+    let dir = tempdir().unwrap();
+    let systemctl = dir.path().join("systemctl");
+    fs::write(&systemctl, r#"#!/bin/sh
+if [ "$1" = "is-active" ]; then
+    printf 'active\nfailed\n'
+    exit 3
+fi
+printf 'docker.service loaded failed failed Docker Application Container Engine\n'
+"#).unwrap();
+    fs::set_permissions(&systemctl, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let units = vec!["sshd.service".to_string(), "docker.service".to_string()];
+    let mut collector = SystemdCollector::new_with_command(units, systemctl.to_string_lossy().to_string());
+    let values = collector.collect();
+    assert_eq!(values.get(&SystemdCollector::unit_metric("sshd.service")), Some(&MetricValue::String("active".to_string())));
+    assert_eq!(values.get(&SystemdCollector::unit_metric("docker.service")), Some(&MetricValue::String("failed".to_string())));
+    assert_eq!(values.get(&MetricId::FailedUnits), Some(&MetricValue::Int(1)));
+    assert_eq!(SystemdCollector::unit_metric("docker.service").label(), "docker");
+}
+
+#[test]
+fn test_time_sync_collector_chrony_and_timesyncd() {
+    assert_eq!(TimeSyncCollector::parse_timesync_offset("       Server: 10.0.0.1\n       Offset: -512us\n"), Some(-0.512));
+    assert_eq!(TimeSyncCollector::parse_timesync_offset("Offset: +2.5s"), Some(2500.0));
+    assert_eq!(TimeSyncCollector::format_offset(-0.512), "0.5 ms slow");
+
+    let dir = tempdir().unwrap();
+    let script = |name: &str, body: &str| {
+        let path = dir.path().join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().to_string()
+    };
+    // chrony: 0.25 s still to correct, i.e. the clock is 250 ms slow
+    let chronyc = script("chronyc", "echo 'A9FEA97B,169.254.169.123,4,1700000000.1,0.250000000,0.0001,0.0002,-1.2,0.0,0.01,0.0003,0.0001,64.0,Normal'\n");
+    let timedatectl = script("timedatectl", "if [ \"$1\" = show ]; then echo no; else echo '  Offset: +1.5ms'; fi\n");
+    let values = TimeSyncCollector::new_with_commands(chronyc, timedatectl.clone()).collect();
+    assert_eq!(values.get(&MetricId::TimeSync), Some(&MetricValue::String("synced (chrony)".to_string())));
+    assert_eq!(values.get(&MetricId::ClockOffset), Some(&MetricValue::String("250.0 ms slow".to_string())));
+
+    // Without chrony, timedatectl answers
+    let missing = dir.path().join("no-chronyc").to_string_lossy().to_string();
+    let values = TimeSyncCollector::new_with_commands(missing, timedatectl).collect();
+    assert_eq!(values.get(&MetricId::TimeSync), Some(&MetricValue::String("unsynced (timesyncd)".to_string())));
+    assert_eq!(values.get(&MetricId::ClockOffset), Some(&MetricValue::String("1.5 ms fast".to_string())));
+}
+
+#[test]
+fn test_kernel_log_collector_counts_new_messages() {
+    assert_eq!(
+        KernelLogCollector::parse_journal_line("1700000000.250000 host kernel: usb 1-2: device descriptor read/64, error -71"),
+        Some((1700000000.25, "usb 1-2: device descriptor read/64, error -71"))
+    );
+    assert_eq!(KernelLogCollector::parse_journal_line("-- No entries --"), None);
+    assert_eq!(
+        KernelLogCollector::parse_kmsg_record("3,1042,90812345,-;nvme0: I/O timeout\n SUBSYSTEM=nvme\n"),
+        Some((3, "nvme0: I/O timeout"))
+    );
+    // Facility bits above the level are ignored
+    assert_eq!(KernelLogCollector::parse_kmsg_record("12,7,1,-;x").map(|r| r.0), Some(4));
+
+    let dir = tempdir().unwrap();
+    // Two messages from now and one from long before the overlay started
+    let journalctl = dir.path().join("journalctl");
+    fs::write(
+        &journalctl,
+        "#!/bin/sh\necho \"1000.0 host kernel: old\"\nnow=$(($(date +%s) + 1))\necho \"$now.1 host kernel: a\"\necho \"$now.2 host kernel: b\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&journalctl, fs::Permissions::from_mode(0o755)).unwrap();
+    let mut collector = KernelLogCollector::new_with_sources(journalctl.to_string_lossy().to_string(), dir.path().join("no-kmsg"));
+    assert_eq!(collector.collect().get(&MetricId::KernelErrors), Some(&MetricValue::String("2 new".to_string())));
+    // Already counted
+    assert_eq!(collector.collect().get(&MetricId::KernelErrors), Some(&MetricValue::String("2 new".to_string())));
+
+    // Neither source readable
+    let mut collector = KernelLogCollector::new_with_sources(dir.path().join("none").to_string_lossy().to_string(), dir.path().join("no-kmsg"));
+    assert_eq!(collector.collect().get(&MetricId::KernelErrors), Some(&MetricValue::String("N/A".to_string())));
+}
+
+#[test]
+fn test_disk_collector_per_mount_metrics() {
+    assert_eq!(DiskCollector::mount_metric("/"), MetricId::Custom("disk_usage_root".to_string()));
+    assert_eq!(DiskCollector::mount_metric("/mnt/data/"), MetricId::Custom("disk_usage_mnt_data".to_string()));
+    assert_eq!(DiskCollector::format_summary(&[("/", Some(41.2)), ("/home", None)]), "/ 41% · /home N/A");
+
+    let manager = Arc::new(Mutex::new(SysinfoManager::new()));
+    let mut collector = DiskCollector::new(manager).with_mounts(vec!["/no/such/mount".to_string()]);
+    let values = collector.collect();
+    assert_eq!(values.get(&DiskCollector::mount_metric("/no/such/mount")), Some(&MetricValue::String("N/A".to_string())));
+    assert_eq!(values.get(&MetricId::DiskSummary), Some(&MetricValue::String("/no/such/mount N/A".to_string())));
+}
+
+#[test]
+fn test_inode_collector_per_mount_usage() {
+    assert_eq!(InodeCollector::mount_metric("/"), MetricId::Custom("inode_usage_root".to_string()));
+    let df = "Filesystem      Inodes   IUsed   IFree IUse% Mounted on\n\
+              /dev/nvme0n1p2 1000000  870000  130000   87% /\n\
+              /dev/sdb1        50000    5000   45000   10% /mnt/my data\n\
+              /dev/sda1            0       0       0     - /home\n";
+    let usage = InodeCollector::parse_df(df);
+    assert_eq!(usage.get("/"), Some(&87.0));
+    assert_eq!(usage.get("/mnt/my data"), Some(&10.0));
+    assert_eq!(usage.get("/home"), None);
+
+    let dir = tempdir().unwrap();
+    let script = dir.path().join("df");
+    fs::write(&script, format!("#!/bin/sh\ncat <<'EOF'\n{}EOF\nexit 1\n", df)).unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    let mounts = vec!["/".to_string(), "/home".to_string(), "/gone".to_string()];
+    let values = InodeCollector::new_with_command(mounts, script.to_string_lossy().to_string()).collect();
+    assert_eq!(values.get(&MetricId::InodeUsage), Some(&MetricValue::String("87.0%".to_string())));
+    assert_eq!(values.get(&InodeCollector::mount_metric("/")), Some(&MetricValue::String("87.0%".to_string())));
+    assert_eq!(values.get(&InodeCollector::mount_metric("/home")), Some(&MetricValue::String("N/A".to_string())));
+    assert_eq!(values.get(&InodeCollector::mount_metric("/gone")), Some(&MetricValue::String("N/A".to_string())));
+}
+
+#[test]
+fn test_command_collector_output_timeout_and_sandbox() {
+    use matrix_overlay::config::CustomCommand;
+    let command = |args: &[&str], timeout_secs: u64, tail: bool| CustomCommand {
+        metric_id: "backup".to_string(),
+        command: args.iter().map(|a| a.to_string()).collect(),
+        interval_secs: 60,
+        timeout_secs,
+        tail,
+    };
+    let backup = MetricId::Custom("backup".to_string());
+
+    // Programs have to live under $HOME
+    let dir = tempfile::tempdir_in(std::env::var("HOME").unwrap()).unwrap();
+    let script = |name: &str, body: &str| {
+        let path = dir.path().join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_string_lossy().to_string()
+    };
+
+    let mut collector = CommandCollector::new(command(&[&script("status", "printf 'running\\nOK 12:00\\n'")], 5, true));
+    assert_eq!(collector.id(), "command:backup");
+    assert_eq!(collector.collect().get(&backup), Some(&MetricValue::String("OK 12:00".to_string())));
+
+    let mut collector = CommandCollector::new(command(&[&script("slow", "sleep 10")], 1, false));
+    assert_eq!(collector.collect().get(&backup), Some(&MetricValue::String("TIMEOUT".to_string())));
+
+    // Paths outside $HOME, and bare names that resolve there, are refused without running anything
+    for program in [&["/usr/bin/id"][..], &["sh", "-c", "id"]] {
+        let mut collector = CommandCollector::new(command(program, 5, false));
+        assert_eq!(collector.collect().get(&backup), Some(&MetricValue::String("ACCESS DENIED".to_string())));
+    }
+}
+
+#[test]
+fn test_mqtt_collector_receives_published_values() {
+    use matrix_overlay::config::{Mqtt, MqttTopic};
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    // Fake broker: accept CONNECT and SUBSCRIBE, then publish one retained-style value
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let read_packet = |stream: &mut std::net::TcpStream| {
+            let mut header = [0u8; 2];
+            stream.read_exact(&mut header).unwrap();
+            let mut body = vec![0u8; header[1] as usize];
+            stream.read_exact(&mut body).unwrap();
+        };
+        read_packet(&mut stream);
+        stream.write_all(&[0x20, 2, 0, 0]).unwrap();
+        read_packet(&mut stream);
+        stream.write_all(&[0x90, 3, 0, 1, 0]).unwrap();
+        let topic = b"home/living/sensor";
+        let payload = br#"{"temperature": 21.5}"#;
+        let mut publish = vec![0x30, (2 + topic.len() + payload.len()) as u8, 0, topic.len() as u8];
+        publish.extend_from_slice(topic);
+        publish.extend_from_slice(payload);
+        stream.write_all(&publish).unwrap();
+        // Then the broker goes away
+        std::thread::sleep(Duration::from_millis(500));
+    });
+
+    let topic = MqttTopic {
+        topic: "home/+/sensor".to_string(),
+        metric_id: "room_temp".to_string(),
+        json_pointer: Some("/temperature".to_string()),
+        unit: Some("°C".to_string()),
+    };
+    let config = Mqtt {
+        host: "127.0.0.1".to_string(),
+        port,
+        client_id: "test".to_string(),
+        username: None,
+        password: None,
+        topics: vec![topic],
+    };
+    let mut collector = MqttCollector::new(config);
+    let room_temp = MetricId::Custom("room_temp".to_string());
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut value = None;
+    while value.is_none() && Instant::now() < deadline {
+        value = collector.collect().get(&room_temp).cloned();
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert_eq!(value, Some(MetricValue::String("21.5°C".to_string())));
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while value != Some(MetricValue::String("Offline".to_string())) && Instant::now() < deadline {
+        value = collector.collect().get(&room_temp).cloned();
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert_eq!(value, Some(MetricValue::String("Offline".to_string())));
 }
 
 #[test]
@@ -156,3 +634,252 @@ fn test_sysinfo_collector_defaults() {
         panic!("Uptime should be int");
     }
 }
+
+#[test]
+fn test_http_json_collector_extracts_pointers() {
+    use matrix_overlay::config::{HttpJsonEndpoint, HttpJsonField};
+
+    let mut server = mockito::Server::new();
+    let _m = server.mock("GET", "/prices")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"bitcoin": {"usd": 64250.5}, "status": "ok", "queue": [3, 7]}"#)
+        .create();
+
+    let field = |pointer: &str, metric_id: &str, unit: Option<&str>| HttpJsonField {
+        pointer: pointer.to_string(),
+        metric_id: metric_id.to_string(),
+        unit: unit.map(str::to_string),
+    };
+    let mut collector = HttpJsonCollector::new(HttpJsonEndpoint {
+        url: format!("{}/prices", server.url()),
+        interval_secs: 60,
+        fields: vec![
+            field("/bitcoin/usd", "btc", Some(" USD")),
+            field("/status", "api_status", None),
+            field("/queue/1", "ci_queue", None),
+            field("/missing", "gone", None),
+        ],
+    });
+
+    let data = collector.collect();
+    let get = |id: &str| data.get(&MetricId::Custom(id.to_string())).cloned();
+    assert_eq!(get("btc"), Some(MetricValue::String("64250.5 USD".to_string())));
+    assert_eq!(get("api_status"), Some(MetricValue::String("ok".to_string())));
+    assert_eq!(get("ci_queue"), Some(MetricValue::Float(7.0)));
+    assert_eq!(get("gone"), Some(MetricValue::String("N/A".to_string())));
+
+    // An unreachable endpoint marks every field offline
+    let mut offline = HttpJsonCollector::new(HttpJsonEndpoint {
+        url: "http://127.0.0.1:1/unreachable".to_string(),
+        interval_secs: 60,
+        fields: vec![field("/x", "btc", None)],
+    });
+    assert_eq!(offline.collect().get(&MetricId::Custom("btc".to_string())), Some(&MetricValue::String("Offline".to_string())));
+}
+
+#[test]
+fn test_remote_collector_namespaces_metrics() {
+    use matrix_overlay::config::RemoteInstance;
+
+    let mut server = mockito::Server::new();
+    let _m = server.mock("GET", "/status.json")
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"visible": true, "metrics": {"cpu_usage": "12.5%", "ram_total": 16, "load_avg": 0.75,
+            "network_details": {"eth0": [1024.0, 2048.0]}, "top_processes": [["nginx", "3.0%"]]}}"#)
+        .create();
+
+    let remote = RemoteInstance { name: "nas".to_string(), url: format!("{}/", server.url()), interval_secs: 10 };
+    assert_eq!(remote.remote_id("nas.cpu_usage"), Some("cpu_usage"));
+    assert_eq!(remote.remote_id("nasa.cpu_usage"), None);
+    let mut collector = RemoteCollector::new(remote, vec!["nas.cpu_usage".to_string()]);
+    assert_eq!(collector.id(), "remote:nas");
+
+    let data = collector.collect();
+    let get = |id: &str| data.get(&MetricId::Custom(id.to_string())).cloned();
+    assert_eq!(get("nas.cpu_usage"), Some(MetricValue::String("12.5%".to_string())));
+    assert_eq!(get("nas.ram_total"), Some(MetricValue::Int(16)));
+    assert_eq!(get("nas.load_avg"), Some(MetricValue::Float(0.75)));
+    assert_eq!(get("nas.network_details").and_then(|v| v.as_f64()), Some(3072.0));
+    assert_eq!(get("nas.top_processes"), Some(MetricValue::Table(vec![vec!["nginx".to_string(), "3.0%".to_string()]])));
+
+    // Only the displayed metrics are marked offline when the instance is down
+    let down = RemoteInstance { name: "pi".to_string(), url: "http://127.0.0.1:1".to_string(), interval_secs: 10 };
+    let data = RemoteCollector::new(down, vec!["pi.cpu_temp".to_string()]).collect();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data.get(&MetricId::Custom("pi.cpu_temp".to_string())), Some(&MetricValue::String("Offline".to_string())));
+}
+
+#[test]
+fn test_github_collector_counts_notifications_and_reviews() {
+    use matrix_overlay::config::GitHub;
+
+    let mut server = mockito::Server::new();
+    let _notifications = server.mock("GET", "/notifications?per_page=50")
+        .match_header("authorization", "Bearer test-token")
+        .with_body(r#"[{"id": "1"}, {"id": "2"}, {"id": "3"}]"#)
+        .create();
+    let _search = server.mock("GET", mockito::Matcher::Regex("^/search/issues".to_string()))
+        .match_header("authorization", "Bearer test-token")
+        .with_body(r#"{"total_count": 4, "items": []}"#)
+        .create();
+
+    let config = GitHub { token: Some("test-token".to_string()), api_url: format!("{}/", server.url()), interval_secs: 300 };
+    let data = GitHubCollector::new(&config).collect();
+    assert_eq!(data.get(&MetricId::GhNotifications), Some(&MetricValue::Int(3)));
+    assert_eq!(data.get(&MetricId::GhReviewQueue), Some(&MetricValue::Int(4)));
+    assert_eq!(MetricId::from_str("gh_review_queue"), Some(MetricId::GhReviewQueue));
+}
+
+#[test]
+fn test_location_search_parses_geocoding_results() {
+    let mut server = mockito::Server::new();
+    let _found = server.mock("GET", mockito::Matcher::Regex("^/v1/search\\?name=San\\+Jos".to_string()))
+        .with_body(r#"{"results": [
+            {"name": "San José", "admin1": "San José", "country": "Costa Rica", "latitude": 9.93, "longitude": -84.08},
+            {"name": "San Jose", "admin1": "California", "country": "United States", "latitude": 37.34, "longitude": -121.89}
+        ]}"#)
+        .create();
+    let _none = server.mock("GET", mockito::Matcher::Regex("^/v1/search\\?name=Nowhere".to_string()))
+        .with_body(r#"{"generationtime_ms": 0.5}"#)
+        .create();
+
+    let places = search_locations_at(&server.url(), "San Jose").unwrap();
+    assert_eq!(places.len(), 2);
+    assert_eq!(places[1].display_name(), "San Jose, California, United States");
+    assert_eq!(places[1].latitude, 37.34);
+
+    assert!(search_locations_at(&server.url(), "Nowhere").unwrap().is_empty());
+}
+
+#[test]
+fn test_ci_collector_states() {
+    use matrix_overlay::config::{Ci, GitHub};
+
+    assert_eq!(CiCollector::github_slug("git@github.com:octo/overlay.git").as_deref(), Some("octo/overlay"));
+    assert_eq!(CiCollector::github_slug("https://github.com/octo/overlay").as_deref(), Some("octo/overlay"));
+    assert_eq!(CiCollector::github_slug("https://gitlab.com/octo/overlay.git"), None);
+    assert_eq!(CiCollector::normalize_state("SUCCESS"), "pass");
+    assert_eq!(CiCollector::normalize_state("timed_out"), "fail");
+    assert_eq!(CiCollector::normalize_state("queued"), "running");
+
+    let mut server = mockito::Server::new();
+    let _json = server.mock("GET", "/builds/api").with_body(r#"{"state": "failed"}"#).create();
+    let _text = server.mock("GET", "/builds/docs").with_body("running\n").create();
+
+    let mut ci = Ci::default();
+    ci.status_urls.insert("api".to_string(), format!("{}/builds/api", server.url()));
+    ci.status_urls.insert("docs".to_string(), format!("{}/builds/docs", server.url()));
+    let repos = vec!["/home/me/src/api".to_string(), "/home/me/src/docs".to_string()];
+    let data = CiCollector::new(repos, &ci, &GitHub::default()).collect();
+    assert_eq!(data.get(&CiCollector::repo_metric("/home/me/src/api")), Some(&MetricValue::String("fail".to_string())));
+    assert_eq!(data.get(&MetricId::Custom("ci_docs".to_string())), Some(&MetricValue::String("running".to_string())));
+}
+
+#[test]
+fn test_tasks_collector_parses_sources() {
+    let export = r#"[
+        {"description": "Water plants", "status": "pending", "urgency": 1.2},
+        {"description": "Renew passport", "status": "pending", "urgency": 8.9},
+        {"description": "Old thing", "status": "completed", "urgency": 20.0}
+    ]"#;
+    assert_eq!(TasksCollector::parse_taskwarrior(export), Some((2, Some("Renew passport".to_string()))));
+    assert_eq!(TasksCollector::parse_taskwarrior("[]"), Some((0, None)));
+    assert_eq!(TasksCollector::parse_taskwarrior("Unknown command"), None);
+
+    let todo = "Call mom +family\nx 2026-01-02 Done already\n(B) Fix login bug @work\n\n(A) File taxes due:2026-04-15\n";
+    assert_eq!(TasksCollector::parse_todo_txt(todo), (3, Some("File taxes due:2026-04-15".to_string())));
+    assert_eq!(TasksCollector::parse_todo_txt("Buy milk\nCall mom\n"), (2, Some("Buy milk".to_string())));
+
+    let config = matrix_overlay::config::Tasks::default();
+    let mut missing = TasksCollector::new_with_command(config, "/nonexistent/task".to_string());
+    assert_eq!(missing.collect().get(&MetricId::Tasks), Some(&MetricValue::String("ERR".to_string())));
+}
+
+#[test]
+fn test_next_event_formatting() {
+    use chrono::{Local, TimeZone};
+
+    let now = Local.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap(); // Friday
+    let at = |d, h, m| Local.with_ymd_and_hms(2026, 10, d, h, m, 0).unwrap();
+    assert_eq!(CalendarCollector::format_event(at(16, 14, 0), false, "Standup", now), "14:00 Standup");
+    assert_eq!(CalendarCollector::format_event(at(20, 9, 30), false, "Dentist", now), "Tue 09:30 Dentist");
+    assert_eq!(CalendarCollector::format_event(at(17, 0, 0), true, "Hike", now), "Tomorrow Hike");
+    assert_eq!(CalendarCollector::format_event(at(30, 9, 0), false, "Review", now), "Oct 30 Review");
+}
+
+#[test]
+fn test_astro_collector_reports_sun_and_moon() {
+    let mut collector = AstroCollector::new(51.5074, -0.1278);
+    let values = collector.collect();
+    for id in [MetricId::Sunrise, MetricId::Sunset, MetricId::DaylightRemaining, MetricId::MoonPhase] {
+        assert!(matches!(values.get(&id), Some(MetricValue::String(_))), "missing {:?}", id);
+    }
+    if let Some(MetricValue::String(sunrise)) = values.get(&MetricId::Sunrise) {
+        assert_eq!(sunrise.len(), 5, "expected HH:MM, got {}", sunrise);
+    }
+    assert!(matrix_overlay::astro::daytime().is_some());
+}
+
+#[test]
+fn test_parse_feed_titles_rss_and_atom() {
+    let rss = r#"<rss><channel><title>Site</title>
+        <item><title>Kernel 7.0 released</title></item>
+        <item><title><![CDATA[Rust & <friends>]]></title></item>
+        <item><title>Tom &amp; Jerry&#39;s
+            comeback</title></item>
+        <item><description>untitled</description></item>
+    </channel></rss>"#;
+    assert_eq!(parse_feed_titles(rss, 10), vec!["Kernel 7.0 released", "Rust & <friends>", "Tom & Jerry's comeback"]);
+
+    let atom = r#"<feed><title>Blog</title>
+        <entry><title type="text">First post</title></entry>
+        <entry><title type="html">Second &lt;b&gt;post&lt;/b&gt;</title></entry>
+    </feed>"#;
+    assert_eq!(parse_feed_titles(atom, 10), vec!["First post", "Second <b>post</b>"]);
+
+    let many: String = (0..15).map(|i| format!("<item><title>n{}</title></item>", i)).collect();
+    assert_eq!(parse_feed_titles(&many, 10).len(), 10);
+    assert_eq!(parse_feed_titles(&many, 3), vec!["n0", "n1", "n2"]);
+    assert!(parse_feed_titles("not a feed", 10).is_empty());
+}
+
+#[test]
+fn test_rss_collector_joins_latest_headlines() {
+    use matrix_overlay::config::RssFeed;
+    let mut server = mockito::Server::new();
+    let _feed = server
+        .mock("GET", "/feed.xml")
+        .with_body("<rss><channel><item><title>One</title></item><item><title>Two</title></item><item><title>Three</title></item></channel></rss>")
+        .create();
+    let feed = |path: &str| RssFeed {
+        url: format!("{}{}", server.url(), path),
+        metric_id: "news".to_string(),
+        headlines: 2,
+        interval_secs: 900,
+        separator: " | ".to_string(),
+    };
+
+    assert_eq!(RssCollector::new(feed("/feed.xml")).id(), "rss:news");
+    let values = RssCollector::new(feed("/feed.xml")).collect();
+    assert_eq!(values.get(&MetricId::Custom("news".to_string())), Some(&MetricValue::String("One | Two".to_string())));
+
+    let values = RssCollector::new(feed("/missing.xml")).collect();
+    assert_eq!(values.get(&MetricId::Custom("news".to_string())), Some(&MetricValue::String("Offline".to_string())));
+}
+
+#[test]
+fn test_notification_rows_newest_first_until_faded() {
+    use matrix_overlay::config::NotificationMirror;
+    let note = |received: i64, summary: &str| MirroredNotification { received, app: "Slack".to_string(), summary: summary.to_string() };
+    let recent: std::collections::VecDeque<_> = [note(100, "old"), note(500, "a"), note(600, "b"), note(700, "c")].into_iter().collect();
+    let mirror = NotificationMirror { count: 2, fade_secs: 300, ignore_apps: Vec::new() };
+
+    let rows = notification_rows(&recent, 710, &mirror);
+    assert_eq!(rows, vec![vec!["700", "Slack", "c"], vec!["600", "Slack", "b"]]);
+
+    // Only what is younger than fade_secs is left
+    let mirror = NotificationMirror { count: 5, ..mirror };
+    let summaries: Vec<String> = notification_rows(&recent, 850, &mirror).into_iter().map(|row| row[2].clone()).collect();
+    assert_eq!(summaries, ["c", "b"]);
+    assert!(notification_rows(&recent, 2000, &mirror).is_empty());
+}